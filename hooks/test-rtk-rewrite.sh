@@ -1,5 +1,5 @@
 #!/bin/bash
-# Test suite for rtk-rewrite.sh
+# Test suite for rtk-rewrite.sh (end-to-end through `rtk rewrite`)
 # Feeds mock JSON through the hook and verifies the rewritten commands.
 #
 # Usage: bash ~/.claude/hooks/test-rtk-rewrite.sh
@@ -81,6 +81,18 @@ test_rewrite "git add ." \
   "git add ." \
   "rtk git add ."
 
+test_rewrite "git blame -L 1,20 a.rs" \
+  "git blame -L 1,20 a.rs" \
+  "rtk git blame -L 1,20 a.rs"
+
+test_rewrite "git merge feature" \
+  "git merge feature" \
+  "rtk git merge feature"
+
+test_rewrite "git rebase origin/main" \
+  "git rebase origin/main" \
+  "rtk git rebase origin/main"
+
 test_rewrite "gh pr list" \
   "gh pr list" \
   "rtk gh pr list"
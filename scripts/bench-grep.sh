@@ -0,0 +1,107 @@
+#!/usr/bin/env bash
+# Benchmark rtk grep's built-in search against ripgrep on a generated fixture tree.
+#
+# Usage: scripts/bench-grep.sh [files] [runs]
+#   files  source files to generate (default 20000)
+#   runs   timed runs per case; the median is reported (default 5)
+#
+# Builds nothing: uses ./target/release/rtk (cargo build --release first).
+# Also checks that both engines print identical output for every case.
+set -eo pipefail
+
+FILES="${1:-20000}"
+RUNS="${2:-5}"
+RTK="$(pwd)/target/release/rtk"
+RED='\033[0;31m'; GREEN='\033[0;32m'; NC='\033[0m'
+
+if [ ! -x "$RTK" ]; then
+    echo "missing $RTK; run: cargo build --release" >&2
+    exit 1
+fi
+if ! command -v rg >/dev/null 2>&1; then
+    echo "ripgrep (rg) is not installed; nothing to compare against" >&2
+    exit 1
+fi
+
+TREE="$(mktemp -d)"
+trap 'rm -rf "$TREE"' EXIT
+# Keep the benchmark out of the user's tracking history
+export RTK_DB_PATH="$TREE/tracking.db"
+
+# ── Fixture tree: nested packages in four languages, plus ignored noise ──
+generate() {
+    git -C "$TREE" init -q
+    printf 'build/\n*.log\n' > "$TREE/.gitignore"
+    local exts=(rs py ts go)
+    local i dir ext
+    for ((i = 0; i < FILES; i++)); do
+        dir="$TREE/pkg$((i % 50))/mod$((i % 7))"
+        ext="${exts[$((i % 4))]}"
+        mkdir -p "$dir"
+        {
+            echo "// module $i: request handling"
+            for ((l = 0; l < 40; l++)); do
+                echo "let value_$l = compute($i, $l); // helper line"
+            done
+            if ((i % 97 == 0)); then echo "TODO(perf): cache handle_request_$i"; fi
+            echo "fn handle_request_$i(ctx: Context) -> Result { ctx.respond() }"
+        } > "$dir/file$i.$ext"
+    done
+    # Gitignored and ignore_dirs content the search must skip
+    mkdir -p "$TREE/build" "$TREE/node_modules/dep"
+    for ((i = 0; i < FILES / 10; i++)); do
+        echo "TODO(perf): generated $i" > "$TREE/build/gen$i.rs"
+        echo "TODO(perf): vendored $i" > "$TREE/node_modules/dep/v$i.js"
+    done
+}
+
+# Nanoseconds since the epoch (BSD date has no %N)
+now_ns() {
+    date +%s%N | grep -v N || python3 -c 'import time; print(time.time_ns())'
+}
+
+# Median wall time in milliseconds of running "$@" $RUNS times
+median_ms() {
+    local times=() start end
+    for ((r = 0; r < RUNS; r++)); do
+        start=$(now_ns)
+        "$@" >/dev/null 2>&1 || true
+        end=$(now_ns)
+        times+=($(((end - start) / 1000000)))
+    done
+    printf '%s\n' "${times[@]}" | sort -n | sed -n "$(((RUNS + 1) / 2))p"
+}
+
+echo "Generating $FILES files in $TREE..."
+generate
+cd "$TREE"
+
+CASES=(
+    "rare literal|TODO\\(perf\\)"
+    "common literal|compute"
+    "regex|handle_request_[0-9]+7\\b"
+    "case-insensitive|-i|REQUEST HANDLING"
+)
+
+printf "\n%-18s │ %9s │ %12s │ %15s │ %s\n" "case" "rg -n" "rtk grep rg" "rtk grep builtin" "same output"
+printf "%s\n" "───────────────────┼───────────┼──────────────┼─────────────────┼────────────"
+for case in "${CASES[@]}"; do
+    IFS='|' read -r name first second <<< "$case"
+    if [ -n "${second:-}" ]; then
+        pattern="$second"; flags=("$first")
+    else
+        pattern="$first"; flags=()
+    fi
+
+    raw=$(median_ms rg -n "${flags[@]}" "$pattern" .)
+    via_rg=$(RTK_GREP_ENGINE=rg median_ms "$RTK" grep "$pattern" . "${flags[@]}")
+    builtin=$(RTK_GREP_ENGINE=builtin median_ms "$RTK" grep "$pattern" . "${flags[@]}")
+
+    if diff -q <(RTK_GREP_ENGINE=rg "$RTK" grep "$pattern" . "${flags[@]}" 2>&1) \
+        <(RTK_GREP_ENGINE=builtin "$RTK" grep "$pattern" . "${flags[@]}" 2>&1) >/dev/null; then
+        same="${GREEN}yes${NC}"
+    else
+        same="${RED}NO${NC}"
+    fi
+    printf "%-18s │ %7sms │ %10sms │ %13sms │ %b\n" "$name" "$raw" "$via_rg" "$builtin" "$same"
+done
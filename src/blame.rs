@@ -0,0 +1,381 @@
+//! Compact `git blame` for `rtk git blame`.
+//!
+//! Raw blame repeats hash, author and date on every line. Here each commit is described
+//! once in a legend (hash, author, relative date, subject) and consecutive lines from
+//! the same commit share one range header. With `--fold-older <age>`, lines from
+//! commits older than that merge into plain "older" ranges and leave the legend.
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+
+/// Hash width shown; git's default abbreviation
+const SHORT_HASH: usize = 7;
+/// Longest subject shown in the legend
+const MAX_SUBJECT_CHARS: usize = 60;
+
+const DAY: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameCommit {
+    pub hash: String,
+    pub author: String,
+    /// Author time, seconds since the epoch
+    pub time: i64,
+    pub summary: String,
+}
+
+impl BlameCommit {
+    fn uncommitted(&self) -> bool {
+        self.hash.bytes().all(|b| b == b'0')
+    }
+
+    fn short(&self) -> &str {
+        &self.hash[..self.hash.len().min(SHORT_HASH)]
+    }
+}
+
+/// Parsed `git blame --porcelain`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Blame {
+    /// In order of first appearance
+    pub commits: Vec<BlameCommit>,
+    /// (index into `commits`, final line number, text)
+    pub lines: Vec<(usize, usize, String)>,
+}
+
+pub fn parse_porcelain(output: &str) -> Blame {
+    let mut blame = Blame::default();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    // Commit and final line of the header being read
+    let mut current: Option<(usize, usize)> = None;
+
+    for line in output.lines() {
+        if let Some(text) = line.strip_prefix('\t') {
+            if let Some((commit, number)) = current.take() {
+                blame.lines.push((commit, number, text.to_string()));
+            }
+            continue;
+        }
+        let mut fields = line.split(' ');
+        let first = fields.next().unwrap_or("");
+        if first.len() == 40 && first.bytes().all(|b| b.is_ascii_hexdigit()) {
+            let number = fields.nth(1).and_then(|n| n.parse().ok()).unwrap_or(0);
+            let commit = *index.entry(first.to_string()).or_insert_with(|| {
+                blame.commits.push(BlameCommit {
+                    hash: first.to_string(),
+                    author: String::new(),
+                    time: 0,
+                    summary: String::new(),
+                });
+                blame.commits.len() - 1
+            });
+            current = Some((commit, number));
+            continue;
+        }
+        let Some((commit, _)) = current else {
+            continue;
+        };
+        let value = line.split_once(' ').map_or("", |(_, v)| v);
+        let entry = &mut blame.commits[commit];
+        match first {
+            "author" => entry.author = value.to_string(),
+            "author-time" => entry.time = value.parse().unwrap_or(0),
+            "summary" => entry.summary = value.to_string(),
+            _ => {}
+        }
+    }
+    blame
+}
+
+/// `90d`, `6w`, `6m`, `2y` or plain days, in seconds
+pub fn parse_age(age: &str) -> Result<i64> {
+    let (number, unit) = match age.find(|c: char| !c.is_ascii_digit()) {
+        Some(at) => age.split_at(at),
+        None => (age, "d"),
+    };
+    let Ok(number) = number.parse::<i64>() else {
+        bail!(
+            "invalid age '{}': expected a number of days, or 4w, 6m, 2y",
+            age
+        );
+    };
+    let days = match unit {
+        "d" => 1,
+        "w" => 7,
+        "m" => 30,
+        "y" => 365,
+        _ => bail!(
+            "invalid age '{}': expected a number of days, or 4w, 6m, 2y",
+            age
+        ),
+    };
+    Ok(number * days * DAY)
+}
+
+/// `3 hours ago`, `5 days ago`, `2 years ago`
+pub fn relative_age(seconds: i64) -> String {
+    let (n, unit) = match seconds.max(0) {
+        s if s < 60 * 60 => (s / 60, "minute"),
+        s if s < DAY => (s / (60 * 60), "hour"),
+        s if s < 30 * DAY => (s / DAY, "day"),
+        s if s < 365 * DAY => (s / (30 * DAY), "month"),
+        s => (s / (365 * DAY), "year"),
+    };
+    format!("{} {}{} ago", n, unit, if n == 1 { "" } else { "s" })
+}
+
+/// Consecutive lines from one commit; None for folded old commits
+struct Range<'a> {
+    commit: Option<usize>,
+    lines: Vec<&'a (usize, usize, String)>,
+}
+
+/// Render `blame` of `file`. Commits older than `fold_older` seconds (relative to
+/// `now`) fold into unattributed ranges.
+pub fn render(file: &str, blame: &Blame, now: i64, fold_older: Option<i64>) -> String {
+    if blame.lines.is_empty() {
+        return format!("{}: no lines", file);
+    }
+    let folded = |commit: usize| {
+        let c = &blame.commits[commit];
+        fold_older.is_some_and(|age| !c.uncommitted() && now - c.time > age)
+    };
+
+    let mut ranges: Vec<Range> = Vec::new();
+    for entry in &blame.lines {
+        let commit = (!folded(entry.0)).then_some(entry.0);
+        match ranges.last_mut() {
+            Some(range) if range.commit == commit => range.lines.push(entry),
+            _ => ranges.push(Range {
+                commit,
+                lines: vec![entry],
+            }),
+        }
+    }
+
+    let mut line_counts: HashMap<usize, usize> = HashMap::new();
+    for (commit, _, _) in &blame.lines {
+        *line_counts.entry(*commit).or_default() += 1;
+    }
+    let mut shown: Vec<usize> = line_counts
+        .keys()
+        .copied()
+        .filter(|&c| !folded(c))
+        .collect();
+    // Newest first; uncommitted lines are newer than anything
+    shown.sort_by_key(|&c| {
+        let commit = &blame.commits[c];
+        std::cmp::Reverse((commit.uncommitted(), commit.time))
+    });
+    let folded_commits = line_counts.len() - shown.len();
+
+    let mut out = vec![format!(
+        "{}: {} lines, {} commit{}",
+        file,
+        blame.lines.len(),
+        line_counts.len(),
+        if line_counts.len() == 1 { "" } else { "s" }
+    )];
+    let author_width = shown
+        .iter()
+        .map(|&c| &blame.commits[c])
+        .filter(|c| !c.uncommitted())
+        .map(|c| c.author.chars().count())
+        .max()
+        .unwrap_or(0);
+    for &c in &shown {
+        let commit = &blame.commits[c];
+        if commit.uncommitted() {
+            out.push(format!(
+                "  {}  uncommitted ({})",
+                "-".repeat(SHORT_HASH),
+                count_lines(line_counts[&c])
+            ));
+            continue;
+        }
+        out.push(format!(
+            "  {}  {:<aw$}  {:<14}  {} ({})",
+            commit.short(),
+            commit.author,
+            relative_age(now - commit.time),
+            truncate(&commit.summary, MAX_SUBJECT_CHARS),
+            count_lines(line_counts[&c]),
+            aw = author_width
+        ));
+    }
+    if folded_commits > 0 {
+        let lines: usize = ranges
+            .iter()
+            .filter(|r| r.commit.is_none())
+            .map(|r| r.lines.len())
+            .sum();
+        out.push(format!(
+            "  older    {} from {} commit{}, folded",
+            count_lines(lines),
+            folded_commits,
+            if folded_commits == 1 { "" } else { "s" }
+        ));
+    }
+
+    let width = blame
+        .lines
+        .iter()
+        .map(|l| l.1)
+        .max()
+        .unwrap_or(0)
+        .to_string()
+        .len();
+    for range in &ranges {
+        let label = match range.commit {
+            Some(c) if blame.commits[c].uncommitted() => "uncommitted".to_string(),
+            Some(c) => blame.commits[c].short().to_string(),
+            None => "older".to_string(),
+        };
+        let first = range.lines[0].1;
+        let last = range.lines[range.lines.len() - 1].1;
+        if first == last {
+            out.push(format!("\n[{}] {}", label, first));
+        } else {
+            out.push(format!("\n[{}] {}-{}", label, first, last));
+        }
+        out.extend(
+            range
+                .lines
+                .iter()
+                .map(|(_, n, text)| format!("{:>width$}  {}", n, text, width = width)),
+        );
+    }
+    out.join("\n")
+}
+
+/// The blame as plain `git blame` prints it, one attributed line per source line; what
+/// the compact form is measured against
+pub fn plain(blame: &Blame) -> String {
+    blame
+        .lines
+        .iter()
+        .map(|(c, n, text)| {
+            let commit = &blame.commits[*c];
+            let date = chrono::DateTime::from_timestamp(commit.time, 0)
+                .map(|d| d.format("%Y-%m-%d %H:%M:%S +0000").to_string())
+                .unwrap_or_default();
+            format!(
+                "{} ({} {} {}) {}\n",
+                commit.short(),
+                commit.author,
+                date,
+                n,
+                text
+            )
+        })
+        .collect()
+}
+
+fn count_lines(n: usize) -> String {
+    format!("{} line{}", n, if n == 1 { "" } else { "s" })
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+    const B: &str = "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+    const NOW: i64 = 1_700_000_000;
+
+    fn porcelain() -> String {
+        let header = |hash: &str, line: usize, author: &str, days: i64, summary: &str| {
+            format!(
+                "{hash} {line} {line} 1\nauthor {author}\nauthor-mail <x@y>\nauthor-time {}\n\
+                 author-tz +0000\nsummary {summary}\nfilename src/lib.rs\n",
+                NOW - days * DAY
+            )
+        };
+        format!(
+            "{}\tuse std::fs;\n{A} 2 2\n\tuse std::io;\n{}\tfn main() {{\n\
+             {A} 4 4\n\t}}\n{}\t// wip\n",
+            header(A, 1, "Ada", 400, "Initial import"),
+            header(B, 3, "Bob Li", 3, "feat: add main"),
+            header(
+                "0000000000000000000000000000000000000000",
+                5,
+                "Not Committed Yet",
+                0,
+                "Version of src/lib.rs from src/lib.rs"
+            ),
+        )
+    }
+
+    #[test]
+    fn test_parse_porcelain() {
+        let blame = parse_porcelain(&porcelain());
+        assert_eq!(blame.commits.len(), 3);
+        assert_eq!(blame.commits[1].author, "Bob Li");
+        assert_eq!(blame.commits[1].summary, "feat: add main");
+        let lines: Vec<(usize, usize)> = blame.lines.iter().map(|l| (l.0, l.1)).collect();
+        assert_eq!(lines, [(0, 1), (0, 2), (1, 3), (0, 4), (2, 5)]);
+        assert_eq!(blame.lines[1].2, "use std::io;");
+    }
+
+    #[test]
+    fn test_render_groups_ranges_with_legend() {
+        let blame = parse_porcelain(&porcelain());
+        assert_eq!(
+            render("src/lib.rs", &blame, NOW, None),
+            "\
+src/lib.rs: 5 lines, 3 commits
+  -------  uncommitted (1 line)
+  bbbbbbb  Bob Li  3 days ago      feat: add main (1 line)
+  aaaaaaa  Ada     1 year ago      Initial import (3 lines)
+
+[aaaaaaa] 1-2
+1  use std::fs;
+2  use std::io;
+
+[bbbbbbb] 3
+3  fn main() {
+
+[aaaaaaa] 4
+4  }
+
+[uncommitted] 5
+5  // wip"
+        );
+    }
+
+    #[test]
+    fn test_render_folds_old_commits() {
+        let blame = parse_porcelain(&porcelain());
+        let out = render("src/lib.rs", &blame, NOW, Some(parse_age("6m").unwrap()));
+        assert!(out.contains("  older    3 lines from 1 commit, folded"));
+        assert!(!out.contains("aaaaaaa"));
+        assert!(out.contains("[older] 1-2\n"));
+        assert!(out.contains("[older] 4\n"));
+    }
+
+    #[test]
+    fn test_plain_repeats_attribution_per_line() {
+        let blame = parse_porcelain(&porcelain());
+        let plain = plain(&blame);
+        assert_eq!(plain.lines().count(), 5);
+        assert!(plain.starts_with("aaaaaaa (Ada 2022-10-10 22:13:20 +0000 1) use std::fs;\n"));
+    }
+
+    #[test]
+    fn test_ages() {
+        assert_eq!(parse_age("90").unwrap(), 90 * DAY);
+        assert_eq!(parse_age("2w").unwrap(), 14 * DAY);
+        assert_eq!(parse_age("1y").unwrap(), 365 * DAY);
+        assert!(parse_age("soon").is_err());
+        assert!(parse_age("3q").is_err());
+        assert_eq!(relative_age(90), "1 minute ago");
+        assert_eq!(relative_age(5 * 60 * 60), "5 hours ago");
+        assert_eq!(relative_age(45 * DAY), "1 month ago");
+    }
+}
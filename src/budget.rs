@@ -0,0 +1,336 @@
+//! Output token budget: `--budget <tokens>` or `[budget]` in config.
+//!
+//! The budget is enforced once for every command rather than inside each filter: `main`
+//! re-runs rtk as a child process with stdout piped (see `wrap.rs`). Output is passed
+//! on as it arrives while it fits; from the first line that doesn't, the rest is held
+//! back and shrunk in priority order:
+//!
+//! 1. drop context (blank lines, code frames, diff context, stack frames)
+//! 2. drop passing items (`✓ ...`, `test foo ... ok`, `PASS ...`)
+//! 3. truncate, keeping the head
+//!
+//! The untouched output is saved to the tee directory and a pointer is appended. If the
+//! command already printed a tee hint (raw output saved on failure), that one is kept.
+//! stderr and stdin pass straight through, so heartbeats and prompts are unaffected.
+use crate::config::Config;
+use crate::tracking::estimate_tokens;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// Commands never cut: stdout read by a program or interactive, `tee`, which prints
+/// the full output a budget or filter left out, and rtk's own introspection
+const EXEMPT_COMMANDS: &[&str] = &["rewrite", "init", "tee", "config", "gain", "cache"];
+
+/// Tokens kept free for the budget note and tee hint
+const NOTE_RESERVE: usize = 30;
+
+const TEE_HINT_PREFIX: &str = "[full output: ";
+
+lazy_static! {
+    /// Code frame gutters: `   |`, `12 |     let x = 1;`, `  │ foo`
+    static ref GUTTER_RE: Regex = Regex::new(r"^\s*\d*\s*[|│](\s|$)").unwrap();
+    /// Underline lines below a code frame: `    ^^^^ expected u32`
+    static ref CARET_RE: Regex = Regex::new(r"^\s+[\^~]+(\s|$)").unwrap();
+    /// Stack frames: `    at foo (src/app.ts:3:9)`, `  File "app.py", line 3, in f`
+    static ref FRAME_RE: Regex = Regex::new(r#"^\s+(at |File ")"#).unwrap();
+    static ref PASSING_RE: Regex =
+        Regex::new(r"^\s*(✓|✔|PASS\b|PASSED\b|ok\b|\[ok\])|\.\.\. ok$|\s+PASSED\b").unwrap();
+}
+
+/// Budget for this invocation, or None when it should run unbudgeted.
+/// The `--budget` flag wins over config; `--budget 0` turns a configured budget off.
+pub fn resolve(flag: Option<usize>) -> Option<usize> {
+    if crate::wrap::is_child() || crate::parser::json_mode() {
+        return None;
+    }
+    let command = crate::wrap::invoked_command();
+    if is_exempt(&command) {
+        return None;
+    }
+
+    let budget = match flag {
+        Some(tokens) => Some(tokens),
+        None => Config::load()
+            .ok()
+            .and_then(|c| c.budget.for_command(&command)),
+    };
+    budget.filter(|&tokens| tokens > 0)
+}
+
+fn is_exempt(command: &str) -> bool {
+    let first = command.split_whitespace().next().unwrap_or("");
+    EXEMPT_COMMANDS.contains(&first)
+}
+
+/// Tokens of output that can be passed on as it arrives, before the rest must be held
+/// back for [`enforce`]. None when `command` is never cut.
+pub fn live_limit(budget: usize, command: &str) -> Option<usize> {
+    (!is_exempt(command)).then(|| budget.saturating_sub(NOTE_RESERVE))
+}
+
+/// What to print after `printed` already went out as it arrived: `rest` unchanged when
+/// the whole output fits, otherwise `rest` shrunk to what is left of the budget, with a
+/// note and a tee pointer to the whole output.
+pub fn enforce(printed: &str, rest: &str, budget: usize, command: &str, exit_code: i32) -> String {
+    let whole = format!("{}{}", printed, rest);
+    if rest.is_empty() || is_exempt(command) || estimate_tokens(&whole) <= budget {
+        return rest.to_string();
+    }
+
+    let room = budget.saturating_sub(estimate_tokens(printed));
+    let shrunk = shrink(rest, room.saturating_sub(NOTE_RESERVE));
+    let mut result = shrunk.lines.join("\n");
+    result.push('\n');
+    result.push_str(&shrunk.note(budget));
+    result.push('\n');
+
+    let hint = shrunk
+        .tee_hint
+        .map(String::from)
+        .or_else(|| crate::tee::tee_dropped(&whole, &command.replace(' ', "_"), exit_code));
+    if let Some(hint) = hint {
+        result.push_str(&hint);
+        result.push('\n');
+    }
+    result
+}
+
+/// What survived shrinking, and what was dropped at each stage
+#[derive(Debug, Default)]
+struct Shrunk<'a> {
+    lines: Vec<&'a str>,
+    /// Tee hint the command printed itself, moved after the budget note
+    tee_hint: Option<&'a str>,
+    context: usize,
+    passing: usize,
+    truncated: usize,
+}
+
+impl Shrunk<'_> {
+    fn note(&self, budget: usize) -> String {
+        let dropped: Vec<String> = [
+            (self.context, "context"),
+            (self.passing, "passing"),
+            (self.truncated, "truncated"),
+        ]
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, what)| format!("{} {}", n, what))
+        .collect();
+        format!(
+            "[budget {} tokens: omitted {} lines]",
+            budget,
+            dropped.join(", ")
+        )
+    }
+}
+
+/// Drop lines stage by stage until `lines` fits in `budget` tokens.
+/// The first line (usually the summary) is never dropped.
+fn shrink(output: &str, budget: usize) -> Shrunk<'_> {
+    let mut shrunk = Shrunk::default();
+    let mut lines = Vec::new();
+    for line in output.lines() {
+        if line.starts_with(TEE_HINT_PREFIX) {
+            shrunk.tee_hint = Some(line);
+        } else {
+            lines.push(line);
+        }
+    }
+    let fits = |lines: &[&str]| estimate_tokens(&lines.join("\n")) <= budget;
+
+    let context = context_lines(&lines);
+    if !fits(&lines) {
+        let before = lines.len();
+        lines = keep_unless(&lines, |i| context[i]);
+        shrunk.context = before - lines.len();
+    }
+
+    if !fits(&lines) {
+        let before = lines.len();
+        let current = lines.clone();
+        lines = keep_unless(&current, |i| is_passing(current[i]));
+        shrunk.passing = before - lines.len();
+    }
+
+    let mut used = 0;
+    let mut kept = 0;
+    for line in &lines {
+        let cost = estimate_tokens(line) + 1;
+        if kept > 0 && used + cost > budget {
+            break;
+        }
+        used += cost;
+        kept += 1;
+    }
+    shrunk.truncated = lines.len() - kept;
+    lines.truncate(kept);
+
+    shrunk.lines = lines;
+    shrunk
+}
+
+/// Lines of `lines` except those (after the first) where `drop(index)` holds
+fn keep_unless<'a>(lines: &[&'a str], drop: impl Fn(usize) -> bool) -> Vec<&'a str> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i == 0 || !drop(i))
+        .map(|(_, line)| *line)
+        .collect()
+}
+
+/// Which lines are context rather than findings
+fn context_lines(lines: &[&str]) -> Vec<bool> {
+    let mut in_hunk = false;
+    lines
+        .iter()
+        .map(|line| {
+            if line.starts_with("@@") {
+                in_hunk = true;
+                return false;
+            }
+            if in_hunk && !line.starts_with([' ', '+', '-']) {
+                in_hunk = false;
+            }
+            line.trim().is_empty()
+                || (in_hunk && line.starts_with(' '))
+                || GUTTER_RE.is_match(line)
+                || CARET_RE.is_match(line)
+                || FRAME_RE.is_match(line)
+        })
+        .collect()
+}
+
+fn is_passing(line: &str) -> bool {
+    PASSING_RE.is_match(line) && !line.to_lowercase().contains("fail")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enforce_leaves_small_output_alone() {
+        let out = "✓ cargo test: 15 passed\n";
+        assert_eq!(enforce("", out, 100, "cargo test", 0), out);
+    }
+
+    #[test]
+    fn test_enforce_skips_exempt_commands() {
+        let saved: String = (0..200)
+            .map(|i| format!("error: failure number {}\n", i))
+            .collect();
+        assert_eq!(enforce("", &saved, 30, "tee show", 1), saved);
+        assert_eq!(enforce("", &saved, 30, "tee last", 1), saved);
+        assert_eq!(enforce("", &saved, 30, "config", 0), saved);
+        assert_eq!(enforce("", &saved, 30, "gain", 0), saved);
+    }
+
+    #[test]
+    fn test_enforce_passes_rest_when_whole_output_fits() {
+        let printed = "running 3 tests\n";
+        let rest = "✓ cargo test: 3 passed\n";
+        assert_eq!(enforce(printed, rest, 100, "cargo test", 0), rest);
+        assert_eq!(live_limit(100, "cargo test"), Some(100 - NOTE_RESERVE));
+        assert_eq!(live_limit(100, "tee show"), None);
+    }
+
+    #[test]
+    fn test_shrink_drops_context_first() {
+        let output = "\
+2 errors
+error[E0308]: mismatched types
+  --> src/main.rs:7:18
+   |
+ 7 |     let x: u32 = \"a\";
+   |                  ^^^ expected u32
+
+error[E0425]: cannot find value `y`
+  --> src/lib.rs:3:5
+   |
+ 3 |     y
+   |     ^ not found";
+        let budget = estimate_tokens(output) - 5;
+        let shrunk = shrink(output, budget);
+        assert_eq!(
+            shrunk.lines,
+            vec![
+                "2 errors",
+                "error[E0308]: mismatched types",
+                "  --> src/main.rs:7:18",
+                "error[E0425]: cannot find value `y`",
+                "  --> src/lib.rs:3:5",
+            ]
+        );
+        assert_eq!(shrunk.context, 7);
+        assert_eq!(shrunk.passing, 0);
+        assert_eq!(shrunk.truncated, 0);
+    }
+
+    #[test]
+    fn test_shrink_drops_passing_before_truncating() {
+        let mut output = String::from("PASS 40 passed, 1 failed\n");
+        for i in 0..40 {
+            output.push_str(&format!("✓ suite::test_{} (3ms)\n", i));
+        }
+        output.push_str("✗ suite::broken\n  expected 1, got 2\n");
+        let shrunk = shrink(&output, 40);
+
+        assert_eq!(shrunk.lines[0], "PASS 40 passed, 1 failed");
+        assert!(shrunk.lines.contains(&"✗ suite::broken"));
+        assert_eq!(shrunk.passing, 40);
+        assert_eq!(shrunk.truncated, 0);
+    }
+
+    #[test]
+    fn test_shrink_truncates_last_and_keeps_tee_hint() {
+        let mut output: String = (0..200)
+            .map(|i| format!("error: failure number {}\n", i))
+            .collect();
+        output.push_str("[full output: ~/.local/share/rtk/tee/1_cargo_test.log]\n");
+        let shrunk = shrink(&output, 100);
+
+        assert!(shrunk.truncated > 0);
+        assert_eq!(shrunk.lines.len() + shrunk.truncated, 200);
+        assert_eq!(shrunk.lines[0], "error: failure number 0");
+        assert!(estimate_tokens(&shrunk.lines.join("\n")) <= 100);
+        assert_eq!(
+            shrunk.tee_hint,
+            Some("[full output: ~/.local/share/rtk/tee/1_cargo_test.log]")
+        );
+        assert_eq!(
+            shrunk.note(100),
+            format!(
+                "[budget 100 tokens: omitted {} truncated lines]",
+                shrunk.truncated
+            )
+        );
+    }
+
+    #[test]
+    fn test_diff_context_lines() {
+        let lines = vec![
+            "src/a.rs",
+            "@@ -1,3 +1,3 @@",
+            " fn a() {",
+            "-    old();",
+            "+    new();",
+            " }",
+            "src/b.rs",
+        ];
+        assert_eq!(
+            context_lines(&lines),
+            vec![false, false, true, false, false, true, false]
+        );
+    }
+
+    #[test]
+    fn test_is_passing() {
+        assert!(is_passing("✓ adds numbers (2ms)"));
+        assert!(is_passing("test parser::ok_case ... ok"));
+        assert!(is_passing("tests/test_a.py::test_x PASSED"));
+        assert!(!is_passing("✓ 3 passed, 1 failed"));
+        assert!(!is_passing("FAIL src/app.test.ts"));
+    }
+}
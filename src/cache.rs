@@ -0,0 +1,691 @@
+//! Opt-in cache of filtered outputs (`[cache] enabled = true` or `RTK_CACHE=1`).
+//!
+//! Agents re-run `rtk git status`, `rtk tsc` or `rtk cargo clippy` with nothing changed.
+//! Outputs are stored in `cache.db` next to the tracking database, keyed by working
+//! directory and the full rtk argument list, together with a fingerprint of the state
+//! the command reads: git HEAD, index and refs, plus size and mtime of the files its
+//! rule watches (respecting .gitignore), and of every file under path arguments that
+//! point outside the working directory (`rtk grep x ../other`, `rtk ls /abs/dir`). Same
+//! key and fingerprint means the stored output is still what the command would print.
+//!
+//! Watched files are walked from where the command looks, not just the working
+//! directory: the repository root for git commands, the workspace root for cargo.
+//!
+//! Only commands with an invalidation rule are cached: the built-in [`RULES`], plus
+//! `[cache.watch]` in config for anything else.
+use crate::config::{CacheMode, Config};
+use crate::tracking::TimedExecution;
+use chrono::{DateTime, Local, Utc};
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use rusqlite::{params, Connection, OptionalExtension};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::UNIX_EPOCH;
+
+/// Cache database, in the same directory as the tracking database
+const CACHE_FILE: &str = "cache.db";
+
+/// Above this many watched files, fingerprinting costs more than it saves: don't cache
+const MAX_WATCHED_FILES: usize = 20_000;
+
+/// Watch every non-ignored file
+const ALL_FILES: &[&str] = &["*"];
+const RUST_FILES: &[&str] = &["*.rs", "Cargo.toml", "Cargo.lock"];
+const TS_FILES: &[&str] = &[
+    "*.ts",
+    "*.tsx",
+    "*.mts",
+    "*.cts",
+    "tsconfig*.json",
+    "package.json",
+];
+const JS_FILES: &[&str] = &[
+    "*.js",
+    "*.jsx",
+    "*.mjs",
+    "*.cjs",
+    "*.ts",
+    "*.tsx",
+    ".eslintrc*",
+    "eslint.config.*",
+    "biome.json",
+    "package.json",
+];
+const PY_FILES: &[&str] = &[
+    "*.py",
+    "pyproject.toml",
+    "ruff.toml",
+    ".ruff.toml",
+    "setup.cfg",
+];
+const GO_FILES: &[&str] = &["*.go", "go.mod", "go.sum", ".golangci.*"];
+
+/// Which files invalidate a command's cached output, besides git state.
+/// An empty list means git state only.
+struct CacheRule {
+    command: &'static str,
+    watch: &'static [&'static str],
+}
+
+/// Built-in rules. Tests and anything with side effects (install, push, commit) are
+/// deliberately absent.
+const RULES: &[CacheRule] = &[
+    CacheRule {
+        command: "git status",
+        watch: ALL_FILES,
+    },
+    CacheRule {
+        command: "git diff",
+        watch: ALL_FILES,
+    },
+    CacheRule {
+        command: "git log",
+        watch: &[],
+    },
+    CacheRule {
+        command: "git show",
+        watch: &[],
+    },
+    CacheRule {
+        command: "ls",
+        watch: ALL_FILES,
+    },
+    CacheRule {
+        command: "tree",
+        watch: ALL_FILES,
+    },
+    CacheRule {
+        command: "find",
+        watch: ALL_FILES,
+    },
+    CacheRule {
+        command: "grep",
+        watch: ALL_FILES,
+    },
+    CacheRule {
+        command: "cargo build",
+        watch: RUST_FILES,
+    },
+    CacheRule {
+        command: "cargo check",
+        watch: RUST_FILES,
+    },
+    CacheRule {
+        command: "cargo clippy",
+        watch: RUST_FILES,
+    },
+    CacheRule {
+        command: "tsc",
+        watch: TS_FILES,
+    },
+    CacheRule {
+        command: "lint",
+        watch: JS_FILES,
+    },
+    CacheRule {
+        command: "ruff check",
+        watch: PY_FILES,
+    },
+    CacheRule {
+        command: "golangci-lint run",
+        watch: GO_FILES,
+    },
+    CacheRule {
+        command: "go build",
+        watch: GO_FILES,
+    },
+    CacheRule {
+        command: "go vet",
+        watch: GO_FILES,
+    },
+];
+
+/// A stored output
+#[derive(Debug)]
+struct CachedOutput {
+    output: String,
+    exit_code: i32,
+    created_at: DateTime<Utc>,
+}
+
+/// Cache slot for the current invocation: check it with [`replay`](Self::replay),
+/// fill it with [`store`](Self::store).
+#[derive(Debug)]
+pub struct CacheLookup {
+    db_path: PathBuf,
+    command: String,
+    key: String,
+    fingerprint: String,
+    mode: CacheMode,
+    max_age_secs: u64,
+}
+
+impl CacheLookup {
+    /// The cache slot for this invocation, or None when caching doesn't apply:
+    /// disabled, no rule for the command, or too many files to fingerprint.
+    pub fn resolve(budget: Option<usize>) -> Option<Self> {
+        // Delta output depends on the previous run, not just the tree
+        if crate::wrap::is_child() || crate::parser::delta_mode() {
+            return None;
+        }
+        let config = Config::load().ok()?;
+        if !config.cache.enabled {
+            return None;
+        }
+        let command = crate::wrap::invoked_command();
+        let watch = rule_for(&command, &config.cache.watch)?;
+        let cwd = std::env::current_dir().ok()?;
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let root = fingerprint_root(&cwd, &command);
+        let fingerprint = fingerprint(&root, &watch, &external_paths(&cwd, &args))?;
+
+        // Budget may come from config rather than the arguments
+        let key = format!(
+            "{}\n{}\nbudget={}",
+            cwd.display(),
+            args.join("\u{1f}"),
+            budget.unwrap_or(0)
+        );
+        let db_path = crate::tracking::get_db_path()
+            .ok()?
+            .with_file_name(CACHE_FILE);
+
+        Some(Self {
+            db_path,
+            command,
+            key,
+            fingerprint,
+            mode: config.cache.mode,
+            max_age_secs: config.cache.max_age_secs,
+        })
+    }
+
+    /// On a hit, print the cached answer and return its exit code.
+    pub fn replay(&self) -> Option<i32> {
+        let timer = TimedExecution::start();
+        let cached = self.lookup()?;
+        let printed = self.render_hit(&cached);
+        print!("{}", printed);
+
+        timer.track(
+            &self.command,
+            &format!("rtk {} (cached)", self.command),
+            &cached.output,
+            &printed,
+        );
+        Some(cached.exit_code)
+    }
+
+    /// Save what the command printed. Failures to write are ignored: the cache is
+    /// an optimisation, never a reason to fail the command.
+    pub fn store(&self, output: &str, exit_code: i32) {
+        let _ = self.try_store(output, exit_code);
+    }
+
+    fn render_hit(&self, cached: &CachedOutput) -> String {
+        let since = cached
+            .created_at
+            .with_timezone(&Local)
+            .format("%H:%M:%S")
+            .to_string();
+        // stdout must stay one JSON document
+        if crate::parser::json_mode() {
+            return cached.output.clone();
+        }
+        match self.mode {
+            CacheMode::Replay => format!("{}[cached: unchanged since {}]\n", cached.output, since),
+            CacheMode::Unchanged if cached.exit_code == 0 => {
+                format!("rtk {}: unchanged since {}\n", self.command, since)
+            }
+            CacheMode::Unchanged => format!(
+                "rtk {}: unchanged since {} (exit {})\n",
+                self.command, since, cached.exit_code
+            ),
+        }
+    }
+
+    fn open(&self) -> rusqlite::Result<Connection> {
+        if let Some(parent) = self.db_path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS outputs (
+                key TEXT PRIMARY KEY,
+                fingerprint TEXT NOT NULL,
+                output TEXT NOT NULL,
+                exit_code INTEGER NOT NULL,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        Ok(conn)
+    }
+
+    fn lookup(&self) -> Option<CachedOutput> {
+        if !self.db_path.exists() {
+            return None;
+        }
+        let conn = self.open().ok()?;
+        let cached = conn
+            .query_row(
+                "SELECT output, exit_code, created_at FROM outputs
+                 WHERE key = ?1 AND fingerprint = ?2",
+                params![self.key, self.fingerprint],
+                |row| {
+                    Ok(CachedOutput {
+                        output: row.get(0)?,
+                        exit_code: row.get(1)?,
+                        created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
+                            .map(|dt| dt.with_timezone(&Utc))
+                            .unwrap_or(DateTime::<Utc>::MIN_UTC),
+                    })
+                },
+            )
+            .optional()
+            .ok()??;
+
+        let age = Utc::now().signed_duration_since(cached.created_at);
+        (age.num_seconds() >= 0 && (age.num_seconds() as u64) < self.max_age_secs).then_some(cached)
+    }
+
+    fn try_store(&self, output: &str, exit_code: i32) -> rusqlite::Result<()> {
+        let conn = self.open()?;
+        let now = Utc::now();
+        conn.execute(
+            "INSERT OR REPLACE INTO outputs (key, fingerprint, output, exit_code, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                self.key,
+                self.fingerprint,
+                output,
+                exit_code,
+                now.to_rfc3339()
+            ],
+        )?;
+        let cutoff = now - chrono::Duration::seconds(self.max_age_secs as i64);
+        conn.execute(
+            "DELETE FROM outputs WHERE created_at < ?1",
+            params![cutoff.to_rfc3339()],
+        )?;
+        Ok(())
+    }
+}
+
+/// Watched globs for `command` (e.g. "cargo clippy"): the longest matching prefix among
+/// `[cache.watch]` and the built-in rules, config first on a tie.
+fn rule_for(command: &str, configured: &BTreeMap<String, Vec<String>>) -> Option<Vec<String>> {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    let matches = |prefix: &str| {
+        let prefix: Vec<&str> = prefix.split_whitespace().collect();
+        (!prefix.is_empty() && words.starts_with(&prefix)).then_some(prefix.len())
+    };
+
+    let from_config = configured
+        .iter()
+        .filter_map(|(prefix, watch)| matches(prefix).map(|len| (len, watch.clone())));
+    let built_in = RULES.iter().filter_map(|rule| {
+        matches(rule.command).map(|len| {
+            (
+                len,
+                rule.watch.iter().map(|g| g.to_string()).collect::<Vec<_>>(),
+            )
+        })
+    });
+
+    // max_by_key keeps the last maximum, so chain built-ins first
+    built_in
+        .chain(from_config)
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, watch)| watch)
+}
+
+/// Directory whose files `command` reads: the repository root for git, the workspace
+/// root for cargo, else `cwd`
+fn fingerprint_root(cwd: &Path, command: &str) -> PathBuf {
+    let tool = command.split_whitespace().next().unwrap_or("");
+    let (program, args): (&str, &[&str]) = match tool {
+        "git" => ("git", &["rev-parse", "--show-toplevel"]),
+        "cargo" => (
+            "cargo",
+            &["locate-project", "--workspace", "--message-format", "plain"],
+        ),
+        _ => return cwd.to_path_buf(),
+    };
+    let output = Command::new(program).args(args).current_dir(cwd).output();
+    let path = match output {
+        Ok(output) if output.status.success() => {
+            PathBuf::from(String::from_utf8_lossy(&output.stdout).trim())
+        }
+        _ => return cwd.to_path_buf(),
+    };
+    // locate-project names the workspace Cargo.toml
+    let root = if tool == "cargo" {
+        path.parent().map(Path::to_path_buf)
+    } else {
+        Some(path)
+    };
+    root.filter(|root| root.is_dir())
+        .unwrap_or_else(|| cwd.to_path_buf())
+}
+
+/// SHA-256 of git state, the watched files under `dir` and every file under `external`;
+/// None when there are too many. A fixed hash, so stored fingerprints stay comparable
+/// across rtk builds.
+fn fingerprint(dir: &Path, watch: &[String], external: &[PathBuf]) -> Option<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(git_state(dir).as_bytes());
+
+    let mut files = Vec::new();
+    if !watch.is_empty() {
+        let mut builder = WalkBuilder::new(dir);
+        if !watch.iter().any(|glob| glob == "*") {
+            let mut overrides = OverrideBuilder::new(dir);
+            for glob in watch {
+                overrides.add(glob).ok()?;
+            }
+            builder.overrides(overrides.build().ok()?);
+        }
+        collect_files(&mut builder, &mut files)?;
+    }
+    // Outside the tree the rule's globs don't apply: any change there may show
+    for root in external {
+        collect_files(&mut WalkBuilder::new(root), &mut files)?;
+    }
+    files.sort();
+    for (path, len, modified) in &files {
+        hasher.update(path.to_string_lossy().as_bytes());
+        hasher.update(format!("\0{}\0{}\n", len, modified).as_bytes());
+    }
+
+    Some(format!("{:x}", hasher.finalize()))
+}
+
+/// Path, size and mtime of the non-ignored files `builder` walks, .git left out;
+/// None past [`MAX_WATCHED_FILES`]
+fn collect_files(builder: &mut WalkBuilder, files: &mut Vec<(PathBuf, u64, u128)>) -> Option<()> {
+    builder
+        .hidden(false)
+        .filter_entry(|entry| entry.file_name() != ".git");
+    for entry in builder.build().flatten() {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let meta = entry.metadata().ok()?;
+        files.push((
+            entry.path().to_path_buf(),
+            meta.len(),
+            modified_nanos(&meta),
+        ));
+        if files.len() > MAX_WATCHED_FILES {
+            return None;
+        }
+    }
+    Some(())
+}
+
+/// Existing paths among `args` that resolve outside `cwd`, canonicalized
+fn external_paths(cwd: &Path, args: &[String]) -> Vec<PathBuf> {
+    let Ok(cwd) = cwd.canonicalize() else {
+        return Vec::new();
+    };
+    let mut external: Vec<PathBuf> = args
+        .iter()
+        .filter(|arg| !arg.starts_with('-'))
+        .filter_map(|arg| cwd.join(arg).canonicalize().ok())
+        .filter(|path| !path.starts_with(&cwd))
+        .collect();
+    external.sort();
+    external.dedup();
+    external
+}
+
+/// HEAD commit plus index and refs timestamps, or empty outside a git repository
+fn git_state(dir: &Path) -> String {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["rev-parse", "--absolute-git-dir", "HEAD"])
+        .output();
+    let Ok(output) = output else {
+        return String::new();
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let (Some(git_dir), head) = (lines.next(), lines.next()) else {
+        return String::new();
+    };
+    let git_dir = Path::new(git_dir);
+
+    let stamp = |path: PathBuf| {
+        std::fs::metadata(path)
+            .map(|m| format!("{}:{}", m.len(), modified_nanos(&m)))
+            .unwrap_or_default()
+    };
+    let newest_ref = walkdir::WalkDir::new(git_dir.join("refs"))
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| modified_nanos(&m))
+        .max()
+        .unwrap_or(0);
+
+    format!(
+        "{} index={} packed={} refs={}",
+        head.unwrap_or("unborn"),
+        stamp(git_dir.join("index")),
+        stamp(git_dir.join("packed-refs")),
+        newest_ref
+    )
+}
+
+fn modified_nanos(meta: &std::fs::Metadata) -> u128 {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or(0)
+}
+
+/// Delete the cache database. Returns whether there was one.
+pub fn clear() -> anyhow::Result<bool> {
+    let path = crate::tracking::get_db_path()?.with_file_name(CACHE_FILE);
+    if !path.exists() {
+        return Ok(false);
+    }
+    std::fs::remove_file(&path)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(dir: &Path, fingerprint: &str, mode: CacheMode) -> CacheLookup {
+        CacheLookup {
+            db_path: dir.join(CACHE_FILE),
+            command: "git status".to_string(),
+            key: "/repo\ngit\u{1f}status\nbudget=0".to_string(),
+            fingerprint: fingerprint.to_string(),
+            mode,
+            max_age_secs: 3600,
+        }
+    }
+
+    #[test]
+    fn test_store_then_hit_same_fingerprint_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let slot = lookup(dir.path(), "aaaa", CacheMode::Replay);
+        assert!(slot.lookup().is_none());
+
+        slot.store("main...origin/main\nM src/lib.rs\n", 1);
+        let cached = slot.lookup().unwrap();
+        assert_eq!(cached.output, "main...origin/main\nM src/lib.rs\n");
+        assert_eq!(cached.exit_code, 1);
+
+        assert!(lookup(dir.path(), "bbbb", CacheMode::Replay)
+            .lookup()
+            .is_none());
+    }
+
+    #[test]
+    fn test_expired_entries_miss() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut slot = lookup(dir.path(), "aaaa", CacheMode::Replay);
+        slot.store("clean\n", 0);
+        slot.max_age_secs = 0;
+        assert!(slot.lookup().is_none());
+    }
+
+    #[test]
+    fn test_render_hit_modes() {
+        let dir = tempfile::tempdir().unwrap();
+        let cached = CachedOutput {
+            output: "2 errors\n".to_string(),
+            exit_code: 2,
+            created_at: Utc::now(),
+        };
+
+        let replay = lookup(dir.path(), "x", CacheMode::Replay).render_hit(&cached);
+        assert!(replay.starts_with("2 errors\n[cached: unchanged since "));
+
+        let unchanged = lookup(dir.path(), "x", CacheMode::Unchanged).render_hit(&cached);
+        assert!(unchanged.starts_with("rtk git status: unchanged since "));
+        assert!(unchanged.ends_with("(exit 2)\n"));
+    }
+
+    #[test]
+    fn test_rule_for_prefers_longest_prefix_and_config() {
+        let mut configured = BTreeMap::new();
+        assert_eq!(
+            rule_for("cargo clippy", &configured),
+            Some(vec![
+                "*.rs".into(),
+                "Cargo.toml".into(),
+                "Cargo.lock".into()
+            ])
+        );
+        assert_eq!(rule_for("git log", &configured), Some(vec![]));
+        assert_eq!(rule_for("cargo test", &configured), None);
+        assert_eq!(rule_for("git push", &configured), None);
+
+        configured.insert("make".to_string(), vec!["*.c".to_string()]);
+        configured.insert("git status".to_string(), vec!["src/*".to_string()]);
+        assert_eq!(rule_for("make lint", &configured), Some(vec!["*.c".into()]));
+        assert_eq!(
+            rule_for("git status", &configured),
+            Some(vec!["src/*".into()])
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_tracks_watched_files_only() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(dir.path().join("notes.md"), "todo").unwrap();
+        let watch: Vec<String> = RUST_FILES.iter().map(|g| g.to_string()).collect();
+
+        let before = fingerprint(dir.path(), &watch, &[]).unwrap();
+        std::fs::write(dir.path().join("notes.md"), "todo: more").unwrap();
+        assert_eq!(fingerprint(dir.path(), &watch, &[]).unwrap(), before);
+
+        std::fs::write(dir.path().join("src/main.rs"), "fn main() { run() }").unwrap();
+        assert_ne!(fingerprint(dir.path(), &watch, &[]).unwrap(), before);
+
+        let all = vec!["*".to_string()];
+        let before = fingerprint(dir.path(), &all, &[]).unwrap();
+        std::fs::write(dir.path().join("notes.md"), "done").unwrap();
+        assert_ne!(fingerprint(dir.path(), &all, &[]).unwrap(), before);
+    }
+
+    #[test]
+    fn test_edit_elsewhere_in_repo_misses_from_subdirectory() {
+        let repo = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .args(args)
+                .current_dir(repo.path())
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success());
+        };
+        git(&["init", "-q"]);
+        let sub = repo.path().join("app");
+        let sibling = repo.path().join("lib");
+        std::fs::create_dir_all(&sub).unwrap();
+        std::fs::create_dir_all(&sibling).unwrap();
+        std::fs::write(sub.join("main.rs"), "fn main() {}").unwrap();
+        std::fs::write(sibling.join("lib.rs"), "pub fn a() {}").unwrap();
+
+        let root = fingerprint_root(&sub, "git status");
+        assert_eq!(
+            root.canonicalize().unwrap(),
+            repo.path().canonicalize().unwrap()
+        );
+        assert_eq!(fingerprint_root(&sub, "ls"), sub);
+
+        let all = vec!["*".to_string()];
+        let data = tempfile::tempdir().unwrap();
+        let slot = |fingerprint: String| CacheLookup {
+            db_path: data.path().join(CACHE_FILE),
+            command: "git status".to_string(),
+            key: "git status".to_string(),
+            fingerprint,
+            mode: CacheMode::Replay,
+            max_age_secs: 3600,
+        };
+        let before = fingerprint(&root, &all, &[]).unwrap();
+        assert_eq!(before.len(), 64);
+        slot(before).store("?? app/\n?? lib/\n", 0);
+        assert!(slot(fingerprint(&root, &all, &[]).unwrap())
+            .lookup()
+            .is_some());
+
+        std::fs::write(sibling.join("lib.rs"), "pub fn a() { b() }").unwrap();
+        assert!(slot(fingerprint(&root, &all, &[]).unwrap())
+            .lookup()
+            .is_none());
+    }
+
+    #[test]
+    fn test_path_outside_cwd_misses_after_edit() {
+        let root = tempfile::tempdir().unwrap();
+        let cwd = root.path().join("project");
+        let other = root.path().join("other");
+        std::fs::create_dir_all(&cwd).unwrap();
+        std::fs::create_dir_all(&other).unwrap();
+        std::fs::write(cwd.join("a.txt"), "here").unwrap();
+        std::fs::write(other.join("b.txt"), "needle").unwrap();
+
+        let args: Vec<String> = ["grep", "needle", "../other", "src", "-n"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        let external = external_paths(&cwd, &args);
+        assert_eq!(external, [other.canonicalize().unwrap()]);
+
+        let all = vec!["*".to_string()];
+        let slot = |fingerprint: String| CacheLookup {
+            db_path: root.path().join(CACHE_FILE),
+            command: "grep".to_string(),
+            key: "grep needle ../other".to_string(),
+            fingerprint,
+            mode: CacheMode::Replay,
+            max_age_secs: 3600,
+        };
+        slot(fingerprint(&cwd, &all, &external).unwrap()).store("other/b.txt: needle\n", 0);
+        assert!(slot(fingerprint(&cwd, &all, &external).unwrap())
+            .lookup()
+            .is_some());
+
+        std::fs::write(other.join("b.txt"), "needle, twice: needle").unwrap();
+        assert!(slot(fingerprint(&cwd, &all, &external).unwrap())
+            .lookup()
+            .is_none());
+    }
+}
@@ -1,7 +1,12 @@
+use crate::parser::{
+    delta_mode, format_parsed, render_delta, render_diagnostics, render_json, truncate_output,
+    BuildOutput, LintIssue, LintResult, LintSeverity, OutputParser, ParseResult, TestFailure,
+    TestResult,
+};
+use crate::stream::{run_streaming, KeptOutput, StreamFilter, StreamSource};
 use crate::tracking;
-use crate::utils::truncate;
 use anyhow::{Context, Result};
-use std::collections::HashMap;
+use serde::Deserialize;
 use std::ffi::OsString;
 use std::process::Command;
 use std::sync::OnceLock;
@@ -27,8 +32,31 @@ pub fn run(cmd: CargoCommand, args: &[String], verbose: u8) -> Result<()> {
     }
 }
 
+/// Renders the `--output json` envelope: (command, raw output, exit code) → JSON
+type JsonRenderer = fn(&str, &str, i32) -> String;
+
+/// Shape of a cargo subcommand's output, for streaming
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CargoOutputKind {
+    /// build, check, clippy: compiler diagnostics, read as `--message-format=json`
+    Build,
+    /// `cargo install`: compiler diagnostics as text
+    Install,
+    /// `cargo test`: libtest harness output
+    Libtest,
+    /// `cargo nextest run`
+    Nextest,
+}
+
 /// Generic cargo command runner with filtering
-fn run_cargo_filtered<F>(subcommand: &str, args: &[String], verbose: u8, filter_fn: F) -> Result<()>
+fn run_cargo_filtered<F>(
+    subcommand: &str,
+    args: &[String],
+    verbose: u8,
+    kind: CargoOutputKind,
+    filter_fn: F,
+    json_fn: Option<JsonRenderer>,
+) -> Result<()>
 where
     F: Fn(&str) -> String,
 {
@@ -36,6 +64,11 @@ where
 
     let mut cmd = Command::new("cargo");
     cmd.arg(subcommand);
+    // Structured diagnostics carry the suggested code and source lines; options go
+    // before the user's args so they never end up after `--`
+    if kind == CargoOutputKind::Build && wants_json_diagnostics(args) {
+        cmd.arg("--message-format=json");
+    }
     for arg in args {
         cmd.arg(arg);
     }
@@ -44,62 +77,270 @@ where
         eprintln!("Running: cargo {} {}", subcommand, args.join(" "));
     }
 
-    let output = cmd
-        .output()
-        .with_context(|| format!("Failed to run cargo {}", subcommand))?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let raw = format!("{}\n{}", stdout, stderr);
-
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
-    let filtered = filter_fn(&raw);
-
-    if let Some(hint) = crate::tee::tee_and_hint(&raw, &format!("cargo_{}", subcommand), exit_code)
-    {
+    let output = run_streaming(
+        cmd,
+        &format!("cargo {}", subcommand),
+        &mut CargoStream::new(kind),
+    )?;
+    let raw = output.kept.combined();
+    // JSON messages are several times their rendered size; track and tee the rendering
+    let (tee_text, raw_tokens) = if kind == CargoOutputKind::Build {
+        let text = rendered_text(&output.tee_text());
+        let tokens = tracking::estimate_tokens(&text);
+        (text, tokens)
+    } else {
+        (output.tee_text(), output.raw_tokens)
+    };
+
+    let exit_code = output.exit_code;
+    let filtered = match json_fn {
+        Some(render) if crate::parser::json_mode() => render(
+            &format!("cargo {} {}", subcommand, args.join(" ")),
+            &raw,
+            exit_code,
+        ),
+        _ => filter_fn(&raw),
+    };
+
+    if let Some(hint) = crate::tee::tee_and_hint_for(
+        &filtered,
+        &tee_text,
+        &format!("cargo_{}", subcommand),
+        exit_code,
+    ) {
         println!("{}\n{}", filtered, hint);
     } else {
         println!("{}", filtered);
     }
 
-    timer.track(
+    timer.track_streamed(
         &format!("cargo {} {}", subcommand, args.join(" ")),
         &format!("rtk cargo {} {}", subcommand, args.join(" ")),
-        &raw,
-        &filtered,
+        raw_tokens,
+        &format!("{}{}", output.emitted, filtered),
     );
 
-    if !output.status.success() {
+    if !output.success {
         std::process::exit(exit_code);
     }
 
     Ok(())
 }
 
+/// Stream filter for cargo: prints compiler errors and failing tests as they happen and
+/// drops passing-test lines, which none of the cargo parsers read.
+struct CargoStream {
+    kind: CargoOutputKind,
+    compiled: usize,
+    passed: usize,
+    failed: usize,
+    /// The next `-->` line belongs to an error we just printed
+    want_location: bool,
+    /// nextest: inside a FAIL block, whose end the parser detects by the next PASS line
+    in_fail_block: bool,
+    /// nextest: past the `Summary` line, FAIL lines are only a recap
+    after_summary: bool,
+}
+
+impl CargoStream {
+    fn new(kind: CargoOutputKind) -> Self {
+        CargoStream {
+            kind,
+            compiled: 0,
+            passed: 0,
+            failed: 0,
+            want_location: false,
+            in_fail_block: false,
+            after_summary: false,
+        }
+    }
+}
+
+impl StreamFilter for CargoStream {
+    fn feed(&mut self, line: &str, source: StreamSource, kept: &mut KeptOutput) -> Option<String> {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("Compiling ") || trimmed.starts_with("Checking ") {
+            self.compiled += 1;
+        }
+
+        match self.kind {
+            CargoOutputKind::Build => match CargoLine::classify(line) {
+                CargoLine::Diagnostic(message) => {
+                    kept.push(source, line);
+                    return live_error(&message);
+                }
+                // Only compiler messages are read back; artifacts are dropped
+                CargoLine::Json(value) => {
+                    if value["reason"] == "compiler-message" {
+                        kept.push(source, line);
+                    }
+                    return None;
+                }
+                CargoLine::Text(_) => {}
+            },
+            CargoOutputKind::Install => {}
+            CargoOutputKind::Libtest => {
+                if line.starts_with("test ") && line.ends_with(" ... ok") {
+                    self.passed += 1;
+                    return None;
+                }
+                if line.starts_with("test ") && line.ends_with(" ... FAILED") {
+                    self.failed += 1;
+                    kept.push(source, line);
+                    return Some(line.to_string());
+                }
+            }
+            CargoOutputKind::Nextest => {
+                if trimmed.starts_with("Summary") {
+                    self.after_summary = true;
+                } else if trimmed.starts_with("PASS [") && !self.after_summary {
+                    self.passed += 1;
+                    if !self.in_fail_block {
+                        return None;
+                    }
+                    self.in_fail_block = false;
+                } else if trimmed.starts_with("FAIL [") && !self.after_summary {
+                    self.failed += 1;
+                    self.in_fail_block = true;
+                    kept.push(source, line);
+                    return Some(trimmed.to_string());
+                }
+            }
+        }
+
+        kept.push(source, line);
+
+        if (line.starts_with("error[") || line.starts_with("error:"))
+            && !line.contains("aborting due to")
+            && !line.contains("could not compile")
+            && !line.contains("test failed")
+            && !line.contains("test run failed")
+        {
+            self.want_location = true;
+            return Some(line.to_string());
+        }
+        if self.want_location && trimmed.starts_with("--> ") {
+            self.want_location = false;
+            return Some(line.to_string());
+        }
+        None
+    }
+
+    fn progress(&self) -> Option<String> {
+        if matches!(self.kind, CargoOutputKind::Build | CargoOutputKind::Install)
+            || self.passed + self.failed == 0
+        {
+            return (self.compiled > 0).then(|| format!("{} crates compiled", self.compiled));
+        }
+        Some(format!("{} passed, {} failed", self.passed, self.failed))
+    }
+}
+
+/// `error[E0308]: msg` and its `-->` location, printed while cargo still runs
+fn live_error(message: &RustcMessage) -> Option<String> {
+    let issue = issue_from_json(message).filter(|i| i.severity == LintSeverity::Error)?;
+    let mut text = match &message.code {
+        Some(code) => format!("error[{}]: {}", code.code, message.message),
+        None => format!("error: {}", message.message),
+    };
+    if !issue.file_path.is_empty() {
+        text.push_str(&format!(
+            "\n  --> {}:{}:{}",
+            issue.file_path, issue.line, issue.column
+        ));
+    }
+    Some(text)
+}
+
 fn run_build(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("build", args, verbose, filter_cargo_build)
+    run_cargo_filtered(
+        "build",
+        args,
+        verbose,
+        CargoOutputKind::Build,
+        filter_cargo_build,
+        Some(render_json::<CargoBuildParser>),
+    )
 }
 
 fn run_test(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("test", args, verbose, filter_cargo_test)
+    run_cargo_filtered(
+        "test",
+        args,
+        verbose,
+        CargoOutputKind::Libtest,
+        |raw| {
+            let full = || format_parsed("cargo test", CargoTestParser::parse(raw), verbose);
+            if delta_mode() {
+                let command = format!("cargo test {}", args.join(" "));
+                render_delta::<CargoTestParser>("cargo test", &command, raw, full)
+            } else {
+                full()
+            }
+        },
+        Some(render_json::<CargoTestParser>),
+    )
 }
 
 fn run_clippy(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("clippy", args, verbose, filter_cargo_clippy)
+    run_cargo_filtered(
+        "clippy",
+        args,
+        verbose,
+        CargoOutputKind::Build,
+        |raw| {
+            if delta_mode() {
+                let command = format!("cargo clippy {}", args.join(" "));
+                render_delta::<CargoClippyParser>("cargo clippy", &command, raw, || {
+                    filter_cargo_clippy(raw)
+                })
+            } else {
+                filter_cargo_clippy(raw)
+            }
+        },
+        Some(render_json::<CargoClippyParser>),
+    )
 }
 
 fn run_check(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("check", args, verbose, filter_cargo_build)
+    run_cargo_filtered(
+        "check",
+        args,
+        verbose,
+        CargoOutputKind::Build,
+        filter_cargo_build,
+        Some(render_json::<CargoBuildParser>),
+    )
 }
 
 fn run_install(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("install", args, verbose, filter_cargo_install)
+    run_cargo_filtered(
+        "install",
+        args,
+        verbose,
+        CargoOutputKind::Install,
+        filter_cargo_install,
+        None,
+    )
 }
 
 fn run_nextest(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("nextest", args, verbose, filter_cargo_nextest)
+    run_cargo_filtered(
+        "nextest",
+        args,
+        verbose,
+        CargoOutputKind::Nextest,
+        |raw| {
+            let full = || format_parsed("cargo nextest", NextestParser::parse(raw), verbose);
+            if delta_mode() {
+                let command = format!("cargo nextest {}", args.join(" "));
+                render_delta::<NextestParser>("cargo nextest", &command, raw, full)
+            } else {
+                full()
+            }
+        },
+        Some(render_json::<NextestParser>),
+    )
 }
 
 /// Format crate name + version into a display string
@@ -285,641 +526,844 @@ fn filter_cargo_install(output: &str) -> String {
     result
 }
 
-/// Push a completed failure block (header + body) into the failures list, then clear the buffers.
-fn flush_failure_block(header: &mut String, body: &mut Vec<String>, failures: &mut Vec<String>) {
-    if header.is_empty() {
-        return;
-    }
-    let mut block = header.clone();
-    if !body.is_empty() {
-        block.push('\n');
-        block.push_str(&body.join("\n"));
+/// Filter cargo build/check output - drop progress lines, keep diagnostics
+pub(crate) fn filter_cargo_build(output: &str) -> String {
+    let issues = parse_rustc_diagnostics(output);
+    if issues.is_empty() {
+        let compiled = output
+            .lines()
+            .map(str::trim_start)
+            .filter(|l| l.starts_with("Compiling") || l.starts_with("Checking"))
+            .count();
+        return format!("✓ cargo build ({} crates compiled)", compiled);
     }
-    failures.push(block);
-    header.clear();
-    body.clear();
+    render_diagnostics("cargo build", &issues) + &fix_hint(output, "cargo fix")
 }
 
-/// Filter cargo nextest output - show failures + compact summary
-fn filter_cargo_nextest(output: &str) -> String {
-    static SUMMARY_RE: OnceLock<regex::Regex> = OnceLock::new();
-    let summary_re = SUMMARY_RE.get_or_init(|| {
-        regex::Regex::new(
-            r"Summary \[\s*([\d.]+)s\]\s+(\d+) tests? run:\s+(\d+) passed(?:,\s+(\d+) failed)?(?:,\s+(\d+) skipped)?"
-        ).expect("invalid nextest summary regex")
-    });
-
-    static STARTING_RE: OnceLock<regex::Regex> = OnceLock::new();
-    let starting_re = STARTING_RE.get_or_init(|| {
-        regex::Regex::new(r"Starting \d+ tests? across (\d+) binar(?:y|ies)")
-            .expect("invalid nextest starting regex")
-    });
+/// Filter cargo clippy output - diagnostics grouped by file, repeats folded
+pub(crate) fn filter_cargo_clippy(output: &str) -> String {
+    let issues = parse_rustc_diagnostics(output);
+    if issues.is_empty() {
+        return "✓ cargo clippy: No issues found".to_string();
+    }
+    render_diagnostics("cargo clippy", &issues) + &fix_hint(output, "cargo clippy --fix")
+}
 
-    let mut failures: Vec<String> = Vec::new();
-    let mut in_failure_block = false;
-    let mut past_summary = false;
-    let mut current_failure_header = String::new();
-    let mut current_failure_body = Vec::new();
-    let mut summary_line = String::new();
-    let mut binaries: u32 = 0;
-    let mut has_cancel_line = false;
+/// `💡 Run ...` footer when rustc marked warning fixes machine-applicable (JSON only)
+fn fix_hint(output: &str, fix_command: &str) -> String {
+    match fixable_warnings(output) {
+        0 => String::new(),
+        n => format!("\n\n💡 Run `{}` to auto-fix {} warnings", fix_command, n),
+    }
+}
 
-    for line in output.lines() {
-        let trimmed = line.trim();
+/// Parser for `cargo test` text output (libtest harness)
+pub struct CargoTestParser;
 
-        // Strip compilation noise
-        if trimmed.starts_with("Compiling")
-            || trimmed.starts_with("Downloading")
-            || trimmed.starts_with("Downloaded")
-            || trimmed.starts_with("Finished")
-            || trimmed.starts_with("Locking")
-            || trimmed.starts_with("Updating")
-        {
-            continue;
-        }
+impl OutputParser for CargoTestParser {
+    type Output = TestResult;
 
-        // Strip separator lines (────)
-        if trimmed.starts_with("────") {
-            continue;
-        }
-
-        // Skip post-summary recap lines (FAIL duplicates + "error: test run failed")
-        if past_summary {
-            continue;
-        }
+    fn parse(input: &str) -> ParseResult<TestResult> {
+        static SUMMARY_RE: OnceLock<regex::Regex> = OnceLock::new();
+        let summary_re = SUMMARY_RE.get_or_init(|| {
+            regex::Regex::new(
+                r"test result: \w+\.\s+(\d+) passed;\s+(\d+) failed;\s+(\d+) ignored;(?:\s+\d+ measured;)?\s+\d+ filtered out(?:;\s+finished in ([\d.]+)s)?",
+            )
+            .unwrap()
+        });
 
-        // Parse binary count from Starting line
-        if trimmed.starts_with("Starting") {
-            if let Some(caps) = starting_re.captures(trimmed) {
-                if let Some(m) = caps.get(1) {
-                    binaries = m.as_str().parse().unwrap_or(0);
-                }
+        let mut passed = 0;
+        let mut failed = 0;
+        let mut skipped = 0;
+        let mut duration_secs = 0.0;
+        let mut has_duration = false;
+        let mut suites = 0;
+
+        for caps in summary_re.captures_iter(input) {
+            suites += 1;
+            passed += caps[1].parse::<usize>().unwrap_or(0);
+            failed += caps[2].parse::<usize>().unwrap_or(0);
+            skipped += caps[3].parse::<usize>().unwrap_or(0);
+            if let Some(d) = caps.get(4) {
+                duration_secs += d.as_str().parse::<f64>().unwrap_or(0.0);
+                has_duration = true;
             }
-            continue;
         }
 
-        // Strip PASS lines
-        if trimmed.starts_with("PASS") {
-            if in_failure_block {
-                flush_failure_block(
-                    &mut current_failure_header,
-                    &mut current_failure_body,
-                    &mut failures,
-                );
-                in_failure_block = false;
-            }
-            continue;
-        }
+        let failures = extract_libtest_failures(input);
 
-        // Detect FAIL lines
-        if trimmed.starts_with("FAIL") {
-            // Close previous failure block if any
-            if in_failure_block {
-                flush_failure_block(
-                    &mut current_failure_header,
-                    &mut current_failure_body,
-                    &mut failures,
-                );
+        if suites == 0 {
+            // Compile error or crashed harness: nothing trustworthy to report
+            if failures.is_empty() {
+                return ParseResult::Passthrough(truncate_output(input, 500));
             }
-            current_failure_header = trimmed.to_string();
-            in_failure_block = true;
-            continue;
-        }
-
-        // Cancellation notice
-        if trimmed.starts_with("Cancelling") || trimmed.starts_with("Canceling") {
-            has_cancel_line = true;
-            continue;
+            let result = TestResult {
+                total: failures.len(),
+                passed: 0,
+                failed: failures.len(),
+                skipped: 0,
+                duration_ms: None,
+                failures,
+            };
+            return ParseResult::Degraded(
+                result,
+                vec!["no `test result:` summary line found".to_string()],
+            );
         }
 
-        // Nextest run ID line
-        if trimmed.starts_with("Nextest run ID") {
-            continue;
-        }
+        let result = TestResult {
+            total: passed + failed + skipped,
+            passed,
+            failed,
+            skipped,
+            duration_ms: has_duration.then(|| (duration_secs * 1000.0).round() as u64),
+            failures,
+        };
 
-        // Parse summary
-        if trimmed.starts_with("Summary") {
-            summary_line = trimmed.to_string();
-            if in_failure_block {
-                flush_failure_block(
-                    &mut current_failure_header,
-                    &mut current_failure_body,
-                    &mut failures,
-                );
-                in_failure_block = false;
-            }
-            past_summary = true;
-            continue;
+        if result.failures.len() < failed {
+            let warning = format!(
+                "{} tests failed but only {} failure details were found",
+                failed,
+                result.failures.len()
+            );
+            return ParseResult::Degraded(result, vec![warning]);
         }
 
-        // Collect failure body lines (stdout/stderr sections)
-        if in_failure_block {
-            current_failure_body.push(line.to_string());
-        }
+        ParseResult::Full(result)
     }
+}
 
-    // Close last failure block
-    if in_failure_block {
-        flush_failure_block(
-            &mut current_failure_header,
-            &mut current_failure_body,
-            &mut failures,
-        );
-    }
+/// Parser for `cargo nextest run` human output
+pub struct NextestParser;
 
-    // Parse summary with regex
-    if let Some(caps) = summary_re.captures(&summary_line) {
-        let duration = caps.get(1).map_or("?", |m| m.as_str());
-        let passed: u32 = caps
-            .get(3)
-            .and_then(|m| m.as_str().parse().ok())
-            .unwrap_or(0);
-        let failed: u32 = caps
-            .get(4)
-            .and_then(|m| m.as_str().parse().ok())
-            .unwrap_or(0);
-        let skipped: u32 = caps
-            .get(5)
-            .and_then(|m| m.as_str().parse().ok())
-            .unwrap_or(0);
-
-        let binary_text = if binaries == 1 {
-            "1 binary".to_string()
-        } else if binaries > 1 {
-            format!("{} binaries", binaries)
-        } else {
-            String::new()
-        };
+impl OutputParser for NextestParser {
+    type Output = TestResult;
+
+    fn parse(input: &str) -> ParseResult<TestResult> {
+        static SUMMARY_RE: OnceLock<regex::Regex> = OnceLock::new();
+        let summary_re = SUMMARY_RE.get_or_init(|| {
+            regex::Regex::new(
+                r"Summary \[\s*([\d.]+)s\]\s+(\d+) tests? run:\s+(\d+) passed(?:,\s+(\d+) failed)?(?:,\s+(\d+) skipped)?",
+            )
+            .expect("invalid nextest summary regex")
+        });
 
-        if failed == 0 {
-            // All pass - compact single line
-            let mut parts = vec![format!("{} passed", passed)];
-            if skipped > 0 {
-                parts.push(format!("{} skipped", skipped));
+        let mut failures: Vec<TestFailure> = Vec::new();
+        let mut current: Option<(String, Vec<String>)> = None;
+        let mut summary_line: Option<String> = None;
+
+        let finish = |current: &mut Option<(String, Vec<String>)>,
+                      failures: &mut Vec<TestFailure>| {
+            if let Some((name, lines)) = current.take() {
+                let (file_path, error_message) = summarize_panic_output(&lines);
+                failures.push(TestFailure {
+                    test_name: name,
+                    file_path,
+                    error_message,
+                    stack_trace: None,
+                });
             }
-            let meta = if binary_text.is_empty() {
-                format!("{}s", duration)
-            } else {
-                format!("{}, {}s", binary_text, duration)
-            };
-            return format!("✓ cargo nextest: {} ({})", parts.join(", "), meta);
-        }
+        };
 
-        // With failures - show failure details then summary
-        let mut result = String::new();
+        for line in input.lines() {
+            let trimmed = line.trim();
 
-        for failure in &failures {
-            result.push_str(failure);
-            result.push('\n');
-        }
+            // Everything after the summary is a recap of FAIL lines we already have
+            if summary_line.is_some() {
+                continue;
+            }
 
-        if has_cancel_line {
-            result.push_str("Cancelling due to test failure\n");
+            if trimmed.starts_with("Summary") {
+                finish(&mut current, &mut failures);
+                summary_line = Some(trimmed.to_string());
+            } else if trimmed.starts_with("FAIL [") {
+                finish(&mut current, &mut failures);
+                // FAIL [   0.006s] (2/4) test-proj tests::failing_test
+                let name = trimmed.rsplit(' ').next().unwrap_or(trimmed).to_string();
+                current = Some((name, Vec::new()));
+            } else if trimmed.starts_with("PASS")
+                || trimmed.starts_with("Cancelling")
+                || trimmed.starts_with("Canceling")
+            {
+                finish(&mut current, &mut failures);
+            } else if let Some((name, lines)) = current.as_mut() {
+                if !trimmed.starts_with("stderr ───")
+                    && !trimmed.starts_with("stdout ───")
+                    && !trimmed.starts_with("────")
+                    && !is_libtest_harness_line(trimmed, name)
+                {
+                    lines.push(line.to_string());
+                }
+            }
         }
+        finish(&mut current, &mut failures);
 
-        let mut summary_parts = vec![format!("{} passed", passed)];
-        if failed > 0 {
-            summary_parts.push(format!("{} failed", failed));
-        }
-        if skipped > 0 {
-            summary_parts.push(format!("{} skipped", skipped));
-        }
-        let meta = if binary_text.is_empty() {
-            format!("{}s", duration)
-        } else {
-            format!("{}, {}s", binary_text, duration)
+        let caps = match summary_line.as_deref().and_then(|l| summary_re.captures(l)) {
+            Some(caps) => caps,
+            None => {
+                if failures.is_empty() {
+                    return ParseResult::Passthrough(truncate_output(input, 500));
+                }
+                let result = TestResult {
+                    total: failures.len(),
+                    passed: 0,
+                    failed: failures.len(),
+                    skipped: 0,
+                    duration_ms: None,
+                    failures,
+                };
+                return ParseResult::Degraded(
+                    result,
+                    vec!["no nextest `Summary` line found".to_string()],
+                );
+            }
         };
-        result.push_str(&format!(
-            "cargo nextest: {} ({})",
-            summary_parts.join(", "),
-            meta
-        ));
 
-        return result.trim().to_string();
-    }
+        let count = |i: usize| {
+            caps.get(i)
+                .and_then(|m| m.as_str().parse::<usize>().ok())
+                .unwrap_or(0)
+        };
+        let (passed, failed, skipped) = (count(3), count(4), count(5));
+        let duration_ms = caps[1]
+            .parse::<f64>()
+            .ok()
+            .map(|secs| (secs * 1000.0).round() as u64);
+
+        let result = TestResult {
+            total: passed + failed + skipped,
+            passed,
+            failed,
+            skipped,
+            duration_ms,
+            failures,
+        };
 
-    // Fallback: if summary regex didn't match, show what we have
-    if !failures.is_empty() {
-        let mut result = String::new();
-        for failure in &failures {
-            result.push_str(failure);
-            result.push('\n');
+        if result.failures.len() < failed {
+            let warning = format!(
+                "{} tests failed but only {} failure details were found",
+                failed,
+                result.failures.len()
+            );
+            return ParseResult::Degraded(result, vec![warning]);
         }
-        if !summary_line.is_empty() {
-            result.push_str(&summary_line);
-        }
-        return result.trim().to_string();
-    }
 
-    if !summary_line.is_empty() {
-        return summary_line;
+        ParseResult::Full(result)
     }
-
-    // Empty or unrecognized
-    String::new()
 }
 
-/// Filter cargo build/check output - strip "Compiling"/"Checking" lines, keep errors + summary
-fn filter_cargo_build(output: &str) -> String {
-    let mut errors: Vec<String> = Vec::new();
-    let mut warnings = 0;
-    let mut error_count = 0;
-    let mut compiled = 0;
-    let mut in_error = false;
-    let mut current_error = Vec::new();
+/// Lines libtest prints around a single test, echoed in nextest's captured stdout
+/// (`running 1 test`, `test x ... FAILED`, the `failures:` list, `test result:`).
+fn is_libtest_harness_line(trimmed: &str, test_name: &str) -> bool {
+    trimmed == test_name
+        || trimmed == "failures:"
+        || trimmed.starts_with("test result:")
+        || (trimmed.starts_with("running ") && trimmed.ends_with(" test"))
+        || (trimmed.starts_with("running ") && trimmed.ends_with(" tests"))
+        || (trimmed.starts_with("test ") && trimmed.contains(" ... "))
+}
 
-    for line in output.lines() {
-        if line.trim_start().starts_with("Compiling") || line.trim_start().starts_with("Checking") {
-            compiled += 1;
+/// Split a failed test's captured output into (panic location, message).
+///
+/// The `thread '...' panicked at file:line:col:` line is dropped once its location is
+/// captured; the old single-line form (`panicked at 'msg', file:line`) is kept as message.
+fn summarize_panic_output(lines: &[String]) -> (String, String) {
+    static PANIC_RE: OnceLock<regex::Regex> = OnceLock::new();
+    let panic_re = PANIC_RE
+        .get_or_init(|| regex::Regex::new(r"panicked at ([^:\s']+:\d+(?::\d+)?)(:?)$").unwrap());
+    // rustc < 1.73: `panicked at '<message>', src/lib.rs:10:5`, message may span lines
+    static LEGACY_END_RE: OnceLock<regex::Regex> = OnceLock::new();
+    let legacy_end_re =
+        LEGACY_END_RE.get_or_init(|| regex::Regex::new(r"^(.*)', ([^\s']+:\d+:\d+)$").unwrap());
+    const LEGACY_START: &str = "panicked at '";
+
+    let mut file_path = String::new();
+    let mut message: Vec<&str> = Vec::new();
+    let has_legacy_location = lines.iter().any(|l| legacy_end_re.is_match(l.trim()));
+    let mut in_legacy = false;
+    for line in lines.iter().map(|l| l.trim()) {
+        if line.is_empty() || line.starts_with("note: run with") {
             continue;
         }
-        if line.trim_start().starts_with("Downloading")
-            || line.trim_start().starts_with("Downloaded")
-        {
-            continue;
+        let mut line = line;
+        if let Some(idx) = line.find(LEGACY_START).filter(|_| has_legacy_location) {
+            line = &line[idx + LEGACY_START.len()..];
+            in_legacy = true;
         }
-        if line.trim_start().starts_with("Finished") {
-            continue;
-        }
-
-        // Detect error/warning blocks
-        if line.starts_with("error[") || line.starts_with("error:") {
-            // Skip "error: aborting due to" summary lines
-            if line.contains("aborting due to") || line.contains("could not compile") {
-                continue;
-            }
-            if in_error && !current_error.is_empty() {
-                errors.push(current_error.join("\n"));
-                current_error.clear();
+        if in_legacy {
+            if let Some(caps) = legacy_end_re.captures(line) {
+                if file_path.is_empty() {
+                    file_path = caps[2].to_string();
+                }
+                message.push(caps.get(1).map_or("", |m| m.as_str()));
+                in_legacy = false;
+            } else {
+                message.push(line);
             }
-            error_count += 1;
-            in_error = true;
-            current_error.push(line.to_string());
-        } else if line.starts_with("warning:")
-            && line.contains("generated")
-            && line.contains("warning")
-        {
-            // "warning: `crate` generated N warnings" summary line
             continue;
-        } else if line.starts_with("warning:") || line.starts_with("warning[") {
-            if in_error && !current_error.is_empty() {
-                errors.push(current_error.join("\n"));
-                current_error.clear();
+        }
+        if let Some(caps) = panic_re.captures(line) {
+            if file_path.is_empty() {
+                file_path = caps[1].to_string();
             }
-            warnings += 1;
-            in_error = true;
-            current_error.push(line.to_string());
-        } else if in_error {
-            if line.trim().is_empty() && current_error.len() > 3 {
-                errors.push(current_error.join("\n"));
-                current_error.clear();
-                in_error = false;
-            } else {
-                current_error.push(line.to_string());
+            if !caps[2].is_empty() {
+                continue;
             }
         }
+        message.push(line);
     }
+    (file_path, message.join("\n"))
+}
 
-    if !current_error.is_empty() {
-        errors.push(current_error.join("\n"));
-    }
-
-    if error_count == 0 && warnings == 0 {
-        return format!("✓ cargo build ({} crates compiled)", compiled);
-    }
-
-    let mut result = String::new();
-    result.push_str(&format!(
-        "cargo build: {} errors, {} warnings ({} crates)\n",
-        error_count, warnings, compiled
-    ));
-    result.push_str("═══════════════════════════════════════\n");
+/// Collect `---- name stdout ----` blocks from libtest output, plus any failing test that
+/// only appears in the trailing `failures:` name list.
+fn extract_libtest_failures(output: &str) -> Vec<TestFailure> {
+    let mut failures: Vec<TestFailure> = Vec::new();
+    let mut current: Option<(String, Vec<String>)> = None;
+    let mut in_name_list = false;
+
+    let finish = |current: &mut Option<(String, Vec<String>)>, failures: &mut Vec<TestFailure>| {
+        if let Some((name, lines)) = current.take() {
+            let (file_path, error_message) = summarize_panic_output(&lines);
+            failures.push(TestFailure {
+                test_name: name,
+                file_path,
+                error_message,
+                stack_trace: None,
+            });
+        }
+    };
 
-    for (i, err) in errors.iter().enumerate().take(15) {
-        result.push_str(err);
-        result.push('\n');
-        if i < errors.len() - 1 {
-            result.push('\n');
+    for line in output.lines() {
+        if let Some(rest) = line.strip_prefix("---- ") {
+            finish(&mut current, &mut failures);
+            in_name_list = false;
+            let name = rest
+                .trim_end_matches(" ----")
+                .trim_end_matches(" stdout")
+                .trim_end_matches(" stderr")
+                .trim()
+                .to_string();
+            current = Some((name, Vec::new()));
+        } else if line == "failures:" {
+            finish(&mut current, &mut failures);
+            in_name_list = true;
+        } else if line.starts_with("test result:") {
+            finish(&mut current, &mut failures);
+            in_name_list = false;
+        } else if in_name_list {
+            let name = line.trim();
+            if !name.is_empty() && !failures.iter().any(|f| f.test_name == name) {
+                failures.push(TestFailure {
+                    test_name: name.to_string(),
+                    file_path: String::new(),
+                    error_message: String::new(),
+                    stack_trace: None,
+                });
+            }
+        } else if let Some((_, lines)) = current.as_mut() {
+            lines.push(line.to_string());
         }
     }
+    finish(&mut current, &mut failures);
 
-    if errors.len() > 15 {
-        result.push_str(&format!("\n... +{} more issues\n", errors.len() - 15));
-    }
+    failures
+}
 
-    result.trim().to_string()
+/// Notes rustc adds to every lint; they carry nothing an agent needs
+fn is_noise_note(note: &str) -> bool {
+    note.contains("on by default")
+        || note.contains("for further information visit")
+        || note.contains("the lint level is defined here")
+        || note.starts_with("`#[")
 }
 
-/// Aggregated test results for compact display
-#[derive(Debug, Default, Clone)]
-struct AggregatedTestResult {
-    passed: usize,
-    failed: usize,
-    ignored: usize,
-    measured: usize,
-    filtered_out: usize,
-    suites: usize,
-    duration_secs: f64,
-    has_duration: bool,
+/// Source lines kept from a diagnostic's primary span
+const MAX_SOURCE_LINES: usize = 3;
+
+/// A rustc diagnostic from `cargo --message-format=json` (the `message` of a
+/// `compiler-message` line)
+#[derive(Debug, Deserialize)]
+struct RustcMessage {
+    message: String,
+    code: Option<RustcCode>,
+    level: String,
+    #[serde(default)]
+    spans: Vec<RustcSpan>,
+    #[serde(default)]
+    children: Vec<RustcMessage>,
+    rendered: Option<String>,
 }
 
-impl AggregatedTestResult {
-    /// Parse a test result summary line
-    /// Format: "test result: ok. 15 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.01s"
-    fn parse_line(line: &str) -> Option<Self> {
-        static RE: OnceLock<regex::Regex> = OnceLock::new();
-        let re = RE.get_or_init(|| {
-            regex::Regex::new(
-                r"test result: (\w+)\.\s+(\d+) passed;\s+(\d+) failed;\s+(\d+) ignored;\s+(\d+) measured;\s+(\d+) filtered out(?:;\s+finished in ([\d.]+)s)?"
-            ).unwrap()
-        });
+#[derive(Debug, Deserialize)]
+struct RustcCode {
+    code: String,
+}
 
-        let caps = re.captures(line)?;
-        let status = caps.get(1)?.as_str();
+#[derive(Debug, Deserialize)]
+struct RustcSpan {
+    file_name: String,
+    line_start: usize,
+    line_end: usize,
+    column_start: usize,
+    /// Exclusive
+    column_end: usize,
+    is_primary: bool,
+    /// Source lines the span covers
+    #[serde(default)]
+    text: Vec<RustcSpanLine>,
+    label: Option<String>,
+    suggested_replacement: Option<String>,
+    suggestion_applicability: Option<String>,
+}
 
-        // Only aggregate if status is "ok" (all tests passed)
-        if status != "ok" {
-            return None;
-        }
+#[derive(Debug, Deserialize)]
+struct RustcSpanLine {
+    text: String,
+}
 
-        let passed = caps.get(2)?.as_str().parse().ok()?;
-        let failed = caps.get(3)?.as_str().parse().ok()?;
-        let ignored = caps.get(4)?.as_str().parse().ok()?;
-        let measured = caps.get(5)?.as_str().parse().ok()?;
-        let filtered_out = caps.get(6)?.as_str().parse().ok()?;
+/// One line of cargo output when `--message-format=json` may be in play
+enum CargoLine<'a> {
+    /// A `compiler-message` line
+    Diagnostic(RustcMessage),
+    /// Any other JSON message: artifacts, `build-finished`, a compiler message of an
+    /// unknown shape
+    Json(serde_json::Value),
+    /// Human-readable output: cargo's own status and errors
+    Text(&'a str),
+}
 
-        let (duration_secs, has_duration) = if let Some(duration_match) = caps.get(7) {
-            (duration_match.as_str().parse().unwrap_or(0.0), true)
-        } else {
-            (0.0, false)
+impl CargoLine<'_> {
+    fn classify(line: &str) -> CargoLine<'_> {
+        if !line.starts_with("{\"") {
+            return CargoLine::Text(line);
+        }
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(line) else {
+            return CargoLine::Text(line);
         };
-
-        Some(Self {
-            passed,
-            failed,
-            ignored,
-            measured,
-            filtered_out,
-            suites: 1,
-            duration_secs,
-            has_duration,
-        })
+        if value["reason"] == "compiler-message" {
+            if let Ok(message) = RustcMessage::deserialize(&value["message"]) {
+                return CargoLine::Diagnostic(message);
+            }
+        }
+        CargoLine::Json(value)
     }
 
-    /// Merge another test result into this one
-    fn merge(&mut self, other: &Self) {
-        self.passed += other.passed;
-        self.failed += other.failed;
-        self.ignored += other.ignored;
-        self.measured += other.measured;
-        self.filtered_out += other.filtered_out;
-        self.suites += other.suites;
-        self.duration_secs += other.duration_secs;
-        self.has_duration = self.has_duration && other.has_duration;
+    /// What cargo would have printed without `--message-format=json`
+    fn rendered(&self) -> Option<&str> {
+        match self {
+            CargoLine::Diagnostic(message) => message.rendered.as_deref(),
+            CargoLine::Json(value) => value["message"]["rendered"].as_str(),
+            CargoLine::Text(line) => Some(line),
+        }
     }
+}
 
-    /// Format as compact single line
-    fn format_compact(&self) -> String {
-        let mut parts = vec![format!("{} passed", self.passed)];
-
-        if self.ignored > 0 {
-            parts.push(format!("{} ignored", self.ignored));
-        }
-        if self.filtered_out > 0 {
-            parts.push(format!("{} filtered out", self.filtered_out));
+/// cargo output with JSON messages replaced by their human-readable rendering
+fn rendered_text(output: &str) -> String {
+    let mut text = String::new();
+    for line in output.lines() {
+        if let Some(rendered) = CargoLine::classify(line).rendered() {
+            text.push_str(rendered.trim_end_matches('\n'));
+            text.push('\n');
         }
+    }
+    text
+}
 
-        let counts = parts.join(", ");
+/// `--message-format` is not set by the user (before any `--` for rustc)
+fn wants_json_diagnostics(args: &[String]) -> bool {
+    !args
+        .iter()
+        .take_while(|a| *a != "--")
+        .any(|a| a == "--message-format" || a.starts_with("--message-format="))
+}
 
-        let suite_text = if self.suites == 1 {
-            "1 suite".to_string()
-        } else {
-            format!("{} suites", self.suites)
-        };
+fn is_machine_applicable(help: &RustcMessage) -> bool {
+    help.spans
+        .iter()
+        .any(|s| s.suggestion_applicability.as_deref() == Some("MachineApplicable"))
+}
 
-        if self.has_duration {
-            format!(
-                "✓ cargo test: {} ({}, {:.2}s)",
-                counts, suite_text, self.duration_secs
-            )
-        } else {
-            format!("✓ cargo test: {} ({})", counts, suite_text)
+/// The code a help message proposes: the edited source line when every replacement is
+/// on one line, otherwise the replacement text on one line
+fn suggested_code(help: &RustcMessage) -> Option<String> {
+    let edits: Vec<&RustcSpan> = help
+        .spans
+        .iter()
+        .filter(|s| s.suggested_replacement.is_some())
+        .collect();
+    let first = edits.first()?;
+    let replacement = |s: &RustcSpan| s.suggested_replacement.clone().unwrap_or_default();
+
+    let one_line = edits.iter().all(|s| {
+        s.line_start == first.line_start
+            && s.line_end == first.line_start
+            && s.text.len() == 1
+            && !replacement(s).contains('\n')
+    });
+    let code = if one_line {
+        let mut line: Vec<char> = first.text[0].text.chars().collect();
+        let mut edits = edits.clone();
+        edits.sort_by_key(|s| std::cmp::Reverse(s.column_start));
+        for span in edits {
+            let start = span.column_start.saturating_sub(1).min(line.len());
+            let end = span.column_end.saturating_sub(1).clamp(start, line.len());
+            line.splice(start..end, replacement(span).chars());
         }
-    }
+        line.into_iter().collect::<String>().trim().to_string()
+    } else {
+        edits
+            .iter()
+            .map(|s| replacement(s))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    (!code.is_empty()).then_some(code)
 }
 
-/// Filter cargo test output - show failures + summary only
-fn filter_cargo_test(output: &str) -> String {
-    let mut failures: Vec<String> = Vec::new();
-    let mut summary_lines: Vec<String> = Vec::new();
-    let mut in_failure_section = false;
-    let mut current_failure = Vec::new();
+fn help_text(help: &RustcMessage) -> String {
+    match suggested_code(help) {
+        Some(code) => format!("{}: `{}`", help.message, code),
+        None => help.message.clone(),
+    }
+}
 
-    for line in output.lines() {
-        // Skip compilation lines
-        if line.trim_start().starts_with("Compiling")
-            || line.trim_start().starts_with("Downloading")
-            || line.trim_start().starts_with("Downloaded")
-            || line.trim_start().starts_with("Finished")
-        {
-            continue;
+/// Convert a JSON diagnostic into a lint issue: primary span and its source lines, the
+/// best `help` as the suggestion (machine-applicable first, then any with code), the
+/// other children as notes. None for summaries (`aborting due to ...`, failure notes).
+fn issue_from_json(message: &RustcMessage) -> Option<LintIssue> {
+    let severity = match message.level.as_str() {
+        level if level.starts_with("error") => LintSeverity::Error,
+        "warning" => LintSeverity::Warning,
+        _ => return None,
+    };
+    if message.spans.is_empty()
+        && (message.message.starts_with("aborting due to") || message.message.ends_with("emitted"))
+    {
+        return None;
+    }
+
+    let mut issue = LintIssue {
+        severity,
+        rule_id: message
+            .code
+            .as_ref()
+            .map(|c| c.code.clone())
+            .unwrap_or_default(),
+        message: message.message.clone(),
+        ..LintIssue::default()
+    };
+    if let Some(span) = message.spans.iter().find(|s| s.is_primary) {
+        issue.file_path = span.file_name.clone();
+        issue.line = span.line_start;
+        issue.column = span.column_start;
+        issue.end_line = Some(span.line_end);
+        issue.end_column = Some(span.column_end.saturating_sub(1));
+        issue.source_lines = span
+            .text
+            .iter()
+            .take(MAX_SOURCE_LINES)
+            .map(|l| l.text.trim_end().to_string())
+            .collect();
+        if let Some(label) = span.label.as_deref().filter(|l| !l.is_empty()) {
+            issue.notes.push(label.to_string());
         }
+    }
 
-        // Skip "running N tests" and individual "test ... ok" lines
-        if line.starts_with("running ") || (line.starts_with("test ") && line.ends_with("... ok")) {
-            continue;
+    let children: Vec<&RustcMessage> = message
+        .children
+        .iter()
+        .filter(|c| !is_noise_note(&c.message))
+        .collect();
+    let is_help = |c: &&RustcMessage| c.level == "help";
+    let best = children
+        .iter()
+        .position(|c| is_help(c) && is_machine_applicable(c))
+        .or_else(|| {
+            children
+                .iter()
+                .position(|c| is_help(c) && suggested_code(c).is_some())
+        })
+        .or_else(|| children.iter().position(is_help));
+    for (i, child) in children.iter().enumerate() {
+        if Some(i) == best {
+            issue.suggestion = Some(help_text(child));
+        } else if is_help(child) {
+            issue.notes.push(format!("help: {}", help_text(child)));
+        } else {
+            issue.notes.push(child.message.clone());
         }
+    }
+    Some(issue)
+}
 
-        // Detect failures section
-        if line == "failures:" {
-            in_failure_section = true;
-            continue;
-        }
+/// Warnings whose suggestion `cargo fix` / `cargo clippy --fix` can apply
+fn fixable_warnings(output: &str) -> usize {
+    output
+        .lines()
+        .filter(|line| match CargoLine::classify(line) {
+            CargoLine::Diagnostic(message) => {
+                message.level == "warning" && message.children.iter().any(is_machine_applicable)
+            }
+            _ => false,
+        })
+        .count()
+}
 
-        if in_failure_section {
-            if line.starts_with("test result:") {
-                in_failure_section = false;
-                summary_lines.push(line.to_string());
-            } else if line.starts_with("    ") || line.starts_with("---- ") {
-                current_failure.push(line.to_string());
-            } else if line.trim().is_empty() && !current_failure.is_empty() {
-                failures.push(current_failure.join("\n"));
-                current_failure.clear();
-            } else if !line.trim().is_empty() {
-                current_failure.push(line.to_string());
+/// Parse cargo's diagnostics: compiler messages from `--message-format=json` lines,
+/// the rest (cargo's own errors, or everything when cargo printed no JSON) with the
+/// human-readable parser. A compiler message whose JSON doesn't fit the expected shape
+/// is read from its `rendered` text.
+fn parse_rustc_diagnostics(output: &str) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    let mut text = String::new();
+    for line in output.lines() {
+        match CargoLine::classify(line) {
+            CargoLine::Diagnostic(message) => issues.extend(issue_from_json(&message)),
+            other => {
+                if let Some(rendered) = other.rendered() {
+                    text.push_str(rendered.trim_end_matches('\n'));
+                    text.push('\n');
+                }
             }
         }
+    }
+    issues.extend(parse_rustc_text(&text));
+    issues
+}
 
-        // Capture test result summary
-        if !in_failure_section && line.starts_with("test result:") {
-            summary_lines.push(line.to_string());
+/// Parse rustc/clippy human-readable diagnostics into lint issues.
+/// Header: `error[E0308]: msg` / `warning: msg`; location: `  --> file:line:col`.
+/// The primary `^^^` label and `= note:` lines become notes, the first `help:` (with
+/// the code it proposes, when shown) the suggestion.
+fn parse_rustc_text(output: &str) -> Vec<LintIssue> {
+    static LINT_RE: OnceLock<regex::Regex> = OnceLock::new();
+    static CARET_RE: OnceLock<regex::Regex> = OnceLock::new();
+    static SUGGESTED_RE: OnceLock<regex::Regex> = OnceLock::new();
+    let lint_re = LINT_RE.get_or_init(|| {
+        regex::Regex::new(r"#\[(?:warn|deny|forbid)\(([\w:]+)\)\]|rust-clippy/[^#\s]*#([\w-]+)")
+            .unwrap()
+    });
+    // `   |         ^^^^^ expected `u32`, found `usize``
+    let caret_re =
+        CARET_RE.get_or_init(|| regex::Regex::new(r"^\s*\|[\s\-|]*?(\^+)\s*(.*)$").unwrap());
+    // Code in a help block: `58 +         None` (1.83+) or `42 |   x.try_into()` above `| ++++`
+    let suggested_re =
+        SUGGESTED_RE.get_or_init(|| regex::Regex::new(r"^\s*\d+\s*([+|])\s?(.*)$").unwrap());
+
+    let mut issues: Vec<LintIssue> = Vec::new();
+    let mut current: Option<LintIssue> = None;
+    let mut seen_caret = false;
+    // Inside the first `help:` block: its message and the last code line seen
+    let mut help: Option<(String, Option<String>)> = None;
+
+    fn finish_help(issue: &mut LintIssue, help: &mut Option<(String, Option<String>)>) {
+        if let Some((message, code)) = help.take() {
+            issue.suggestion = Some(match code {
+                Some(code) => format!("{}: `{}`", message, code),
+                None => message,
+            });
         }
     }
 
-    if !current_failure.is_empty() {
-        failures.push(current_failure.join("\n"));
-    }
+    for line in output.lines() {
+        let is_error = line.starts_with("error:") || line.starts_with("error[");
+        let is_warning = line.starts_with("warning:") || line.starts_with("warning[");
 
-    let mut result = String::new();
+        if is_error || is_warning {
+            if let Some(mut issue) = current.take() {
+                finish_help(&mut issue, &mut help);
+                issues.push(issue);
+            }
+            // Summary lines are not diagnostics
+            if line.contains("aborting due to")
+                || line.contains("could not compile")
+                || (line.contains("generated") && line.contains("warning"))
+            {
+                continue;
+            }
 
-    if failures.is_empty() && !summary_lines.is_empty() {
-        // All passed - try to aggregate
-        let mut aggregated: Option<AggregatedTestResult> = None;
-        let mut all_parsed = true;
+            let (head, message) = line.split_once(": ").unwrap_or((line, ""));
+            let code = head
+                .split_once('[')
+                .map(|(_, c)| c.trim_end_matches(']').to_string())
+                .unwrap_or_default();
 
-        for line in &summary_lines {
-            if let Some(parsed) = AggregatedTestResult::parse_line(line) {
-                if let Some(ref mut agg) = aggregated {
-                    agg.merge(&parsed);
+            seen_caret = false;
+            current = Some(LintIssue {
+                severity: if is_error {
+                    LintSeverity::Error
                 } else {
-                    aggregated = Some(parsed);
+                    LintSeverity::Warning
+                },
+                rule_id: code,
+                message: message.to_string(),
+                ..LintIssue::default()
+            });
+        } else if let Some(issue) = current.as_mut() {
+            let trimmed = line.trim_start();
+            if let Some(loc) = trimmed.strip_prefix("--> ") {
+                if issue.file_path.is_empty() {
+                    let mut parts = loc.rsplitn(3, ':');
+                    let col = parts.next().and_then(|c| c.parse().ok());
+                    let ln = parts.next().and_then(|l| l.parse().ok());
+                    match (parts.next(), ln, col) {
+                        (Some(file), Some(ln), Some(col)) => {
+                            issue.file_path = file.to_string();
+                            issue.line = ln;
+                            issue.column = col;
+                        }
+                        _ => issue.file_path = loc.to_string(),
+                    }
                 }
-            } else {
-                all_parsed = false;
-                break;
+                continue;
             }
-        }
 
-        // If all lines parsed successfully and we have at least one suite, return compact format
-        if all_parsed {
-            if let Some(agg) = aggregated {
-                if agg.suites > 0 {
-                    return agg.format_compact();
+            if let Some(message) = line.strip_prefix("help: ") {
+                if issue.suggestion.is_none() && help.is_none() {
+                    help = Some((message.to_string(), None));
+                } else {
+                    finish_help(issue, &mut help);
+                    issue.notes.push(format!("help: {}", message));
+                }
+            } else if let Some(note) = line.strip_prefix("note: ") {
+                finish_help(issue, &mut help);
+                if !is_noise_note(note) {
+                    issue.notes.push(note.to_string());
+                }
+            } else if let Some(note) = trimmed
+                .strip_prefix("= note: ")
+                .or_else(|| trimmed.strip_prefix("= help: "))
+            {
+                if !is_noise_note(note) {
+                    issue.notes.push(note.to_string());
+                }
+            } else if let Some((message, code)) = help.as_mut() {
+                if let Some(caps) = suggested_re.captures(line) {
+                    let text = caps[2].trim();
+                    if &caps[1] == "+" && code.is_none() && !text.is_empty() {
+                        *code = Some(text.to_string());
+                        finish_help(issue, &mut help);
+                    } else if &caps[1] == "|" {
+                        *code = Some(text.to_string());
+                    }
+                } else if trimmed.starts_with('|') && trimmed.contains('+') && code.is_some() {
+                    // `| ++++` marks the line above as the suggested code
+                    finish_help(issue, &mut help);
+                } else if trimmed.is_empty() {
+                    let message = std::mem::take(message);
+                    help = None;
+                    issue.suggestion = Some(message);
+                }
+            } else if !seen_caret && issue.line > 0 {
+                if let Some(caps) = caret_re.captures(line) {
+                    seen_caret = true;
+                    let width = caps[1].len();
+                    issue.end_line = Some(issue.line);
+                    issue.end_column = Some(issue.column + width.saturating_sub(1));
+                    let label = caps[2].trim();
+                    if let Some(fix) = label.strip_prefix("help: ") {
+                        issue.suggestion = Some(fix.to_string());
+                    } else if !label.is_empty() {
+                        issue.notes.push(label.to_string());
+                    }
                 }
             }
-        }
 
-        // Fallback: use original behavior if regex failed
-        for line in &summary_lines {
-            result.push_str(&format!("✓ {}\n", line));
+            if issue.rule_id.is_empty() {
+                if let Some(caps) = lint_re.captures(trimmed) {
+                    issue.rule_id = match (caps.get(1), caps.get(2)) {
+                        (Some(attr), _) => attr.as_str().to_string(),
+                        (None, Some(clippy)) => format!("clippy::{}", clippy.as_str()),
+                        _ => String::new(),
+                    };
+                }
+            }
         }
-        return result.trim().to_string();
     }
-
-    if !failures.is_empty() {
-        result.push_str(&format!("FAILURES ({}):\n", failures.len()));
-        result.push_str("═══════════════════════════════════════\n");
-        for (i, failure) in failures.iter().enumerate().take(10) {
-            result.push_str(&format!("{}. {}\n", i + 1, truncate(failure, 200)));
-        }
-        if failures.len() > 10 {
-            result.push_str(&format!("\n... +{} more failures\n", failures.len() - 10));
-        }
-        result.push('\n');
+    if let Some(mut issue) = current.take() {
+        finish_help(&mut issue, &mut help);
+        issues.push(issue);
     }
 
-    for line in &summary_lines {
-        result.push_str(&format!("{}\n", line));
-    }
-
-    if result.trim().is_empty() {
-        // Fallback: show last meaningful lines
-        let meaningful: Vec<&str> = output
-            .lines()
-            .filter(|l| !l.trim().is_empty() && !l.trim_start().starts_with("Compiling"))
-            .collect();
-        for line in meaningful.iter().rev().take(5).rev() {
-            result.push_str(&format!("{}\n", line));
-        }
-    }
-
-    result.trim().to_string()
+    issues
 }
 
-/// Filter cargo clippy output - group warnings by lint rule
-fn filter_cargo_clippy(output: &str) -> String {
-    let mut by_rule: HashMap<String, Vec<String>> = HashMap::new();
-    let mut error_count = 0;
-    let mut warning_count = 0;
+/// Parser for `cargo build` / `cargo check` output
+pub struct CargoBuildParser;
 
-    // Parse clippy output lines
-    // Format: "warning: description\n  --> file:line:col\n  |\n  | code\n"
-    let mut current_rule = String::new();
+impl OutputParser for CargoBuildParser {
+    type Output = BuildOutput;
 
-    for line in output.lines() {
-        // Skip compilation lines
-        if line.trim_start().starts_with("Compiling")
-            || line.trim_start().starts_with("Checking")
-            || line.trim_start().starts_with("Downloading")
-            || line.trim_start().starts_with("Downloaded")
-            || line.trim_start().starts_with("Finished")
-        {
-            continue;
-        }
+    fn parse(input: &str) -> ParseResult<BuildOutput> {
+        static FINISHED_RE: OnceLock<regex::Regex> = OnceLock::new();
+        let finished_re =
+            FINISHED_RE.get_or_init(|| regex::Regex::new(r"Finished .* in ([\d.]+)(m?s)").unwrap());
 
-        // "warning: unused variable [unused_variables]" or "warning: description [clippy::rule_name]"
-        if (line.starts_with("warning:") || line.starts_with("warning["))
-            || (line.starts_with("error:") || line.starts_with("error["))
-        {
-            // Skip summary lines: "warning: `rtk` (bin) generated 5 warnings"
-            if line.contains("generated") && line.contains("warning") {
-                continue;
-            }
-            // Skip "error: aborting" / "error: could not compile"
-            if line.contains("aborting due to") || line.contains("could not compile") {
-                continue;
-            }
+        let issues = parse_rustc_diagnostics(input);
+        let errors = issues
+            .iter()
+            .filter(|i| i.severity == LintSeverity::Error)
+            .count();
+        let warnings = issues.len() - errors;
 
-            let is_error = line.starts_with("error");
-            if is_error {
-                error_count += 1;
+        let duration_ms = finished_re.captures(input).and_then(|caps| {
+            let value: f64 = caps[1].parse().ok()?;
+            Some(if &caps[2] == "ms" {
+                value as u64
             } else {
-                warning_count += 1;
-            }
+                (value * 1000.0) as u64
+            })
+        });
+        let failed = input.contains("could not compile") || errors > 0;
 
-            // Extract rule name from brackets
-            current_rule = if let Some(bracket_start) = line.rfind('[') {
-                if let Some(bracket_end) = line.rfind(']') {
-                    line[bracket_start + 1..bracket_end].to_string()
-                } else {
-                    line.to_string()
-                }
-            } else {
-                // No bracket: use the message itself as the rule
-                let prefix = if is_error { "error: " } else { "warning: " };
-                line.strip_prefix(prefix).unwrap_or(line).to_string()
-            };
-        } else if line.trim_start().starts_with("--> ") {
-            let location = line.trim_start().trim_start_matches("--> ").to_string();
-            if !current_rule.is_empty() {
-                by_rule
-                    .entry(current_rule.clone())
-                    .or_default()
-                    .push(location);
-            }
+        if duration_ms.is_none() && issues.is_empty() {
+            return ParseResult::Passthrough(truncate_output(input, 500));
         }
-    }
 
-    if error_count == 0 && warning_count == 0 {
-        return "✓ cargo clippy: No issues found".to_string();
+        let result = BuildOutput {
+            success: !failed,
+            duration_ms,
+            warnings,
+            errors,
+            bundles: vec![],
+            routes: vec![],
+            issues,
+        };
+
+        if duration_ms.is_none() && !failed {
+            return ParseResult::Degraded(result, vec!["no `Finished` line found".to_string()]);
+        }
+        ParseResult::Full(result)
     }
+}
 
-    let mut result = String::new();
-    result.push_str(&format!(
-        "cargo clippy: {} errors, {} warnings\n",
-        error_count, warning_count
-    ));
-    result.push_str("═══════════════════════════════════════\n");
+/// Parser for `cargo clippy` output
+pub struct CargoClippyParser;
 
-    // Sort rules by frequency
-    let mut rule_counts: Vec<_> = by_rule.iter().collect();
-    rule_counts.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
+impl OutputParser for CargoClippyParser {
+    type Output = LintResult;
 
-    for (rule, locations) in rule_counts.iter().take(15) {
-        result.push_str(&format!("  {} ({}x)\n", rule, locations.len()));
-        for loc in locations.iter().take(3) {
-            result.push_str(&format!("    {}\n", loc));
-        }
-        if locations.len() > 3 {
-            result.push_str(&format!("    ... +{} more\n", locations.len() - 3));
+    fn parse(input: &str) -> ParseResult<LintResult> {
+        let finished = input.contains("Finished") || input.contains("could not compile");
+        let issues = parse_rustc_diagnostics(input);
+
+        if !finished && issues.is_empty() {
+            return ParseResult::Passthrough(truncate_output(input, 500));
         }
-    }
 
-    if by_rule.len() > 15 {
-        result.push_str(&format!("\n... +{} more rules\n", by_rule.len() - 15));
+        let result = LintResult::from_issues(issues);
+        if !finished {
+            return ParseResult::Degraded(result, vec!["no `Finished` line found".to_string()]);
+        }
+        ParseResult::Full(result)
     }
-
-    result.trim().to_string()
 }
 
 /// Runs an unsupported cargo subcommand by passing it through directly
@@ -949,6 +1393,8 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::envelope::tests::assert_json_snapshot;
+    use crate::parser::{FormatMode, TokenFormatter};
 
     #[test]
     fn test_filter_cargo_build_success() {
@@ -980,7 +1426,7 @@ error: aborting due to 1 previous error
     }
 
     #[test]
-    fn test_filter_cargo_test_all_pass() {
+    fn test_cargo_test_parser_all_pass() {
         let output = r#"   Compiling rtk v0.5.0
     Finished test [unoptimized + debuginfo] target(s) in 2.53s
      Running target/debug/deps/rtk-abc123
@@ -992,18 +1438,24 @@ test utils::tests::test_strip_ansi_simple ... ok
 
 test result: ok. 15 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.01s
 "#;
-        let result = filter_cargo_test(output);
+        let result = CargoTestParser::parse(output);
+        assert_eq!(result.tier(), 1);
+        let data = result.unwrap();
+        assert_eq!((data.passed, data.failed, data.total), (15, 0, 15));
+        assert_eq!(data.duration_ms, Some(10));
+
+        let formatted = data.format(FormatMode::Compact);
         assert!(
-            result.contains("✓ cargo test: 15 passed (1 suite, 0.01s)"),
-            "Expected compact format, got: {}",
-            result
+            formatted.starts_with("PASS (15) FAIL (0)"),
+            "got: {}",
+            formatted
         );
-        assert!(!result.contains("Compiling"));
-        assert!(!result.contains("test utils"));
+        assert!(!formatted.contains("Compiling"));
+        assert!(!formatted.contains("test utils"));
     }
 
     #[test]
-    fn test_filter_cargo_test_failures() {
+    fn test_cargo_test_parser_failures() {
         let output = r#"running 5 tests
 test foo::test_a ... ok
 test foo::test_b ... FAILED
@@ -1019,14 +1471,22 @@ failures:
 
 test result: FAILED. 4 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out
 "#;
-        let result = filter_cargo_test(output);
-        assert!(result.contains("FAILURES"));
-        assert!(result.contains("test_b"));
-        assert!(result.contains("test result:"));
+        let result = CargoTestParser::parse(output);
+        assert_eq!(result.tier(), 1);
+        let data = result.unwrap();
+        assert_eq!((data.passed, data.failed), (4, 1));
+        assert_eq!(data.failures.len(), 1);
+        assert_eq!(data.failures[0].test_name, "foo::test_b");
+        // Old single-line panic format keeps the message inline
+        assert!(data.failures[0].error_message.contains("assert_eq!(1, 2)"));
+
+        let formatted = data.format(FormatMode::Compact);
+        assert!(formatted.contains("FAIL (1)"), "got: {}", formatted);
+        assert!(formatted.contains("1. foo::test_b"), "got: {}", formatted);
     }
 
     #[test]
-    fn test_filter_cargo_test_multi_suite_all_pass() {
+    fn test_cargo_test_parser_multi_suite_all_pass() {
         let output = r#"   Compiling rtk v0.5.0
     Finished test [unoptimized + debuginfo] target(s) in 2.53s
      Running unittests src/lib.rs (target/debug/deps/rtk-abc123)
@@ -1049,17 +1509,21 @@ test result: ok. 25 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; fin
 running 32 tests
 test result: ok. 32 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.45s
 "#;
-        let result = filter_cargo_test(output);
+        let data = CargoTestParser::parse(output).unwrap();
+        assert_eq!((data.passed, data.failed, data.total), (137, 0, 137));
+        assert_eq!(data.duration_ms, Some(1450));
+
+        let formatted = data.format(FormatMode::Compact);
         assert!(
-            result.contains("✓ cargo test: 137 passed (4 suites, 1.45s)"),
-            "Expected aggregated format, got: {}",
-            result
+            formatted.starts_with("PASS (137) FAIL (0)"),
+            "got: {}",
+            formatted
         );
-        assert!(!result.contains("running"));
+        assert!(!formatted.contains("running"));
     }
 
     #[test]
-    fn test_filter_cargo_test_multi_suite_with_failures() {
+    fn test_cargo_test_parser_multi_suite_with_failures() {
         let output = r#"     Running unittests src/lib.rs
 
 running 20 tests
@@ -1082,19 +1546,20 @@ test result: FAILED. 14 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out;
 running 10 tests
 test result: ok. 10 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.02s
 "#;
-        let result = filter_cargo_test(output);
-        // Should NOT aggregate when there are failures
-        assert!(result.contains("FAILURES"), "got: {}", result);
-        assert!(result.contains("test_bad"), "got: {}", result);
-        assert!(result.contains("test result:"), "got: {}", result);
-        // Should show individual summaries
-        assert!(result.contains("20 passed"), "got: {}", result);
-        assert!(result.contains("14 passed"), "got: {}", result);
-        assert!(result.contains("10 passed"), "got: {}", result);
+        let result = CargoTestParser::parse(output);
+        assert_eq!(result.tier(), 1);
+        let data = result.unwrap();
+        // Counts are summed across all suites, failures kept per test
+        assert_eq!((data.passed, data.failed), (44, 1));
+        assert_eq!(data.failures[0].test_name, "foo::test_bad");
+        assert_eq!(
+            data.failures[0].error_message,
+            "thread panicked at 'assertion failed'"
+        );
     }
 
     #[test]
-    fn test_filter_cargo_test_all_suites_zero_tests() {
+    fn test_cargo_test_parser_all_suites_zero_tests() {
         let output = r#"     Running unittests src/empty1.rs
 
 running 0 tests
@@ -1113,16 +1578,14 @@ running 0 tests
 
 test result: ok. 0 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
 "#;
-        let result = filter_cargo_test(output);
-        assert!(
-            result.contains("✓ cargo test: 0 passed (3 suites, 0.00s)"),
-            "Expected compact format for zero tests, got: {}",
-            result
-        );
+        let result = CargoTestParser::parse(output);
+        assert_eq!(result.tier(), 1);
+        let data = result.unwrap();
+        assert_eq!((data.total, data.passed, data.failed), (0, 0, 0));
     }
 
     #[test]
-    fn test_filter_cargo_test_with_ignored_and_filtered() {
+    fn test_cargo_test_parser_with_ignored_and_filtered() {
         let output = r#"     Running unittests src/lib.rs
 
 running 50 tests
@@ -1133,42 +1596,42 @@ test result: ok. 45 passed; 0 failed; 3 ignored; 0 measured; 2 filtered out; fin
 running 20 tests
 test result: ok. 18 passed; 0 failed; 2 ignored; 0 measured; 0 filtered out; finished in 0.20s
 "#;
-        let result = filter_cargo_test(output);
+        let data = CargoTestParser::parse(output).unwrap();
+        assert_eq!((data.passed, data.skipped, data.total), (63, 5, 68));
+
+        let formatted = data.format(FormatMode::Verbose);
         assert!(
-            result.contains("✓ cargo test: 63 passed, 5 ignored, 2 filtered out (2 suites, 0.70s)"),
-            "Expected compact format with ignored and filtered, got: {}",
-            result
+            formatted.contains("63 passed, 0 failed, 5 skipped (total: 68)"),
+            "got: {}",
+            formatted
         );
     }
 
     #[test]
-    fn test_filter_cargo_test_single_suite_compact() {
+    fn test_cargo_test_parser_ultra() {
         let output = r#"     Running unittests src/main.rs
 
 running 15 tests
 test result: ok. 15 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.01s
 "#;
-        let result = filter_cargo_test(output);
-        assert!(
-            result.contains("✓ cargo test: 15 passed (1 suite, 0.01s)"),
-            "Expected singular 'suite', got: {}",
-            result
-        );
+        let data = CargoTestParser::parse(output).unwrap();
+        assert_eq!(data.format(FormatMode::Ultra), "✓15 ✗0 ⊘0 (10ms)");
     }
 
     #[test]
-    fn test_filter_cargo_test_regex_fallback() {
+    fn test_cargo_test_parser_malformed_summary_passthrough() {
         let output = r#"     Running unittests src/main.rs
 
 running 15 tests
 test result: MALFORMED LINE WITHOUT PROPER FORMAT
 "#;
-        let result = filter_cargo_test(output);
-        // Should fallback to original behavior (show line with checkmark)
+        let result = CargoTestParser::parse(output);
+        assert_eq!(result.tier(), 3);
+        let formatted = format_parsed("cargo test", result, 0);
         assert!(
-            result.contains("✓ test result: MALFORMED"),
-            "Expected fallback format, got: {}",
-            result
+            formatted.contains("test result: MALFORMED"),
+            "Expected raw passthrough, got: {}",
+            formatted
         );
     }
 
@@ -1374,7 +1837,7 @@ error: aborting due to 2 previous errors
     }
 
     #[test]
-    fn test_filter_cargo_nextest_all_pass() {
+    fn test_nextest_parser_all_pass() {
         let output = r#"   Compiling rtk v0.15.2
     Finished `test` profile [unoptimized + debuginfo] target(s) in 0.04s
 ────────────────────────────
@@ -1385,16 +1848,19 @@ error: aborting due to 2 previous errors
 ────────────────────────────
      Summary [   0.192s] 301 tests run: 301 passed, 0 skipped
 "#;
-        let result = filter_cargo_nextest(output);
+        let result = NextestParser::parse(output);
+        assert_eq!(result.tier(), 1);
+        let data = result.unwrap();
+        assert_eq!((data.passed, data.failed, data.skipped), (301, 0, 0));
+        assert_eq!(data.duration_ms, Some(192));
         assert_eq!(
-            result, "✓ cargo nextest: 301 passed (1 binary, 0.192s)",
-            "got: {}",
-            result
+            data.format(FormatMode::Compact),
+            "PASS (301) FAIL (0)\n\nTime: 192ms"
         );
     }
 
     #[test]
-    fn test_filter_cargo_nextest_with_failures() {
+    fn test_nextest_parser_with_failures() {
         let output = r#"    Starting 4 tests across 1 binary (1 test skipped)
         PASS [   0.006s] (1/4) test-proj tests::passing_test
         FAIL [   0.006s] (2/4) test-proj tests::failing_test
@@ -1421,65 +1887,49 @@ error: aborting due to 2 previous errors
         FAIL [   0.006s] (4/4) test-proj tests::another_failing
 error: test run failed
 "#;
-        let result = filter_cargo_nextest(output);
-        assert!(
-            result.contains("tests::failing_test"),
-            "should contain first failure: {}",
-            result
-        );
-        assert!(
-            result.contains("tests::another_failing"),
-            "should contain second failure: {}",
-            result
-        );
-        assert!(
-            result.contains("panicked"),
-            "should contain stderr detail: {}",
-            result
-        );
-        assert!(
-            result.contains("2 passed, 2 failed, 1 skipped"),
-            "should contain summary: {}",
-            result
+        let result = NextestParser::parse(output);
+        assert_eq!(result.tier(), 1);
+        let data = result.unwrap();
+        assert_eq!((data.passed, data.failed, data.skipped), (2, 2, 1));
+        // Post-summary FAIL recaps must not create duplicate entries
+        assert_eq!(data.failures.len(), 2);
+        assert_eq!(data.failures[0].test_name, "tests::failing_test");
+        assert_eq!(data.failures[0].file_path, "src/lib.rs:15:9");
+        assert_eq!(
+            data.failures[0].error_message,
+            "assertion `left == right` failed\nleft: 1\nright: 2"
         );
+        assert_eq!(data.failures[1].test_name, "tests::another_failing");
+        assert_eq!(data.failures[1].error_message, "something went wrong");
+
+        let formatted = data.format(FormatMode::Compact);
         assert!(
-            !result.contains("PASS"),
-            "should not contain PASS lines: {}",
-            result
-        );
-        // Post-summary FAIL recaps must not create duplicate FAIL header entries
-        // (test names may appear in both header and stderr body naturally)
-        assert_eq!(
-            result.matches("FAIL [").count(),
-            2,
-            "should have exactly 2 FAIL headers (no post-summary duplicates): {}",
-            result
+            formatted.contains("1. tests::failing_test (src/lib.rs:15:9)"),
+            "got: {}",
+            formatted
         );
+        assert!(!formatted.contains("PASS ["), "got: {}", formatted);
         assert!(
-            !result.contains("error: test run failed"),
-            "should not contain post-summary error line: {}",
-            result
+            !formatted.contains("error: test run failed"),
+            "got: {}",
+            formatted
         );
     }
 
     #[test]
-    fn test_filter_cargo_nextest_with_skipped() {
+    fn test_nextest_parser_with_skipped() {
         let output = r#"    Starting 50 tests across 2 binaries (3 tests skipped)
         PASS [   0.010s] (1/50) rtk::bin/rtk test_one
         PASS [   0.010s] (50/50) rtk::bin/rtk test_last
 ────────────────────────────
      Summary [   0.500s] 50 tests run: 50 passed, 3 skipped
 "#;
-        let result = filter_cargo_nextest(output);
-        assert_eq!(
-            result, "✓ cargo nextest: 50 passed, 3 skipped (2 binaries, 0.500s)",
-            "got: {}",
-            result
-        );
+        let data = NextestParser::parse(output).unwrap();
+        assert_eq!((data.passed, data.skipped, data.total), (50, 3, 53));
     }
 
     #[test]
-    fn test_filter_cargo_nextest_single_failure_detail() {
+    fn test_nextest_parser_single_failure_detail() {
         let output = r#"    Starting 2 tests across 1 binary
         PASS [   0.005s] (1/2) proj tests::good
         FAIL [   0.005s] (2/2) proj tests::bad
@@ -1494,43 +1944,37 @@ error: test run failed
         FAIL [   0.005s] (2/2) proj tests::bad
 error: test run failed
 "#;
-        let result = filter_cargo_nextest(output);
+        let data = NextestParser::parse(output).unwrap();
+        assert_eq!(data.failures.len(), 1);
+        assert_eq!(data.failures[0].error_message, "assertion failed: false");
+
+        let formatted = data.format(FormatMode::Compact);
         assert!(
-            result.contains("assertion failed: false"),
-            "should show panic message: {}",
-            result
+            formatted.contains("assertion failed: false"),
+            "got: {}",
+            formatted
         );
         assert!(
-            result.contains("1 passed, 1 failed"),
-            "should show summary: {}",
-            result
-        );
-        // Post-summary recap must not duplicate FAIL headers
-        assert_eq!(
-            result.matches("FAIL [").count(),
-            1,
-            "should have exactly 1 FAIL header (no post-summary duplicate): {}",
-            result
+            formatted.contains("PASS (1) FAIL (1)"),
+            "got: {}",
+            formatted
         );
     }
 
     #[test]
-    fn test_filter_cargo_nextest_multiple_binaries() {
+    fn test_nextest_parser_multiple_binaries() {
         let output = r#"    Starting 100 tests across 5 binaries
         PASS [   0.010s] (100/100) test_last
 ────────────────────────────
      Summary [   1.234s] 100 tests run: 100 passed, 0 skipped
 "#;
-        let result = filter_cargo_nextest(output);
-        assert_eq!(
-            result, "✓ cargo nextest: 100 passed (5 binaries, 1.234s)",
-            "got: {}",
-            result
-        );
+        let data = NextestParser::parse(output).unwrap();
+        assert_eq!(data.passed, 100);
+        assert_eq!(data.duration_ms, Some(1234));
     }
 
     #[test]
-    fn test_filter_cargo_nextest_compilation_stripped() {
+    fn test_nextest_parser_compilation_stripped() {
         let output = r#"   Compiling serde v1.0.200
    Compiling rtk v0.15.2
    Downloading crates ...
@@ -1541,37 +1985,19 @@ error: test run failed
 ────────────────────────────
      Summary [   0.050s] 10 tests run: 10 passed, 0 skipped
 "#;
-        let result = filter_cargo_nextest(output);
-        assert!(
-            !result.contains("Compiling"),
-            "should strip Compiling: {}",
-            result
-        );
-        assert!(
-            !result.contains("Downloading"),
-            "should strip Downloading: {}",
-            result
-        );
-        assert!(
-            !result.contains("Finished"),
-            "should strip Finished: {}",
-            result
-        );
+        let formatted = format_parsed("cargo nextest", NextestParser::parse(output), 0);
+        assert!(!formatted.contains("Compiling"), "got: {}", formatted);
+        assert!(!formatted.contains("Downloading"), "got: {}", formatted);
+        assert!(!formatted.contains("Finished"), "got: {}", formatted);
         assert!(
-            result.contains("✓ cargo nextest: 10 passed"),
+            formatted.starts_with("PASS (10) FAIL (0)"),
             "got: {}",
-            result
+            formatted
         );
     }
 
     #[test]
-    fn test_filter_cargo_nextest_empty() {
-        let result = filter_cargo_nextest("");
-        assert!(result.is_empty(), "got: {}", result);
-    }
-
-    #[test]
-    fn test_filter_cargo_nextest_cancellation_notice() {
+    fn test_nextest_parser_cancellation_notice() {
         let output = r#"    Starting 3 tests across 1 binary
         FAIL [   0.005s] (1/3) proj tests::bad
 
@@ -1585,38 +2011,281 @@ error: test run failed
         FAIL [   0.005s] (1/3) proj tests::bad
 error: test run failed
 "#;
-        let result = filter_cargo_nextest(output);
-        assert!(
-            result.contains("Cancelling due to test failure"),
-            "should include cancel notice: {}",
-            result
-        );
-        assert!(
-            result.contains("1 failed"),
-            "should show failure count: {}",
-            result
-        );
-        // Post-summary recap must not duplicate FAIL headers
-        assert_eq!(
-            result.matches("FAIL [").count(),
-            1,
-            "should have exactly 1 FAIL header (no post-summary duplicate): {}",
-            result
-        );
+        let data = NextestParser::parse(output).unwrap();
+        assert_eq!(data.failed, 1);
+        assert_eq!(data.failures.len(), 1);
+        // The cancel notice ends the failure block instead of leaking into its message
+        assert_eq!(data.failures[0].error_message, "thread panicked at 'oops'");
     }
 
     #[test]
-    fn test_filter_cargo_nextest_summary_regex_fallback() {
+    fn test_nextest_parser_malformed_summary() {
         let output = r#"    Starting 5 tests across 1 binary
         PASS [   0.005s] (5/5) test_last
 ────────────────────────────
      Summary MALFORMED LINE
 "#;
-        let result = filter_cargo_nextest(output);
+        let result = NextestParser::parse(output);
+        assert_eq!(result.tier(), 3);
+        let formatted = format_parsed("cargo nextest", result, 0);
         assert!(
-            result.contains("Summary MALFORMED"),
-            "should fall back to raw summary: {}",
-            result
+            formatted.contains("Summary MALFORMED"),
+            "got: {}",
+            formatted
         );
     }
+
+    #[test]
+    fn test_nextest_parser_failures_without_summary_degrade() {
+        let output = "        FAIL [   0.005s] (1/3) proj tests::bad\n\n  stderr ───\n\n    boom\n";
+        let result = NextestParser::parse(output);
+        assert_eq!(result.tier(), 2);
+        assert_eq!(result.unwrap().failures[0].error_message, "boom");
+    }
+
+    #[test]
+    fn test_nextest_parser_empty_passthrough() {
+        assert_eq!(NextestParser::parse("").tier(), 3);
+    }
+
+    #[test]
+    fn test_json_snapshot_cargo_test() {
+        let actual = render_json::<CargoTestParser>(
+            "cargo test",
+            include_str!("../tests/fixtures/json_output/cargo_test.input.txt"),
+            101,
+        );
+        assert_json_snapshot(
+            &actual,
+            include_str!("../tests/fixtures/json_output/cargo_test.expected.json"),
+        );
+    }
+
+    #[test]
+    fn test_json_snapshot_cargo_build() {
+        let actual = render_json::<CargoBuildParser>(
+            "cargo build",
+            include_str!("../tests/fixtures/json_output/cargo_build.input.txt"),
+            101,
+        );
+        assert_json_snapshot(
+            &actual,
+            include_str!("../tests/fixtures/json_output/cargo_build.expected.json"),
+        );
+    }
+
+    #[test]
+    fn test_json_snapshot_cargo_clippy() {
+        let actual = render_json::<CargoClippyParser>(
+            "cargo clippy",
+            include_str!("../tests/fixtures/json_output/cargo_clippy.input.txt"),
+            0,
+        );
+        assert_json_snapshot(
+            &actual,
+            include_str!("../tests/fixtures/json_output/cargo_clippy.expected.json"),
+        );
+    }
+
+    #[test]
+    fn test_summarize_panic_output_legacy_format() {
+        let lines: Vec<String> = [
+            "thread 'tests::bad' panicked at 'assertion failed: `(left == right)`",
+            "  left: `2`,",
+            " right: `1`', src/store.rs:88:9",
+            "note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+        let (file_path, message) = summarize_panic_output(&lines);
+        assert_eq!(file_path, "src/store.rs:88:9");
+        assert_eq!(
+            message,
+            "assertion failed: `(left == right)`\nleft: `2`,\nright: `1`"
+        );
+    }
+
+    /// Feed a captured output through `CargoStream` as if it arrived on stdout.
+    fn stream_fixture(kind: CargoOutputKind, raw: &str) -> (KeptOutput, Vec<String>) {
+        let mut stream = CargoStream::new(kind);
+        let mut kept = KeptOutput::default();
+        let emitted = raw
+            .lines()
+            .filter_map(|line| stream.feed(line, StreamSource::Stdout, &mut kept))
+            .collect();
+        (kept, emitted)
+    }
+
+    #[test]
+    fn test_cargo_stream_libtest_same_summary_as_buffered() {
+        for version in ["1.70", "1.83"] {
+            let path = format!(
+                "{}/tests/fixtures/cargo_test/{}/failures.txt",
+                env!("CARGO_MANIFEST_DIR"),
+                version
+            );
+            let raw = std::fs::read_to_string(path).unwrap();
+            let (kept, emitted) = stream_fixture(CargoOutputKind::Libtest, &raw);
+            assert_eq!(
+                format_parsed("cargo test", CargoTestParser::parse(&kept.stdout), 0),
+                format_parsed("cargo test", CargoTestParser::parse(&raw), 0)
+            );
+            assert!(kept.stdout.len() < raw.len() / 2);
+            assert_eq!(
+                emitted,
+                vec![
+                    "test store::tests::removes_item ... FAILED",
+                    "test report::tests::renders_totals ... FAILED",
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_cargo_stream_nextest_same_summary_as_buffered() {
+        let path = format!(
+            "{}/tests/fixtures/cargo_nextest/0.9.72/failures.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let raw = std::fs::read_to_string(path).unwrap();
+        let (kept, emitted) = stream_fixture(CargoOutputKind::Nextest, &raw);
+        assert_eq!(
+            format_parsed("cargo nextest", NextestParser::parse(&kept.stdout), 0),
+            format_parsed("cargo nextest", NextestParser::parse(&raw), 0)
+        );
+        assert!(!kept.stdout.contains("case_03"));
+        assert_eq!(
+            emitted,
+            vec!["FAIL [   0.006s] inventory store::tests::removes_item"]
+        );
+    }
+
+    #[test]
+    fn test_cargo_stream_build_emits_errors_with_location() {
+        let path = format!(
+            "{}/tests/fixtures/cargo_build/1.75/errors.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let raw = std::fs::read_to_string(path).unwrap();
+        let (kept, emitted) = stream_fixture(CargoOutputKind::Build, &raw);
+        assert_eq!(filter_cargo_build(&kept.stdout), filter_cargo_build(&raw));
+        assert_eq!(
+            emitted,
+            vec![
+                "error[E0308]: mismatched types",
+                "  --> src/store.rs:42:9",
+                "error[E0425]: cannot find value `limit` in this scope",
+                "  --> src/main.rs:17:28",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cargo_stream_json_emits_errors_and_drops_artifacts() {
+        let path = format!(
+            "{}/tests/fixtures/cargo_build/1.95/json_errors.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let raw = std::fs::read_to_string(path).unwrap();
+        let (kept, emitted) = stream_fixture(CargoOutputKind::Build, &raw);
+        assert_eq!(filter_cargo_build(&kept.stdout), filter_cargo_build(&raw));
+        assert!(!kept.stdout.contains("build-finished"));
+        assert_eq!(
+            emitted,
+            vec![
+                "error[E0308]: mismatched types\n  --> src/lib.rs:9:9",
+                "error[E0507]: cannot move out of index of `Vec<String>`\n  --> src/lib.rs:13:21",
+            ]
+        );
+    }
+
+    fn json_line(message: serde_json::Value) -> String {
+        serde_json::json!({ "reason": "compiler-message", "message": message }).to_string()
+    }
+
+    #[test]
+    fn test_parse_json_diagnostic_suggestion_and_source() {
+        let line = json_line(serde_json::json!({
+            "message": "missing lifetime specifier",
+            "code": { "code": "E0106" },
+            "level": "error",
+            "spans": [{
+                "file_name": "src/store.rs", "line_start": 12, "line_end": 12,
+                "column_start": 44, "column_end": 45, "is_primary": true,
+                "text": [{ "text": "    pub fn first_name(&self, key: &str) -> &str {" }],
+                "label": "expected named lifetime parameter",
+                "suggested_replacement": null, "suggestion_applicability": null
+            }],
+            "children": [
+                { "message": "for further information visit https://doc.rust-lang.org",
+                  "level": "help", "spans": [], "children": [] },
+                { "message": "consider introducing a named lifetime parameter",
+                  "level": "help", "children": [],
+                  "spans": [
+                    { "file_name": "src/store.rs", "line_start": 12, "line_end": 12,
+                      "column_start": 22, "column_end": 22, "is_primary": true,
+                      "text": [{ "text": "    pub fn first_name(&self, key: &str) -> &str {" }],
+                      "suggested_replacement": "<'a>",
+                      "suggestion_applicability": "MaybeIncorrect" },
+                    { "file_name": "src/store.rs", "line_start": 12, "line_end": 12,
+                      "column_start": 44, "column_end": 45, "is_primary": true,
+                      "text": [{ "text": "    pub fn first_name(&self, key: &str) -> &str {" }],
+                      "suggested_replacement": "&'a ",
+                      "suggestion_applicability": "MaybeIncorrect" }
+                  ] }
+            ],
+            "rendered": "error[E0106]: missing lifetime specifier\n"
+        }));
+
+        let issues = parse_rustc_diagnostics(&line);
+        assert_eq!(issues.len(), 1);
+        let issue = &issues[0];
+        assert_eq!(issue.rule_id, "E0106");
+        assert_eq!((issue.line, issue.column), (12, 44));
+        assert_eq!((issue.end_line, issue.end_column), (Some(12), Some(44)));
+        assert_eq!(
+            issue.source_lines,
+            vec!["    pub fn first_name(&self, key: &str) -> &str {"]
+        );
+        assert_eq!(
+            issue.suggestion.as_deref(),
+            Some(
+                "consider introducing a named lifetime parameter: \
+                 `pub fn first_name<'a>(&self, key: &str) -> &'a str {`"
+            )
+        );
+        assert_eq!(issue.notes, vec!["expected named lifetime parameter"]);
+        // Not a warning, so nothing for `cargo fix`
+        assert_eq!(fixable_warnings(&line), 0);
+    }
+
+    #[test]
+    fn test_parse_json_falls_back_to_rendered_and_text() {
+        // A compiler message of an unexpected shape is read from its rendering, and
+        // cargo's own text errors are still parsed next to JSON lines
+        let odd = json_line(serde_json::json!({
+            "level": "error",
+            "rendered": "error[E0425]: cannot find value `x` in this scope\n --> src/main.rs:3:5\n"
+        }));
+        let output = format!(
+            "{}\n{}\nerror: failed to run custom build command for `openssl-sys v0.9.102`\n",
+            odd, r#"{"reason":"build-finished","success":false}"#
+        );
+        let issues = parse_rustc_diagnostics(&output);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].rule_id, "E0425");
+        assert_eq!(issues[0].file_path, "src/main.rs");
+        assert!(issues[1].message.contains("custom build command"));
+        assert!(!rendered_text(&output).contains("build-finished"));
+    }
+
+    #[test]
+    fn test_wants_json_diagnostics() {
+        let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+        assert!(wants_json_diagnostics(&args("--release")));
+        assert!(wants_json_diagnostics(&args("-- --message-format=x")));
+        assert!(!wants_json_diagnostics(&args("--message-format=short")));
+        assert!(!wants_json_diagnostics(&args("--message-format json")));
+    }
 }
@@ -0,0 +1,295 @@
+//! Failed-step extraction from GitHub Actions logs, for `rtk gh run view --log-failed`.
+//!
+//! gh prints every log line as `job<TAB>step<TAB>timestamp text`. The prefixes are
+//! stripped, each step is split at its `##[group]Run <command>` headers, and only the
+//! parts that logged an `##[error]` are kept. Their output goes through the filter rtk
+//! would use for the same command locally (cargo, pytest, vitest, go test, tsc), picked
+//! from the command or, failing that, from the output itself; anything else keeps
+//! its last lines.
+use crate::cargo_cmd::{filter_cargo_build, filter_cargo_clippy, CargoTestParser, NextestParser};
+use crate::go_cmd::GoTestParser;
+use crate::parser::{format_parsed, OutputParser, ParseResult, TestResult};
+use crate::pytest_cmd::PytestParser;
+use crate::tsc_cmd::filter_tsc_output;
+use crate::vitest_cmd::VitestParser;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref TIMESTAMP: Regex =
+        Regex::new(r"^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d+)?Z ?").unwrap();
+    static ref TSC_ERROR: Regex = Regex::new(r"error TS\d+:").unwrap();
+}
+
+/// Output lines kept from a failing step no filter recognises
+const TAIL_LINES: usize = 30;
+
+/// One part of a job's log: a step, or a `Run` section of it
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Section {
+    pub job: String,
+    pub step: String,
+    /// The command from `##[group]Run <command>`
+    pub command: Option<String>,
+    /// Output lines, group contents left out
+    pub output: Vec<String>,
+    /// `##[error]` messages
+    pub errors: Vec<String>,
+}
+
+/// Split a `--log-failed` log into sections: per job and step, and per `Run` group
+pub fn parse(raw: &str) -> Vec<Section> {
+    let raw = crate::utils::strip_ansi(raw);
+    let mut sections: Vec<Section> = Vec::new();
+    let mut in_group = false;
+
+    for line in raw.lines() {
+        let (job, step, text) = match line.splitn(3, '\t').collect::<Vec<_>>()[..] {
+            [job, step, text] => (job, step, text),
+            _ => ("", "", line),
+        };
+        let text = text.trim_start_matches('\u{feff}');
+        let text = TIMESTAMP.replace(text, "");
+        let text = text.trim_start_matches('\u{feff}');
+
+        let run = text
+            .strip_prefix("##[group]Run ")
+            .map(|cmd| cmd.trim().to_string());
+        let same_step = sections
+            .last()
+            .is_some_and(|s| s.job == job && s.step == step);
+        if !same_step || run.is_some() {
+            in_group = false;
+            sections.push(Section {
+                job: job.to_string(),
+                step: step.to_string(),
+                command: run,
+                ..Section::default()
+            });
+        }
+        let Some(section) = sections.last_mut() else {
+            continue;
+        };
+
+        if text.starts_with("##[group]") {
+            in_group = true;
+        } else if text.starts_with("##[endgroup]") {
+            in_group = false;
+        } else if let Some(error) = text.strip_prefix("##[error]") {
+            section.errors.push(error.trim().to_string());
+        } else if text.starts_with("##[") || in_group {
+            // ##[warning], ##[debug], ##[notice] and group contents
+        } else {
+            section.output.push(text.to_string());
+        }
+    }
+    sections
+}
+
+/// The command a failing section ran, as far as its filter is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tool {
+    CargoTest,
+    CargoNextest,
+    CargoBuild,
+    CargoClippy,
+    Pytest,
+    Vitest,
+    GoTest,
+    Tsc,
+}
+
+impl Tool {
+    /// From the `Run` command when it names the tool, else from what the output looks like
+    fn detect(command: Option<&str>, output: &str) -> Option<Tool> {
+        if let Some(command) = command {
+            let words: Vec<&str> = command.split_whitespace().collect();
+            let has = |a: &str, b: &str| words.windows(2).any(|w| w[0] == a && w[1] == b);
+            let tool = if words.contains(&"nextest") {
+                Some(Tool::CargoNextest)
+            } else if has("cargo", "test") {
+                Some(Tool::CargoTest)
+            } else if has("cargo", "clippy") {
+                Some(Tool::CargoClippy)
+            } else if has("cargo", "build") || has("cargo", "check") {
+                Some(Tool::CargoBuild)
+            } else if has("go", "test") {
+                Some(Tool::GoTest)
+            } else if command.contains("pytest") {
+                Some(Tool::Pytest)
+            } else if command.contains("vitest") {
+                Some(Tool::Vitest)
+            } else if words.contains(&"tsc") {
+                Some(Tool::Tsc)
+            } else {
+                None
+            };
+            if tool.is_some() {
+                return tool;
+            }
+        }
+
+        if output.contains("test result: ") {
+            Some(Tool::CargoTest)
+        } else if output.contains("test session starts") || output.contains("short test summary") {
+            Some(Tool::Pytest)
+        } else if output.contains("\"numTotalTests\"") {
+            Some(Tool::Vitest)
+        } else if output.contains("{\"Time\":") && output.contains("\"Action\":") {
+            Some(Tool::GoTest)
+        } else if TSC_ERROR.is_match(output) {
+            Some(Tool::Tsc)
+        } else if output.contains("error: could not compile") {
+            Some(Tool::CargoBuild)
+        } else {
+            None
+        }
+    }
+
+    /// The local rtk filter for this tool; `None` when its parser could not read the output
+    fn filter(self, output: &str, verbose: u8) -> Option<String> {
+        let parsed = |name: &str, result: ParseResult<TestResult>| match result {
+            ParseResult::Passthrough(_) => None,
+            result => Some(format_parsed(name, result, verbose)),
+        };
+        match self {
+            Tool::CargoTest => parsed("cargo test", CargoTestParser::parse(output)),
+            Tool::CargoNextest => parsed("cargo nextest", NextestParser::parse(output)),
+            Tool::Pytest => parsed("pytest", PytestParser::parse(output)),
+            Tool::Vitest => parsed("vitest", VitestParser::parse(output)),
+            Tool::GoTest => parsed("go test", GoTestParser::parse(output)),
+            Tool::CargoBuild => Some(filter_cargo_build(output)),
+            Tool::CargoClippy => Some(filter_cargo_clippy(output)),
+            Tool::Tsc => Some(filter_tsc_output(output)),
+        }
+    }
+}
+
+/// The failing sections, each with its output filtered and its errors
+pub fn render(sections: &[Section], verbose: u8) -> String {
+    let mut failing: Vec<&Section> = sections.iter().filter(|s| !s.errors.is_empty()).collect();
+    // A failed job whose error was not marked: its last section is where it stopped
+    if failing.is_empty() {
+        failing.extend(sections.last());
+    }
+    if failing.is_empty() {
+        return "No failed steps in log".to_string();
+    }
+
+    let mut out = Vec::new();
+    for section in failing {
+        let mut title = format!("❌ {}", section.job);
+        if !section.step.is_empty() && section.step != "UNKNOWN STEP" {
+            title.push_str(&format!(" › {}", section.step));
+        }
+        if let Some(command) = &section.command {
+            title.push_str(&format!(": {}", command));
+        }
+        out.push(title);
+
+        let output = section.output.join("\n");
+        let filtered = Tool::detect(section.command.as_deref(), &output)
+            .and_then(|tool| tool.filter(&output, verbose))
+            .unwrap_or_else(|| tail(&section.output));
+        if !filtered.trim().is_empty() {
+            out.push(filtered.trim_end().to_string());
+        }
+        out.extend(section.errors.iter().map(|e| format!("##[error] {}", e)));
+        out.push(String::new());
+    }
+    out.join("\n").trim_end().to_string()
+}
+
+/// The last [`TAIL_LINES`] non-blank output lines, where a failure usually shows
+fn tail(lines: &[String]) -> String {
+    let lines: Vec<&String> = lines.iter().filter(|l| !l.trim().is_empty()).collect();
+    let skip = lines.len().saturating_sub(TAIL_LINES);
+    let mut out = Vec::new();
+    if skip > 0 {
+        out.push(format!("... {} lines above", skip));
+    }
+    out.extend(lines[skip..].iter().map(|l| l.to_string()));
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_strips_prefixes_and_groups() {
+        let raw = "\
+build\tRun tests\t2024-06-03T10:15:01.1370000Z \u{feff}##[group]Run cargo test --all
+build\tRun tests\t2024-06-03T10:15:01.2740000Z cargo test --all
+build\tRun tests\t2024-06-03T10:15:01.4110000Z ##[endgroup]
+build\tRun tests\t2024-06-03T10:15:02.0000000Z \x1b[1m\x1b[32m   Compiling\x1b[0m demo v0.1.0
+build\tRun tests\t2024-06-03T10:15:03.0000000Z ##[warning]Node 16 is deprecated
+build\tRun tests\t2024-06-03T10:15:04.0000000Z ##[error]Process completed with exit code 101.
+";
+        assert_eq!(
+            parse(raw),
+            [Section {
+                job: "build".to_string(),
+                step: "Run tests".to_string(),
+                command: Some("cargo test --all".to_string()),
+                output: vec!["   Compiling demo v0.1.0".to_string()],
+                errors: vec!["Process completed with exit code 101.".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_splits_unknown_step_at_run_groups() {
+        let raw = "\
+ci\tUNKNOWN STEP\t2024-06-03T10:15:01Z ##[group]Run npm ci
+ci\tUNKNOWN STEP\t2024-06-03T10:15:01Z ##[endgroup]
+ci\tUNKNOWN STEP\t2024-06-03T10:15:02Z added 312 packages
+ci\tUNKNOWN STEP\t2024-06-03T10:15:03Z ##[group]Run npx tsc --noEmit
+ci\tUNKNOWN STEP\t2024-06-03T10:15:03Z ##[endgroup]
+ci\tUNKNOWN STEP\t2024-06-03T10:15:04Z src/a.ts(3,5): error TS2322: Type 'string' is not assignable to type 'number'.
+ci\tUNKNOWN STEP\t2024-06-03T10:15:05Z ##[error]Process completed with exit code 2.
+";
+        let sections = parse(raw);
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].command.as_deref(), Some("npm ci"));
+        assert!(sections[0].errors.is_empty());
+        assert_eq!(sections[1].command.as_deref(), Some("npx tsc --noEmit"));
+        assert_eq!(sections[1].errors.len(), 1);
+    }
+
+    #[test]
+    fn test_detect_tool() {
+        assert_eq!(
+            Tool::detect(Some("cargo test --all-features"), ""),
+            Some(Tool::CargoTest)
+        );
+        assert_eq!(
+            Tool::detect(Some("cargo nextest run"), ""),
+            Some(Tool::CargoNextest)
+        );
+        assert_eq!(Tool::detect(Some("go test ./..."), ""), Some(Tool::GoTest));
+        assert_eq!(Tool::detect(Some("npx tsc --noEmit"), ""), Some(Tool::Tsc));
+        // `make test` says nothing; the output does
+        assert_eq!(
+            Tool::detect(Some("make test"), "==== short test summary info ===="),
+            Some(Tool::Pytest)
+        );
+        assert_eq!(Tool::detect(Some("./deploy.sh"), "done"), None);
+    }
+
+    #[test]
+    fn test_render_unknown_tool_keeps_tail() {
+        let section = Section {
+            job: "deploy".to_string(),
+            step: "Ship".to_string(),
+            command: Some("./deploy.sh".to_string()),
+            output: (1..=40).map(|i| format!("line {}", i)).collect(),
+            errors: vec!["Process completed with exit code 1.".to_string()],
+        };
+        let rendered = render(&[section], 0);
+        assert!(
+            rendered.starts_with("❌ deploy › Ship: ./deploy.sh\n... 10 lines above\nline 11\n")
+        );
+        assert!(rendered.ends_with("line 40\n##[error] Process completed with exit code 1."));
+    }
+}
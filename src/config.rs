@@ -1,6 +1,32 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use toml::Value;
+
+/// Project-local config, found by walking up from the current directory
+pub const PROJECT_CONFIG_FILE: &str = ".rtk.toml";
+
+/// Keys a project `.rtk.toml` may not set: any cloned repository has one, and these
+/// choose which files rtk writes, reads or deletes (tee cleanup removes old `*.log`
+/// files from `tee.directory`). Only the global file and the environment set them.
+const GLOBAL_ONLY_KEYS: &[&str] = &[
+    "tee.directory",
+    "tee.max_files",
+    "tracking.database_path",
+    "tracking.tokenizer_path",
+];
+
+/// Environment variables that override config keys; applied after both files
+const ENV_OVERRIDES: &[(&str, &str)] = &[
+    ("RTK_TEE", "tee.enabled"),
+    ("RTK_TEE_MODE", "tee.mode"),
+    ("RTK_TEE_DIR", "tee.directory"),
+    ("RTK_DB_PATH", "tracking.database_path"),
+    ("RTK_CACHE", "cache.enabled"),
+    ("RTK_GREP_ENGINE", "grep.engine"),
+];
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct Config {
@@ -12,6 +38,18 @@ pub struct Config {
     pub filters: FilterConfig,
     #[serde(default)]
     pub tee: crate::tee::TeeConfig,
+    #[serde(default)]
+    pub grep: GrepConfig,
+    #[serde(default)]
+    pub diff: DiffConfig,
+    #[serde(default)]
+    pub gh: GhConfig,
+    #[serde(default)]
+    pub commands: CommandsConfig,
+    #[serde(default)]
+    pub budget: BudgetConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -20,6 +58,12 @@ pub struct TrackingConfig {
     pub history_days: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub database_path: Option<PathBuf>,
+    /// Token estimator: "heuristic" (default) or "cl100k"
+    #[serde(default)]
+    pub tokenizer: crate::tokenizer::TokenizerKind,
+    /// Override for the cl100k vocabulary file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokenizer_path: Option<PathBuf>,
 }
 
 impl Default for TrackingConfig {
@@ -28,6 +72,8 @@ impl Default for TrackingConfig {
             enabled: true,
             history_days: 90,
             database_path: None,
+            tokenizer: crate::tokenizer::TokenizerKind::default(),
+            tokenizer_path: None,
         }
     }
 }
@@ -50,9 +96,13 @@ impl Default for DisplayConfig {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct FilterConfig {
     pub ignore_dirs: Vec<String>,
     pub ignore_files: Vec<String>,
+    /// User-defined declarative filters (`[[filters.custom]]`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub custom: Vec<crate::custom_filter::CustomFilterConfig>,
 }
 
 impl Default for FilterConfig {
@@ -67,21 +117,229 @@ impl Default for FilterConfig {
                 "vendor".into(),
             ],
             ignore_files: vec!["*.lock".into(), "*.min.js".into(), "*.min.css".into()],
+            custom: Vec::new(),
+        }
+    }
+}
+
+/// Settings for `rtk grep`: defaults for `--max` / `--max-len` and the search backend
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GrepConfig {
+    pub max_results: usize,
+    pub max_line_len: usize,
+    /// `auto` (ripgrep when installed), `rg` or `builtin`
+    pub engine: String,
+}
+
+impl Default for GrepConfig {
+    fn default() -> Self {
+        Self {
+            max_results: 50,
+            max_line_len: 80,
+            engine: "auto".to_string(),
         }
     }
 }
 
+/// Settings for `rtk git diff --summarize`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiffConfig {
+    /// Gitignore-style globs of lockfiles and generated files; their changes collapse
+    /// to one line
+    pub generated: Vec<String>,
+}
+
+impl Default for DiffConfig {
+    fn default() -> Self {
+        Self {
+            generated: vec![
+                "*.lock".into(),
+                "package-lock.json".into(),
+                "pnpm-lock.yaml".into(),
+                "go.sum".into(),
+                "*.min.js".into(),
+                "*.min.css".into(),
+                "*.snap".into(),
+                "*.pb.go".into(),
+                "*_pb2.py".into(),
+                "*.generated.*".into(),
+            ],
+        }
+    }
+}
+
+/// Settings for `rtk gh`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GhConfig {
+    /// Globs of comment authors hidden from `rtk gh pr review-comments` (`*` wildcard)
+    pub bot_authors: Vec<String>,
+}
+
+impl Default for GhConfig {
+    fn default() -> Self {
+        Self {
+            bot_authors: vec![
+                "*[bot]".into(),
+                "github-actions".into(),
+                "codecov*".into(),
+                "coveralls*".into(),
+                "sonarcloud*".into(),
+                "dependabot*".into(),
+                "renovate*".into(),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct CommandsConfig {
+    /// Commands the rewrite hook leaves alone, matched by leading words
+    /// (`"git"` covers every git command, `"cargo test"` only that subcommand)
+    pub disabled: Vec<String>,
+}
+
+impl CommandsConfig {
+    /// Whether `cmd` (leading `VAR=value` assignments allowed) is disabled.
+    pub fn is_disabled(&self, cmd: &str) -> bool {
+        let words: Vec<&str> = cmd
+            .split_whitespace()
+            .skip_while(|w| w.contains('='))
+            .collect();
+        self.disabled.iter().any(|entry| {
+            let prefix: Vec<&str> = entry.split_whitespace().collect();
+            !prefix.is_empty() && words.starts_with(&prefix)
+        })
+    }
+}
+
+/// Output token budgets, see `budget.rs`
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct BudgetConfig {
+    /// Budget for commands without their own entry; unset means unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<usize>,
+    /// Budgets keyed by rtk command prefix, e.g. `"cargo test" = 4000`
+    pub commands: BTreeMap<String, usize>,
+}
+
+impl BudgetConfig {
+    /// Budget for `command` (e.g. "cargo test"): the longest matching key, else `default`.
+    pub fn for_command(&self, command: &str) -> Option<usize> {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        self.commands
+            .iter()
+            .filter(|(key, _)| {
+                let prefix: Vec<&str> = key.split_whitespace().collect();
+                !prefix.is_empty() && words.starts_with(&prefix)
+            })
+            .max_by_key(|(key, _)| key.split_whitespace().count())
+            .map(|(_, &tokens)| tokens)
+            .or(self.default)
+    }
+}
+
+/// Output cache, see `cache.rs`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub enabled: bool,
+    pub mode: CacheMode,
+    /// Entries older than this are ignored and pruned
+    pub max_age_secs: u64,
+    /// Extra invalidation rules: command prefix → globs of files that invalidate it,
+    /// e.g. `"make lint" = ["*.c", "Makefile"]`
+    pub watch: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: CacheMode::default(),
+            max_age_secs: 86_400,
+            watch: BTreeMap::new(),
+        }
+    }
+}
+
+/// What a cache hit prints
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheMode {
+    /// The cached output again, with an "unchanged since" note
+    #[default]
+    Replay,
+    /// Only "unchanged since <time>"
+    Unchanged,
+}
+
+/// Where an effective config value came from
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigSource {
+    Default,
+    Global(PathBuf),
+    Project(PathBuf),
+    Env(&'static str),
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::Global(_) => write!(f, "global"),
+            ConfigSource::Project(_) => write!(f, "project"),
+            ConfigSource::Env(var) => write!(f, "env {}", var),
+        }
+    }
+}
+
+/// The merged config plus provenance, for `rtk config`
+#[derive(Debug)]
+pub struct LayeredConfig {
+    pub config: Config,
+    /// Merged TOML the config was deserialized from
+    pub value: Value,
+    /// Layers that contributed, lowest precedence first
+    pub layers: Vec<ConfigSource>,
+    /// Dotted key → layers that set it (several for appended arrays)
+    pub origins: BTreeMap<String, Vec<ConfigSource>>,
+    /// [`GLOBAL_ONLY_KEYS`] the project file set, which were ignored
+    pub ignored: Vec<String>,
+}
+
 impl Config {
+    /// Effective config: defaults, then the global file, then the nearest `.rtk.toml`,
+    /// then environment overrides.
     pub fn load() -> Result<Self> {
-        let path = get_config_path()?;
+        Ok(Self::load_layered()?.config)
+    }
+
+    pub fn load_layered() -> Result<LayeredConfig> {
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let env: Vec<(&'static str, &'static str, String)> = ENV_OVERRIDES
+            .iter()
+            .filter_map(|&(var, key)| std::env::var(var).ok().map(|v| (var, key, v)))
+            .collect();
+        let project = find_project_config(&cwd);
+        let layered = load_layers(&get_config_path()?, project.as_deref(), &env)?;
 
-        if path.exists() {
-            let content = std::fs::read_to_string(&path)?;
-            let config: Config = toml::from_str(&content)?;
-            Ok(config)
-        } else {
-            Ok(Config::default())
+        // Config is loaded many times per run; say it once
+        static WARNED: std::sync::Once = std::sync::Once::new();
+        if let (Some(project), false) = (&project, layered.ignored.is_empty()) {
+            WARNED.call_once(|| {
+                eprintln!(
+                    "rtk: ignoring {} in {} (only the global config or environment may set them)",
+                    layered.ignored.join(", "),
+                    project.display()
+                );
+            });
         }
+        Ok(layered)
     }
 
     pub fn save(&self) -> Result<()> {
@@ -108,20 +366,565 @@ fn get_config_path() -> Result<PathBuf> {
     Ok(config_dir.join("rtk").join("config.toml"))
 }
 
+/// Nearest `.rtk.toml` in `start` or one of its ancestors.
+pub fn find_project_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+fn load_layers(
+    global: &Path,
+    project: Option<&Path>,
+    env: &[(&'static str, &'static str, String)],
+) -> Result<LayeredConfig> {
+    let mut value = Value::try_from(Config::default())?;
+    let mut origins = BTreeMap::new();
+    record_origins(&value, "", &ConfigSource::Default, &mut origins);
+    let mut layers = vec![ConfigSource::Default];
+    let mut ignored = Vec::new();
+
+    let files = [
+        Some(global).map(|p| ConfigSource::Global(p.to_path_buf())),
+        project.map(|p| ConfigSource::Project(p.to_path_buf())),
+    ];
+    for source in files.into_iter().flatten() {
+        let path = match &source {
+            ConfigSource::Global(p) | ConfigSource::Project(p) => p,
+            _ => unreachable!(),
+        };
+        if !path.exists() {
+            continue;
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut overlay: Value = toml::from_str(&content)
+            .with_context(|| format!("Invalid config in {}", path.display()))?;
+        if matches!(source, ConfigSource::Project(_)) {
+            for key in GLOBAL_ONLY_KEYS {
+                if remove_path(&mut overlay, key) {
+                    ignored.push(key.to_string());
+                }
+            }
+        }
+        merge_value(&mut value, overlay, "", &source, &mut origins);
+        layers.push(source);
+    }
+
+    for &(var, key, ref raw) in env {
+        let source = ConfigSource::Env(var);
+        let existing = get_path(&value, key);
+        if let Some(v) = env_value(existing, raw) {
+            set_path(&mut value, key, v);
+            origins.insert(key.to_string(), vec![source.clone()]);
+            layers.push(source);
+        }
+    }
+
+    let config: Config = value
+        .clone()
+        .try_into()
+        .context("Invalid effective config (after merging files and environment)")?;
+    Ok(LayeredConfig {
+        config,
+        value,
+        layers,
+        origins,
+        ignored,
+    })
+}
+
+fn join_key(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// `name` of an array-of-tables entry (`[[filters.custom]]`), used to match entries across layers
+fn entry_name(value: &Value) -> Option<&str> {
+    value.get("name").and_then(Value::as_str)
+}
+
+/// Mark every leaf under `value` as coming from `source`.
+fn record_origins(
+    value: &Value,
+    path: &str,
+    source: &ConfigSource,
+    origins: &mut BTreeMap<String, Vec<ConfigSource>>,
+) {
+    match value {
+        Value::Table(table) => {
+            for (key, v) in table {
+                record_origins(v, &join_key(path, key), source, origins);
+            }
+        }
+        Value::Array(items) if items.iter().any(|i| entry_name(i).is_some()) => {
+            for item in items {
+                if let Some(name) = entry_name(item) {
+                    origins.insert(join_key(path, name), vec![source.clone()]);
+                }
+            }
+        }
+        _ => {
+            origins.insert(path.to_string(), vec![source.clone()]);
+        }
+    }
+}
+
+/// Deep-merge `overlay` into `base`. Tables merge key by key and scalars are replaced.
+/// Arrays are extended: new values are appended, and named entries (`[[filters.custom]]`)
+/// replace the entry with the same name or go first, so the overlay's entries win.
+fn merge_value(
+    base: &mut Value,
+    overlay: Value,
+    path: &str,
+    source: &ConfigSource,
+    origins: &mut BTreeMap<String, Vec<ConfigSource>>,
+) {
+    match (base, overlay) {
+        (Value::Table(base_table), Value::Table(overlay_table)) => {
+            for (key, v) in overlay_table {
+                let key_path = join_key(path, &key);
+                match base_table.get_mut(&key) {
+                    Some(existing) => merge_value(existing, v, &key_path, source, origins),
+                    None => {
+                        record_origins(&v, &key_path, source, origins);
+                        base_table.insert(key, v);
+                    }
+                }
+            }
+        }
+        (Value::Array(base_items), Value::Array(overlay_items)) => {
+            let mut named_front = Vec::new();
+            for item in overlay_items {
+                if let Some(name) = entry_name(&item).map(String::from) {
+                    origins.insert(join_key(path, &name), vec![source.clone()]);
+                    match base_items
+                        .iter_mut()
+                        .find(|b| entry_name(b) == Some(name.as_str()))
+                    {
+                        Some(existing) => *existing = item,
+                        None => named_front.push(item),
+                    }
+                } else if !base_items.contains(&item) {
+                    base_items.push(item);
+                    let sources = origins.entry(path.to_string()).or_default();
+                    if !sources.contains(source) {
+                        sources.push(source.clone());
+                    }
+                }
+            }
+            base_items.splice(0..0, named_front);
+        }
+        (base, overlay) => {
+            record_origins(&overlay, path, source, origins);
+            *base = overlay;
+        }
+    }
+}
+
+fn get_path<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.').try_fold(value, |v, part| v.get(part))
+}
+
+/// Remove `key` from `value`; whether it was there
+fn remove_path(value: &mut Value, key: &str) -> bool {
+    let (parent, last) = match key.rsplit_once('.') {
+        Some((parent, last)) => (
+            parent.split('.').try_fold(value, |v, part| v.get_mut(part)),
+            last,
+        ),
+        None => (Some(value), key),
+    };
+    parent
+        .and_then(Value::as_table_mut)
+        .is_some_and(|table| table.remove(last).is_some())
+}
+
+fn set_path(value: &mut Value, key: &str, new: Value) {
+    let mut current = value;
+    let mut parts = key.split('.').peekable();
+    while let Some(part) = parts.next() {
+        let Value::Table(table) = current else {
+            return;
+        };
+        if parts.peek().is_none() {
+            table.insert(part.to_string(), new);
+            return;
+        }
+        current = table
+            .entry(part.to_string())
+            .or_insert_with(|| Value::Table(Default::default()));
+    }
+}
+
+/// Parse an environment override with the type of the value it replaces.
+fn env_value(existing: Option<&Value>, raw: &str) -> Option<Value> {
+    match existing {
+        Some(Value::Boolean(_)) => match raw {
+            "0" | "false" => Some(Value::Boolean(false)),
+            "1" | "true" => Some(Value::Boolean(true)),
+            _ => None,
+        },
+        Some(Value::Integer(_)) => raw.parse().ok().map(Value::Integer),
+        _ => Some(Value::String(raw.to_string())),
+    }
+}
+
+/// Render the merged config as TOML, each value annotated with where it came from.
+fn render_with_origins(layered: &LayeredConfig) -> String {
+    let mut out = String::new();
+    if let Value::Table(root) = &layered.value {
+        render_table(root, "", Some(&layered.origins), &mut out);
+    }
+    out.trim_start().to_string()
+}
+
+/// `key` as written in TOML: bare when possible, quoted otherwise (`"cargo test"`)
+fn toml_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        key.to_string()
+    } else {
+        Value::String(key.to_string()).to_string()
+    }
+}
+
+fn render_table(
+    table: &toml::map::Map<String, Value>,
+    path: &str,
+    origins: Option<&BTreeMap<String, Vec<ConfigSource>>>,
+    out: &mut String,
+) {
+    let is_table_array =
+        |v: &Value| matches!(v, Value::Array(items) if items.first().is_some_and(Value::is_table));
+
+    for (key, v) in table {
+        if v.is_table() || is_table_array(v) {
+            continue;
+        }
+        let line = format!("{} = {}", toml_key(key), v);
+        match origins.and_then(|o| o.get(&join_key(path, key))) {
+            Some(sources) => out.push_str(&format!(
+                "{:<44} # {}\n",
+                line,
+                sources
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" + ")
+            )),
+            None => out.push_str(&format!("{}\n", line)),
+        }
+    }
+
+    for (key, v) in table {
+        let key_path = join_key(path, key);
+        match v {
+            Value::Table(sub) => {
+                out.push_str(&format!("\n[{}]\n", key_path));
+                render_table(sub, &key_path, origins, out);
+            }
+            Value::Array(items) if is_table_array(v) => {
+                for item in items {
+                    let source = entry_name(item)
+                        .and_then(|name| origins?.get(&join_key(&key_path, name)))
+                        .and_then(|sources| sources.last());
+                    match source {
+                        Some(source) => out.push_str(&format!(
+                            "\n{:<44} # {}\n",
+                            format!("[[{}]]", key_path),
+                            source
+                        )),
+                        None => out.push_str(&format!("\n[[{}]]\n", key_path)),
+                    }
+                    if let Value::Table(entry) = item {
+                        render_table(entry, &key_path, None, out);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 pub fn show_config() -> Result<()> {
-    let path = get_config_path()?;
-    println!("Config: {}", path.display());
-    println!();
+    let layered = Config::load_layered()?;
 
-    if path.exists() {
-        let config = Config::load()?;
-        println!("{}", toml::to_string_pretty(&config)?);
+    println!("Config sources (later ones override earlier ones):");
+    println!("  default");
+    let global = get_config_path()?;
+    if global.exists() {
+        println!("  global   {}", global.display());
     } else {
-        println!("(default config, file not created)");
-        println!();
-        let config = Config::default();
-        println!("{}", toml::to_string_pretty(&config)?);
+        println!("  global   {} (not found)", global.display());
     }
+    for layer in &layered.layers {
+        match layer {
+            ConfigSource::Project(path) if layered.ignored.is_empty() => {
+                println!("  project  {}", path.display())
+            }
+            ConfigSource::Project(path) => println!(
+                "  project  {} (ignored: {}; global config or env only)",
+                path.display(),
+                layered.ignored.join(", ")
+            ),
+            ConfigSource::Env(var) => println!("  env      {}", var),
+            _ => {}
+        }
+    }
+    println!();
+    print!("{}", render_with_origins(&layered));
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_defaults_without_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let layered = load_layers(&dir.path().join("missing.toml"), None, &[]).unwrap();
+        assert_eq!(layered.config.grep.max_results, 50);
+        assert_eq!(layered.layers, vec![ConfigSource::Default]);
+        assert_eq!(layered.origins["tee.mode"], vec![ConfigSource::Default]);
+    }
+
+    #[test]
+    fn test_project_overrides_global() {
+        let dir = tempfile::tempdir().unwrap();
+        let global = write(
+            dir.path(),
+            "global.toml",
+            "[tee]\nmode = \"always\"\nmax_files = 5\n[grep]\nmax_results = 20\n",
+        );
+        let project = write(
+            dir.path(),
+            ".rtk.toml",
+            "[tee]\nmode = \"never\"\n[filters]\nignore_dirs = [\"bazel-out\"]\n",
+        );
+        let layered = load_layers(&global, Some(&project), &[]).unwrap();
+        let config = &layered.config;
+
+        assert_eq!(config.tee.mode, crate::tee::TeeMode::Never);
+        assert_eq!(config.tee.max_files, 5);
+        assert_eq!(config.grep.max_results, 20);
+        // Arrays extend the lower layers instead of replacing them
+        assert!(config
+            .filters
+            .ignore_dirs
+            .contains(&"node_modules".to_string()));
+        assert!(config
+            .filters
+            .ignore_dirs
+            .contains(&"bazel-out".to_string()));
+
+        assert_eq!(
+            layered.origins["tee.mode"],
+            vec![ConfigSource::Project(project.clone())]
+        );
+        assert_eq!(
+            layered.origins["tee.max_files"],
+            vec![ConfigSource::Global(global.clone())]
+        );
+        assert_eq!(
+            layered.origins["filters.ignore_dirs"],
+            vec![ConfigSource::Default, ConfigSource::Project(project)]
+        );
+    }
+
+    #[test]
+    fn test_project_cannot_set_paths_or_retention() {
+        let dir = tempfile::tempdir().unwrap();
+        let global = write(dir.path(), "global.toml", "[tee]\nmax_files = 5\n");
+        let project = write(
+            dir.path(),
+            ".rtk.toml",
+            "[tee]\ndirectory = \"/home/u/somewhere\"\nmax_files = 1\nmode = \"always\"\n[tracking]\ndatabase_path = \"/etc/passwd\"\ntokenizer_path = \"/etc/shadow\"\n",
+        );
+        let layered = load_layers(&global, Some(&project), &[]).unwrap();
+        let config = &layered.config;
+
+        assert_eq!(config.tee.directory, None);
+        assert_eq!(config.tee.max_files, 5);
+        assert_eq!(config.tracking.database_path, None);
+        assert_eq!(config.tracking.tokenizer_path, None);
+        // Everything else in the file still applies
+        assert_eq!(config.tee.mode, crate::tee::TeeMode::Always);
+        assert_eq!(layered.ignored, GLOBAL_ONLY_KEYS);
+        assert_eq!(
+            layered.origins["tee.max_files"],
+            vec![ConfigSource::Global(global.clone())]
+        );
+
+        // The global file and the environment still may
+        let global = write(
+            dir.path(),
+            "global.toml",
+            "[tee]\ndirectory = \"/tmp/tee\"\n",
+        );
+        let env = [(
+            "RTK_DB_PATH",
+            "tracking.database_path",
+            "/tmp/x.db".to_string(),
+        )];
+        let layered = load_layers(&global, None, &env).unwrap();
+        assert_eq!(
+            layered.config.tee.directory,
+            Some(PathBuf::from("/tmp/tee"))
+        );
+        assert_eq!(
+            layered.config.tracking.database_path,
+            Some(PathBuf::from("/tmp/x.db"))
+        );
+        assert!(layered.ignored.is_empty());
+    }
+
+    #[test]
+    fn test_project_custom_filters_go_first_and_replace_by_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let global = write(
+            dir.path(),
+            "global.toml",
+            "[[filters.custom]]\nname = \"plan\"\nmatch = \"^terraform plan\"\n\n[[filters.custom]]\nname = \"bazel\"\nmatch = \"^bazel\"\n",
+        );
+        let project = write(
+            dir.path(),
+            ".rtk.toml",
+            "[[filters.custom]]\nname = \"bazel\"\nmatch = \"^bazel build\"\n\n[[filters.custom]]\nname = \"make\"\nmatch = \"^make\"\n",
+        );
+        let layered = load_layers(&global, Some(&project), &[]).unwrap();
+        let custom = &layered.config.filters.custom;
+
+        let names: Vec<&str> = custom.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["make", "plan", "bazel"]);
+        assert_eq!(custom[2].match_command, "^bazel build");
+        assert_eq!(
+            layered.origins["filters.custom.plan"],
+            vec![ConfigSource::Global(global)]
+        );
+    }
+
+    #[test]
+    fn test_env_overrides_apply_last() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = write(dir.path(), ".rtk.toml", "[tee]\nmode = \"never\"\n");
+        let env = [
+            ("RTK_TEE_MODE", "tee.mode", "always".to_string()),
+            ("RTK_TEE", "tee.enabled", "0".to_string()),
+            (
+                "RTK_DB_PATH",
+                "tracking.database_path",
+                "/tmp/x.db".to_string(),
+            ),
+        ];
+        let layered = load_layers(&dir.path().join("none.toml"), Some(&project), &env).unwrap();
+
+        assert_eq!(layered.config.tee.mode, crate::tee::TeeMode::Always);
+        assert!(!layered.config.tee.enabled);
+        assert_eq!(
+            layered.config.tracking.database_path,
+            Some(PathBuf::from("/tmp/x.db"))
+        );
+        assert_eq!(
+            layered.origins["tee.mode"],
+            vec![ConfigSource::Env("RTK_TEE_MODE")]
+        );
+    }
+
+    #[test]
+    fn test_invalid_project_file_names_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = write(dir.path(), ".rtk.toml", "[tee\n");
+        let err = load_layers(&dir.path().join("none.toml"), Some(&project), &[]).unwrap_err();
+        assert!(format!("{:#}", err).contains(".rtk.toml"));
+    }
+
+    #[test]
+    fn test_find_project_config_walks_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("a/b/c");
+        std::fs::create_dir_all(&nested).unwrap();
+        let project = write(dir.path(), PROJECT_CONFIG_FILE, "");
+        assert_eq!(find_project_config(&nested), Some(project));
+    }
+
+    #[test]
+    fn test_render_annotates_origins() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = write(
+            dir.path(),
+            ".rtk.toml",
+            "[grep]\nmax_results = 10\n\n[[filters.custom]]\nname = \"make\"\nmatch = \"^make\"\n",
+        );
+        let layered = load_layers(&dir.path().join("none.toml"), Some(&project), &[]).unwrap();
+        let rendered = render_with_origins(&layered);
+
+        assert!(rendered.contains("[grep]"));
+        let line = rendered
+            .lines()
+            .find(|l| l.starts_with("max_results"))
+            .unwrap();
+        assert!(line.ends_with("# project"), "got: {}", line);
+        let line = rendered
+            .lines()
+            .find(|l| l.starts_with("max_line_len"))
+            .unwrap();
+        assert!(line.ends_with("# default"), "got: {}", line);
+        assert!(rendered.contains("[[filters.custom]]"));
+        // Whatever we print must itself be valid TOML
+        let reparsed: Config = toml::from_str(&rendered).unwrap();
+        assert_eq!(reparsed.grep.max_results, 10);
+    }
+
+    #[test]
+    fn test_budget_for_command_prefers_longest_prefix() {
+        let budget: BudgetConfig =
+            toml::from_str("default = 3000\n[commands]\n\"cargo\" = 2000\n\"cargo test\" = 5000\n")
+                .unwrap();
+        assert_eq!(budget.for_command("cargo test"), Some(5000));
+        assert_eq!(budget.for_command("cargo build"), Some(2000));
+        assert_eq!(budget.for_command("git diff"), Some(3000));
+        assert_eq!(BudgetConfig::default().for_command("git diff"), None);
+    }
+
+    #[test]
+    fn test_render_quotes_non_bare_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = write(
+            dir.path(),
+            ".rtk.toml",
+            "[budget.commands]\n\"cargo test\" = 4000\n",
+        );
+        let layered = load_layers(&dir.path().join("none.toml"), Some(&project), &[]).unwrap();
+        let rendered = render_with_origins(&layered);
+        assert!(rendered.contains("\"cargo test\" = 4000"));
+        let reparsed: Config = toml::from_str(&rendered).unwrap();
+        assert_eq!(reparsed.budget.for_command("cargo test"), Some(4000));
+    }
+
+    #[test]
+    fn test_commands_disabled_matches_leading_words() {
+        let commands = CommandsConfig {
+            disabled: vec!["git".into(), "cargo test".into()],
+        };
+        assert!(commands.is_disabled("git status"));
+        assert!(commands.is_disabled("RUST_LOG=debug cargo test --lib"));
+        assert!(!commands.is_disabled("cargo build"));
+        assert!(!commands.is_disabled("gitk"));
+    }
+}
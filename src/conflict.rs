@@ -0,0 +1,543 @@
+//! In-progress merges, rebases, cherry-picks and reverts, and their conflicts.
+//!
+//! `rtk git status` shows the operation as a banner with the commands to continue or
+//! abort it; `rtk git merge` and `rtk git rebase` print a conflict report when they
+//! stop: each conflicted file with its conflict hunks, "ours" and "theirs" snippets
+//! trimmed to a line budget.
+use std::fs;
+use std::path::Path;
+
+/// Snippet lines shown per side of a conflict hunk
+const MAX_SNIPPET_LINES: usize = 4;
+/// Longest snippet line
+const MAX_SNIPPET_CHARS: usize = 100;
+/// Snippet lines in the whole report; later hunks are counted, not shown
+const MAX_REPORT_LINES: usize = 60;
+/// Files named in the `git add` hint before it falls back to `<files>`
+const MAX_ADD_FILES: usize = 3;
+
+/// Porcelain status codes of unmerged paths and how git describes them
+const UNMERGED: &[(&str, &str)] = &[
+    ("UU", "both modified"),
+    ("AA", "both added"),
+    ("DD", "both deleted"),
+    ("AU", "added by us"),
+    ("UA", "added by them"),
+    ("DU", "deleted by us"),
+    ("UD", "deleted by them"),
+];
+
+/// An operation git stopped in the middle of
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    Merge {
+        /// What is being merged: the branch named in MERGE_MSG, else MERGE_HEAD
+        source: String,
+    },
+    Rebase {
+        /// Branch being rebased
+        branch: String,
+        onto: String,
+        /// (current step, total steps)
+        step: Option<(usize, usize)>,
+        /// Commit being applied: short hash and subject
+        current: Option<String>,
+    },
+    CherryPick {
+        commit: String,
+    },
+    Revert {
+        commit: String,
+    },
+}
+
+impl Operation {
+    /// The operation in progress in the repository whose git directory is `git_dir`
+    pub fn detect(git_dir: &Path) -> Option<Operation> {
+        let read = |name: &str| -> Option<String> {
+            fs::read_to_string(git_dir.join(name))
+                .ok()
+                .map(|s| s.trim().to_string())
+        };
+        let short = |id: String| id.chars().take(7).collect::<String>();
+
+        for dir in ["rebase-merge", "rebase-apply"] {
+            // rebase-apply without `rebasing` belongs to `git am`
+            if dir == "rebase-apply" && !git_dir.join(dir).join("rebasing").exists() {
+                continue;
+            }
+            let Some(head) = read(&format!("{}/head-name", dir)) else {
+                continue;
+            };
+            let (step_file, total_file) = if dir == "rebase-merge" {
+                ("msgnum", "end")
+            } else {
+                ("next", "last")
+            };
+            let number = |name: &str| -> Option<usize> {
+                read(&format!("{}/{}", dir, name)).and_then(|n| n.parse().ok())
+            };
+            let step = number(step_file).zip(number(total_file));
+            let current = if dir == "rebase-merge" {
+                // Last command done: `pick 3f2a1b4 subject`
+                read("rebase-merge/done").and_then(|done| {
+                    done.lines()
+                        .rev()
+                        .find(|l| !l.trim().is_empty() && !l.starts_with('#'))
+                        .and_then(|l| l.split_once(' '))
+                        .map(|(_, rest)| match rest.split_once(' ') {
+                            Some((id, subject)) => format!("{} {}", short(id.to_string()), subject),
+                            None => short(rest.to_string()),
+                        })
+                })
+            } else {
+                read("rebase-apply/original-commit").map(|id| {
+                    let subject = read("rebase-apply/msg")
+                        .and_then(|m| m.lines().next().map(str::to_string))
+                        .unwrap_or_default();
+                    format!("{} {}", short(id), subject).trim_end().to_string()
+                })
+            };
+            return Some(Operation::Rebase {
+                branch: head.trim_start_matches("refs/heads/").to_string(),
+                onto: short(read(&format!("{}/onto", dir)).unwrap_or_default()),
+                step,
+                current,
+            });
+        }
+
+        if let Some(head) = read("MERGE_HEAD") {
+            let source = read("MERGE_MSG")
+                .and_then(|msg| {
+                    let first = msg.lines().next()?.to_string();
+                    let start = first.find('\'')? + 1;
+                    let end = start + first[start..].find('\'')?;
+                    Some(first[start..end].to_string())
+                })
+                .unwrap_or_else(|| short(head));
+            return Some(Operation::Merge { source });
+        }
+        if let Some(head) = read("CHERRY_PICK_HEAD") {
+            return Some(Operation::CherryPick {
+                commit: short(head),
+            });
+        }
+        read("REVERT_HEAD").map(|head| Operation::Revert {
+            commit: short(head),
+        })
+    }
+
+    fn command(&self) -> &'static str {
+        match self {
+            Operation::Merge { .. } => "merge",
+            Operation::Rebase { .. } => "rebase",
+            Operation::CherryPick { .. } => "cherry-pick",
+            Operation::Revert { .. } => "revert",
+        }
+    }
+
+    /// One line: what is in progress and how far along it is
+    pub fn banner(&self) -> String {
+        match self {
+            Operation::Merge { source } => format!("🔀 Merge of {} in progress", source),
+            Operation::Rebase {
+                branch,
+                onto,
+                step,
+                current,
+            } => {
+                let mut line = format!("🔀 Rebase of {} onto {} in progress", branch, onto);
+                if let Some((step, total)) = step {
+                    line.push_str(&format!(
+                        ": step {}/{}, {} left",
+                        step,
+                        total,
+                        total.saturating_sub(*step)
+                    ));
+                }
+                if let Some(current) = current {
+                    line.push_str(&format!("\n   applying {}", current));
+                }
+                line
+            }
+            Operation::CherryPick { commit } => {
+                format!("🔀 Cherry-pick of {} in progress", commit)
+            }
+            Operation::Revert { commit } => format!("🔀 Revert of {} in progress", commit),
+        }
+    }
+
+    /// The exact commands to go on, skip the current commit, or give up
+    pub fn next_steps(&self, conflicted: &[String]) -> Vec<String> {
+        let cmd = self.command();
+        let mut steps = vec!["Next:".to_string()];
+        if conflicted.is_empty() {
+            steps.push(format!("  git {} --continue", cmd));
+        } else {
+            let files = if conflicted.len() <= MAX_ADD_FILES {
+                conflicted.join(" ")
+            } else {
+                "<files>".to_string()
+            };
+            steps.push(format!(
+                "  git add {} && git {} --continue   # once resolved",
+                files, cmd
+            ));
+        }
+        if matches!(
+            self,
+            Operation::Rebase { .. } | Operation::CherryPick { .. }
+        ) {
+            steps.push(format!("  git {} --skip   # drop this commit", cmd));
+        }
+        steps.push(format!(
+            "  git {} --abort   # back to where you started",
+            cmd
+        ));
+        steps
+    }
+}
+
+/// Unmerged paths in `git status --porcelain` output: (status code, path)
+pub fn unmerged(porcelain: &str) -> Vec<(String, String)> {
+    porcelain
+        .lines()
+        .filter_map(|line| {
+            let code = line.get(0..2)?;
+            let path = line.get(3..)?;
+            UNMERGED
+                .iter()
+                .any(|(c, _)| *c == code)
+                .then(|| (code.to_string(), path.to_string()))
+        })
+        .collect()
+}
+
+/// One `<<<<<<<` ... `>>>>>>>` block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictHunk {
+    /// Line of the `<<<<<<<` marker (1-based)
+    pub line: usize,
+    pub ours_label: String,
+    pub theirs_label: String,
+    pub ours: Vec<String>,
+    pub theirs: Vec<String>,
+}
+
+/// Conflict blocks in a file's content; the diff3 base section is skipped
+pub fn parse_markers(content: &str) -> Vec<ConflictHunk> {
+    enum Side {
+        Outside,
+        Ours,
+        Base,
+        Theirs,
+    }
+    let marker = |line: &str, m: &str| -> Option<String> {
+        let rest = line.strip_prefix(m)?;
+        (rest.is_empty() || rest.starts_with(' ')).then(|| rest.trim().to_string())
+    };
+
+    let mut hunks: Vec<ConflictHunk> = Vec::new();
+    let mut side = Side::Outside;
+    for (i, line) in content.lines().enumerate() {
+        match side {
+            Side::Outside => {
+                if let Some(label) = marker(line, "<<<<<<<") {
+                    hunks.push(ConflictHunk {
+                        line: i + 1,
+                        ours_label: label,
+                        theirs_label: String::new(),
+                        ours: Vec::new(),
+                        theirs: Vec::new(),
+                    });
+                    side = Side::Ours;
+                }
+            }
+            Side::Ours | Side::Base => {
+                if marker(line, "|||||||").is_some() {
+                    side = Side::Base;
+                } else if line == "=======" {
+                    side = Side::Theirs;
+                } else if let (Side::Ours, Some(hunk)) = (&side, hunks.last_mut()) {
+                    hunk.ours.push(line.to_string());
+                }
+            }
+            Side::Theirs => {
+                let Some(hunk) = hunks.last_mut() else {
+                    continue;
+                };
+                if let Some(label) = marker(line, ">>>>>>>") {
+                    hunk.theirs_label = label;
+                    side = Side::Outside;
+                } else {
+                    hunk.theirs.push(line.to_string());
+                }
+            }
+        }
+    }
+    hunks
+}
+
+/// A conflicted path and, when it still has content, its conflict blocks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictedFile {
+    pub path: String,
+    pub code: String,
+    pub hunks: Vec<ConflictHunk>,
+}
+
+impl ConflictedFile {
+    /// Read the working-tree copy under `root` for its conflict markers
+    pub fn load(root: &Path, code: &str, path: &str) -> Self {
+        let hunks = fs::read_to_string(root.join(path))
+            .map(|content| parse_markers(&content))
+            .unwrap_or_default();
+        ConflictedFile {
+            path: path.to_string(),
+            code: code.to_string(),
+            hunks,
+        }
+    }
+}
+
+/// The operation banner, each conflicted file with its hunks, and the next commands
+pub fn render_report(operation: Option<&Operation>, files: &[ConflictedFile]) -> String {
+    let mut out = Vec::new();
+    if let Some(op) = operation {
+        out.push(op.banner());
+    }
+    if !files.is_empty() {
+        out.push(format!(
+            "⚠️  Conflicts in {}:",
+            crate::log_summary::plural(files.len(), "file")
+        ));
+    }
+
+    let mut budget = MAX_REPORT_LINES;
+    let mut hidden = 0;
+    for file in files {
+        let how = UNMERGED
+            .iter()
+            .find(|(c, _)| *c == file.code)
+            .map_or("conflicted", |(_, how)| how);
+        if file.hunks.is_empty() {
+            out.push(format!("📄 {} ({})", file.path, how));
+            continue;
+        }
+        out.push(format!(
+            "📄 {} ({}, {} conflict{})",
+            file.path,
+            how,
+            file.hunks.len(),
+            if file.hunks.len() == 1 { "" } else { "s" }
+        ));
+        for hunk in &file.hunks {
+            let cost = 1
+                + hunk.ours.len().min(MAX_SNIPPET_LINES + 1)
+                + hunk.theirs.len().min(MAX_SNIPPET_LINES + 1);
+            if cost > budget {
+                hidden += 1;
+                continue;
+            }
+            budget -= cost;
+            out.push(format!(
+                "  @ {}: ours {} ({}) | theirs {} ({})",
+                hunk.line,
+                or_unnamed(&hunk.ours_label),
+                count_lines(hunk.ours.len()),
+                or_unnamed(&hunk.theirs_label),
+                count_lines(hunk.theirs.len())
+            ));
+            out.extend(snippet('<', &hunk.ours));
+            out.extend(snippet('>', &hunk.theirs));
+        }
+    }
+    if hidden > 0 {
+        out.push(format!(
+            "  ... {} more conflict{} not shown",
+            hidden,
+            if hidden == 1 { "" } else { "s" }
+        ));
+    }
+
+    if let Some(op) = operation {
+        let paths: Vec<String> = files.iter().map(|f| f.path.clone()).collect();
+        out.extend(op.next_steps(&paths));
+    }
+    out.join("\n")
+}
+
+fn snippet(mark: char, lines: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = lines
+        .iter()
+        .take(MAX_SNIPPET_LINES)
+        .map(|l| {
+            let l = l.trim_end();
+            match l.char_indices().nth(MAX_SNIPPET_CHARS) {
+                Some((cut, _)) => format!("    {} {}…", mark, &l[..cut]),
+                None => format!("    {} {}", mark, l),
+            }
+        })
+        .collect();
+    if lines.len() > MAX_SNIPPET_LINES {
+        out.push(format!(
+            "    {} ... +{}",
+            mark,
+            lines.len() - MAX_SNIPPET_LINES
+        ));
+    }
+    out
+}
+
+fn or_unnamed(label: &str) -> &str {
+    if label.is_empty() {
+        "-"
+    } else {
+        label
+    }
+}
+
+fn count_lines(n: usize) -> String {
+    format!("{} line{}", n, if n == 1 { "" } else { "s" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFLICTED: &str = "\
+fn main() {
+<<<<<<< HEAD
+    let port = 8080;
+||||||| base
+    let port = 80;
+=======
+    let port = env_port();
+>>>>>>> 3f2a1b4 (read port from env)
+    serve(port);
+}
+";
+
+    #[test]
+    fn test_parse_markers_skips_base() {
+        let hunks = parse_markers(CONFLICTED);
+        assert_eq!(
+            hunks,
+            [ConflictHunk {
+                line: 2,
+                ours_label: "HEAD".to_string(),
+                theirs_label: "3f2a1b4 (read port from env)".to_string(),
+                ours: vec!["    let port = 8080;".to_string()],
+                theirs: vec!["    let port = env_port();".to_string()],
+            }]
+        );
+        // `=======` inside ordinary code is not a marker outside a conflict
+        assert!(parse_markers("a\n=======\nb\n").is_empty());
+    }
+
+    #[test]
+    fn test_unmerged_codes() {
+        let porcelain = "## main\nUU src/a.rs\nM  src/b.rs\nDU gone.rs\nAA new.rs\n";
+        assert_eq!(
+            unmerged(porcelain),
+            [
+                ("UU".to_string(), "src/a.rs".to_string()),
+                ("DU".to_string(), "gone.rs".to_string()),
+                ("AA".to_string(), "new.rs".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_rebase_and_merge() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = dir.path();
+        assert_eq!(Operation::detect(git), None);
+
+        fs::write(git.join("MERGE_HEAD"), "abcdef0123456789\n").unwrap();
+        fs::write(git.join("MERGE_MSG"), "Merge branch 'feature' into main\n").unwrap();
+        assert_eq!(
+            Operation::detect(git),
+            Some(Operation::Merge {
+                source: "feature".to_string()
+            })
+        );
+
+        let rebase = git.join("rebase-merge");
+        fs::create_dir(&rebase).unwrap();
+        fs::write(rebase.join("head-name"), "refs/heads/feature\n").unwrap();
+        fs::write(rebase.join("onto"), "1234567890abcdef\n").unwrap();
+        fs::write(rebase.join("msgnum"), "3\n").unwrap();
+        fs::write(rebase.join("end"), "7\n").unwrap();
+        fs::write(
+            rebase.join("done"),
+            "pick 1111111 first\npick 3f2a1b4 read port from env\n",
+        )
+        .unwrap();
+        let op = Operation::detect(git).unwrap();
+        assert_eq!(
+            op.banner(),
+            "🔀 Rebase of feature onto 1234567 in progress: step 3/7, 4 left\n   \
+             applying 3f2a1b4 read port from env"
+        );
+    }
+
+    #[test]
+    fn test_report_with_next_steps() {
+        let op = Operation::Rebase {
+            branch: "feature".to_string(),
+            onto: "1234567".to_string(),
+            step: Some((1, 2)),
+            current: None,
+        };
+        let files = vec![
+            ConflictedFile {
+                path: "src/main.rs".to_string(),
+                code: "UU".to_string(),
+                hunks: parse_markers(CONFLICTED),
+            },
+            ConflictedFile {
+                path: "old.rs".to_string(),
+                code: "UD".to_string(),
+                hunks: Vec::new(),
+            },
+        ];
+        assert_eq!(
+            render_report(Some(&op), &files),
+            "\
+🔀 Rebase of feature onto 1234567 in progress: step 1/2, 1 left
+⚠️  Conflicts in 2 files:
+📄 src/main.rs (both modified, 1 conflict)
+  @ 2: ours HEAD (1 line) | theirs 3f2a1b4 (read port from env) (1 line)
+    <     let port = 8080;
+    >     let port = env_port();
+📄 old.rs (deleted by them)
+Next:
+  git add src/main.rs old.rs && git rebase --continue   # once resolved
+  git rebase --skip   # drop this commit
+  git rebase --abort   # back to where you started"
+        );
+    }
+
+    #[test]
+    fn test_report_trims_to_budget() {
+        let long: Vec<String> = (0..10).map(|i| format!("line {}", i)).collect();
+        let hunk = ConflictHunk {
+            line: 1,
+            ours_label: "HEAD".to_string(),
+            theirs_label: "x".to_string(),
+            ours: long.clone(),
+            theirs: long,
+        };
+        let files = vec![ConflictedFile {
+            path: "a.txt".to_string(),
+            code: "UU".to_string(),
+            hunks: vec![hunk; 20],
+        }];
+        let report = render_report(None, &files);
+        assert!(report.contains("    < ... +6"));
+        // Each hunk costs 11 lines of the 60-line budget
+        assert_eq!(report.matches("  @ 1:").count(), 5);
+        assert!(report.ends_with("  ... 15 more conflicts not shown"));
+    }
+}
@@ -160,14 +160,11 @@ fn docker_logs(args: &[String], _verbose: u8) -> Result<()> {
         return Ok(());
     }
 
-    let output = Command::new("docker")
-        .args(["logs", "--tail", "100", container])
-        .output()
-        .context("Failed to run docker logs")?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let raw = format!("{}\n{}", stdout, stderr);
+    let mut cmd = Command::new("docker");
+    cmd.args(["logs", "--tail", "100", container]);
+    // Deduplication needs every line, so nothing is dropped while streaming
+    let output = crate::stream::run_streaming(cmd, "docker logs", &mut crate::stream::KeepAll)?;
+    let raw = output.kept.combined();
 
     let analyzed = crate::log_cmd::run_stdin_str(&raw);
     let rtk = format!("🐳 Logs for {}:\n{}", container, analyzed);
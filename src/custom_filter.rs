@@ -0,0 +1,514 @@
+//! User-defined declarative filters loaded from `[[filters.custom]]` in config.toml.
+//!
+//! Each filter matches a command line by regex and runs its output through a
+//! fixed pipeline of line rules:
+//!
+//! 1. `strip_ansi` — remove color codes
+//! 2. `keep` — keep only lines matching at least one regex (if any are set)
+//! 3. `strip` — drop lines matching any regex
+//! 4. `dedup` — collapse consecutive identical lines into `[×N] line`
+//! 5. `head` / `tail` — limit the remaining lines
+//! 6. `summary` — append a line built from `counts`
+//!
+//! `counts` and `{lines}` see every output line after `strip_ansi`, before `keep`
+//! and `strip`, so a summary can count lines the filter drops.
+//!
+//! ```toml
+//! [[filters.custom]]
+//! name = "terraform-plan"
+//! match = "^terraform\\s+plan"
+//! strip = ["^\\s*$", "Refreshing state"]
+//! dedup = true
+//! head = 40
+//! summary = "{create} to add, {destroy} to destroy"
+//!
+//! [filters.custom.counts]
+//! create = "will be created"
+//! destroy = "will be destroyed"
+//! ```
+
+use crate::config::Config;
+use crate::tracking;
+use crate::utils::strip_ansi;
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::process::Command;
+
+/// A `[[filters.custom]]` entry as written in config.toml.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomFilterConfig {
+    /// Display name, also used as the tee file slug
+    pub name: String,
+    /// Regex matched against the full command line (e.g. "^terraform\\s+plan")
+    #[serde(rename = "match")]
+    pub match_command: String,
+    /// Strip ANSI color codes before any other rule
+    pub strip_ansi: bool,
+    /// Keep only lines matching at least one of these regexes
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub keep: Vec<String>,
+    /// Drop lines matching any of these regexes
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub strip: Vec<String>,
+    /// Collapse consecutive identical lines
+    pub dedup: bool,
+    /// Keep at most the first N lines
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head: Option<usize>,
+    /// Keep at most the last N lines
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tail: Option<usize>,
+    /// Named regexes counted over the output lines before `keep`/`strip`, usable as
+    /// `{name}` in `summary`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub counts: BTreeMap<String, String>,
+    /// Summary template appended after the filtered lines (`{lines}` = output line count
+    /// before `keep`/`strip`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+/// A custom filter with all of its regexes compiled.
+#[derive(Debug)]
+pub struct CustomFilter {
+    pub name: String,
+    matcher: Regex,
+    strip_ansi: bool,
+    keep: Vec<Regex>,
+    strip: Vec<Regex>,
+    dedup: bool,
+    head: Option<usize>,
+    tail: Option<usize>,
+    counts: Vec<(String, Regex)>,
+    summary: Option<String>,
+}
+
+fn compile_all(name: &str, field: &str, patterns: &[String]) -> Result<Vec<Regex>> {
+    patterns
+        .iter()
+        .map(|p| {
+            Regex::new(p).with_context(|| {
+                format!(
+                    "Invalid regex in filters.custom '{}' {}: {}",
+                    name, field, p
+                )
+            })
+        })
+        .collect()
+}
+
+impl CustomFilter {
+    /// Compile a config entry, reporting which filter and field holds a bad regex.
+    pub fn compile(config: &CustomFilterConfig) -> Result<Self> {
+        let name = if config.name.is_empty() {
+            config.match_command.clone()
+        } else {
+            config.name.clone()
+        };
+
+        let matcher = Regex::new(&config.match_command).with_context(|| {
+            format!(
+                "Invalid regex in filters.custom '{}' match: {}",
+                name, config.match_command
+            )
+        })?;
+
+        let counts = config
+            .counts
+            .iter()
+            .map(|(key, pattern)| {
+                Regex::new(pattern)
+                    .map(|re| (key.clone(), re))
+                    .with_context(|| {
+                        format!(
+                            "Invalid regex in filters.custom '{}' counts.{}: {}",
+                            name, key, pattern
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            keep: compile_all(&name, "keep", &config.keep)?,
+            strip: compile_all(&name, "strip", &config.strip)?,
+            name,
+            matcher,
+            strip_ansi: config.strip_ansi,
+            dedup: config.dedup,
+            head: config.head,
+            tail: config.tail,
+            counts,
+            summary: config.summary.clone(),
+        })
+    }
+
+    pub fn matches(&self, command_line: &str) -> bool {
+        self.matcher.is_match(command_line)
+    }
+
+    /// Run the full rule pipeline over raw command output.
+    pub fn apply(&self, raw: &str) -> String {
+        let text = if self.strip_ansi {
+            strip_ansi(raw)
+        } else {
+            raw.to_string()
+        };
+
+        let mut lines: Vec<String> = text
+            .lines()
+            .filter(|line| self.keep.is_empty() || self.keep.iter().any(|re| re.is_match(line)))
+            .filter(|line| !self.strip.iter().any(|re| re.is_match(line)))
+            .map(|line| line.to_string())
+            .collect();
+
+        if self.dedup {
+            lines = dedup_consecutive(&lines);
+        }
+
+        lines = limit_lines(lines, self.head, self.tail);
+
+        if let Some(template) = &self.summary {
+            lines.push(self.render_summary(template, &text));
+        }
+
+        if lines.iter().all(|l| l.trim().is_empty()) {
+            return format!("✓ {}", self.name);
+        }
+
+        lines.join("\n")
+    }
+
+    fn render_summary(&self, template: &str, text: &str) -> String {
+        let mut summary = template.replace("{lines}", &text.lines().count().to_string());
+        for (key, re) in &self.counts {
+            let count = text.lines().filter(|line| re.is_match(line)).count();
+            summary = summary.replace(&format!("{{{}}}", key), &count.to_string());
+        }
+        summary
+    }
+}
+
+/// Collapse runs of identical lines into a single `[×N] line` entry.
+fn dedup_consecutive(lines: &[String]) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let mut run = 1;
+        while i + run < lines.len() && lines[i + run] == lines[i] {
+            run += 1;
+        }
+        if run > 1 {
+            result.push(format!("[×{}] {}", run, lines[i]));
+        } else {
+            result.push(lines[i].clone());
+        }
+        i += run;
+    }
+    result
+}
+
+/// Apply head/tail limits, inserting a marker for the omitted middle.
+fn limit_lines(lines: Vec<String>, head: Option<usize>, tail: Option<usize>) -> Vec<String> {
+    let total = lines.len();
+    match (head, tail) {
+        (Some(h), Some(t)) if total > h + t => {
+            let mut result: Vec<String> = lines[..h].to_vec();
+            result.push(format!("... {} lines omitted", total - h - t));
+            result.extend_from_slice(&lines[total - t..]);
+            result
+        }
+        (Some(h), None) if total > h => {
+            let mut result: Vec<String> = lines[..h].to_vec();
+            result.push(format!("... +{} more lines", total - h));
+            result
+        }
+        (None, Some(t)) if total > t => {
+            let mut result = vec![format!("... {} lines omitted", total - t)];
+            result.extend_from_slice(&lines[total - t..]);
+            result
+        }
+        _ => lines,
+    }
+}
+
+/// Find the first custom filter whose `match` regex accepts the command line.
+pub fn find_filter(config: &Config, command_line: &str) -> Result<Option<CustomFilter>> {
+    for entry in &config.filters.custom {
+        let filter = CustomFilter::compile(entry)?;
+        if filter.matches(command_line) {
+            return Ok(Some(filter));
+        }
+    }
+    Ok(None)
+}
+
+/// Run an external command through the first matching custom filter.
+pub fn run(args: &[OsString], verbose: u8) -> Result<()> {
+    if args.is_empty() {
+        anyhow::bail!("custom filter requires a command to execute");
+    }
+
+    let cmd_name = args[0].to_string_lossy().into_owned();
+    let cmd_args: Vec<String> = args[1..]
+        .iter()
+        .map(|s| s.to_string_lossy().into_owned())
+        .collect();
+    let command_line = tracking::args_display(args);
+
+    let config = Config::load()?;
+    let filter = find_filter(&config, &command_line)?.with_context(|| {
+        format!(
+            "unrecognized subcommand '{}' (no [[filters.custom]] entry matches '{}')",
+            cmd_name, command_line
+        )
+    })?;
+
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Running: {} (custom filter: {})", command_line, filter.name);
+    }
+
+    let output = Command::new(&cmd_name)
+        .args(&cmd_args)
+        .output()
+        .with_context(|| format!("Failed to run {}", cmd_name))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let raw = format!("{}\n{}", stdout, stderr);
+
+    let exit_code = output
+        .status
+        .code()
+        .unwrap_or(if output.status.success() { 0 } else { 1 });
+    let filtered = filter.apply(&raw);
+
+    if let Some(hint) = crate::tee::tee_and_hint(&raw, &filter.name, exit_code) {
+        println!("{}\n{}", filtered, hint);
+    } else {
+        println!("{}", filtered);
+    }
+
+    timer.track(
+        &command_line,
+        &format!("rtk {}", command_line),
+        &raw,
+        &filtered,
+    );
+
+    if !output.status.success() {
+        std::process::exit(exit_code);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TERRAFORM_PLAN: &str = include_str!("../tests/fixtures/custom_filter/terraform_plan.txt");
+    const BAZEL_BUILD: &str = include_str!("../tests/fixtures/custom_filter/bazel_build.txt");
+
+    fn terraform_config() -> CustomFilterConfig {
+        toml::from_str(
+            r#"
+name = "terraform-plan"
+match = "^terraform\\s+plan"
+strip_ansi = true
+keep = ["will be", "^Plan:", "^Error"]
+summary = "{create} to add, {destroy} to destroy"
+
+[counts]
+create = "will be created"
+destroy = "will be destroyed"
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_config_deserialize_custom_filters() {
+        let config: Config = toml::from_str(
+            r#"
+[[filters.custom]]
+name = "deploy"
+match = "^./deploy\\.sh"
+dedup = true
+head = 10
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.filters.custom.len(), 1);
+        let entry = &config.filters.custom[0];
+        assert_eq!(entry.name, "deploy");
+        assert_eq!(entry.match_command, "^./deploy\\.sh");
+        assert!(entry.dedup);
+        assert_eq!(entry.head, Some(10));
+        assert!(entry.tail.is_none());
+        // Default ignore lists survive a partial [filters] table
+        assert!(config
+            .filters
+            .ignore_dirs
+            .contains(&"node_modules".to_string()));
+    }
+
+    #[test]
+    fn test_matches_command_line() {
+        let filter = CustomFilter::compile(&terraform_config()).unwrap();
+        assert!(filter.matches("terraform plan -var-file=prod.tfvars"));
+        assert!(!filter.matches("terraform apply"));
+    }
+
+    #[test]
+    fn test_invalid_regex_reports_filter_and_field() {
+        let config = CustomFilterConfig {
+            name: "broken".to_string(),
+            match_command: "^ok".to_string(),
+            strip: vec!["(unclosed".to_string()],
+            ..Default::default()
+        };
+        let err = CustomFilter::compile(&config).unwrap_err().to_string();
+        assert!(err.contains("broken"));
+        assert!(err.contains("strip"));
+    }
+
+    #[test]
+    fn test_terraform_plan_fixture() {
+        let filter = CustomFilter::compile(&terraform_config()).unwrap();
+        let result = filter.apply(TERRAFORM_PLAN);
+        assert!(result.contains("aws_instance.web will be created"));
+        assert!(result.contains("aws_s3_bucket.logs will be destroyed"));
+        assert!(result.contains("Plan: 2 to add, 1 to change, 1 to destroy."));
+        assert!(result.ends_with("2 to add, 1 to destroy"));
+        assert!(!result.contains("Refreshing state"));
+        assert!(!result.contains("\x1b["));
+        assert!(result.len() < TERRAFORM_PLAN.len() / 3);
+    }
+
+    #[test]
+    fn test_bazel_build_fixture_strip_dedup_tail() {
+        let config = CustomFilterConfig {
+            name: "bazel".to_string(),
+            match_command: "^bazel\\s+build".to_string(),
+            strip: vec![r"^\s*$".to_string(), r"^Loading:".to_string()],
+            dedup: true,
+            tail: Some(10),
+            ..Default::default()
+        };
+        let filter = CustomFilter::compile(&config).unwrap();
+        let result = filter.apply(BAZEL_BUILD);
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines.len(), 11);
+        assert!(lines[0].starts_with("... "));
+        assert!(result.contains("[×3] WARNING: //lib:core: deprecated attribute 'linkstatic'"));
+        assert!(result.contains("INFO: Build completed successfully"));
+        assert!(!result.contains("Loading:"));
+    }
+
+    #[test]
+    fn test_head_and_tail_marker() {
+        let lines: Vec<String> = (1..=10).map(|i| format!("line {}", i)).collect();
+        let result = limit_lines(lines, Some(2), Some(2));
+        assert_eq!(
+            result,
+            vec![
+                "line 1",
+                "line 2",
+                "... 6 lines omitted",
+                "line 9",
+                "line 10"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_head_only() {
+        let lines: Vec<String> = (1..=5).map(|i| format!("line {}", i)).collect();
+        let result = limit_lines(lines, Some(3), None);
+        assert_eq!(
+            result,
+            vec!["line 1", "line 2", "line 3", "... +2 more lines"]
+        );
+    }
+
+    #[test]
+    fn test_limits_not_applied_when_short() {
+        let lines: Vec<String> = vec!["a".to_string(), "b".to_string()];
+        assert_eq!(limit_lines(lines.clone(), Some(1), Some(1)), lines);
+    }
+
+    #[test]
+    fn test_dedup_consecutive_only() {
+        let lines: Vec<String> = ["a", "a", "b", "a"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(dedup_consecutive(&lines), vec!["[×2] a", "b", "a"]);
+    }
+
+    #[test]
+    fn test_summary_lines_placeholder() {
+        let config = CustomFilterConfig {
+            name: "count".to_string(),
+            match_command: ".*".to_string(),
+            strip: vec![".*".to_string()],
+            summary: Some("{lines} lines".to_string()),
+            ..Default::default()
+        };
+        let filter = CustomFilter::compile(&config).unwrap();
+        assert_eq!(filter.apply("a\nb\nc"), "3 lines");
+    }
+
+    #[test]
+    fn test_counts_see_uncolored_lines_before_keep_and_strip() {
+        let config = CustomFilterConfig {
+            name: "plan".to_string(),
+            match_command: ".*".to_string(),
+            strip_ansi: true,
+            keep: vec!["^Plan:".to_string()],
+            summary: Some("{create} created".to_string()),
+            counts: [("create".to_string(), "^\\+ .* will be created$".to_string())]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+        let filter = CustomFilter::compile(&config).unwrap();
+        let raw = "\x1b[32m+ a will be created\x1b[0m\n+ b will be created\nPlan: 2 to add";
+        assert_eq!(filter.apply(raw), "Plan: 2 to add\n2 created");
+    }
+
+    #[test]
+    fn test_empty_result_confirms() {
+        let config = CustomFilterConfig {
+            name: "quiet".to_string(),
+            match_command: ".*".to_string(),
+            strip: vec![".*".to_string()],
+            ..Default::default()
+        };
+        let filter = CustomFilter::compile(&config).unwrap();
+        assert_eq!(filter.apply("noise\nmore noise"), "✓ quiet");
+    }
+
+    #[test]
+    fn test_find_filter_first_match_wins() {
+        let mut config = Config::default();
+        config.filters.custom = vec![
+            CustomFilterConfig {
+                name: "first".to_string(),
+                match_command: "^deploy".to_string(),
+                ..Default::default()
+            },
+            CustomFilterConfig {
+                name: "second".to_string(),
+                match_command: "^deploy prod".to_string(),
+                ..Default::default()
+            },
+        ];
+        let found = find_filter(&config, "deploy prod").unwrap().unwrap();
+        assert_eq!(found.name, "first");
+        assert!(find_filter(&config, "other").unwrap().is_none());
+    }
+}
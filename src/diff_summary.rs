@@ -0,0 +1,716 @@
+//! Summarized unified diffs for `rtk git diff --summarize`.
+//!
+//! Per-file stats come first. Pure renames, whitespace-only changes, binaries and
+//! lockfile/generated files (`diff.generated` globs) collapse to their stat line. The
+//! remaining line budget goes to the hunks that change the most lines, each labeled
+//! with the function or type that encloses it; the rest are listed by label only.
+use crate::outline::SymbolIndex;
+use anyhow::{Context, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Changed lines shown per hunk before it is cut
+const MAX_HUNK_LINES: usize = 20;
+/// Files listed in the stat block; the rest are folded into one line
+const MAX_STAT_FILES: usize = 30;
+/// Widest path column in the stat block
+const MAX_PATH_WIDTH: usize = 50;
+/// Longest git function context used as a hunk label
+const MAX_LABEL_CHARS: usize = 60;
+/// Files where reindenting changes meaning, so it never counts as whitespace only
+const INDENT_SIGNIFICANT: &[&str] = &["py", "yaml", "yml", "mk", "Makefile"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStatus {
+    Modified,
+    Added,
+    Deleted,
+    Renamed,
+}
+
+/// One file of a unified diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiff {
+    /// New path (old path for deletions)
+    pub path: String,
+    /// Path before a rename
+    pub old_path: Option<String>,
+    pub status: FileStatus,
+    pub binary: bool,
+    /// Abbreviated id of the new blob, from the `index` line
+    pub new_blob: Option<String>,
+    pub hunks: Vec<Hunk>,
+}
+
+/// One `@@` hunk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// `-10,7 +12,9`
+    pub range: String,
+    /// Function context git printed after the second `@@`
+    pub context: String,
+    /// First new-side line of the hunk (1-based)
+    pub new_start: usize,
+    /// Body lines with their ` `, `+`, `-` or `\` prefix
+    pub lines: Vec<String>,
+}
+
+/// Why a file is shown as its stat line only
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Collapse {
+    Renamed,
+    Binary,
+    Lockfile,
+    Generated,
+    Whitespace,
+}
+
+impl Collapse {
+    fn label(self) -> &'static str {
+        match self {
+            Collapse::Renamed => "renamed",
+            Collapse::Binary => "binary",
+            Collapse::Lockfile => "lockfile",
+            Collapse::Generated => "generated",
+            Collapse::Whitespace => "whitespace only",
+        }
+    }
+}
+
+impl Hunk {
+    fn changed(&self) -> impl Iterator<Item = &String> {
+        self.lines
+            .iter()
+            .filter(|l| l.starts_with('+') || l.starts_with('-'))
+    }
+
+    fn added(&self) -> usize {
+        self.lines.iter().filter(|l| l.starts_with('+')).count()
+    }
+
+    fn removed(&self) -> usize {
+        self.lines.iter().filter(|l| l.starts_with('-')).count()
+    }
+
+    /// Removed and added lines are the same once all whitespace is dropped
+    /// (reindentation, blank lines, trailing spaces). With `keep_indent`, only blank
+    /// lines and trailing spaces may differ.
+    fn whitespace_only(&self, keep_indent: bool) -> bool {
+        let squash = |prefix: char| -> String {
+            let side = self.lines.iter().filter_map(|l| l.strip_prefix(prefix));
+            if keep_indent {
+                side.map(str::trim_end)
+                    .filter(|l| !l.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            } else {
+                side.flat_map(|l| l.chars().filter(|c| !c.is_whitespace()))
+                    .collect()
+            }
+        };
+        squash('-') == squash('+')
+    }
+
+    /// How much the hunk matters: changed lines that aren't blank
+    fn weight(&self) -> usize {
+        self.changed().filter(|l| !l[1..].trim().is_empty()).count()
+    }
+
+    /// New-side line of the first change, where a deletion counts as the line after it.
+    /// Lines that were only reindented or moved within the hunk are passed over when
+    /// something else changed.
+    fn first_change_line(&self) -> usize {
+        let side = |prefix: char| -> HashSet<&str> {
+            self.lines
+                .iter()
+                .filter_map(|l| l.strip_prefix(prefix))
+                .map(str::trim)
+                .collect()
+        };
+        let (removed, added) = (side('-'), side('+'));
+        let mut changes = Vec::new();
+        let mut line = self.new_start;
+        for l in &self.lines {
+            if let Some(text) = l.strip_prefix('+') {
+                changes.push((line, !removed.contains(text.trim())));
+                line += 1;
+            } else if let Some(text) = l.strip_prefix('-') {
+                changes.push((line, !added.contains(text.trim())));
+            } else if l.starts_with(' ') {
+                line += 1;
+            }
+        }
+        changes
+            .iter()
+            .find(|(_, real)| *real)
+            .or(changes.first())
+            .map_or(self.new_start, |(line, _)| *line)
+    }
+}
+
+impl FileDiff {
+    pub fn added(&self) -> usize {
+        self.hunks.iter().map(Hunk::added).sum()
+    }
+
+    pub fn removed(&self) -> usize {
+        self.hunks.iter().map(Hunk::removed).sum()
+    }
+
+    /// Whether this file is reduced to its stat line, and why
+    pub fn collapse(&self, generated: &Gitignore) -> Option<Collapse> {
+        if self.binary {
+            return Some(Collapse::Binary);
+        }
+        if self.status == FileStatus::Renamed && self.hunks.is_empty() {
+            return Some(Collapse::Renamed);
+        }
+        if generated
+            .matched_path_or_any_parents(&self.path, false)
+            .is_ignore()
+        {
+            let name = Path::new(&self.path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            return Some(if name.contains("lock") || name == "go.sum" {
+                Collapse::Lockfile
+            } else {
+                Collapse::Generated
+            });
+        }
+        if self.status == FileStatus::Modified
+            && !self.hunks.is_empty()
+            && self.hunks.iter().all(|h| self.whitespace_only(h))
+        {
+            return Some(Collapse::Whitespace);
+        }
+        None
+    }
+
+    fn whitespace_only(&self, hunk: &Hunk) -> bool {
+        let path = Path::new(&self.path);
+        let keep_indent = [path.extension(), path.file_name()]
+            .into_iter()
+            .flatten()
+            .any(|part| INDENT_SIGNIFICANT.iter().any(|s| part == *s));
+        hunk.whitespace_only(keep_indent)
+    }
+
+    fn display_path(&self) -> String {
+        match &self.old_path {
+            Some(old) => format!("{} → {}", old, self.path),
+            None => self.path.clone(),
+        }
+    }
+
+    /// Matches `--file`: the new or old path, or a trailing part of either
+    fn matches(&self, query: &str) -> bool {
+        let query = query.trim_start_matches("./");
+        std::iter::once(&self.path)
+            .chain(self.old_path.as_ref())
+            .any(|p| p == query || p.ends_with(&format!("/{}", query)))
+    }
+}
+
+/// Matcher for `diff.generated` globs, with gitignore semantics (`*.lock` matches in
+/// any directory, `gen/` a whole directory)
+pub fn generated_matcher(globs: &[String]) -> Result<Gitignore> {
+    let mut builder = GitignoreBuilder::new("");
+    for glob in globs {
+        builder
+            .add_line(None, glob)
+            .with_context(|| format!("invalid diff.generated glob: {}", glob))?;
+    }
+    builder.build().context("invalid diff.generated globs")
+}
+
+/// Split `git diff` output into files and hunks
+pub fn parse(diff: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    let mut in_header = false;
+
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            files.push(FileDiff {
+                path: rest.rsplit_once(" b/").map_or(rest, |(_, b)| b).to_string(),
+                old_path: None,
+                status: FileStatus::Modified,
+                binary: false,
+                new_blob: None,
+                hunks: Vec::new(),
+            });
+            in_header = true;
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+
+        if let Some(rest) = line.strip_prefix("@@ ") {
+            in_header = false;
+            let (range, context) = rest.split_once(" @@").unwrap_or((rest, ""));
+            let new_start = range
+                .split_whitespace()
+                .find_map(|r| r.strip_prefix('+'))
+                .and_then(|r| r.split(',').next())
+                .and_then(|n| n.parse().ok())
+                .unwrap_or(1);
+            file.hunks.push(Hunk {
+                range: range.to_string(),
+                context: context.trim().to_string(),
+                new_start,
+                lines: Vec::new(),
+            });
+        } else if in_header {
+            if line.starts_with("new file mode") {
+                file.status = FileStatus::Added;
+            } else if line.starts_with("deleted file mode") {
+                file.status = FileStatus::Deleted;
+            } else if let Some(from) = line.strip_prefix("rename from ") {
+                file.status = FileStatus::Renamed;
+                file.old_path = Some(from.to_string());
+            } else if let Some(to) = line.strip_prefix("rename to ") {
+                file.path = to.to_string();
+            } else if let Some(ids) = line.strip_prefix("index ") {
+                file.new_blob = ids
+                    .split_whitespace()
+                    .next()
+                    .and_then(|ids| ids.split_once(".."))
+                    .map(|(_, new)| new.to_string())
+                    .filter(|new| new.bytes().any(|b| b != b'0'));
+            } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+                file.binary = true;
+            } else if let Some(path) = line.strip_prefix("+++ b/") {
+                file.path = path.to_string();
+            } else if let Some(path) = line.strip_prefix("--- a/") {
+                if file.status == FileStatus::Deleted {
+                    file.path = path.to_string();
+                }
+            }
+        } else if let Some(hunk) = file.hunks.last_mut() {
+            hunk.lines.push(line.to_string());
+        }
+    }
+    files
+}
+
+/// What `summarize` prints and how much of it
+pub struct SummaryOptions<'a> {
+    /// Lines available to hunk bodies
+    pub budget: usize,
+    /// `--file`: print every hunk of this file, nothing else
+    pub file: Option<&'a str>,
+    pub generated: &'a Gitignore,
+}
+
+/// Files worth labeling: everything that isn't collapsed (and is the `--file` one)
+pub fn files_to_label<'a>(files: &'a [FileDiff], options: &SummaryOptions) -> Vec<&'a FileDiff> {
+    files
+        .iter()
+        .filter(|f| options.file.is_none_or(|q| f.matches(q)))
+        .filter(|f| f.collapse(options.generated).is_none())
+        .collect()
+}
+
+/// Render the summary. `contents` holds new-side file contents by path, used to find
+/// the symbol enclosing each hunk; git's own function context is the fallback.
+pub fn summarize(
+    files: &[FileDiff],
+    contents: &HashMap<String, String>,
+    options: &SummaryOptions,
+) -> String {
+    let indexes: HashMap<&str, SymbolIndex> = contents
+        .iter()
+        .map(|(path, content)| (path.as_str(), SymbolIndex::build(path, content)))
+        .collect();
+    let label = |file: &FileDiff, hunk: &Hunk| -> String {
+        indexes
+            .get(file.path.as_str())
+            .and_then(|index| index.enclosing(hunk.first_change_line()))
+            .unwrap_or_else(|| {
+                let context = hunk.context.as_str();
+                match context.char_indices().nth(MAX_LABEL_CHARS) {
+                    Some((cut, _)) => format!("{}…", &context[..cut]),
+                    None => context.to_string(),
+                }
+            })
+    };
+
+    if let Some(query) = options.file {
+        let Some(file) = files.iter().find(|f| f.matches(query)) else {
+            return format!("no changes in {}", query);
+        };
+        let mut out = vec![stat_line(
+            file,
+            options.generated,
+            file.display_path().len(),
+        )];
+        out.push(format!("\n📄 {}", file.display_path()));
+        for hunk in &file.hunks {
+            out.push(hunk_header(&label(file, hunk), hunk));
+            out.extend(hunk.lines.iter().map(|l| format!("  {}", l)));
+        }
+        return out.join("\n");
+    }
+
+    let mut out = Vec::new();
+    let (added, removed) = files
+        .iter()
+        .fold((0, 0), |(a, r), f| (a + f.added(), r + f.removed()));
+    out.push(format!(
+        "{} file{} changed, +{} -{}",
+        files.len(),
+        if files.len() == 1 { "" } else { "s" },
+        added,
+        removed
+    ));
+    let listed = &files[..files.len().min(MAX_STAT_FILES)];
+    let width = listed
+        .iter()
+        .map(|f| f.display_path().chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(MAX_PATH_WIDTH);
+    out.extend(
+        listed
+            .iter()
+            .map(|f| stat_line(f, options.generated, width)),
+    );
+    let rest = &files[listed.len()..];
+    if !rest.is_empty() {
+        let (added, removed) = rest
+            .iter()
+            .fold((0, 0), |(a, r), f| (a + f.added(), r + f.removed()));
+        out.push(format!(
+            "  ... +{} more file{} (+{} -{})",
+            rest.len(),
+            if rest.len() == 1 { "" } else { "s" },
+            added,
+            removed
+        ));
+    }
+
+    let selected = select_hunks(files, options);
+    let mut hidden_hunks = 0;
+    let mut hidden_files = 0;
+    for (f, file) in files.iter().enumerate() {
+        if file.collapse(options.generated).is_some() {
+            continue;
+        }
+        if !(0..file.hunks.len()).any(|h| selected.contains(&(f, h))) {
+            if !file.hunks.is_empty() {
+                hidden_hunks += file.hunks.len();
+                hidden_files += 1;
+            }
+            continue;
+        }
+
+        out.push(format!("\n📄 {}", file.display_path()));
+        let mut others = Vec::new();
+        for (h, hunk) in file.hunks.iter().enumerate() {
+            let name = label(file, hunk);
+            if !selected.contains(&(f, h)) {
+                others.push(if file.whitespace_only(hunk) {
+                    format!("{} (whitespace)", or_top(&name))
+                } else {
+                    format!("{} (+{} -{})", or_top(&name), hunk.added(), hunk.removed())
+                });
+                continue;
+            }
+            out.push(hunk_header(&name, hunk));
+            let changed: Vec<&String> = hunk.changed().collect();
+            out.extend(
+                changed
+                    .iter()
+                    .take(MAX_HUNK_LINES)
+                    .map(|l| format!("  {}", l)),
+            );
+            if changed.len() > MAX_HUNK_LINES {
+                out.push(format!("  ... +{} lines", changed.len() - MAX_HUNK_LINES));
+            }
+        }
+        if !others.is_empty() {
+            out.push(format!(
+                "  ... {} more hunk{}: {}",
+                others.len(),
+                if others.len() == 1 { "" } else { "s" },
+                others.join(", ")
+            ));
+        }
+    }
+
+    if hidden_hunks > 0 {
+        out.push(format!(
+            "\n... {} hunk{} in {} file{} not shown (--file <path> to expand)",
+            hidden_hunks,
+            if hidden_hunks == 1 { "" } else { "s" },
+            hidden_files,
+            if hidden_files == 1 { "" } else { "s" }
+        ));
+    }
+    out.join("\n")
+}
+
+/// Hunks whose bodies fit the budget, heaviest first: (file index, hunk index)
+fn select_hunks(files: &[FileDiff], options: &SummaryOptions) -> HashSet<(usize, usize)> {
+    let mut candidates: Vec<(usize, usize, &Hunk)> = files
+        .iter()
+        .enumerate()
+        .filter(|(_, file)| file.collapse(options.generated).is_none())
+        .flat_map(|(f, file)| {
+            file.hunks
+                .iter()
+                .enumerate()
+                .map(move |(h, hunk)| (f, h, hunk))
+                .filter(|(_, _, hunk)| !file.whitespace_only(hunk))
+        })
+        .collect();
+    // Stable: equally heavy hunks keep diff order
+    candidates.sort_by_key(|(_, _, hunk)| std::cmp::Reverse(hunk.weight()));
+
+    let mut selected = HashSet::new();
+    let mut files_shown = HashSet::new();
+    let mut left = options.budget;
+    for (f, h, hunk) in candidates {
+        let changed = hunk.changed().count();
+        // Header, body (cut at MAX_HUNK_LINES plus a marker) and the file's own header
+        let cost = 1 + changed.min(MAX_HUNK_LINES + 1) + usize::from(!files_shown.contains(&f));
+        if cost > left {
+            continue;
+        }
+        left -= cost;
+        files_shown.insert(f);
+        selected.insert((f, h));
+    }
+    selected
+}
+
+fn stat_line(file: &FileDiff, generated: &Gitignore, width: usize) -> String {
+    let path = file.display_path();
+    let collapse = file.collapse(generated);
+    if let Some(only @ (Collapse::Renamed | Collapse::Binary)) = collapse {
+        return format!("  {:<width$}  {}", path, only.label(), width = width);
+    }
+    let counts = format!("+{} -{}", file.added(), file.removed());
+    let note = match (collapse, file.status) {
+        (Some(collapse), _) => collapse.label().to_string(),
+        (None, FileStatus::Added) => "new".to_string(),
+        (None, FileStatus::Deleted) => "deleted".to_string(),
+        (None, _) => format!(
+            "({} hunk{})",
+            file.hunks.len(),
+            if file.hunks.len() == 1 { "" } else { "s" }
+        ),
+    };
+    format!("  {:<width$}  {:<11} {}", path, counts, note, width = width)
+}
+
+fn hunk_header(label: &str, hunk: &Hunk) -> String {
+    let counts = format!("(+{} -{})", hunk.added(), hunk.removed());
+    if label.is_empty() {
+        format!("  @@ {} @@ {}", hunk.range, counts)
+    } else {
+        format!("  @@ {} @@ {} {}", hunk.range, label, counts)
+    }
+}
+
+/// Hunks outside any symbol are at the top level of the file
+fn or_top(label: &str) -> &str {
+    if label.is_empty() {
+        "(top level)"
+    } else {
+        label
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/src/store.rs b/src/store.rs
+index 1111111..2222222 100644
+--- a/src/store.rs
++++ b/src/store.rs
+@@ -1,6 +1,7 @@
+ impl Store {
+     fn load() {
+-        read();
++        read_all();
++        check();
+     }
+ }
+@@ -20,3 +21,3 @@ fn save() {
+ fn save() {
+-  write();
++    write();
+ }
+diff --git a/Cargo.lock b/Cargo.lock
+index 3333333..4444444 100644
+--- a/Cargo.lock
++++ b/Cargo.lock
+@@ -1 +1 @@
+-version = 1
++version = 2
+diff --git a/old.txt b/new.txt
+similarity index 100%
+rename from old.txt
+rename to new.txt
+diff --git a/logo.png b/logo.png
+index 5555555..6666666 100644
+Binary files a/logo.png and b/logo.png differ
+diff --git a/gone.py b/gone.py
+deleted file mode 100644
+index 7777777..0000000
+--- a/gone.py
++++ /dev/null
+@@ -1,2 +0,0 @@
+-def gone():
+-    pass
+";
+
+    fn generated() -> Gitignore {
+        generated_matcher(&crate::config::DiffConfig::default().generated).unwrap()
+    }
+
+    #[test]
+    fn test_parse_files_and_hunks() {
+        let files = parse(DIFF);
+        assert_eq!(files.len(), 5);
+        let store = &files[0];
+        assert_eq!(store.new_blob.as_deref(), Some("2222222"));
+        assert_eq!((store.added(), store.removed()), (3, 2));
+        assert_eq!(store.hunks[1].context, "fn save() {");
+        assert_eq!(store.hunks[1].new_start, 21);
+        assert_eq!(files[2].old_path.as_deref(), Some("old.txt"));
+        assert_eq!(files[2].path, "new.txt");
+        assert!(files[3].binary);
+        assert_eq!(files[4].status, FileStatus::Deleted);
+        assert_eq!(files[4].new_blob, None);
+    }
+
+    #[test]
+    fn test_collapse_reasons() {
+        let files = parse(DIFF);
+        let generated = generated();
+        let reasons: Vec<Option<Collapse>> = files.iter().map(|f| f.collapse(&generated)).collect();
+        assert_eq!(
+            reasons,
+            [
+                None,
+                Some(Collapse::Lockfile),
+                Some(Collapse::Renamed),
+                Some(Collapse::Binary),
+                None
+            ]
+        );
+        // Reindenting is cosmetic in Rust but not in Python
+        let hunk = &files[0].hunks[1];
+        assert!(files[0].whitespace_only(hunk));
+        let mut py = files[0].clone();
+        py.path = "src/store.py".to_string();
+        assert!(!py.whitespace_only(hunk));
+    }
+
+    #[test]
+    fn test_summary_labels_hunks_and_collapses_noise() {
+        let files = parse(DIFF);
+        let generated = generated();
+        let options = SummaryOptions {
+            budget: 100,
+            file: None,
+            generated: &generated,
+        };
+        let content =
+            "impl Store {\n    fn load() {\n        read_all();\n        check();\n    }\n}\n";
+        let contents = HashMap::from([("src/store.rs".to_string(), content.to_string())]);
+        assert_eq!(
+            summarize(&files, &contents, &options),
+            "\
+5 files changed, +4 -5
+  src/store.rs       +3 -2       (2 hunks)
+  Cargo.lock         +1 -1       lockfile
+  old.txt → new.txt  renamed
+  logo.png           binary
+  gone.py            +0 -2       deleted
+
+📄 src/store.rs
+  @@ -1,6 +1,7 @@ Store::load (+2 -1)
+  -        read();
+  +        read_all();
+  +        check();
+  ... 1 more hunk: fn save() { (whitespace)
+
+📄 gone.py
+  @@ -1,2 +0,0 @@ (+0 -2)
+  -def gone():
+  -    pass"
+        );
+    }
+
+    #[test]
+    fn test_budget_goes_to_heaviest_hunks() {
+        let files = parse(DIFF);
+        let generated = generated();
+        let options = SummaryOptions {
+            budget: 5,
+            file: None,
+            generated: &generated,
+        };
+        let summary = summarize(&files, &HashMap::new(), &options);
+        // Store::load (3 changed lines, cost 5) fits; the deletion doesn't
+        assert!(summary.contains("+        check();"));
+        assert!(!summary.contains("-def gone():"));
+        assert!(summary.ends_with("... 1 hunk in 1 file not shown (--file <path> to expand)"));
+    }
+
+    #[test]
+    fn test_stat_block_folds_files_past_cap() {
+        let diff: String = (0..MAX_STAT_FILES + 2)
+            .map(|i| {
+                format!(
+                    "diff --git a/f{i}.txt b/f{i}.txt\n--- a/f{i}.txt\n+++ b/f{i}.txt\n@@ -1 +1 @@\n-a\n+b\n"
+                )
+            })
+            .collect();
+        let files = parse(&diff);
+        let generated = generated();
+        let options = SummaryOptions {
+            budget: 0,
+            file: None,
+            generated: &generated,
+        };
+        let summary = summarize(&files, &HashMap::new(), &options);
+        let lines: Vec<&str> = summary.lines().collect();
+        assert_eq!(lines[0], "32 files changed, +32 -32");
+        assert!(lines[MAX_STAT_FILES].starts_with("  f29.txt"));
+        assert_eq!(lines[MAX_STAT_FILES + 1], "  ... +2 more files (+2 -2)");
+        assert!(!summary.contains("f30.txt"));
+    }
+
+    #[test]
+    fn test_file_expands_every_hunk() {
+        let files = parse(DIFF);
+        let generated = generated();
+        let options = SummaryOptions {
+            budget: 0,
+            file: Some("./store.rs"),
+            generated: &generated,
+        };
+        let summary = summarize(&files, &HashMap::new(), &options);
+        assert!(summary.starts_with("  src/store.rs  +3 -2       (2 hunks)\n\n📄 src/store.rs"));
+        assert!(summary
+            .contains("  @@ -20,3 +21,3 @@ fn save() { (+1 -1)\n   fn save() {\n  -  write();"));
+
+        let missing = SummaryOptions {
+            file: Some("nope.rs"),
+            ..options
+        };
+        assert_eq!(
+            summarize(&files, &HashMap::new(), &missing),
+            "no changes in nope.rs"
+        );
+    }
+}
@@ -78,6 +78,7 @@ pub fn run(
 
         for ext_cmd in &extracted {
             let parts = split_command_chain(&ext_cmd.command);
+            let segment_count = parts.len().max(1);
             for part in parts {
                 total_commands += 1;
 
@@ -101,10 +102,11 @@ pub fn run(
 
                         bucket.count += 1;
 
-                        // Estimate tokens for this command
-                        let output_tokens = if let Some(len) = ext_cmd.output_len {
-                            // Real: from tool_result content length
-                            len / 4
+                        // Estimate tokens for this segment
+                        let output_tokens = if let Some(tokens) = ext_cmd.output_tokens {
+                            // Real: the tool_result covers the whole chain, so each
+                            // segment gets an equal share instead of the full output
+                            tokens / segment_count
                         } else {
                             // Fallback: category average
                             let subcmd = extract_subcmd(part);
@@ -10,7 +10,8 @@ use walkdir::WalkDir;
 #[derive(Debug)]
 pub struct ExtractedCommand {
     pub command: String,
-    pub output_len: Option<usize>,
+    /// Estimated tokens in the tool_result content (configured estimator)
+    pub output_tokens: Option<usize>,
     #[allow(dead_code)]
     pub session_id: String,
     /// Actual output content (first ~1000 chars for error detection)
@@ -186,11 +187,11 @@ impl SessionProvider for ClaudeProvider {
                             if block.get("type").and_then(|t| t.as_str()) == Some("tool_result") {
                                 if let Some(id) = block.get("tool_use_id").and_then(|i| i.as_str())
                                 {
-                                    // Get content, length, and error status
+                                    // Get content, token estimate, and error status
                                     let content =
                                         block.get("content").and_then(|c| c.as_str()).unwrap_or("");
 
-                                    let output_len = content.len();
+                                    let output_tokens = crate::tracking::estimate_tokens(content);
                                     let is_error = block
                                         .get("is_error")
                                         .and_then(|e| e.as_bool())
@@ -202,7 +203,7 @@ impl SessionProvider for ClaudeProvider {
 
                                     tool_results.insert(
                                         id.to_string(),
-                                        (output_len, content_preview, is_error),
+                                        (output_tokens, content_preview, is_error),
                                     );
                                 }
                             }
@@ -215,14 +216,14 @@ impl SessionProvider for ClaudeProvider {
 
         // Match tool_uses with their results
         for (tool_id, command, sequence_index) in pending_tool_uses {
-            let (output_len, output_content, is_error) = tool_results
+            let (output_tokens, output_content, is_error) = tool_results
                 .get(&tool_id)
-                .map(|(len, content, err)| (Some(*len), Some(content.clone()), *err))
+                .map(|(tokens, content, err)| (Some(*tokens), Some(content.clone()), *err))
                 .unwrap_or((None, None, false));
 
             commands.push(ExtractedCommand {
                 command,
-                output_len,
+                output_tokens,
                 session_id: session_id.clone(),
                 output_content,
                 is_error,
@@ -259,10 +260,10 @@ mod tests {
         let cmds = provider.extract_commands(jsonl.path()).unwrap();
         assert_eq!(cmds.len(), 1);
         assert_eq!(cmds[0].command, "git status");
-        assert!(cmds[0].output_len.is_some());
+        assert!(cmds[0].output_tokens.is_some());
         assert_eq!(
-            cmds[0].output_len.unwrap(),
-            "On branch master\nnothing to commit".len()
+            cmds[0].output_tokens.unwrap(),
+            crate::tracking::estimate_tokens("On branch master\nnothing to commit")
         );
     }
 
@@ -8,6 +8,9 @@ struct RtkRule {
     savings_pct: f64,
     subcmd_savings: &'static [(&'static str, f64)],
     subcmd_status: &'static [(&'static str, super::report::RtkStatus)],
+    /// Regex → replacement pairs applied to the env-stripped command by `rtk rewrite`.
+    /// First match wins; empty means the command is classified but never rewritten.
+    rewrites: &'static [(&'static str, &'static str)],
 }
 
 /// Result of classifying a command.
@@ -25,11 +28,11 @@ pub enum Classification {
     Ignored,
 }
 
-/// Average token counts per category for estimation when no output_len available.
+/// Average token counts per category for estimation when no tool_result output is available.
 pub fn category_avg_tokens(category: &str, subcmd: &str) -> usize {
     match category {
         "Git" => match subcmd {
-            "log" | "diff" | "show" => 200,
+            "log" | "diff" | "show" | "blame" => 200,
             _ => 40,
         },
         "Cargo" => match subcmd {
@@ -49,27 +52,33 @@ pub fn category_avg_tokens(category: &str, subcmd: &str) -> usize {
 
 // Patterns ordered to match RTK_RULES indices exactly.
 const PATTERNS: &[&str] = &[
-    r"^git\s+(status|log|diff|show|add|commit|push|pull|branch|fetch|stash|worktree)",
-    r"^gh\s+(pr|issue|run|repo|api)",
-    r"^cargo\s+(build|test|clippy|check|fmt)",
+    r"^git\s+(status|log|diff|show|add|commit|push|pull|branch|fetch|stash|worktree|blame|merge|rebase)",
+    r"^gh\s+(pr|issue|run|repo|api|release)",
+    r"^cargo\s+(build|test|clippy|check|fmt|install)",
     r"^pnpm\s+(list|ls|outdated|install)",
-    r"^npm\s+(run|exec)",
+    r"^npm\s+(run|exec|test)",
     r"^npx\s+",
     r"^(cat|head|tail)\s+",
     r"^(rg|grep)\s+",
     r"^ls(\s|$)",
     r"^find\s+",
-    r"^(npx\s+|pnpm\s+)?tsc(\s|$)",
+    r"^(npx\s+|pnpm\s+)?(vue-)?tsc(\s|$)",
     r"^(npx\s+|pnpm\s+)?(eslint|biome|lint)(\s|$)",
     r"^(npx\s+|pnpm\s+)?prettier",
     r"^(npx\s+|pnpm\s+)?next\s+build",
     r"^(pnpm\s+|npx\s+)?(vitest|jest|test)(\s|$)",
     r"^(npx\s+|pnpm\s+)?playwright",
     r"^(npx\s+|pnpm\s+)?prisma",
-    r"^docker\s+(ps|images|logs)",
-    r"^kubectl\s+(get|logs)",
+    r"^docker\s+(ps|images|logs|compose|run|build|exec)",
+    r"^kubectl\s+(get|logs|describe|apply)",
     r"^curl\s+",
     r"^wget\s+",
+    r"^diff\s+",
+    r"^(python3?\s+-m\s+)?pytest(\s|$)",
+    r"^ruff\s+(check|format)",
+    r"^(uv\s+)?pip\s+(list|outdated|install|show)",
+    r"^go\s+(test|build|vet)",
+    r"^golangci-lint(\s|$)",
 ];
 
 const RULES: &[RtkRule] = &[
@@ -80,17 +89,22 @@ const RULES: &[RtkRule] = &[
         subcmd_savings: &[
             ("diff", 80.0),
             ("show", 80.0),
+            ("blame", 80.0),
+            ("merge", 70.0),
+            ("rebase", 70.0),
             ("add", 59.0),
             ("commit", 59.0),
         ],
         subcmd_status: &[],
+        rewrites: &[(r"^git\s", "rtk git ")],
     },
     RtkRule {
         rtk_cmd: "rtk gh",
         category: "GitHub",
         savings_pct: 82.0,
         subcmd_savings: &[("pr", 87.0), ("run", 82.0), ("issue", 80.0)],
-        subcmd_status: &[],
+        subcmd_status: &[("release", super::report::RtkStatus::Passthrough)],
+        rewrites: &[(r"^gh\s+(pr|issue|run|api|release)(\s|$)", "rtk gh $1$2")],
     },
     RtkRule {
         rtk_cmd: "rtk cargo",
@@ -98,6 +112,7 @@ const RULES: &[RtkRule] = &[
         savings_pct: 80.0,
         subcmd_savings: &[("test", 90.0), ("check", 80.0)],
         subcmd_status: &[("fmt", super::report::RtkStatus::Passthrough)],
+        rewrites: &[(r"^cargo\s", "rtk cargo ")],
     },
     RtkRule {
         rtk_cmd: "rtk pnpm",
@@ -105,6 +120,7 @@ const RULES: &[RtkRule] = &[
         savings_pct: 80.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrites: &[(r"^pnpm\s+(list|ls|outdated)(\s|$)", "rtk pnpm $1$2")],
     },
     RtkRule {
         rtk_cmd: "rtk npm",
@@ -112,6 +128,10 @@ const RULES: &[RtkRule] = &[
         savings_pct: 70.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrites: &[
+            (r"^npm\s+test(\s|$)", "rtk npm test$1"),
+            (r"^npm\s+run\s+", "rtk npm "),
+        ],
     },
     RtkRule {
         rtk_cmd: "rtk npx",
@@ -119,6 +139,7 @@ const RULES: &[RtkRule] = &[
         savings_pct: 70.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrites: &[],
     },
     RtkRule {
         rtk_cmd: "rtk read",
@@ -126,6 +147,14 @@ const RULES: &[RtkRule] = &[
         savings_pct: 60.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrites: &[
+            (r"^cat\s+", "rtk read "),
+            (r"^head\s+-(\d+)\s+(\S+)$", "rtk read $2 --max-lines $1"),
+            (
+                r"^head\s+--lines=(\d+)\s+(\S+)$",
+                "rtk read $2 --max-lines $1",
+            ),
+        ],
     },
     RtkRule {
         rtk_cmd: "rtk grep",
@@ -133,6 +162,7 @@ const RULES: &[RtkRule] = &[
         savings_pct: 75.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrites: &[(r"^(rg|grep)\s+", "rtk grep ")],
     },
     RtkRule {
         rtk_cmd: "rtk ls",
@@ -140,13 +170,16 @@ const RULES: &[RtkRule] = &[
         savings_pct: 65.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrites: &[(r"^ls", "rtk ls")],
     },
+    // find/wget are classified but not rewritten: their rtk flags differ from the originals.
     RtkRule {
         rtk_cmd: "rtk find",
         category: "Files",
         savings_pct: 70.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrites: &[],
     },
     RtkRule {
         rtk_cmd: "rtk tsc",
@@ -154,6 +187,7 @@ const RULES: &[RtkRule] = &[
         savings_pct: 83.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrites: &[(r"^(npx\s+|pnpm\s+)?(vue-)?tsc", "rtk tsc")],
     },
     RtkRule {
         rtk_cmd: "rtk lint",
@@ -161,6 +195,10 @@ const RULES: &[RtkRule] = &[
         savings_pct: 84.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrites: &[
+            (r"^pnpm\s+lint", "rtk lint"),
+            (r"^(npx\s+)?eslint", "rtk lint"),
+        ],
     },
     RtkRule {
         rtk_cmd: "rtk prettier",
@@ -168,6 +206,7 @@ const RULES: &[RtkRule] = &[
         savings_pct: 70.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrites: &[(r"^(npx\s+)?prettier(\s|$)", "rtk prettier$2")],
     },
     RtkRule {
         rtk_cmd: "rtk next",
@@ -175,6 +214,7 @@ const RULES: &[RtkRule] = &[
         savings_pct: 87.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrites: &[],
     },
     RtkRule {
         rtk_cmd: "rtk vitest",
@@ -182,6 +222,10 @@ const RULES: &[RtkRule] = &[
         savings_pct: 99.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrites: &[
+            (r"^(pnpm\s+)?(npx\s+)?vitest(\s+run)?", "rtk vitest run"),
+            (r"^pnpm\s+test", "rtk vitest run"),
+        ],
     },
     RtkRule {
         rtk_cmd: "rtk playwright",
@@ -189,6 +233,7 @@ const RULES: &[RtkRule] = &[
         savings_pct: 94.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrites: &[(r"^(npx\s+|pnpm\s+)?playwright(\s|$)", "rtk playwright$2")],
     },
     RtkRule {
         rtk_cmd: "rtk prisma",
@@ -196,20 +241,30 @@ const RULES: &[RtkRule] = &[
         savings_pct: 88.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrites: &[(r"^(npx\s+)?prisma(\s|$)", "rtk prisma$2")],
     },
     RtkRule {
         rtk_cmd: "rtk docker",
         category: "Infra",
         savings_pct: 85.0,
         subcmd_savings: &[],
-        subcmd_status: &[],
+        subcmd_status: &[
+            ("run", super::report::RtkStatus::Passthrough),
+            ("build", super::report::RtkStatus::Passthrough),
+            ("exec", super::report::RtkStatus::Passthrough),
+        ],
+        rewrites: &[(r"^docker\s", "rtk docker ")],
     },
     RtkRule {
         rtk_cmd: "rtk kubectl",
         category: "Infra",
         savings_pct: 85.0,
         subcmd_savings: &[],
-        subcmd_status: &[],
+        subcmd_status: &[
+            ("describe", super::report::RtkStatus::Passthrough),
+            ("apply", super::report::RtkStatus::Passthrough),
+        ],
+        rewrites: &[(r"^kubectl\s", "rtk kubectl ")],
     },
     RtkRule {
         rtk_cmd: "rtk curl",
@@ -217,6 +272,7 @@ const RULES: &[RtkRule] = &[
         savings_pct: 70.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrites: &[(r"^curl\s", "rtk curl ")],
     },
     RtkRule {
         rtk_cmd: "rtk wget",
@@ -224,6 +280,55 @@ const RULES: &[RtkRule] = &[
         savings_pct: 65.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrites: &[],
+    },
+    RtkRule {
+        rtk_cmd: "rtk diff",
+        category: "Files",
+        savings_pct: 60.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+        rewrites: &[(r"^diff\s", "rtk diff ")],
+    },
+    RtkRule {
+        rtk_cmd: "rtk pytest",
+        category: "Tests",
+        savings_pct: 90.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+        rewrites: &[(r"^(python3?\s+-m\s+)?pytest", "rtk pytest")],
+    },
+    RtkRule {
+        rtk_cmd: "rtk ruff",
+        category: "Build",
+        savings_pct: 80.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+        rewrites: &[(r"^ruff\s", "rtk ruff ")],
+    },
+    RtkRule {
+        rtk_cmd: "rtk pip",
+        category: "PackageManager",
+        savings_pct: 70.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+        rewrites: &[(r"^(uv\s+)?pip\s", "rtk pip ")],
+    },
+    RtkRule {
+        rtk_cmd: "rtk go",
+        category: "Build",
+        savings_pct: 85.0,
+        subcmd_savings: &[("test", 90.0)],
+        subcmd_status: &[],
+        rewrites: &[(r"^go\s", "rtk go ")],
+    },
+    RtkRule {
+        rtk_cmd: "rtk golangci-lint",
+        category: "Build",
+        savings_pct: 85.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+        rewrites: &[(r"^golangci-lint", "rtk golangci-lint")],
     },
 ];
 
@@ -292,6 +397,15 @@ lazy_static! {
         .collect();
     static ref ENV_PREFIX: Regex =
         Regex::new(r"^(?:sudo\s+|env\s+|[A-Z_][A-Z0-9_]*=[^\s]*\s+)+").unwrap();
+    static ref REWRITES: Vec<Vec<(Regex, &'static str)>> = RULES
+        .iter()
+        .map(|r| {
+            r.rewrites
+                .iter()
+                .map(|(p, rep)| (Regex::new(p).expect("invalid rewrite regex"), *rep))
+                .collect()
+        })
+        .collect();
 }
 
 /// Classify a single (already-split) command.
@@ -372,6 +486,28 @@ pub fn classify_command(cmd: &str) -> Classification {
     }
 }
 
+/// Rewrite a single (already-split) command to its rtk equivalent.
+/// Env prefixes (`FOO=bar`, `sudo`, `env`) are kept verbatim in front of the rewrite.
+/// Returns `None` for ignored/unsupported commands and rules without a rewrite.
+pub fn rewrite_command(cmd: &str) -> Option<String> {
+    let trimmed = cmd.trim();
+    if trimmed.contains("<<") {
+        return None;
+    }
+    if !matches!(classify_command(trimmed), Classification::Supported { .. }) {
+        return None;
+    }
+
+    let prefix_len = ENV_PREFIX.find(trimmed).map(|m| m.end()).unwrap_or(0);
+    let (prefix, body) = trimmed.split_at(prefix_len);
+
+    let idx = REGEX_SET.matches(body).into_iter().next_back()?;
+    REWRITES[idx]
+        .iter()
+        .find(|(re, _)| re.is_match(body))
+        .map(|(re, rep)| format!("{}{}", prefix, re.replace(body, *rep)))
+}
+
 /// Extract the base command (first word, or first two if it looks like a subcommand pattern).
 fn extract_base_command(cmd: &str) -> &str {
     let parts: Vec<&str> = cmd.splitn(3, char::is_whitespace).collect();
@@ -401,10 +537,30 @@ fn extract_base_command(cmd: &str) -> &str {
     }
 }
 
-/// Split a command chain on `&&`, `||`, `;` outside quotes.
-/// For pipes `|`, only keep the first command.
+/// One command of a `&&`/`||`/`;`/`|` chain, with its position in the original string.
+#[derive(Debug, PartialEq)]
+pub struct ChainSegment<'a> {
+    /// Trimmed command text, a subslice of the input.
+    pub text: &'a str,
+    /// Byte offset of `text` within the input.
+    pub offset: usize,
+    /// Reads its stdin from a previous command's pipe.
+    pub piped_from: bool,
+    /// Its stdout is piped into another command.
+    pub piped_to: bool,
+}
+
+impl ChainSegment<'_> {
+    /// Part of a `|` pipeline: rewriting would change what the other side sees.
+    pub fn is_piped(&self) -> bool {
+        self.piped_from || self.piped_to
+    }
+}
+
+/// Split a command chain on `&&`, `||`, `;` and `|` outside quotes, keeping byte offsets
+/// so callers can rewrite segments in place. Backslash escapes are honoured.
 /// Lines with `<<` (heredoc) or `$((` are returned whole.
-pub fn split_command_chain(cmd: &str) -> Vec<&str> {
+pub fn chain_segments(cmd: &str) -> Vec<ChainSegment<'_>> {
     let trimmed = cmd.trim();
     if trimmed.is_empty() {
         return vec![];
@@ -412,21 +568,28 @@ pub fn split_command_chain(cmd: &str) -> Vec<&str> {
 
     // Heredoc or arithmetic expansion: treat as single command
     if trimmed.contains("<<") || trimmed.contains("$((") {
-        return vec![trimmed];
+        return vec![ChainSegment {
+            text: trimmed,
+            offset: cmd.len() - cmd.trim_start().len(),
+            piped_from: false,
+            piped_to: false,
+        }];
     }
 
-    let mut results = Vec::new();
-    let mut start = 0;
-    let bytes = trimmed.as_bytes();
+    let mut segments = Vec::new();
+    let bytes = cmd.as_bytes();
     let len = bytes.len();
+    let mut start = 0;
     let mut i = 0;
     let mut in_single = false;
     let mut in_double = false;
-    let mut pipe_seen = false;
+    let mut piped_from = false;
 
     while i < len {
-        let b = bytes[i];
-        match b {
+        match bytes[i] {
+            b'\\' if !in_single => {
+                i += 2;
+            }
             b'\'' if !in_double => {
                 in_single = !in_single;
                 i += 1;
@@ -437,36 +600,30 @@ pub fn split_command_chain(cmd: &str) -> Vec<&str> {
             }
             b'|' if !in_single && !in_double => {
                 if i + 1 < len && bytes[i + 1] == b'|' {
-                    // ||
-                    let segment = trimmed[start..i].trim();
-                    if !segment.is_empty() {
-                        results.push(segment);
-                    }
+                    push_segment(&mut segments, cmd, start, i, piped_from, false);
+                    piped_from = false;
                     i += 2;
-                    start = i;
                 } else {
-                    // pipe: keep only first command
-                    let segment = trimmed[start..i].trim();
-                    if !segment.is_empty() {
-                        results.push(segment);
-                    }
-                    pipe_seen = true;
-                    break;
+                    push_segment(&mut segments, cmd, start, i, piped_from, true);
+                    piped_from = true;
+                    // `|&` pipes stderr too
+                    i += if i + 1 < len && bytes[i + 1] == b'&' {
+                        2
+                    } else {
+                        1
+                    };
                 }
+                start = i;
             }
             b'&' if !in_single && !in_double && i + 1 < len && bytes[i + 1] == b'&' => {
-                let segment = trimmed[start..i].trim();
-                if !segment.is_empty() {
-                    results.push(segment);
-                }
+                push_segment(&mut segments, cmd, start, i, piped_from, false);
+                piped_from = false;
                 i += 2;
                 start = i;
             }
             b';' if !in_single && !in_double => {
-                let segment = trimmed[start..i].trim();
-                if !segment.is_empty() {
-                    results.push(segment);
-                }
+                push_segment(&mut segments, cmd, start, i, piped_from, false);
+                piped_from = false;
                 i += 1;
                 start = i;
             }
@@ -476,14 +633,42 @@ pub fn split_command_chain(cmd: &str) -> Vec<&str> {
         }
     }
 
-    if !pipe_seen && start < len {
-        let segment = trimmed[start..].trim();
-        if !segment.is_empty() {
-            results.push(segment);
-        }
+    if start < len {
+        push_segment(&mut segments, cmd, start, len, piped_from, false);
+    }
+
+    segments
+}
+
+fn push_segment<'a>(
+    segments: &mut Vec<ChainSegment<'a>>,
+    cmd: &'a str,
+    start: usize,
+    end: usize,
+    piped_from: bool,
+    piped_to: bool,
+) {
+    let raw = &cmd[start..end.min(cmd.len())];
+    let text = raw.trim();
+    if !text.is_empty() {
+        segments.push(ChainSegment {
+            text,
+            offset: start + (raw.len() - raw.trim_start().len()),
+            piped_from,
+            piped_to,
+        });
     }
+}
 
-    results
+/// Split a command chain on `&&`, `||`, `;` outside quotes.
+/// For pipes `|`, only keep the first command of each pipeline.
+/// Lines with `<<` (heredoc) or `$((` are returned whole.
+pub fn split_command_chain(cmd: &str) -> Vec<&str> {
+    chain_segments(cmd)
+        .into_iter()
+        .filter(|s| !s.piped_from)
+        .map(|s| s.text)
+        .collect()
 }
 
 #[cfg(test)]
@@ -517,6 +702,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_classify_git_rebase() {
+        assert_eq!(
+            classify_command("git rebase -i origin/main"),
+            Classification::Supported {
+                rtk_equivalent: "rtk git",
+                category: "Git",
+                estimated_savings_pct: 70.0,
+                status: RtkStatus::Existing,
+            }
+        );
+        assert_eq!(
+            rewrite_command("git merge --no-ff feature").as_deref(),
+            Some("rtk git merge --no-ff feature")
+        );
+    }
+
+    #[test]
+    fn test_classify_git_blame() {
+        assert_eq!(
+            classify_command("git blame -L 10,40 src/main.rs"),
+            Classification::Supported {
+                rtk_equivalent: "rtk git",
+                category: "Git",
+                estimated_savings_pct: 80.0,
+                status: RtkStatus::Existing,
+            }
+        );
+        assert_eq!(
+            rewrite_command("git blame src/main.rs").as_deref(),
+            Some("rtk git blame src/main.rs")
+        );
+    }
+
     #[test]
     fn test_classify_cargo_test_filter() {
         assert_eq!(
@@ -689,7 +908,7 @@ mod tests {
         // Verify that every GitCommand subcommand has a matching pattern
         for subcmd in [
             "status", "log", "diff", "show", "add", "commit", "push", "pull", "branch", "fetch",
-            "stash", "worktree",
+            "stash", "worktree", "blame", "merge", "rebase",
         ] {
             let cmd = format!("git {subcmd}");
             match classify_command(&cmd) {
@@ -732,4 +951,58 @@ mod tests {
         let cmd = "cat <<'EOF'\nhello && world\nEOF";
         assert_eq!(split_command_chain(cmd), vec![cmd]);
     }
+
+    #[test]
+    fn test_split_continues_after_pipeline() {
+        assert_eq!(
+            split_command_chain("git log | head -5 && cargo test"),
+            vec!["git log", "cargo test"]
+        );
+    }
+
+    #[test]
+    fn test_chain_segments_offsets() {
+        let cmd = "  cargo fmt &&  cargo test ; ls";
+        let segs = chain_segments(cmd);
+        assert_eq!(segs.len(), 3);
+        for seg in &segs {
+            assert_eq!(&cmd[seg.offset..seg.offset + seg.text.len()], seg.text);
+            assert!(!seg.is_piped());
+        }
+        assert_eq!(segs[1].text, "cargo test");
+    }
+
+    #[test]
+    fn test_chain_segments_pipe_flags() {
+        let segs = chain_segments("cargo test |& tail -5 | wc -l || echo failed");
+        let flags: Vec<_> = segs
+            .iter()
+            .map(|s| (s.text, s.piped_from, s.piped_to))
+            .collect();
+        assert_eq!(
+            flags,
+            vec![
+                ("cargo test", false, true),
+                ("tail -5", true, true),
+                ("wc -l", true, false),
+                ("echo failed", false, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chain_segments_escaped_operators() {
+        let segs = chain_segments(r"echo a \; b && git status");
+        assert_eq!(segs.len(), 2);
+        assert_eq!(segs[0].text, r"echo a \; b");
+    }
+
+    #[test]
+    fn test_rewrite_command_env_prefix() {
+        assert_eq!(
+            rewrite_command("RUST_LOG=debug cargo test"),
+            Some("RUST_LOG=debug rtk cargo test".to_string())
+        );
+        assert_eq!(rewrite_command("find . -name x"), None);
+    }
 }
@@ -7,6 +7,8 @@ pub enum FilterLevel {
     None,
     Minimal,
     Aggressive,
+    /// Structural outline: one line per item, with line numbers
+    Outline,
 }
 
 impl FromStr for FilterLevel {
@@ -17,6 +19,7 @@ impl FromStr for FilterLevel {
             "none" => Ok(FilterLevel::None),
             "minimal" => Ok(FilterLevel::Minimal),
             "aggressive" => Ok(FilterLevel::Aggressive),
+            "outline" => Ok(FilterLevel::Outline),
             _ => Err(format!("Unknown filter level: {}", s)),
         }
     }
@@ -28,6 +31,7 @@ impl std::fmt::Display for FilterLevel {
             FilterLevel::None => write!(f, "none"),
             FilterLevel::Minimal => write!(f, "minimal"),
             FilterLevel::Aggressive => write!(f, "aggressive"),
+            FilterLevel::Outline => write!(f, "outline"),
         }
     }
 }
@@ -306,62 +310,47 @@ impl FilterStrategy for AggressiveFilter {
     }
 }
 
+pub struct OutlineFilter;
+
+impl FilterStrategy for OutlineFilter {
+    fn filter(&self, content: &str, lang: &Language) -> String {
+        match crate::outline::outline(content, lang) {
+            Some(items) if !items.is_empty() => crate::outline::render(&items),
+            // No scanner for this language, or nothing structural in the file
+            _ => MinimalFilter.filter(content, lang),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "outline"
+    }
+}
+
 pub fn get_filter(level: FilterLevel) -> Box<dyn FilterStrategy> {
     match level {
         FilterLevel::None => Box::new(NoFilter),
         FilterLevel::Minimal => Box::new(MinimalFilter),
         FilterLevel::Aggressive => Box::new(AggressiveFilter),
+        FilterLevel::Outline => Box::new(OutlineFilter),
     }
 }
 
+/// Keep the first lines of `content` and put one marker where the rest was cut. The kept
+/// lines stay contiguous, so line numbers printed next to them stay right.
 pub fn smart_truncate(content: &str, max_lines: usize, _lang: &Language) -> String {
     let lines: Vec<&str> = content.lines().collect();
     if lines.len() <= max_lines {
         return content.to_string();
     }
 
-    let mut result = Vec::with_capacity(max_lines);
-    let mut kept_lines = 0;
-    let mut skipped_section = false;
-
-    for line in &lines {
-        let trimmed = line.trim();
-
-        // Always keep signatures and important structural elements
-        let is_important = FUNC_SIGNATURE.is_match(trimmed)
-            || IMPORT_PATTERN.is_match(trimmed)
-            || trimmed.starts_with("pub ")
-            || trimmed.starts_with("export ")
-            || trimmed == "}"
-            || trimmed == "{";
-
-        if is_important || kept_lines < max_lines / 2 {
-            if skipped_section {
-                result.push(format!(
-                    "    // ... {} lines omitted",
-                    lines.len() - kept_lines
-                ));
-                skipped_section = false;
-            }
-            result.push((*line).to_string());
-            kept_lines += 1;
-        } else {
-            skipped_section = true;
-        }
-
-        if kept_lines >= max_lines - 1 {
-            break;
-        }
-    }
-
-    if skipped_section || kept_lines < lines.len() {
-        result.push(format!(
-            "// ... {} more lines (total: {})",
-            lines.len() - kept_lines,
-            lines.len()
-        ));
-    }
-
+    // The marker takes one of the lines
+    let kept = max_lines.saturating_sub(1);
+    let mut result: Vec<String> = lines[..kept].iter().map(|l| l.to_string()).collect();
+    result.push(format!(
+        "// ... {} more lines (total: {})",
+        lines.len() - kept,
+        lines.len()
+    ));
     result.join("\n")
 }
 
@@ -380,6 +369,21 @@ mod tests {
             FilterLevel::from_str("aggressive").unwrap(),
             FilterLevel::Aggressive
         );
+        assert_eq!(
+            FilterLevel::from_str("outline").unwrap(),
+            FilterLevel::Outline
+        );
+    }
+
+    #[test]
+    fn test_outline_filter_falls_back_to_minimal() {
+        let rust = "// Comment\nfn main() {\n    run();\n}\n";
+        assert_eq!(OutlineFilter.filter(rust, &Language::Rust), "2  fn main()");
+        let shell = "# Comment\necho hi\n";
+        assert_eq!(
+            OutlineFilter.filter(shell, &Language::Shell),
+            MinimalFilter.filter(shell, &Language::Shell)
+        );
     }
 
     #[test]
@@ -402,4 +406,17 @@ fn main() {
         assert!(!result.contains("// This is a comment"));
         assert!(result.contains("fn main()"));
     }
+    #[test]
+    fn test_smart_truncate_emits_one_marker() {
+        let code: String = (0..12)
+            .map(|i| format!("fn f{}() {{\n    {};\n}}\n", i, i))
+            .collect();
+        let result = smart_truncate(&code, 10, &Language::Rust);
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines.len(), 10);
+        assert_eq!(lines[..3], ["fn f0() {", "    0;", "}"]);
+        assert_eq!(lines[9], "// ... 27 more lines (total: 36)");
+        assert_eq!(result.matches("// ...").count(), 1);
+        assert_eq!(smart_truncate(&code, 40, &Language::Rust), code);
+    }
 }
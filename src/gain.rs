@@ -65,6 +65,18 @@ pub fn run(
             ),
         );
         print_efficiency_meter(summary.avg_savings_pct); // added: visual meter
+        if summary.by_estimator.len() > 1 {
+            let mix = summary
+                .by_estimator
+                .iter()
+                .map(|(name, count)| format!("{} {}", name, count))
+                .collect::<Vec<_>>()
+                .join(", ");
+            print_kpi(
+                "Estimators",
+                format!("{} (mixed, totals not comparable)", mix),
+            );
+        }
         println!();
 
         if !summary.by_command.is_empty() {
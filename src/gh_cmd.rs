@@ -3,11 +3,13 @@
 //! Provides token-optimized alternatives to verbose `gh` commands.
 //! Focuses on extracting essential information from JSON outputs.
 
+use crate::ci_log;
+use crate::gh_review;
 use crate::git;
 use crate::json_cmd;
 use crate::tracking;
 use crate::utils::{ok_confirmation, truncate};
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde_json::Value;
@@ -21,6 +23,8 @@ lazy_static! {
     static ref HORIZONTAL_RULE_RE: Regex =
         Regex::new(r"(?m)^\s*(?:---+|\*\*\*+|___+)\s*$").unwrap();
     static ref MULTI_BLANK_RE: Regex = Regex::new(r"\n{3,}").unwrap();
+    static ref PR_URL_RE: Regex =
+        Regex::new(r"^https?://([^/]+)/([^/]+)/([^/]+)/pull/(\d+)").unwrap();
 }
 
 /// Filter markdown body to remove noise while preserving meaningful content.
@@ -132,6 +136,7 @@ fn run_pr(args: &[String], verbose: u8, ultra_compact: bool) -> Result<()> {
         "list" => list_prs(&args[1..], verbose, ultra_compact),
         "view" => view_pr(&args[1..], verbose, ultra_compact),
         "checks" => pr_checks(&args[1..], verbose, ultra_compact),
+        "review-comments" => pr_review_comments(&args[1..], verbose),
         "status" => pr_status(verbose, ultra_compact),
         "create" => pr_create(&args[1..], verbose),
         "merge" => pr_merge(&args[1..], verbose),
@@ -395,6 +400,141 @@ fn view_pr(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
     Ok(())
 }
 
+/// The PR `gh pr review-comments` reads, from `-R`/`--repo`, a PR URL or a number
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ReviewTarget {
+    /// Host from `-R HOST/OWNER/REPO` or the URL, for GitHub Enterprise
+    host: Option<String>,
+    /// (owner, repo); None means the repository of the current directory
+    repo: Option<(String, String)>,
+    number: Option<String>,
+}
+
+impl ReviewTarget {
+    /// `[HOST/]OWNER/REPO`, as `gh -R` takes it
+    fn repo_arg(&self) -> Option<String> {
+        let (owner, repo) = self.repo.as_ref()?;
+        Some(match &self.host {
+            Some(host) => format!("{}/{}/{}", host, owner, repo),
+            None => format!("{}/{}", owner, repo),
+        })
+    }
+}
+
+fn review_target(args: &[String]) -> Result<ReviewTarget> {
+    let mut target = ReviewTarget::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let repo = if arg == "-R" || arg == "--repo" {
+            let value = iter
+                .next()
+                .with_context(|| format!("{} needs OWNER/REPO", arg))?;
+            Some(value.as_str())
+        } else {
+            arg.strip_prefix("--repo=")
+        };
+        if let Some(repo) = repo {
+            match repo.split('/').collect::<Vec<_>>()[..] {
+                [owner, name] => target.repo = Some((owner.to_string(), name.to_string())),
+                [host, owner, name] => {
+                    target.host = Some(host.to_string());
+                    target.repo = Some((owner.to_string(), name.to_string()));
+                }
+                _ => bail!("expected [HOST/]OWNER/REPO, got {}", repo),
+            }
+        } else if let Some(caps) = PR_URL_RE.captures(arg) {
+            if &caps[1] != "github.com" {
+                target.host = Some(caps[1].to_string());
+            }
+            target.repo = Some((caps[2].to_string(), caps[3].to_string()));
+            target.number = Some(caps[4].to_string());
+        } else if !arg.starts_with('-') {
+            let number = arg.trim_start_matches('#');
+            if number.parse::<u64>().is_err() {
+                bail!("not a PR number or URL: {}", arg);
+            }
+            target.number = Some(number.to_string());
+        }
+    }
+    Ok(target)
+}
+
+/// Unresolved review threads by file and line; `--all` expands resolved and outdated ones
+fn pr_review_comments(args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let all = args.iter().any(|a| a == "--all");
+    let target = review_target(args)?;
+    let repo_arg = target.repo_arg();
+    let pr_number = match &target.number {
+        Some(number) => number.clone(),
+        // No number: the PR of the current branch, like `gh pr view`
+        None => {
+            let mut cmd = Command::new("gh");
+            cmd.args(["pr", "view", "--json", "number", "--jq", ".number"]);
+            if let Some(repo) = &repo_arg {
+                cmd.args(["-R", repo]);
+            }
+            let output = cmd.output().context("Failed to run gh pr view")?;
+            if !output.status.success() {
+                eprintln!("{}", String::from_utf8_lossy(&output.stderr).trim());
+                std::process::exit(output.status.code().unwrap_or(1));
+            }
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        }
+    };
+    let pr_label = match &repo_arg {
+        Some(repo) => format!("{}#{}", repo, pr_number),
+        None => format!("#{}", pr_number),
+    };
+
+    if verbose > 0 {
+        eprintln!("gh api graphql (review threads of {})", pr_label);
+    }
+    let mut cmd = Command::new("gh");
+    cmd.args(["api", "graphql"]);
+    if let Some(host) = &target.host {
+        cmd.args(["--hostname", host]);
+    }
+    match &target.repo {
+        // -f: an owner or repo named like a number must stay a string
+        Some((owner, repo)) => cmd.args([
+            "-f",
+            &format!("owner={}", owner),
+            "-f",
+            &format!("repo={}", repo),
+        ]),
+        None => cmd.args(["-F", "owner={owner}", "-F", "repo={repo}"]),
+    };
+    let output = cmd
+        .args(["-F", &format!("number={}", pr_number)])
+        .args(["-f", &format!("query={}", gh_review::QUERY)])
+        .output()
+        .context("Failed to run gh api graphql")?;
+    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+
+    if !output.status.success() && raw.trim().is_empty() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        eprintln!("{}", stderr.trim());
+        std::process::exit(output.status.code().unwrap_or(1));
+    }
+
+    let threads = gh_review::parse(&raw).context("Failed to parse review threads")?;
+    let config = crate::config::Config::load().unwrap_or_default();
+    let bots = gh_review::BotFilter::new(&config.gh.bot_authors)
+        .context("Invalid [gh] bot_authors pattern")?;
+    let rendered = gh_review::render(&threads, &bots, all);
+    println!("{}", rendered);
+
+    timer.track(
+        &format!("gh api graphql (review threads {})", pr_label),
+        &format!("rtk gh pr review-comments {}", args.join(" ")),
+        &raw,
+        &rendered,
+    );
+    Ok(())
+}
+
 fn pr_checks(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
@@ -790,12 +930,10 @@ fn list_runs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
 /// Flags like --log-failed, --log, and --json produce output that the filter
 /// would incorrectly strip.
 fn should_passthrough_run_view(extra_args: &[String]) -> bool {
-    extra_args
-        .iter()
-        .any(|a| a == "--log-failed" || a == "--log" || a == "--json")
+    extra_args.iter().any(|a| a == "--log" || a == "--json")
 }
 
-fn view_run(args: &[String], _verbose: u8) -> Result<()> {
+fn view_run(args: &[String], verbose: u8) -> Result<()> {
     if args.is_empty() {
         return Err(anyhow::anyhow!("Run ID required"));
     }
@@ -807,6 +945,9 @@ fn view_run(args: &[String], _verbose: u8) -> Result<()> {
     if should_passthrough_run_view(extra_args) {
         return run_passthrough_with_extra("gh", &["run", "view", run_id], extra_args);
     }
+    if extra_args.iter().any(|a| a == "--log-failed") {
+        return view_run_failed_log(run_id, extra_args, verbose);
+    }
 
     let timer = tracking::TimedExecution::start();
 
@@ -869,6 +1010,39 @@ fn view_run(args: &[String], _verbose: u8) -> Result<()> {
     Ok(())
 }
 
+/// `--log-failed`: the failing steps only, their output through the matching rtk filter
+fn view_run_failed_log(run_id: &str, extra_args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let output = Command::new("gh")
+        .args(["run", "view", run_id])
+        .args(extra_args)
+        .output()
+        .context("Failed to run gh run view --log-failed")?;
+    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        eprintln!("{}", stderr.trim());
+        std::process::exit(output.status.code().unwrap_or(1));
+    }
+
+    let sections = ci_log::parse(&raw);
+    if verbose > 0 {
+        eprintln!("{} log sections", sections.len());
+    }
+    let filtered = ci_log::render(&sections, verbose);
+    println!("{}", filtered);
+
+    timer.track(
+        &format!("gh run view {} {}", run_id, extra_args.join(" ")),
+        &format!("rtk gh run view {} {}", run_id, extra_args.join(" ")),
+        &raw,
+        &filtered,
+    );
+    Ok(())
+}
+
 fn run_repo(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<()> {
     // Parse subcommand (default to "view")
     let (subcommand, rest_args) = if args.is_empty() {
@@ -1226,6 +1400,43 @@ fn run_passthrough(cmd: &str, subcommand: &str, args: &[String]) -> Result<()> {
 mod tests {
     use super::*;
 
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_review_target_repo_flag() {
+        let target = review_target(&strings(&["-R", "owner/repo", "42"])).unwrap();
+        assert_eq!(target.repo, Some(("owner".to_string(), "repo".to_string())));
+        assert_eq!(target.number.as_deref(), Some("42"));
+        assert_eq!(target.repo_arg().as_deref(), Some("owner/repo"));
+
+        let target = review_target(&strings(&["#7", "--repo=ghe.corp/team/app", "--all"])).unwrap();
+        assert_eq!(target.host.as_deref(), Some("ghe.corp"));
+        assert_eq!(target.number.as_deref(), Some("7"));
+        assert_eq!(target.repo_arg().as_deref(), Some("ghe.corp/team/app"));
+
+        assert_eq!(
+            review_target(&strings(&["--all"])).unwrap(),
+            ReviewTarget::default()
+        );
+        assert!(review_target(&strings(&["-R"])).is_err());
+        assert!(review_target(&strings(&["-R", "repo-only", "1"])).is_err());
+        assert!(review_target(&strings(&["owner/repo"])).is_err());
+    }
+
+    #[test]
+    fn test_review_target_pr_url() {
+        let url = "https://github.com/rtk-ai/rtk/pull/118/files";
+        let target = review_target(&strings(&[url])).unwrap();
+        assert_eq!(target.host, None);
+        assert_eq!(target.repo, Some(("rtk-ai".to_string(), "rtk".to_string())));
+        assert_eq!(target.number.as_deref(), Some("118"));
+
+        let target = review_target(&strings(&["https://ghe.corp/team/app/pull/3"])).unwrap();
+        assert_eq!(target.repo_arg().as_deref(), Some("ghe.corp/team/app"));
+    }
+
     #[test]
     fn test_truncate() {
         assert_eq!(truncate("short", 10), "short");
@@ -1278,8 +1489,9 @@ mod tests {
     }
 
     #[test]
-    fn test_run_view_passthrough_log_failed() {
-        assert!(should_passthrough_run_view(&["--log-failed".into()]));
+    fn test_run_view_no_passthrough_log_failed() {
+        // --log-failed is filtered down to the failing steps by ci_log
+        assert!(!should_passthrough_run_view(&["--log-failed".into()]));
     }
 
     #[test]
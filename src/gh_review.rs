@@ -0,0 +1,456 @@
+//! Review threads of a pull request, for `rtk gh pr review-comments`.
+//!
+//! `gh pr view` has no review threads, so they come from the GraphQL API. Unresolved
+//! threads are shown grouped by file and ordered by line, each with the tail of its
+//! diff hunk and the conversation; resolved and outdated threads collapse to one
+//! `path:line` list each, and comments from bots are dropped.
+use anyhow::{bail, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+
+lazy_static! {
+    static ref HTML_COMMENT: Regex = Regex::new(r"(?s)<!--.*?-->").unwrap();
+}
+
+/// `gh api graphql` query; `owner` and `repo` are filled in by gh from the current repo
+pub const QUERY: &str = "query($owner: String!, $repo: String!, $number: Int!) {
+  repository(owner: $owner, name: $repo) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 100) {
+        pageInfo { hasNextPage }
+        nodes {
+          isResolved isOutdated path line originalLine startLine
+          comments(first: 50) { nodes { author { login } body diffHunk } }
+        }
+      }
+    }
+  }
+}";
+
+/// Diff hunk lines shown above a thread, counted back from the commented line
+const HUNK_CONTEXT_LINES: usize = 3;
+/// Lines kept from each comment body
+const MAX_BODY_LINES: usize = 6;
+/// Longest comment or hunk line
+const MAX_LINE_CHARS: usize = 160;
+/// `path:line` entries listed per collapsed group
+const MAX_COLLAPSED: usize = 10;
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    data: Option<Data>,
+    #[serde(default)]
+    errors: Vec<GraphqlError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphqlError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Data {
+    repository: Option<Repository>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Repository {
+    pull_request: Option<PullRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PullRequest {
+    review_threads: Connection<ReviewThread>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Connection<T> {
+    nodes: Vec<T>,
+    #[serde(default)]
+    page_info: PageInfo,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfo {
+    has_next_page: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewThread {
+    pub is_resolved: bool,
+    pub is_outdated: bool,
+    pub path: String,
+    /// Line in the current diff; null once the thread is outdated
+    pub line: Option<u32>,
+    pub original_line: Option<u32>,
+    /// First line of a multi-line comment
+    pub start_line: Option<u32>,
+    comments: Connection<Comment>,
+}
+
+impl ReviewThread {
+    pub fn comments(&self) -> &[Comment] {
+        &self.comments.nodes
+    }
+
+    fn line(&self) -> Option<u32> {
+        self.line.or(self.original_line)
+    }
+
+    fn location(&self) -> String {
+        match (self.start_line, self.line()) {
+            (Some(start), Some(end)) if start < end => format!("L{}-{}", start, end),
+            (_, Some(line)) => format!("L{}", line),
+            _ => "file".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    /// Null when the account was deleted
+    author: Option<Author>,
+    pub body: String,
+    pub diff_hunk: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Author {
+    login: String,
+}
+
+impl Comment {
+    pub fn author(&self) -> &str {
+        self.author.as_ref().map_or("ghost", |a| a.login.as_str())
+    }
+}
+
+/// The review threads of a pull request
+#[derive(Debug)]
+pub struct Threads {
+    pub threads: Vec<ReviewThread>,
+    /// More than the first 100 threads exist
+    pub truncated: bool,
+}
+
+/// Parse the `gh api graphql` response for [`QUERY`]
+pub fn parse(json: &str) -> Result<Threads> {
+    let response: Response = serde_json::from_str(json)?;
+    if let Some(error) = response.errors.first() {
+        bail!("GraphQL error: {}", error.message);
+    }
+    let Some(pr) = response
+        .data
+        .and_then(|d| d.repository)
+        .and_then(|r| r.pull_request)
+    else {
+        bail!("pull request not found");
+    };
+    Ok(Threads {
+        truncated: pr.review_threads.page_info.has_next_page,
+        threads: pr.review_threads.nodes,
+    })
+}
+
+/// Matches comment authors against `[gh] bot_authors` globs (`*` wildcard, any case)
+pub struct BotFilter {
+    patterns: Vec<Regex>,
+}
+
+impl BotFilter {
+    pub fn new(patterns: &[String]) -> Result<Self> {
+        let patterns = patterns
+            .iter()
+            .map(|p| {
+                let re = regex::escape(p).replace(r"\*", ".*");
+                Regex::new(&format!("(?i)^{}$", re))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(BotFilter { patterns })
+    }
+
+    pub fn is_bot(&self, login: &str) -> bool {
+        self.patterns.iter().any(|p| p.is_match(login))
+    }
+}
+
+/// Unresolved threads in full, the rest collapsed. `all` shows resolved and outdated
+/// threads in full too.
+pub fn render(threads: &Threads, bots: &BotFilter, all: bool) -> String {
+    let mut bot_comments = 0;
+    let mut open: Vec<(&ReviewThread, Vec<&Comment>)> = Vec::new();
+    let mut resolved = Vec::new();
+    let mut outdated = Vec::new();
+
+    for thread in &threads.threads {
+        let (human, bot): (Vec<&Comment>, Vec<&Comment>) = thread
+            .comments()
+            .iter()
+            .partition(|c| !bots.is_bot(c.author()));
+        bot_comments += bot.len();
+        // A thread a bot started is the bot's, whoever replied
+        let started_by_bot = thread
+            .comments()
+            .first()
+            .is_some_and(|c| bots.is_bot(c.author()));
+        if human.is_empty() || started_by_bot {
+            bot_comments += human.len();
+            continue;
+        }
+        if all || !(thread.is_resolved || thread.is_outdated) {
+            open.push((thread, human));
+        } else if thread.is_resolved {
+            resolved.push(thread);
+        } else {
+            outdated.push(thread);
+        }
+    }
+
+    let mut files: Vec<&str> = Vec::new();
+    for (thread, _) in &open {
+        if !files.contains(&thread.path.as_str()) {
+            files.push(&thread.path);
+        }
+    }
+    let unresolved: Vec<&ReviewThread> = open
+        .iter()
+        .map(|(t, _)| *t)
+        .filter(|t| !t.is_resolved && !t.is_outdated)
+        .collect();
+    let mut unresolved_files: Vec<&str> = unresolved.iter().map(|t| t.path.as_str()).collect();
+    unresolved_files.sort_unstable();
+    unresolved_files.dedup();
+
+    let mut header = if unresolved.is_empty() {
+        "💬 No unresolved review threads".to_string()
+    } else {
+        format!(
+            "💬 {} unresolved thread{} in {} file{}",
+            unresolved.len(),
+            plural(unresolved.len()),
+            unresolved_files.len(),
+            plural(unresolved_files.len())
+        )
+    };
+    let mut hidden = Vec::new();
+    if !resolved.is_empty() {
+        hidden.push(format!("{} resolved", resolved.len()));
+    }
+    if !outdated.is_empty() {
+        hidden.push(format!("{} outdated", outdated.len()));
+    }
+    if bot_comments > 0 {
+        hidden.push(format!(
+            "{} bot comment{} hidden",
+            bot_comments,
+            plural(bot_comments)
+        ));
+    }
+    if !hidden.is_empty() {
+        header.push_str(&format!(" ({})", hidden.join(", ")));
+    }
+    let mut out = vec![header];
+
+    for file in files {
+        out.push(format!("📄 {}", file));
+        let mut in_file: Vec<&(&ReviewThread, Vec<&Comment>)> =
+            open.iter().filter(|(t, _)| t.path == file).collect();
+        in_file.sort_by_key(|(t, _)| t.line());
+        for (thread, comments) in in_file {
+            render_thread(&mut out, thread, comments);
+        }
+    }
+
+    out.extend(collapsed("✓ Resolved", &resolved));
+    out.extend(collapsed("⌛ Outdated", &outdated));
+    if threads.truncated {
+        out.push("... more than 100 threads; only the first 100 are shown".to_string());
+    }
+    out.join("\n")
+}
+
+fn render_thread(out: &mut Vec<String>, thread: &ReviewThread, comments: &[&Comment]) {
+    let Some(first) = comments.first() else {
+        return;
+    };
+    let mut title = format!("  {} {}", thread.location(), first.author());
+    if thread.is_resolved {
+        title.push_str(" [resolved]");
+    }
+    if thread.is_outdated {
+        title.push_str(" [outdated]");
+    }
+    out.push(title);
+
+    // GitHub's hunk ends at the commented line; its tail is the context that matters
+    let hunk: Vec<&str> = first
+        .diff_hunk
+        .lines()
+        .filter(|l| !l.starts_with("@@"))
+        .collect();
+    for line in &hunk[hunk.len().saturating_sub(HUNK_CONTEXT_LINES)..] {
+        out.push(format!(
+            "    │{}",
+            crate::utils::truncate(line, MAX_LINE_CHARS)
+        ));
+    }
+
+    for (i, comment) in comments.iter().enumerate() {
+        let body = body_lines(&comment.body);
+        if i == 0 {
+            out.extend(body.iter().map(|l| format!("    {}", l)));
+        } else {
+            let mut lines = body.iter();
+            let head = lines.next().map_or("", String::as_str);
+            out.push(format!("    ↳ {}: {}", comment.author(), head));
+            out.extend(lines.map(|l| format!("      {}", l)));
+        }
+    }
+}
+
+/// Comment body without HTML comments and blank lines, capped to [`MAX_BODY_LINES`]
+fn body_lines(body: &str) -> Vec<String> {
+    let body = HTML_COMMENT.replace_all(body, "");
+    let lines: Vec<&str> = body
+        .lines()
+        .map(str::trim_end)
+        .filter(|l| !l.trim().is_empty())
+        .collect();
+    let mut kept: Vec<String> = lines
+        .iter()
+        .take(MAX_BODY_LINES)
+        .map(|l| crate::utils::truncate(l, MAX_LINE_CHARS))
+        .collect();
+    if lines.len() > MAX_BODY_LINES {
+        kept.push(format!("... +{} lines", lines.len() - MAX_BODY_LINES));
+    }
+    kept
+}
+
+fn collapsed(label: &str, threads: &[&ReviewThread]) -> Option<String> {
+    if threads.is_empty() {
+        return None;
+    }
+    let mut places: Vec<String> = threads
+        .iter()
+        .take(MAX_COLLAPSED)
+        .map(|t| match t.line() {
+            Some(line) => format!("{}:{}", t.path, line),
+            None => t.path.clone(),
+        })
+        .collect();
+    if threads.len() > MAX_COLLAPSED {
+        places.push(format!("+{} more", threads.len() - MAX_COLLAPSED));
+    }
+    Some(format!("{}: {}", label, places.join(", ")))
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/gh_review/threads.json");
+
+    fn bots() -> BotFilter {
+        BotFilter::new(&["*[bot]".to_string(), "github-actions".to_string()]).unwrap()
+    }
+
+    #[test]
+    fn test_parse_fixture() {
+        let threads = parse(FIXTURE).unwrap();
+        assert!(!threads.truncated);
+        assert_eq!(threads.threads.len(), 6);
+        let first = &threads.threads[0];
+        assert_eq!(first.path, "src/server.rs");
+        assert_eq!(first.line, Some(42));
+        assert_eq!(first.comments().len(), 3);
+        assert_eq!(first.comments()[2].author(), "codecov[bot]");
+        assert_eq!(threads.threads[2].comments()[0].author(), "ghost");
+        assert!(threads.threads[3].is_resolved);
+        assert_eq!(threads.threads[4].line, None);
+    }
+
+    #[test]
+    fn test_parse_graphql_error() {
+        let json =
+            r#"{"data": null, "errors": [{"message": "Could not resolve to a PullRequest"}]}"#;
+        let err = parse(json).unwrap_err();
+        assert!(err.to_string().contains("Could not resolve"));
+    }
+
+    #[test]
+    fn test_bot_filter_globs() {
+        let bots = bots();
+        assert!(bots.is_bot("codecov[bot]"));
+        assert!(bots.is_bot("GitHub-Actions"));
+        assert!(!bots.is_bot("alice"));
+        assert!(!bots.is_bot("github-actions-fan"));
+    }
+
+    #[test]
+    fn test_render_fixture() {
+        let threads = parse(FIXTURE).unwrap();
+        assert_eq!(
+            render(&threads, &bots(), false),
+            "\
+💬 3 unresolved threads in 2 files (1 resolved, 1 outdated, 2 bot comments hidden)
+📄 src/server.rs
+  L10-12 carol
+    │ use std::env;
+    │+use std::fs;
+    │+use std::io;
+    Nit: these imports are unused.
+    ```suggestion
+    use std::env;
+    ```
+  L42 alice
+    │+    let port = env::var(\"PORT\")
+    │+        .unwrap()
+    │+        .parse::<u16>()?;
+    This unwrap panics when `PORT` is unset.
+    Could we fall back to 8080?
+    ↳ bob: Agreed, and log which one we picked.
+📄 README.md
+  L3 ghost
+    │ # Server
+    │-Receive requests.
+    │+Recieve requests.
+    Typo: recieve
+✓ Resolved: src/lib.rs:7
+⌛ Outdated: src/old.rs:19"
+        );
+    }
+
+    #[test]
+    fn test_render_all_expands_collapsed() {
+        let threads = parse(FIXTURE).unwrap();
+        let rendered = render(&threads, &bots(), true);
+        assert!(rendered.starts_with("💬 3 unresolved threads in 2 files (2 bot comments hidden)"));
+        assert!(rendered.contains("  L7 alice [resolved]\n"));
+        assert!(rendered.contains("  L19 bob [outdated]\n"));
+        assert!(!rendered.contains("Resolved:"));
+    }
+
+    #[test]
+    fn test_body_lines_capped() {
+        let body = (1..=9).map(|i| format!("line {}", i)).collect::<Vec<_>>();
+        let lines = body_lines(&body.join("\n\n"));
+        assert_eq!(lines.len(), MAX_BODY_LINES + 1);
+        assert_eq!(lines.last().unwrap(), "... +3 lines");
+    }
+}
@@ -1,7 +1,25 @@
+use crate::blame;
+use crate::conflict::{self, ConflictedFile, Operation};
+use crate::diff_summary;
+use crate::log_summary;
+use crate::parser::{
+    json_mode, render_json, truncate_output, GitCommit, GitResult, OutputParser, ParseResult,
+};
 use crate::tracking;
 use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
 use std::ffi::OsString;
-use std::process::Command;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+lazy_static! {
+    /// Per-file diffstat line printed by merge and pull: ` src/main.rs | 12 +++---`
+    static ref STAT_LINE: Regex = Regex::new(r"^\s*\S.* \|\s+(\d+ ?[+-]*|Bin .*)$").unwrap();
+}
 
 #[derive(Debug, Clone)]
 pub enum GitCommand {
@@ -17,9 +35,18 @@ pub enum GitCommand {
     Fetch,
     Stash { subcommand: Option<String> },
     Worktree,
+    Blame,
+    Merge,
+    Rebase,
 }
 
 pub fn run(cmd: GitCommand, args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()> {
+    if json_mode() {
+        if let Some(sub) = json_subcommand(&cmd) {
+            return run_json(sub, args, verbose);
+        }
+    }
+
     match cmd {
         GitCommand::Diff => run_diff(args, max_lines, verbose),
         GitCommand::Log => run_log(args, max_lines, verbose),
@@ -33,12 +60,233 @@ pub fn run(cmd: GitCommand, args: &[String], max_lines: Option<usize>, verbose:
         GitCommand::Fetch => run_fetch(args, verbose),
         GitCommand::Stash { subcommand } => run_stash(subcommand.as_deref(), args, verbose),
         GitCommand::Worktree => run_worktree(args, verbose),
+        GitCommand::Blame => run_blame(args, verbose),
+        GitCommand::Merge => run_sequencer("merge", args, verbose),
+        GitCommand::Rebase => run_sequencer("rebase", args, verbose),
+    }
+}
+
+/// Field separator for the machine-readable `--format` used in JSON mode
+const JSON_LOG_FORMAT: &str = "--format=%H%x1f%an%x1f%aI%x1f%s";
+
+/// Subcommands with a canonical GitResult in `--output json` mode; the rest print text.
+fn json_subcommand(cmd: &GitCommand) -> Option<&'static str> {
+    match cmd {
+        GitCommand::Status => Some("status"),
+        GitCommand::Diff => Some("diff"),
+        GitCommand::Show => Some("show"),
+        GitCommand::Log => Some("log"),
+        _ => None,
+    }
+}
+
+/// Run a git subcommand with machine-readable flags and print the JSON envelope.
+fn run_json(sub: &str, args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let mut cmd = Command::new("git");
+    cmd.arg(sub);
+    match sub {
+        "status" => {
+            cmd.args(["--porcelain", "-b"]);
+        }
+        "diff" => {
+            cmd.arg("--numstat");
+        }
+        "show" => {
+            cmd.arg("--numstat").arg(JSON_LOG_FORMAT);
+        }
+        _ => {
+            cmd.arg(JSON_LOG_FORMAT);
+            if !args.iter().any(|a| is_limit_flag(a)) {
+                cmd.arg("-10");
+            }
+        }
+    }
+    // The parsers read JSON_LOG_FORMAT only, so a user format flag must not replace it
+    cmd.args(json_git_args(sub, args));
+
+    if verbose > 0 {
+        eprintln!("Running: git {} {} (json)", sub, args.join(" "));
+    }
+
+    let output = cmd
+        .output()
+        .with_context(|| format!("Failed to run git {}", sub))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let exit_code = output.status.code().unwrap_or(1);
+    let raw = if output.status.success() {
+        stdout.to_string()
+    } else {
+        format!("{}{}", stdout, stderr)
+    };
+
+    let command = format!("git {} {}", sub, args.join(" "));
+    let json = match sub {
+        "status" => render_json::<GitStatusParser>(&command, &raw, exit_code),
+        "diff" => render_json::<GitDiffParser>(&command, &raw, exit_code),
+        "show" => render_json::<GitShowParser>(&command, &raw, exit_code),
+        _ => render_json::<GitLogParser>(&command, &raw, exit_code),
+    };
+    println!("{}", json);
+
+    timer.track(
+        &command,
+        &format!("rtk git {} {} --output json", sub, args.join(" ")),
+        &raw,
+        &json,
+    );
+
+    if !output.status.success() {
+        std::process::exit(exit_code);
+    }
+    Ok(())
+}
+
+/// `args` for `run_json`, without format flags for the subcommands that print commits
+fn json_git_args<'a>(sub: &str, args: &'a [String]) -> Vec<&'a String> {
+    let prints_commits = matches!(sub, "log" | "show");
+    args.iter()
+        .filter(|arg| !(prints_commits && is_format_flag(arg)))
+        .collect()
+}
+
+/// Flags choosing how `git log` prints each commit
+fn is_format_flag(arg: &str) -> bool {
+    arg.starts_with("--oneline") || arg.starts_with("--pretty") || arg.starts_with("--format")
+}
+
+fn is_limit_flag(arg: &str) -> bool {
+    (arg.starts_with('-') && arg.chars().nth(1).is_some_and(|c| c.is_ascii_digit()))
+        || arg == "-n"
+        || arg.starts_with("--max-count")
+}
+
+/// Parser for `git status --porcelain -b`
+pub struct GitStatusParser;
+
+impl OutputParser for GitStatusParser {
+    type Output = GitResult;
+
+    fn parse(input: &str) -> ParseResult<GitResult> {
+        let mut files_changed = 0;
+        for line in input.lines().filter(|l| !l.is_empty()) {
+            if line.starts_with("## ") {
+                continue;
+            }
+            // Porcelain v1 lines are `XY path`
+            if line.len() < 4 || line.as_bytes()[2] != b' ' {
+                return ParseResult::Passthrough(truncate_output(input, 500));
+            }
+            files_changed += 1;
+        }
+        ParseResult::Full(GitResult {
+            operation: "status".to_string(),
+            files_changed,
+            insertions: 0,
+            deletions: 0,
+            commits: vec![],
+        })
+    }
+}
+
+/// Parser for `git diff --numstat`
+pub struct GitDiffParser;
+
+impl OutputParser for GitDiffParser {
+    type Output = GitResult;
+
+    fn parse(input: &str) -> ParseResult<GitResult> {
+        parse_git_records("diff", input)
+    }
+}
+
+/// Parser for `git show --numstat` with the JSON log format
+pub struct GitShowParser;
+
+impl OutputParser for GitShowParser {
+    type Output = GitResult;
+
+    fn parse(input: &str) -> ParseResult<GitResult> {
+        parse_git_records("show", input)
+    }
+}
+
+/// Parser for `git log` with the JSON log format
+pub struct GitLogParser;
+
+impl OutputParser for GitLogParser {
+    type Output = GitResult;
+
+    fn parse(input: &str) -> ParseResult<GitResult> {
+        parse_git_records("log", input)
+    }
+}
+
+/// Parse a mix of `JSON_LOG_FORMAT` commit lines and `--numstat` lines.
+fn parse_git_records(operation: &str, input: &str) -> ParseResult<GitResult> {
+    let mut result = GitResult {
+        operation: operation.to_string(),
+        files_changed: 0,
+        insertions: 0,
+        deletions: 0,
+        commits: vec![],
+    };
+    let mut unrecognized = 0;
+
+    for line in input.lines().filter(|l| !l.trim().is_empty()) {
+        if line.contains('\x1f') {
+            let fields: Vec<&str> = line.splitn(4, '\x1f').collect();
+            if fields.len() == 4 {
+                result.commits.push(GitCommit {
+                    hash: fields[0].to_string(),
+                    author: fields[1].to_string(),
+                    timestamp: Some(fields[2].to_string()),
+                    message: fields[3].to_string(),
+                });
+                continue;
+            }
+        }
+
+        // numstat: `added<TAB>deleted<TAB>path`, `-` for binary files
+        let cols: Vec<&str> = line.splitn(3, '\t').collect();
+        let count = |c: &str| {
+            if c == "-" {
+                Some(0)
+            } else {
+                c.parse::<usize>().ok()
+            }
+        };
+        match (cols.as_slice(), cols.first().and_then(|c| count(c))) {
+            ([_, deleted, _], Some(added)) if count(deleted).is_some() => {
+                result.files_changed += 1;
+                result.insertions += added;
+                result.deletions += count(deleted).unwrap_or(0);
+            }
+            _ => unrecognized += 1,
+        }
+    }
+
+    if unrecognized > 0 {
+        if result.files_changed == 0 && result.commits.is_empty() {
+            return ParseResult::Passthrough(truncate_output(input, 500));
+        }
+        return ParseResult::Degraded(
+            result,
+            vec![format!("{} unrecognized lines skipped", unrecognized)],
+        );
     }
+    ParseResult::Full(result)
 }
 
 fn run_diff(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
+    if let Some((file, git_args)) = summary_args(args)? {
+        return run_diff_summary(&git_args, file.as_deref(), max_lines, verbose, timer);
+    }
+
     // Check if user wants stat output
     let wants_stat = args
         .iter()
@@ -123,6 +371,175 @@ fn run_diff(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
     Ok(())
 }
 
+/// Flags that replace the patch with a stat or file list, which the summary can't read
+const DIFF_STAT_FLAGS: &[&str] = &[
+    "--stat",
+    "--shortstat",
+    "--numstat",
+    "--name-only",
+    "--name-status",
+];
+
+/// Split rtk's `--summarize` and `--file <path>` (which implies it) from the git
+/// arguments; None when neither is present. [`DIFF_STAT_FLAGS`] are dropped.
+fn summary_args(args: &[String]) -> Result<Option<(Option<String>, Vec<String>)>> {
+    let mut summarize = false;
+    let mut file = None;
+    let mut git_args = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let flag = arg.split('=').next().unwrap_or(arg);
+        if arg == "--" {
+            git_args.push(arg.clone());
+            git_args.extend(iter.by_ref().cloned());
+        } else if DIFF_STAT_FLAGS.contains(&flag) {
+            continue;
+        } else if arg == "--summarize" {
+            summarize = true;
+        } else if arg == "--file" {
+            let path = iter.next().context("--file needs a path")?;
+            file = Some(path.clone());
+        } else if let Some(path) = arg.strip_prefix("--file=") {
+            file = Some(path.to_string());
+        } else {
+            git_args.push(arg.clone());
+        }
+    }
+    Ok((summarize || file.is_some()).then_some((file, git_args)))
+}
+
+fn run_diff_summary(
+    args: &[String],
+    file: Option<&str>,
+    max_lines: Option<usize>,
+    verbose: u8,
+    timer: tracking::TimedExecution,
+) -> Result<()> {
+    let mut cmd = Command::new("git");
+    cmd.arg("diff").args(args);
+    let output = cmd.output().context("Failed to run git diff")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        eprintln!("{}", stderr);
+        std::process::exit(output.status.code().unwrap_or(1));
+    }
+    let raw = String::from_utf8_lossy(&output.stdout);
+
+    let config = crate::config::Config::load().unwrap_or_default();
+    let generated = diff_summary::generated_matcher(&config.diff.generated)?;
+    let options = diff_summary::SummaryOptions {
+        budget: max_lines.unwrap_or(100),
+        file,
+        generated: &generated,
+    };
+    let files = diff_summary::parse(&raw);
+    let contents = new_side_contents(&diff_summary::files_to_label(&files, &options));
+    if verbose > 0 {
+        eprintln!(
+            "{} files in diff, {} with symbol labels",
+            files.len(),
+            contents.len()
+        );
+    }
+
+    let summary = if files.is_empty() {
+        String::new()
+    } else {
+        diff_summary::summarize(&files, &contents, &options)
+    };
+    println!("{}", summary);
+
+    timer.track(
+        &format!("git diff {}", args.join(" ")),
+        &format!("rtk git diff --summarize {}", args.join(" ")),
+        &raw,
+        &summary,
+    );
+    Ok(())
+}
+
+/// New-side contents of `files` by path, for hunk labels: blobs named on the diff's
+/// `index` lines, read in one `git cat-file --batch`, else the working-tree copy
+/// (a working-tree diff names blobs that were never written to the object store)
+fn new_side_contents(files: &[&diff_summary::FileDiff]) -> HashMap<String, String> {
+    let mut contents = HashMap::new();
+    if files.is_empty() {
+        return contents;
+    }
+
+    let with_blob: Vec<&diff_summary::FileDiff> = files
+        .iter()
+        .copied()
+        .filter(|f| f.new_blob.is_some())
+        .collect();
+    let names: String = with_blob
+        .iter()
+        .filter_map(|f| f.new_blob.as_deref())
+        .map(|id| format!("{}\n", id))
+        .collect();
+    if let Ok(mut child) = Command::new("git")
+        .args(["cat-file", "--batch"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(names.as_bytes());
+        }
+        if let Ok(output) = child.wait_with_output() {
+            let blobs = parse_cat_file_batch(&output.stdout);
+            for (file, blob) in with_blob.iter().zip(blobs) {
+                if let Some(blob) = blob {
+                    contents.insert(file.path.clone(), blob);
+                }
+            }
+        }
+    }
+
+    if files.iter().all(|f| contents.contains_key(&f.path)) {
+        return contents;
+    }
+    let root = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+        .ok()
+        .map(|o| PathBuf::from(String::from_utf8_lossy(&o.stdout).trim()))
+        .unwrap_or_default();
+    for file in files {
+        if !contents.contains_key(&file.path) {
+            if let Ok(content) = fs::read_to_string(root.join(&file.path)) {
+                contents.insert(file.path.clone(), content);
+            }
+        }
+    }
+    contents
+}
+
+/// `git cat-file --batch` output, one entry per requested object in request order:
+/// the blob's text, or None for a missing object
+fn parse_cat_file_batch(out: &[u8]) -> Vec<Option<String>> {
+    let mut blobs = Vec::new();
+    let mut pos = 0;
+    while let Some(nl) = out[pos..].iter().position(|&b| b == b'\n') {
+        let header = String::from_utf8_lossy(&out[pos..pos + nl]).to_string();
+        pos += nl + 1;
+        // "<id> <type> <size>", or "<name> missing"
+        let size = match header.split(' ').collect::<Vec<_>>()[..] {
+            [_, _, size] => size.parse::<usize>().ok(),
+            _ => None,
+        };
+        let Some(size) = size else {
+            blobs.push(None);
+            continue;
+        };
+        let end = (pos + size).min(out.len());
+        blobs.push(Some(String::from_utf8_lossy(&out[pos..end]).to_string()));
+        pos = (end + 1).min(out.len());
+    }
+    blobs
+}
+
 fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
@@ -298,18 +715,19 @@ pub(crate) fn compact_diff(diff: &str, max_lines: usize) -> String {
 fn run_log(args: &[String], _max_lines: Option<usize>, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
+    if args.iter().any(|arg| arg == "--summary") {
+        let git_args: Vec<String> = args.iter().filter(|a| *a != "--summary").cloned().collect();
+        return run_log_summary(&git_args, verbose, timer);
+    }
+
     let mut cmd = Command::new("git");
     cmd.arg("log");
 
     // Check if user provided format flags
-    let has_format_flag = args.iter().any(|arg| {
-        arg.starts_with("--oneline") || arg.starts_with("--pretty") || arg.starts_with("--format")
-    });
+    let has_format_flag = args.iter().any(|arg| is_format_flag(arg));
 
     // Check if user provided limit flag
-    let has_limit_flag = args.iter().any(|arg| {
-        arg.starts_with('-') && arg.chars().nth(1).map_or(false, |c| c.is_ascii_digit())
-    });
+    let has_limit_flag = args.iter().any(|arg| is_limit_flag(arg));
 
     // Apply RTK defaults only if user didn't specify them
     if !has_format_flag {
@@ -371,6 +789,57 @@ fn run_log(args: &[String], _max_lines: Option<usize>, verbose: u8) -> Result<()
     Ok(())
 }
 
+/// Commits `rtk git log --summary` covers when no range or limit is given
+const DEFAULT_SUMMARY_COMMITS: usize = 100;
+
+fn run_log_summary(args: &[String], verbose: u8, timer: tracking::TimedExecution) -> Result<()> {
+    let git_args = summary_log_args(args);
+    if verbose > 0 {
+        eprintln!("Running: git {}", git_args.join(" "));
+    }
+    let mut cmd = Command::new("git");
+    cmd.args(&git_args);
+
+    let output = cmd.output().context("Failed to run git log")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        eprintln!("{}", stderr);
+        std::process::exit(output.status.code().unwrap_or(1));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let commits = log_summary::parse(&stdout);
+    if verbose > 0 {
+        eprintln!("{} commits in range", commits.len());
+    }
+    let summary = log_summary::summarize(&commits);
+    println!("{}", summary);
+
+    timer.track(
+        &format!("git {}", git_args.join(" ")),
+        &format!("rtk git log --summary {}", args.join(" ")),
+        &stdout,
+        &summary,
+    );
+    Ok(())
+}
+
+/// `git log` arguments for `--summary`: the user's format flags are dropped, since
+/// [`log_summary::parse`] only reads its own format
+fn summary_log_args(args: &[String]) -> Vec<String> {
+    let mut git_args: Vec<String> = ["log", "--no-color", "--numstat", log_summary::LOG_FORMAT]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    // Without a range, summarize recent history rather than all of it
+    let has_range = args.iter().any(|arg| arg.contains(".."));
+    if !has_range && !args.iter().any(|arg| is_limit_flag(arg)) {
+        git_args.push(format!("-{}", DEFAULT_SUMMARY_COMMITS));
+    }
+    git_args.extend(args.iter().filter(|arg| !is_format_flag(arg)).cloned());
+    git_args
+}
+
 /// Filter git log output: truncate long messages, cap lines
 fn filter_log_output(output: &str, limit: usize) -> String {
     let lines: Vec<&str> = output.lines().collect();
@@ -417,6 +886,7 @@ fn format_status_output(porcelain: &str) -> String {
     let mut staged_files = Vec::new();
     let mut modified_files = Vec::new();
     let mut untracked_files = Vec::new();
+    let mut conflict_files = Vec::new();
 
     for line in lines.iter().skip(1) {
         if line.len() < 3 {
@@ -425,12 +895,18 @@ fn format_status_output(porcelain: &str) -> String {
         let status = line.get(0..2).unwrap_or("  ");
         let file = line.get(3..).unwrap_or("");
 
+        // Unmerged paths (UU, AA, DU, ...) are neither staged nor modified
+        if matches!(status, "UU" | "AA" | "DD" | "AU" | "UA" | "DU" | "UD") {
+            conflicts += 1;
+            conflict_files.push(file);
+            continue;
+        }
+
         match status.chars().next().unwrap_or(' ') {
             'M' | 'A' | 'D' | 'R' | 'C' => {
                 staged += 1;
                 staged_files.push(file);
             }
-            'U' => conflicts += 1,
             _ => {}
         }
 
@@ -480,14 +956,23 @@ fn format_status_output(porcelain: &str) -> String {
     }
 
     if conflicts > 0 {
-        output.push_str(&format!("⚠️  Conflicts: {} files\n", conflicts));
+        output.push_str(&format!(
+            "⚠️  Conflicts in {}:\n",
+            log_summary::plural(conflicts, "file")
+        ));
+        for f in conflict_files.iter().take(5) {
+            output.push_str(&format!("   {}\n", f));
+        }
+        if conflict_files.len() > 5 {
+            output.push_str(&format!("   ... +{} more\n", conflict_files.len() - 5));
+        }
     }
 
     output.trim_end().to_string()
 }
 
 /// Minimal filtering for git status with user-provided args
-fn filter_status_with_args(output: &str) -> String {
+pub(crate) fn filter_status_with_args(output: &str) -> String {
     let mut result = Vec::new();
 
     for line in output.lines() {
@@ -574,7 +1059,22 @@ fn run_status(args: &[String], verbose: u8) -> Result<()> {
     let formatted = if !stderr.is_empty() && stderr.contains("not a git repository") {
         "Not a git repository".to_string()
     } else {
-        format_status_output(&stdout)
+        let status = format_status_output(&stdout);
+        match repo_dirs().and_then(|(_, git_dir)| Operation::detect(&git_dir)) {
+            Some(op) => {
+                let conflicted: Vec<String> = conflict::unmerged(&stdout)
+                    .into_iter()
+                    .map(|(_, path)| path)
+                    .collect();
+                format!(
+                    "{}\n{}\n{}",
+                    op.banner(),
+                    status,
+                    op.next_steps(&conflicted).join("\n")
+                )
+            }
+            None => status,
+        }
     };
 
     println!("{}", formatted);
@@ -1299,6 +1799,241 @@ fn filter_worktree_list(output: &str) -> String {
     result.join("\n")
 }
 
+/// Blame grouped into per-commit line ranges with a commit legend
+fn run_blame(args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let mut fold_older = None;
+    let mut git_args = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--fold-older" {
+            let age = iter
+                .next()
+                .context("--fold-older needs an age (90d, 6m, 2y)")?;
+            fold_older = Some(blame::parse_age(age)?);
+        } else if let Some(age) = arg.strip_prefix("--fold-older=") {
+            fold_older = Some(blame::parse_age(age)?);
+        } else {
+            git_args.push(arg.clone());
+        }
+    }
+
+    // Machine-readable formats asked for explicitly pass through untouched
+    let wants_raw = git_args.iter().any(|arg| {
+        matches!(
+            arg.as_str(),
+            "-p" | "--porcelain" | "--line-porcelain" | "--incremental"
+        )
+    });
+    let mut cmd = Command::new("git");
+    cmd.arg("blame");
+    if !wants_raw {
+        cmd.arg("--porcelain");
+    }
+    cmd.args(&git_args);
+
+    let output = cmd.output().context("Failed to run git blame")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        eprintln!("{}", stderr.trim());
+        std::process::exit(output.status.code().unwrap_or(1));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if wants_raw {
+        print!("{}", stdout);
+        return Ok(());
+    }
+
+    let parsed = blame::parse_porcelain(&stdout);
+    if verbose > 0 {
+        eprintln!(
+            "{} lines from {} commits",
+            parsed.lines.len(),
+            parsed.commits.len()
+        );
+    }
+    // The file is the argument that isn't a flag, a flag's value or a revision
+    let file = blame_file(&git_args).unwrap_or("file");
+    let rendered = blame::render(file, &parsed, chrono::Utc::now().timestamp(), fold_older);
+    println!("{}", rendered);
+
+    timer.track(
+        &format!("git blame {}", args.join(" ")),
+        &format!("rtk git blame {}", args.join(" ")),
+        &blame::plain(&parsed),
+        &rendered,
+    );
+    Ok(())
+}
+
+/// The path in `git blame` arguments: after `--` if present, else the last argument
+/// that is neither a flag nor the value of `-L`
+fn blame_file(args: &[String]) -> Option<&str> {
+    if let Some(at) = args.iter().position(|a| a == "--") {
+        return args.get(at + 1).map(String::as_str);
+    }
+    let mut file = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "-L" {
+            iter.next();
+        } else if !arg.starts_with('-') {
+            file = Some(arg.as_str());
+        }
+    }
+    file
+}
+
+/// The work tree root and the absolute git directory of the current repository
+fn repo_dirs() -> Option<(PathBuf, PathBuf)> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel", "--absolute-git-dir"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let root = PathBuf::from(lines.next()?);
+    let git_dir = PathBuf::from(lines.next()?);
+    Some((root, git_dir))
+}
+
+/// The conflict report for the operation in progress, if git stopped in one
+fn conflict_report() -> Option<String> {
+    let (root, git_dir) = repo_dirs()?;
+    let op = Operation::detect(&git_dir);
+    let porcelain = Command::new("git")
+        .args(["status", "--porcelain"])
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+        .unwrap_or_default();
+    let files: Vec<ConflictedFile> = conflict::unmerged(&porcelain)
+        .iter()
+        .map(|(code, path)| ConflictedFile::load(&root, code, path))
+        .collect();
+    if op.is_none() && files.is_empty() {
+        return None;
+    }
+    Some(conflict::render_report(op.as_ref(), &files))
+}
+
+/// Flags that hand the terminal to git: an editor, or an interactive todo list
+fn sequencer_is_interactive(args: &[String]) -> bool {
+    args.iter().any(|arg| {
+        matches!(
+            arg.as_str(),
+            "-i" | "--interactive" | "--continue" | "--edit-todo" | "-e" | "--edit"
+        )
+    })
+}
+
+/// Drop what git prints while merging or rebasing that says nothing about the outcome:
+/// progress, per-file stats, hints and, when a conflict report follows, the conflict
+/// lines it replaces
+fn filter_sequencer_output(output: &str, has_report: bool) -> String {
+    let output = crate::utils::strip_ansi(output);
+    let mut kept = Vec::new();
+    // `Rebasing (2/5)` progress is redrawn in place with carriage returns
+    for line in output.lines().flat_map(|l| l.split('\r')) {
+        let trimmed = line.trim();
+        if trimmed.is_empty()
+            || trimmed.starts_with("hint:")
+            || trimmed.starts_with("Auto-merging ")
+            || trimmed.starts_with("Rebasing (")
+            || trimmed.starts_with("create mode ")
+            || trimmed.starts_with("delete mode ")
+            || trimmed.starts_with("rename ")
+            || trimmed.starts_with("mode change ")
+            || STAT_LINE.is_match(line)
+        {
+            continue;
+        }
+        if has_report
+            && (trimmed.starts_with("CONFLICT (")
+                || trimmed.starts_with("Automatic merge failed")
+                || trimmed.starts_with("error: could not apply")
+                || trimmed.starts_with("Resolve all conflicts")
+                || trimmed.starts_with("You can instead skip")
+                || trimmed.starts_with("To abort and get back")
+                || trimmed.starts_with("Could not apply "))
+        {
+            continue;
+        }
+        kept.push(trimmed.to_string());
+    }
+    kept.join("\n")
+}
+
+/// `git merge` / `git rebase`: the outcome in a few lines, and a conflict report with
+/// the commands to continue or abort when git stops
+fn run_sequencer(subcommand: &str, args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("git {} {}", subcommand, args.join(" "));
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.arg(subcommand).args(args);
+
+    if sequencer_is_interactive(args) {
+        let status = cmd
+            .status()
+            .with_context(|| format!("Failed to run git {}", subcommand))?;
+        if let Some(report) = conflict_report() {
+            println!("{}", report);
+        }
+        timer.track_passthrough(
+            &format!("git {} {}", subcommand, args.join(" ")),
+            &format!("rtk git {} {} (passthrough)", subcommand, args.join(" ")),
+        );
+        if !status.success() {
+            std::process::exit(status.code().unwrap_or(1));
+        }
+        return Ok(());
+    }
+
+    let output = cmd
+        .output()
+        .with_context(|| format!("Failed to run git {}", subcommand))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let raw_output = format!("{}\n{}", stdout, stderr);
+
+    let report = conflict_report();
+    let mut rendered = filter_sequencer_output(&raw_output, report.is_some());
+    if let Some(report) = &report {
+        if !rendered.is_empty() {
+            rendered.push('\n');
+        }
+        rendered.push_str(report);
+    }
+    if rendered.is_empty() {
+        rendered = "ok ✓".to_string();
+    }
+
+    if output.status.success() {
+        println!("{}", rendered);
+    } else {
+        eprintln!("{}", rendered);
+    }
+
+    timer.track(
+        &format!("git {} {}", subcommand, args.join(" ")),
+        &format!("rtk git {} {}", subcommand, args.join(" ")),
+        &raw_output,
+        &rendered,
+    );
+
+    if !output.status.success() {
+        std::process::exit(output.status.code().unwrap_or(1));
+    }
+    Ok(())
+}
+
 /// Runs an unsupported git subcommand by passing it through directly
 pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
@@ -1326,6 +2061,7 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::envelope::tests::assert_json_snapshot;
 
     #[test]
     fn test_compact_diff() {
@@ -1342,6 +2078,65 @@ mod tests {
         assert!(result.contains("+"));
     }
 
+    #[test]
+    fn test_summary_args() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(summary_args(&args(&["--cached"])).unwrap(), None);
+        assert_eq!(
+            summary_args(&args(&["--summarize", "HEAD~2"])).unwrap(),
+            Some((None, args(&["HEAD~2"])))
+        );
+        assert_eq!(
+            summary_args(&args(&["main", "--file", "src/a.rs"])).unwrap(),
+            Some((Some("src/a.rs".to_string()), args(&["main"])))
+        );
+        assert_eq!(
+            summary_args(&args(&["--file=b.rs"])).unwrap(),
+            Some((Some("b.rs".to_string()), vec![]))
+        );
+        assert!(summary_args(&args(&["--file"])).is_err());
+        assert_eq!(
+            summary_args(&args(&[
+                "--summarize",
+                "--stat=80",
+                "--name-only",
+                "HEAD",
+                "--",
+                "--stat"
+            ]))
+            .unwrap(),
+            Some((None, args(&["HEAD", "--", "--stat"])))
+        );
+    }
+
+    #[test]
+    fn test_blame_file() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(blame_file(&args(&["src/a.rs"])), Some("src/a.rs"));
+        assert_eq!(
+            blame_file(&args(&["-L", "10,20", "src/a.rs"])),
+            Some("src/a.rs")
+        );
+        assert_eq!(
+            blame_file(&args(&["HEAD~2", "src/a.rs", "-w"])),
+            Some("src/a.rs")
+        );
+        assert_eq!(
+            blame_file(&args(&["HEAD", "--", "-odd.rs"])),
+            Some("-odd.rs")
+        );
+        assert_eq!(blame_file(&args(&["-L10,20"])), None);
+    }
+
+    #[test]
+    fn test_parse_cat_file_batch() {
+        let out = b"abc123 missing\nabcdef0123 blob 6\nfn a\n\n\ndef456 blob 2\nxy\n";
+        assert_eq!(
+            parse_cat_file_batch(out),
+            [None, Some("fn a\n\n".to_string()), Some("xy".to_string())]
+        );
+    }
+
     #[test]
     fn test_filter_branch_output() {
         let output = "* main\n  feature/auth\n  fix/bug-123\n  remotes/origin/HEAD -> origin/main\n  remotes/origin/main\n  remotes/origin/feature/auth\n  remotes/origin/release/v2\n";
@@ -1411,6 +2206,9 @@ mod tests {
         assert!(result.contains("debug.log"));
         assert!(result.contains("test.sh"));
         assert!(!result.contains("Modified"));
+
+        let single = format_status_output("## main\nUU src/a.rs\n");
+        assert!(single.contains("⚠️  Conflicts in 1 file:\n   src/a.rs"));
     }
 
     #[test]
@@ -1432,6 +2230,48 @@ A  added.rs
         assert!(result.contains("untracked.txt"));
     }
 
+    #[test]
+    fn test_format_status_output_conflicts() {
+        let porcelain = "## HEAD (no branch)\nUU src/a.rs\nAA new.rs\nDU gone.rs\nM  src/b.rs\n";
+        let result = format_status_output(porcelain);
+        assert!(result.contains("⚠️  Conflicts in 3 files:\n   src/a.rs\n   new.rs\n   gone.rs"));
+        // Unmerged paths are not counted as staged or modified
+        assert!(result.contains("✅ Staged: 1 files\n   src/b.rs"));
+        assert!(!result.contains("Modified"));
+    }
+
+    #[test]
+    fn test_filter_sequencer_output() {
+        let merge = "Auto-merging src/a.rs\n\
+CONFLICT (content): Merge conflict in src/a.rs\n\
+Automatic merge failed; fix conflicts and then commit the result.\n";
+        assert_eq!(filter_sequencer_output(merge, true), "");
+
+        let merged = "Updating 1a2b3c4..5d6e7f8\nFast-forward\n src/a.rs | 12 +++++++---\n \
+2 files changed, 9 insertions(+), 3 deletions(-)\n create mode 100644 c.txt\n";
+        assert_eq!(
+            filter_sequencer_output(merged, false),
+            "Updating 1a2b3c4..5d6e7f8\nFast-forward\n2 files changed, 9 insertions(+), 3 deletions(-)"
+        );
+
+        let rebase = "Rebasing (1/3)\rRebasing (2/3)\r\x1b[KSuccessfully rebased and updated refs/heads/side.\n";
+        assert_eq!(
+            filter_sequencer_output(rebase, false),
+            "Successfully rebased and updated refs/heads/side."
+        );
+    }
+
+    #[test]
+    fn test_sequencer_is_interactive() {
+        assert!(sequencer_is_interactive(&[
+            "-i".to_string(),
+            "main".to_string()
+        ]));
+        assert!(sequencer_is_interactive(&["--continue".to_string()]));
+        assert!(!sequencer_is_interactive(&["--abort".to_string()]));
+        assert!(!sequencer_is_interactive(&["feature".to_string()]));
+    }
+
     #[test]
     fn test_format_status_output_truncation() {
         // Test that >5 staged files show "... +N more"
@@ -1460,6 +2300,40 @@ M  file7.rs
         // Compile-time verification that the function exists with correct signature
     }
 
+    #[test]
+    fn test_json_git_args_drop_format_flags() {
+        let args: Vec<String> = ["--oneline", "-n", "3", "--format=%s", "main"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(json_git_args("log", &args), ["-n", "3", "main"]);
+        assert_eq!(json_git_args("show", &args[3..]), ["main"]);
+        assert_eq!(json_git_args("diff", &args[4..]), ["main"]);
+        assert!(args.iter().any(|a| is_limit_flag(a)));
+    }
+
+    #[test]
+    fn test_summary_log_args_drop_format_flags() {
+        let args: Vec<String> = ["--oneline", "--pretty=short", "8e718e8..HEAD", "--", "src"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            summary_log_args(&args),
+            [
+                "log",
+                "--no-color",
+                "--numstat",
+                log_summary::LOG_FORMAT,
+                "8e718e8..HEAD",
+                "--",
+                "src"
+            ]
+        );
+        let recent = summary_log_args(&["--format=%h".to_string()]);
+        assert_eq!(recent[4..], ["-100"]);
+    }
+
     #[test]
     fn test_filter_log_output() {
         let output = "abc1234 This is a commit message (2 days ago) <author>\ndef5678 Another commit (1 week ago) <other>\n";
@@ -1657,4 +2531,56 @@ no changes added to commit (use "git add" and/or "git commit -a")
             ]
         );
     }
+
+    #[test]
+    fn test_json_snapshot_git_log() {
+        let actual = render_json::<GitLogParser>(
+            "git log -2",
+            include_str!("../tests/fixtures/json_output/git_log.input.txt"),
+            0,
+        );
+        assert_json_snapshot(
+            &actual,
+            include_str!("../tests/fixtures/json_output/git_log.expected.json"),
+        );
+    }
+
+    #[test]
+    fn test_json_snapshot_git_diff() {
+        let actual = render_json::<GitDiffParser>(
+            "git diff",
+            include_str!("../tests/fixtures/json_output/git_diff.input.txt"),
+            0,
+        );
+        assert_json_snapshot(
+            &actual,
+            include_str!("../tests/fixtures/json_output/git_diff.expected.json"),
+        );
+    }
+
+    #[test]
+    fn test_json_snapshot_git_status() {
+        let actual = render_json::<GitStatusParser>(
+            "git status",
+            include_str!("../tests/fixtures/json_output/git_status.input.txt"),
+            0,
+        );
+        assert_json_snapshot(
+            &actual,
+            include_str!("../tests/fixtures/json_output/git_status.expected.json"),
+        );
+    }
+
+    #[test]
+    fn test_parse_git_records_degraded_on_unknown_lines() {
+        let input = "3\t1\tsrc/a.rs\nwarning: CRLF will be replaced\n";
+        match parse_git_records("diff", input) {
+            ParseResult::Degraded(result, warnings) => {
+                assert_eq!(result.files_changed, 1);
+                assert_eq!(warnings.len(), 1);
+            }
+            _ => panic!("expected degraded parse"),
+        }
+        assert_eq!(parse_git_records("diff", "fatal: bad revision").tier(), 3);
+    }
 }
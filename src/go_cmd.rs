@@ -1,6 +1,12 @@
+use crate::parser::{
+    format_parsed, render_delta, render_diagnostics, render_json, truncate_output, LintIssue,
+    LintResult, LintSeverity, OutputParser, ParseResult, TestFailure, TestResult,
+};
+use crate::stream::{run_streaming, KeptOutput, StreamFilter, StreamSource};
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
+use regex::Regex;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::ffi::OsString;
@@ -23,14 +29,6 @@ struct GoTestEvent {
     elapsed: Option<f64>,
 }
 
-#[derive(Debug, Default)]
-struct PackageResult {
-    pass: usize,
-    fail: usize,
-    skip: usize,
-    failed_tests: Vec<(String, Vec<String>)>, // (test_name, output_lines)
-}
-
 pub fn run_test(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
@@ -50,46 +48,118 @@ pub fn run_test(args: &[String], verbose: u8) -> Result<()> {
         eprintln!("Running: go test -json {}", args.join(" "));
     }
 
-    let output = cmd
-        .output()
+    // stderr (build errors, etc.) is passed through as it arrives
+    let output = run_streaming(cmd, "go test", &mut GoTestStream::default())
         .context("Failed to run go test. Is Go installed?")?;
+    let stdout = &output.kept.stdout;
+
+    let exit_code = output.exit_code;
+    let filtered = if crate::parser::json_mode() {
+        render_json::<GoTestParser>(&format!("go test {}", args.join(" ")), stdout, exit_code)
+    } else if crate::parser::delta_mode() {
+        render_delta::<GoTestParser>(
+            "go test",
+            &format!("go test {}", args.join(" ")),
+            stdout,
+            || format_parsed("go test", GoTestParser::parse(stdout), verbose),
+        )
+    } else {
+        format_parsed("go test", GoTestParser::parse(stdout), verbose)
+    };
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let raw = format!("{}\n{}", stdout, stderr);
-
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
-    let filtered = filter_go_test_json(&stdout);
-
-    if let Some(hint) = crate::tee::tee_and_hint(&raw, "go_test", exit_code) {
+    if let Some(hint) = crate::tee::tee_and_hint(&output.tee_text(), "go_test", exit_code) {
         println!("{}\n{}", filtered, hint);
     } else {
         println!("{}", filtered);
     }
 
-    // Include stderr if present (build errors, etc.)
-    if !stderr.trim().is_empty() {
-        eprintln!("{}", stderr.trim());
-    }
-
-    timer.track(
+    timer.track_streamed(
         &format!("go test {}", args.join(" ")),
         &format!("rtk go test {}", args.join(" ")),
-        &raw,
-        &filtered,
+        output.raw_tokens,
+        &format!("{}{}", output.emitted, filtered),
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
+    if !output.success {
         std::process::exit(exit_code);
     }
 
     Ok(())
 }
 
+/// Stream filter for `go test -json`: holds each test's output events until the test
+/// ends, keeps them only if it failed, and prints failures as they happen.
+#[derive(Default)]
+struct GoTestStream {
+    pending: HashMap<(String, String), Vec<String>>,
+    passed: usize,
+    failed: usize,
+}
+
+impl StreamFilter for GoTestStream {
+    fn feed(&mut self, line: &str, source: StreamSource, kept: &mut KeptOutput) -> Option<String> {
+        if source == StreamSource::Stderr {
+            return Some(line.to_string());
+        }
+        let event: GoTestEvent = match serde_json::from_str(line.trim()) {
+            Ok(e) => e,
+            Err(_) => {
+                kept.push(source, line);
+                return None;
+            }
+        };
+        let Some(test) = event.test else {
+            // Package-level events feed the package summary and build failures
+            kept.push(source, line);
+            return None;
+        };
+        let key = (event.package.unwrap_or_default(), test);
+
+        match event.action.as_str() {
+            "output" => {
+                self.pending.entry(key).or_default().push(line.to_string());
+                None
+            }
+            "pass" | "skip" => {
+                if event.action == "pass" {
+                    self.passed += 1;
+                }
+                self.pending.remove(&key);
+                kept.push(source, line);
+                None
+            }
+            "fail" => {
+                self.failed += 1;
+                for held in self.pending.remove(&key).unwrap_or_default() {
+                    kept.push(source, &held);
+                }
+                kept.push(source, line);
+                Some(format!("FAIL {} ({})", key.1, key.0))
+            }
+            _ => {
+                kept.push(source, line);
+                None
+            }
+        }
+    }
+
+    fn progress(&self) -> Option<String> {
+        Some(format!("{} passed, {} failed", self.passed, self.failed))
+    }
+
+    fn flush(&mut self, kept: &mut KeptOutput) {
+        // Tests that never finished (timeout, crash): keep their output for the parser
+        let mut leftover: Vec<_> = self.pending.drain().collect();
+        leftover.sort();
+        for (_, lines) in leftover {
+            for line in lines {
+                kept.push(StreamSource::Stdout, &line);
+            }
+        }
+    }
+}
+
 pub fn run_build(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
@@ -171,7 +241,7 @@ pub fn run_vet(args: &[String], verbose: u8) -> Result<()> {
         .unwrap_or(if output.status.success() { 0 } else { 1 });
     let filtered = filter_go_vet(&raw);
 
-    if let Some(hint) = crate::tee::tee_and_hint(&raw, "go_vet", exit_code) {
+    if let Some(hint) = crate::tee::tee_and_hint_for(&filtered, &raw, "go_vet", exit_code) {
         if !filtered.is_empty() {
             println!("{}\n{}", filtered, hint);
         } else {
@@ -241,128 +311,195 @@ pub fn run_other(args: &[OsString], verbose: u8) -> Result<()> {
     Ok(())
 }
 
-/// Parse go test -json output (NDJSON format)
-fn filter_go_test_json(output: &str) -> String {
-    let mut packages: HashMap<String, PackageResult> = HashMap::new();
-    let mut current_test_output: HashMap<(String, String), Vec<String>> = HashMap::new(); // (package, test) -> outputs
-
-    for line in output.lines() {
-        let trimmed = line.trim();
-        if trimmed.is_empty() {
-            continue;
-        }
-
-        let event: GoTestEvent = match serde_json::from_str(trimmed) {
-            Ok(e) => e,
-            Err(_) => continue, // Skip non-JSON lines
-        };
-
-        let package = event.package.unwrap_or_else(|| "unknown".to_string());
-        let pkg_result = packages.entry(package.clone()).or_default();
-
-        match event.action.as_str() {
-            "pass" => {
-                if event.test.is_some() {
-                    pkg_result.pass += 1;
-                }
+/// Parser for `go test -json` NDJSON output
+pub struct GoTestParser;
+
+impl OutputParser for GoTestParser {
+    type Output = TestResult;
+
+    fn parse(input: &str) -> ParseResult<TestResult> {
+        let mut passed = 0;
+        let mut failed = 0;
+        let mut skipped = 0;
+        let mut elapsed_secs = 0.0;
+        let mut events = 0;
+        let mut skipped_lines = 0;
+        let mut failures: Vec<TestFailure> = Vec::new();
+        let mut outputs: HashMap<(String, Option<String>), Vec<String>> = HashMap::new();
+        let mut failed_packages: Vec<String> = Vec::new();
+
+        for line in input.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
             }
-            "fail" => {
-                if let Some(test) = &event.test {
-                    pkg_result.fail += 1;
-
-                    // Collect output for failed test
-                    let key = (package.clone(), test.clone());
-                    let outputs = current_test_output.remove(&key).unwrap_or_default();
-                    pkg_result.failed_tests.push((test.clone(), outputs));
+            let event: GoTestEvent = match serde_json::from_str(trimmed) {
+                Ok(e) => e,
+                Err(_) => {
+                    skipped_lines += 1;
+                    continue;
                 }
-            }
-            "skip" => {
-                if event.test.is_some() {
-                    pkg_result.skip += 1;
+            };
+            events += 1;
+
+            let package = event.package.unwrap_or_default();
+            let key = (package.clone(), event.test.clone());
+            match (event.action.as_str(), &event.test) {
+                ("output", _) => {
+                    if let Some(text) = event.output {
+                        outputs
+                            .entry(key)
+                            .or_default()
+                            .push(text.trim_end().to_string());
+                    }
                 }
-            }
-            "output" => {
-                // Collect output for current test
-                if let (Some(test), Some(output_text)) = (&event.test, &event.output) {
-                    let key = (package.clone(), test.clone());
-                    current_test_output
-                        .entry(key)
-                        .or_default()
-                        .push(output_text.trim_end().to_string());
+                ("pass", Some(_)) => passed += 1,
+                ("skip", Some(_)) => skipped += 1,
+                ("fail", Some(test)) => {
+                    failed += 1;
+                    let lines = outputs.remove(&key).unwrap_or_default();
+                    failures.push(TestFailure {
+                        test_name: test.clone(),
+                        file_path: package,
+                        error_message: go_failure_message(&lines),
+                        stack_trace: None,
+                    });
+                }
+                ("pass", None) | ("fail", None) => {
+                    elapsed_secs += event.elapsed.unwrap_or(0.0);
+                    if event.action == "fail" {
+                        failed_packages.push(package);
+                    }
                 }
+                _ => {}
             }
-            _ => {} // run, pause, cont, etc.
         }
-    }
-
-    // Build summary
-    let total_packages = packages.len();
-    let total_pass: usize = packages.values().map(|p| p.pass).sum();
-    let total_fail: usize = packages.values().map(|p| p.fail).sum();
-    let total_skip: usize = packages.values().map(|p| p.skip).sum();
 
-    if total_fail == 0 && total_pass == 0 {
-        return "Go test: No tests found".to_string();
-    }
+        if events == 0 {
+            return match parse_go_test_text(input) {
+                Some(result) => ParseResult::Degraded(
+                    result,
+                    vec!["no `go test -json` events, parsed text output".to_string()],
+                ),
+                None => ParseResult::Passthrough(truncate_output(input, 500)),
+            };
+        }
 
-    if total_fail == 0 {
-        return format!(
-            "✓ Go test: {} passed in {} packages",
-            total_pass, total_packages
-        );
-    }
+        // Package failed without any failing test: build error or panic in init/TestMain
+        for package in failed_packages {
+            if !failures.iter().any(|f| f.file_path == package) {
+                let lines = outputs.remove(&(package.clone(), None)).unwrap_or_default();
+                failed += 1;
+                failures.push(TestFailure {
+                    test_name: format!("{} [package]", package),
+                    file_path: package,
+                    error_message: go_failure_message(&lines),
+                    stack_trace: None,
+                });
+            }
+        }
 
-    let mut result = String::new();
-    result.push_str(&format!(
-        "Go test: {} passed, {} failed",
-        total_pass, total_fail
-    ));
-    if total_skip > 0 {
-        result.push_str(&format!(", {} skipped", total_skip));
-    }
-    result.push_str(&format!(" in {} packages\n", total_packages));
-    result.push_str("═══════════════════════════════════════\n");
+        let result = TestResult {
+            total: passed + failed + skipped,
+            passed,
+            failed,
+            skipped,
+            duration_ms: Some((elapsed_secs * 1000.0).round() as u64),
+            failures,
+        };
 
-    // Show failed tests grouped by package
-    for (package, pkg_result) in packages.iter() {
-        if pkg_result.fail == 0 {
-            continue;
+        if skipped_lines > 0 {
+            return ParseResult::Degraded(
+                result,
+                vec![format!("{} non-JSON lines skipped", skipped_lines)],
+            );
         }
+        ParseResult::Full(result)
+    }
+}
 
-        result.push_str(&format!(
-            "\n📦 {} ({} passed, {} failed)\n",
-            compact_package_name(package),
-            pkg_result.pass,
-            pkg_result.fail
-        ));
-
-        for (test, outputs) in &pkg_result.failed_tests {
-            result.push_str(&format!("  ❌ {}\n", test));
-
-            // Show failure output (limit to key lines)
-            let relevant_lines: Vec<&String> = outputs
-                .iter()
-                .filter(|line| {
-                    let lower = line.to_lowercase();
-                    !line.trim().is_empty()
-                        && !line.starts_with("=== RUN")
-                        && !line.starts_with("--- FAIL")
-                        && (lower.contains("error")
-                            || lower.contains("expected")
-                            || lower.contains("got")
-                            || lower.contains("panic")
-                            || line.trim().starts_with("at "))
-                })
-                .take(5)
-                .collect();
-
-            for line in relevant_lines {
-                result.push_str(&format!("     {}\n", truncate(line, 100)));
+/// Tier 2: plain `go test` output (`--- FAIL: TestX (0.00s)`, `ok  pkg 0.01s`).
+/// Without `-v` passing tests are not listed, so `passed` only counts `--- PASS` lines.
+fn parse_go_test_text(input: &str) -> Option<TestResult> {
+    lazy_static::lazy_static! {
+        static ref RESULT_RE: Regex =
+            Regex::new(r"^\s*--- (PASS|FAIL|SKIP): (\S+) \(([\d.]+)s\)").unwrap();
+        static ref PACKAGE_RE: Regex =
+            Regex::new(r"^(ok|FAIL)\s+(\S+)\s+([\d.]+)s").unwrap();
+    }
+
+    let (mut passed, mut failed, mut skipped) = (0, 0, 0);
+    let mut elapsed_secs = 0.0;
+    let mut packages = 0;
+    let mut failures: Vec<TestFailure> = Vec::new();
+    let mut current: Option<Vec<String>> = None;
+
+    for line in input.lines() {
+        if let Some(caps) = RESULT_RE.captures(line) {
+            match &caps[1] {
+                "PASS" => passed += 1,
+                "SKIP" => skipped += 1,
+                _ => {
+                    failed += 1;
+                    failures.push(TestFailure {
+                        test_name: caps[2].to_string(),
+                        file_path: String::new(),
+                        error_message: String::new(),
+                        stack_trace: None,
+                    });
+                    current = Some(Vec::new());
+                    continue;
+                }
+            }
+            current = None;
+        } else if let Some(caps) = PACKAGE_RE.captures(line) {
+            packages += 1;
+            elapsed_secs += caps[3].parse::<f64>().unwrap_or(0.0);
+            for f in failures.iter_mut().filter(|f| f.file_path.is_empty()) {
+                f.file_path = caps[2].to_string();
+            }
+            current = None;
+        } else if let Some(lines) = current.as_mut() {
+            // Indented messages under `--- FAIL` belong to that test
+            if line.starts_with(' ') || line.starts_with('\t') {
+                lines.push(line.to_string());
+                if let Some(f) = failures.last_mut() {
+                    f.error_message = go_failure_message(lines);
+                }
+            } else {
+                current = None;
             }
         }
     }
 
-    result.trim().to_string()
+    if packages == 0 && passed + failed + skipped == 0 {
+        return None;
+    }
+    Some(TestResult {
+        total: passed + failed + skipped,
+        passed,
+        failed,
+        skipped,
+        duration_ms: Some((elapsed_secs * 1000.0).round() as u64),
+        failures,
+    })
+}
+
+/// Keep the informative lines of a failed test's output (drop RUN/FAIL/PASS markers).
+fn go_failure_message(lines: &[String]) -> String {
+    lines
+        .iter()
+        .map(|l| l.trim())
+        .filter(|l| {
+            !l.is_empty()
+                && !l.starts_with("=== RUN")
+                && !l.starts_with("=== PAUSE")
+                && !l.starts_with("=== CONT")
+                && !l.starts_with("--- FAIL")
+                && *l != "FAIL"
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 /// Filter go build output - show only errors
@@ -408,51 +545,71 @@ fn filter_go_build(output: &str) -> String {
     result.trim().to_string()
 }
 
-/// Filter go vet output - show issues
-fn filter_go_vet(output: &str) -> String {
-    let mut issues: Vec<String> = Vec::new();
+/// Parser for `go vet` text output (`./file.go:line:col: message`) into a LintResult.
+/// Tab-indented lines after a finding become its notes.
+pub struct GoVetParser;
 
-    for line in output.lines() {
-        let trimmed = line.trim();
+impl OutputParser for GoVetParser {
+    type Output = LintResult;
 
-        // Collect issue lines (vet reports issues with file:line:col format)
-        if !trimmed.is_empty() && !trimmed.starts_with('#') && trimmed.contains(".go:") {
-            issues.push(trimmed.to_string());
+    fn parse(input: &str) -> ParseResult<LintResult> {
+        lazy_static::lazy_static! {
+            static ref VET_LINE: Regex =
+                Regex::new(r"^(?:vet: )?(.+?\.go):(\d+)(?::(\d+))?: (.+)$").unwrap();
         }
-    }
-
-    if issues.is_empty() {
-        return "✓ Go vet: No issues found".to_string();
-    }
-
-    let mut result = String::new();
-    result.push_str(&format!("Go vet: {} issues\n", issues.len()));
-    result.push_str("═══════════════════════════════════════\n");
 
-    for (i, issue) in issues.iter().take(20).enumerate() {
-        result.push_str(&format!("{}. {}\n", i + 1, truncate(issue, 120)));
-    }
+        let mut issues: Vec<LintIssue> = Vec::new();
+        let mut unparsed = false;
+        for line in input.lines() {
+            if let Some(caps) = VET_LINE.captures(line.trim_end()) {
+                issues.push(LintIssue {
+                    file_path: caps[1].to_string(),
+                    line: caps[2].parse().unwrap_or(0),
+                    column: caps
+                        .get(3)
+                        .and_then(|m| m.as_str().parse().ok())
+                        .unwrap_or(0),
+                    // vet has no severity levels: every finding fails the command
+                    severity: LintSeverity::Error,
+                    message: caps[4].to_string(),
+                    ..LintIssue::default()
+                });
+            } else if line.starts_with('\t') && !issues.is_empty() {
+                if let Some(issue) = issues.last_mut() {
+                    issue.notes.push(line.trim().to_string());
+                }
+            } else if !line.trim().is_empty() && !line.starts_with('#') {
+                unparsed = true;
+            }
+        }
 
-    if issues.len() > 20 {
-        result.push_str(&format!("\n... +{} more issues\n", issues.len() - 20));
+        if issues.is_empty() && unparsed {
+            // e.g. `go: cannot find main module`
+            return ParseResult::Passthrough(truncate_output(input.trim(), 500));
+        }
+        ParseResult::Full(LintResult::from_issues(issues))
     }
-
-    result.trim().to_string()
 }
 
-/// Compact package name (remove long paths)
-fn compact_package_name(package: &str) -> String {
-    // Remove common module prefixes like github.com/user/repo/
-    if let Some(pos) = package.rfind('/') {
-        package[pos + 1..].to_string()
-    } else {
-        package.to_string()
+/// Filter go vet output - findings grouped by file
+fn filter_go_vet(output: &str) -> String {
+    match GoVetParser::parse(output) {
+        ParseResult::Full(result) | ParseResult::Degraded(result, _) => {
+            if result.issues.is_empty() {
+                "✓ Go vet: No issues found".to_string()
+            } else {
+                render_diagnostics("go vet", &result.issues)
+            }
+        }
+        ParseResult::Passthrough(raw) => raw,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::envelope::tests::assert_json_snapshot;
+    use crate::parser::{FormatMode, TokenFormatter};
 
     #[test]
     fn test_filter_go_test_all_pass() {
@@ -461,10 +618,14 @@ mod tests {
 {"Time":"2024-01-01T10:00:02Z","Action":"pass","Package":"example.com/foo","Test":"TestBar","Elapsed":0.5}
 {"Time":"2024-01-01T10:00:02Z","Action":"pass","Package":"example.com/foo","Elapsed":0.5}"#;
 
-        let result = filter_go_test_json(output);
-        assert!(result.contains("✓ Go test"));
-        assert!(result.contains("1 passed"));
-        assert!(result.contains("1 packages"));
+        let result = GoTestParser::parse(output);
+        assert_eq!(result.tier(), 1);
+        let data = result.unwrap();
+        assert_eq!((data.passed, data.failed), (1, 0));
+        assert_eq!(data.duration_ms, Some(500));
+        assert!(data
+            .format(FormatMode::Compact)
+            .starts_with("PASS (1) FAIL (0)"));
     }
 
     #[test]
@@ -475,10 +636,22 @@ mod tests {
 {"Time":"2024-01-01T10:00:03Z","Action":"fail","Package":"example.com/foo","Test":"TestFail","Elapsed":0.5}
 {"Time":"2024-01-01T10:00:03Z","Action":"fail","Package":"example.com/foo","Elapsed":0.5}"#;
 
-        let result = filter_go_test_json(output);
-        assert!(result.contains("1 failed"));
-        assert!(result.contains("TestFail"));
-        assert!(result.contains("expected 5, got 3"));
+        let data = GoTestParser::parse(output).unwrap();
+        assert_eq!(data.failed, 1);
+        assert_eq!(data.failures.len(), 1);
+        assert_eq!(data.failures[0].error_message, "Error: expected 5, got 3");
+
+        let formatted = data.format(FormatMode::Compact);
+        assert!(
+            formatted.contains("1. TestFail (example.com/foo)"),
+            "got: {}",
+            formatted
+        );
+        assert!(
+            formatted.contains("expected 5, got 3"),
+            "got: {}",
+            formatted
+        );
     }
 
     #[test]
@@ -515,15 +688,88 @@ main.go:15:2: cannot use x (type int) as type string"#;
 utils.go:15:5: unreachable code"#;
 
         let result = filter_go_vet(output);
-        assert!(result.contains("2 issues"));
-        assert!(result.contains("Printf format"));
-        assert!(result.contains("unreachable code"));
+        assert!(result.starts_with("go vet: 2 errors, 0 warnings in 2 files"));
+        assert!(result.contains("main.go:42:2: error: Printf format %d has arg x"));
+        assert!(result.contains("utils.go:15:5: error: unreachable code"));
+    }
+
+    #[test]
+    fn test_filter_go_vet_unparsed_passthrough() {
+        let result = filter_go_vet("go: cannot find main module\n");
+        assert_eq!(result, "go: cannot find main module");
     }
 
     #[test]
-    fn test_compact_package_name() {
-        assert_eq!(compact_package_name("github.com/user/repo/pkg"), "pkg");
-        assert_eq!(compact_package_name("example.com/foo"), "foo");
-        assert_eq!(compact_package_name("simple"), "simple");
+    fn test_json_snapshot_go_test() {
+        let actual = render_json::<GoTestParser>(
+            "go test ./...",
+            include_str!("../tests/fixtures/json_output/go_test.input.txt"),
+            1,
+        );
+        assert_json_snapshot(
+            &actual,
+            include_str!("../tests/fixtures/json_output/go_test.expected.json"),
+        );
+    }
+
+    #[test]
+    fn test_go_test_parser_passthrough_without_json() {
+        let result = GoTestParser::parse("# example.com/app\n./main.go:3:2: undefined: foo\n");
+        assert_eq!(result.tier(), 3);
+    }
+
+    #[test]
+    fn test_go_test_parser_text_fallback_degrades() {
+        let output = "--- FAIL: TestDiv (0.00s)\n    calc_test.go:14: got 2, want 3\nFAIL\nFAIL\texample.com/app/calc\t0.012s\nok  \texample.com/app/util\t0.003s\n";
+        let result = GoTestParser::parse(output);
+        assert_eq!(result.tier(), 2);
+        let data = result.unwrap();
+        assert_eq!(data.failed, 1);
+        assert_eq!(data.failures[0].file_path, "example.com/app/calc");
+        assert_eq!(
+            data.failures[0].error_message,
+            "calc_test.go:14: got 2, want 3"
+        );
+        assert_eq!(data.duration_ms, Some(15));
+    }
+
+    #[test]
+    fn test_go_test_parser_package_failure_without_tests() {
+        let input = r#"{"Action":"output","Package":"example.com/app","Output":"panic: boom\n"}
+{"Action":"fail","Package":"example.com/app","Elapsed":0.1}"#;
+        match GoTestParser::parse(input) {
+            ParseResult::Full(result) => {
+                assert_eq!(result.failed, 1);
+                assert_eq!(result.failures[0].error_message, "panic: boom");
+            }
+            _ => panic!("expected full parse"),
+        }
+    }
+
+    #[test]
+    fn test_go_test_stream_same_summary_as_buffered() {
+        let path = format!(
+            "{}/tests/fixtures/go_test/1.22/failures.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let raw = std::fs::read_to_string(path).unwrap();
+        let mut stream = GoTestStream::default();
+        let mut kept = KeptOutput::default();
+        let emitted: Vec<String> = raw
+            .lines()
+            .filter_map(|line| stream.feed(line, StreamSource::Stdout, &mut kept))
+            .collect();
+        stream.flush(&mut kept);
+
+        assert_eq!(
+            format_parsed("go test", GoTestParser::parse(&kept.stdout), 0),
+            format_parsed("go test", GoTestParser::parse(&raw), 0)
+        );
+        assert!(!kept.stdout.contains("=== RUN   TestAdd"));
+        assert_eq!(
+            emitted,
+            vec!["FAIL TestDiscount (github.com/acme/shop/internal/cart)"]
+        );
+        assert_eq!(stream.progress().unwrap(), "8 passed, 1 failed");
     }
 }
@@ -1,8 +1,11 @@
+use crate::parser::{
+    render_diagnostics, render_json, truncate_output, LintIssue, LintResult, LintSeverity,
+    OutputParser, ParseResult,
+};
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
 use serde::Deserialize;
-use std::collections::HashMap;
 use std::process::Command;
 
 #[derive(Debug, Deserialize)]
@@ -23,6 +26,16 @@ struct Issue {
     text: String,
     #[serde(rename = "Pos")]
     pos: Position,
+    #[serde(rename = "Severity", default)]
+    severity: String,
+    #[serde(rename = "Replacement", default)]
+    replacement: Option<Replacement>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Replacement {
+    #[serde(rename = "NewLines", default)]
+    new_lines: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -63,9 +76,22 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let filtered = filter_golangci_json(&stdout);
+    let exit_code = output.status.code().unwrap_or(1);
+    let filtered = if crate::parser::json_mode() {
+        render_json::<GolangciParser>(
+            &format!("golangci-lint run {}", args.join(" ")),
+            &stdout,
+            exit_code,
+        )
+    } else {
+        filter_golangci_json(&stdout)
+    };
 
-    println!("{}", filtered);
+    if let Some(hint) = crate::tee::tee_and_hint_for(&filtered, &raw, "golangci", exit_code) {
+        println!("{}\n{}", filtered, hint);
+    } else {
+        println!("{}", filtered);
+    }
 
     // Include stderr if present (config errors, etc.)
     if !stderr.trim().is_empty() && verbose > 0 {
@@ -84,117 +110,63 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     Ok(())
 }
 
-/// Filter golangci-lint JSON output - group by linter and file
-fn filter_golangci_json(output: &str) -> String {
-    let result: Result<GolangciOutput, _> = serde_json::from_str(output);
-
-    let golangci_output = match result {
-        Ok(o) => o,
-        Err(e) => {
-            // Fallback if JSON parsing fails
-            return format!(
-                "golangci-lint (JSON parse failed: {})\n{}",
-                e,
-                truncate(output, 500)
-            );
-        }
-    };
-
-    let issues = golangci_output.issues;
-
-    if issues.is_empty() {
-        return "✓ golangci-lint: No issues found".to_string();
-    }
-
-    let total_issues = issues.len();
-
-    // Count unique files
-    let unique_files: std::collections::HashSet<_> =
-        issues.iter().map(|i| &i.pos.filename).collect();
-    let total_files = unique_files.len();
-
-    // Group by linter
-    let mut by_linter: HashMap<String, usize> = HashMap::new();
-    for issue in &issues {
-        *by_linter.entry(issue.from_linter.clone()).or_insert(0) += 1;
-    }
-
-    // Group by file
-    let mut by_file: HashMap<&str, usize> = HashMap::new();
-    for issue in &issues {
-        *by_file.entry(&issue.pos.filename).or_insert(0) += 1;
+/// Parser for `golangci-lint run --out-format=json` into a canonical LintResult
+pub struct GolangciParser;
+
+impl OutputParser for GolangciParser {
+    type Output = LintResult;
+
+    fn parse(input: &str) -> ParseResult<LintResult> {
+        let output: GolangciOutput = match serde_json::from_str(input) {
+            Ok(o) => o,
+            Err(_) => return ParseResult::Passthrough(truncate_output(input, 500)),
+        };
+        ParseResult::Full(LintResult::from_issues(
+            output
+                .issues
+                .into_iter()
+                .map(|i| LintIssue {
+                    // golangci-lint leaves severity empty unless configured
+                    severity: match i.severity.as_str() {
+                        "warning" => LintSeverity::Warning,
+                        "info" => LintSeverity::Info,
+                        _ => LintSeverity::Error,
+                    },
+                    file_path: i.pos.filename,
+                    line: i.pos.line,
+                    column: i.pos.column,
+                    rule_id: i.from_linter,
+                    message: i.text,
+                    suggestion: i
+                        .replacement
+                        .and_then(|r| r.new_lines)
+                        .map(|lines| format!("replace with `{}`", lines.join("\n").trim())),
+                    ..LintIssue::default()
+                })
+                .collect(),
+        ))
     }
+}
 
-    let mut file_counts: Vec<_> = by_file.iter().collect();
-    file_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-    // Build output
-    let mut result = String::new();
-    result.push_str(&format!(
-        "golangci-lint: {} issues in {} files\n",
-        total_issues, total_files
-    ));
-    result.push_str("═══════════════════════════════════════\n");
-
-    // Show top linters
-    let mut linter_counts: Vec<_> = by_linter.iter().collect();
-    linter_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-    if !linter_counts.is_empty() {
-        result.push_str("Top linters:\n");
-        for (linter, count) in linter_counts.iter().take(10) {
-            result.push_str(&format!("  {} ({}x)\n", linter, count));
-        }
-        result.push('\n');
-    }
-
-    // Show top files
-    result.push_str("Top files:\n");
-    for (file, count) in file_counts.iter().take(10) {
-        let short_path = compact_path(file);
-        result.push_str(&format!("  {} ({} issues)\n", short_path, count));
-
-        // Show top 3 linters in this file
-        let mut file_linters: HashMap<String, usize> = HashMap::new();
-        for issue in issues.iter().filter(|i| &i.pos.filename == *file) {
-            *file_linters.entry(issue.from_linter.clone()).or_insert(0) += 1;
+/// Filter golangci-lint JSON output - issues grouped by file
+pub(crate) fn filter_golangci_json(output: &str) -> String {
+    match GolangciParser::parse(output) {
+        ParseResult::Full(result) | ParseResult::Degraded(result, _) => {
+            render_diagnostics("golangci-lint", &result.issues)
         }
-
-        let mut file_linter_counts: Vec<_> = file_linters.iter().collect();
-        file_linter_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-        for (linter, count) in file_linter_counts.iter().take(3) {
-            result.push_str(&format!("    {} ({})\n", linter, count));
+        ParseResult::Passthrough(_) => {
+            format!(
+                "golangci-lint (JSON parse failed)\n{}",
+                truncate(output, 500)
+            )
         }
     }
-
-    if file_counts.len() > 10 {
-        result.push_str(&format!("\n... +{} more files\n", file_counts.len() - 10));
-    }
-
-    result.trim().to_string()
-}
-
-/// Compact file path (remove common prefixes)
-fn compact_path(path: &str) -> String {
-    let path = path.replace('\\', "/");
-
-    if let Some(pos) = path.rfind("/pkg/") {
-        format!("pkg/{}", &path[pos + 5..])
-    } else if let Some(pos) = path.rfind("/cmd/") {
-        format!("cmd/{}", &path[pos + 5..])
-    } else if let Some(pos) = path.rfind("/internal/") {
-        format!("internal/{}", &path[pos + 10..])
-    } else if let Some(pos) = path.rfind('/') {
-        path[pos + 1..].to_string()
-    } else {
-        path
-    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::envelope::tests::assert_json_snapshot;
 
     #[test]
     fn test_filter_golangci_no_issues() {
@@ -227,28 +199,23 @@ mod tests {
 }"#;
 
         let result = filter_golangci_json(output);
-        assert!(result.contains("3 issues"));
-        assert!(result.contains("2 files"));
-        assert!(result.contains("errcheck"));
-        assert!(result.contains("gosimple"));
-        assert!(result.contains("main.go"));
-        assert!(result.contains("utils.go"));
+        assert!(result.starts_with("golangci-lint: 3 errors, 0 warnings in 2 files"));
+        assert!(result.contains(
+            "main.go:42:5: error[errcheck]: Error return value not checked\n  also: main.go:50:10"
+        ));
+        assert!(result.contains("utils.go:15:2: error[gosimple]: Should use strings.Contains"));
     }
 
     #[test]
-    fn test_compact_path() {
-        assert_eq!(
-            compact_path("/Users/foo/project/pkg/handler/server.go"),
-            "pkg/handler/server.go"
-        );
-        assert_eq!(
-            compact_path("/home/user/app/cmd/main/main.go"),
-            "cmd/main/main.go"
+    fn test_json_snapshot_golangci() {
+        let actual = render_json::<GolangciParser>(
+            "golangci-lint run",
+            include_str!("../tests/fixtures/json_output/golangci.input.json"),
+            1,
         );
-        assert_eq!(
-            compact_path("/project/internal/config/loader.go"),
-            "internal/config/loader.go"
+        assert_json_snapshot(
+            &actual,
+            include_str!("../tests/fixtures/json_output/golangci.expected.json"),
         );
-        assert_eq!(compact_path("relative/file.go"), "file.go");
     }
 }
@@ -0,0 +1,275 @@
+//! Golden-file regression tests: every registered filter over real tool output
+//!
+//! Fixtures live in `tests/fixtures/<tool>/<version>/`:
+//! - `<case>.txt`: raw tool output, captured from that tool version
+//! - `<case>.golden`: expected filtered output
+//! - `<case>.keep` (optional): lines that must survive filtering, one per line
+//!   (error locations, failing test names)
+//!
+//! Each case also has to save at least the tool's `min_savings` share of tokens,
+//! measured with `tracking::estimate_tokens`.
+//!
+//! After an intended output change, regenerate the goldens and review the diff:
+//!
+//! ```text
+//! RTK_BLESS=1 cargo test golden
+//! ```
+use crate::cargo_cmd::{filter_cargo_build, filter_cargo_clippy, CargoTestParser, NextestParser};
+use crate::ci_log;
+use crate::git::{compact_diff, filter_status_with_args};
+use crate::go_cmd::GoTestParser;
+use crate::golangci_cmd::filter_golangci_json;
+use crate::lint_cmd::filter_eslint_json;
+use crate::parser::{format_parsed, OutputParser};
+use crate::pytest_cmd::PytestParser;
+use crate::ruff_cmd::filter_ruff_check_json;
+use crate::tracking::estimate_tokens;
+use crate::tsc_cmd::filter_tsc_output;
+use crate::vitest_cmd::VitestParser;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Fixture directories that hold other test data, not golden cases
+const NON_GOLDEN_DIRS: &[&str] = &["custom_filter", "gh_review", "json_output"];
+
+/// A filter under golden test, keyed by its fixture directory name
+struct GoldenFilter {
+    tool: &'static str,
+    /// Minimum share of tokens the filter must remove, 0.0..1.0
+    min_savings: f64,
+    filter: fn(&str) -> String,
+}
+
+const FILTERS: &[GoldenFilter] = &[
+    GoldenFilter {
+        tool: "cargo_build",
+        min_savings: 0.3,
+        filter: filter_cargo_build,
+    },
+    GoldenFilter {
+        tool: "cargo_clippy",
+        min_savings: 0.7,
+        filter: filter_cargo_clippy,
+    },
+    GoldenFilter {
+        tool: "cargo_test",
+        min_savings: 0.8,
+        filter: |raw| format_parsed("cargo test", CargoTestParser::parse(raw), 0),
+    },
+    GoldenFilter {
+        tool: "cargo_nextest",
+        min_savings: 0.8,
+        filter: |raw| format_parsed("cargo nextest", NextestParser::parse(raw), 0),
+    },
+    GoldenFilter {
+        tool: "pytest",
+        min_savings: 0.75,
+        filter: |raw| format_parsed("pytest", PytestParser::parse(raw), 0),
+    },
+    GoldenFilter {
+        tool: "go_test",
+        min_savings: 0.9,
+        filter: |raw| format_parsed("go test", GoTestParser::parse(raw), 0),
+    },
+    GoldenFilter {
+        tool: "vitest",
+        min_savings: 0.85,
+        filter: |raw| format_parsed("vitest", VitestParser::parse(raw), 0),
+    },
+    GoldenFilter {
+        tool: "tsc",
+        // tsc already prints one line per error; the shared diagnostic grammar keeps that
+        min_savings: 0.05,
+        filter: filter_tsc_output,
+    },
+    GoldenFilter {
+        tool: "eslint",
+        min_savings: 0.7,
+        filter: filter_eslint_json,
+    },
+    GoldenFilter {
+        tool: "ruff",
+        min_savings: 0.75,
+        filter: filter_ruff_check_json,
+    },
+    GoldenFilter {
+        tool: "golangci_lint",
+        min_savings: 0.7,
+        filter: filter_golangci_json,
+    },
+    GoldenFilter {
+        tool: "git_diff",
+        min_savings: 0.3,
+        filter: |raw| compact_diff(raw, 100),
+    },
+    GoldenFilter {
+        tool: "gh_run_log",
+        min_savings: 0.7,
+        filter: |raw| ci_log::render(&ci_log::parse(raw), 0),
+    },
+    GoldenFilter {
+        tool: "git_status",
+        min_savings: 0.3,
+        filter: filter_status_with_args,
+    },
+];
+
+fn fixtures_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn bless_mode() -> bool {
+    std::env::var("RTK_BLESS").is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+fn sorted_entries(dir: &Path) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("cannot read {}: {}", dir.display(), e))
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .collect();
+    entries.sort();
+    entries
+}
+
+/// Every `<case>.txt` under `tests/fixtures/<tool>/*/`
+fn fixture_cases(tool: &str) -> Vec<PathBuf> {
+    let tool_dir = fixtures_root().join(tool);
+    if !tool_dir.is_dir() {
+        return Vec::new();
+    }
+    sorted_entries(&tool_dir)
+        .into_iter()
+        .filter(|p| p.is_dir())
+        .flat_map(|version_dir| sorted_entries(&version_dir))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "txt"))
+        .collect()
+}
+
+/// Share of tokens removed by filtering, 0.0 when the output is not smaller
+fn savings_ratio(raw: &str, filtered: &str) -> f64 {
+    let input = estimate_tokens(raw);
+    if input == 0 {
+        return 0.0;
+    }
+    let output = estimate_tokens(filtered);
+    input.saturating_sub(output) as f64 / input as f64
+}
+
+/// Key lines from `<case>.keep` that are missing from the filtered output
+fn missing_keep_lines(keep: &str, filtered: &str) -> Vec<String> {
+    keep.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter(|l| !filtered.contains(l))
+        .map(String::from)
+        .collect()
+}
+
+/// Run one case; returns the problems found (empty = pass).
+fn check_case(golden_filter: &GoldenFilter, input_path: &Path, bless: bool) -> Vec<String> {
+    let name = input_path
+        .strip_prefix(fixtures_root())
+        .unwrap_or(input_path)
+        .display()
+        .to_string();
+    let raw = fs::read_to_string(input_path).expect("fixture is not UTF-8");
+    let filtered = (golden_filter.filter)(&raw);
+    let mut problems = Vec::new();
+
+    let golden_path = input_path.with_extension("golden");
+    if bless {
+        fs::write(&golden_path, format!("{}\n", filtered.trim_end()))
+            .unwrap_or_else(|e| panic!("cannot write {}: {}", golden_path.display(), e));
+    } else {
+        match fs::read_to_string(&golden_path) {
+            Ok(expected) if expected.trim_end() == filtered.trim_end() => {}
+            Ok(expected) => problems.push(format!(
+                "{}: output differs from golden\n--- expected\n{}\n--- actual\n{}",
+                name,
+                expected.trim_end(),
+                filtered.trim_end()
+            )),
+            Err(_) => problems.push(format!(
+                "{}: missing {} (run with RTK_BLESS=1 to create it)",
+                name,
+                golden_path.display()
+            )),
+        }
+    }
+
+    let savings = savings_ratio(&raw, &filtered);
+    if savings < golden_filter.min_savings {
+        problems.push(format!(
+            "{}: saved {:.0}% of tokens, expected at least {:.0}%",
+            name,
+            savings * 100.0,
+            golden_filter.min_savings * 100.0
+        ));
+    }
+
+    if let Ok(keep) = fs::read_to_string(input_path.with_extension("keep")) {
+        for line in missing_keep_lines(&keep, &filtered) {
+            problems.push(format!("{}: key line dropped: {}", name, line));
+        }
+    }
+
+    problems
+}
+
+#[test]
+fn test_golden_fixtures() {
+    let bless = bless_mode();
+    let mut problems = Vec::new();
+
+    for golden_filter in FILTERS {
+        let cases = fixture_cases(golden_filter.tool);
+        if cases.is_empty() {
+            problems.push(format!(
+                "{}: no fixtures in tests/fixtures/{}/<version>/",
+                golden_filter.tool, golden_filter.tool
+            ));
+        }
+        for case in cases {
+            problems.extend(check_case(golden_filter, &case, bless));
+        }
+    }
+
+    assert!(
+        problems.is_empty(),
+        "{} golden problem(s):\n\n{}",
+        problems.len(),
+        problems.join("\n\n")
+    );
+}
+
+#[test]
+fn test_golden_fixture_dirs_are_registered() {
+    for dir in sorted_entries(&fixtures_root()) {
+        let name = dir.file_name().unwrap().to_string_lossy().to_string();
+        if !dir.is_dir() || NON_GOLDEN_DIRS.contains(&name.as_str()) {
+            continue;
+        }
+        assert!(
+            FILTERS.iter().any(|f| f.tool == name),
+            "tests/fixtures/{} has no filter registered in golden.rs",
+            name
+        );
+    }
+}
+
+#[test]
+fn test_missing_keep_lines() {
+    let keep = "# failing tests\nsrc/lib.rs:10\n\ntests::bad\n";
+    assert!(missing_keep_lines(keep, "tests::bad at src/lib.rs:10").is_empty());
+    assert_eq!(
+        missing_keep_lines(keep, "tests::bad"),
+        vec!["src/lib.rs:10"]
+    );
+}
+
+#[test]
+fn test_savings_ratio() {
+    assert_eq!(savings_ratio("", "anything"), 0.0);
+    assert_eq!(savings_ratio("short", "much much longer output"), 0.0);
+    assert!(savings_ratio(&"word ".repeat(100), "word") > 0.9);
+}
@@ -1,8 +1,49 @@
+use crate::outline::SymbolIndex;
+use crate::search::{self, Hit, SearchOptions};
 use crate::tracking;
 use anyhow::{Context, Result};
 use regex::Regex;
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
 use std::process::Command;
+use std::str::FromStr;
+
+/// Line numbers listed per symbol in `--by-symbol` output
+const MAX_LINES_PER_SYMBOL: usize = 10;
+
+/// Which search backend `rtk grep` runs (`grep.engine` in config, `RTK_GREP_ENGINE`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Engine {
+    /// ripgrep when it is installed, the built-in search otherwise
+    Auto,
+    Ripgrep,
+    Builtin,
+}
+
+impl FromStr for Engine {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Engine::Auto),
+            "rg" | "ripgrep" => Ok(Engine::Ripgrep),
+            "builtin" => Ok(Engine::Builtin),
+            _ => Err(format!(
+                "unknown grep engine '{}' (expected auto, rg or builtin)",
+                s
+            )),
+        }
+    }
+}
+
+/// Matches and context lines from whichever backend ran, plus its exit code
+struct SearchOutput {
+    hits: Vec<Hit>,
+    exit_code: i32,
+    stderr: String,
+}
 
 pub fn run(
     pattern: &str,
@@ -10,8 +51,11 @@ pub fn run(
     max_line_len: usize,
     max_results: usize,
     context_only: bool,
+    by_symbol: bool,
     file_type: Option<&str>,
     extra_args: &[String],
+    engine: Engine,
+    ignore_dirs: &[String],
     verbose: u8,
 ) -> Result<()> {
     let timer = tracking::TimedExecution::start();
@@ -23,38 +67,43 @@ pub fn run(
     // Fix: convert BRE alternation \| → | for rg (which uses PCRE-style regex)
     let rg_pattern = pattern.replace(r"\|", "|");
 
-    let mut rg_cmd = Command::new("rg");
-    rg_cmd.args(["-n", "--no-heading", &rg_pattern, path]);
-
-    if let Some(ft) = file_type {
-        rg_cmd.arg("--type").arg(ft);
-    }
-
-    for arg in extra_args {
-        // Fix: skip grep-ism -r flag (rg is recursive by default; rg -r means --replace)
-        if arg == "-r" || arg == "--recursive" {
-            continue;
+    let ripgrep = match engine {
+        Engine::Builtin => None,
+        Engine::Ripgrep => Some(
+            run_ripgrep(&rg_pattern, path, file_type, extra_args, ignore_dirs)
+                .context("rg failed (is ripgrep installed?)")?,
+        ),
+        Engine::Auto => match run_ripgrep(&rg_pattern, path, file_type, extra_args, ignore_dirs) {
+            Ok(output) => Some(output),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e).context("rg failed"),
+        },
+    };
+    let search = match ripgrep {
+        Some(output) => output,
+        None => {
+            if verbose > 0 {
+                eprintln!("grep: using built-in search");
+            }
+            run_builtin(&rg_pattern, path, file_type, extra_args, ignore_dirs)?
         }
-        rg_cmd.arg(arg);
-    }
-
-    let output = rg_cmd
-        .output()
-        .or_else(|_| Command::new("grep").args(["-rn", pattern, path]).output())
-        .context("grep/rg failed")?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let exit_code = output.status.code().unwrap_or(1);
-
-    let raw_output = stdout.to_string();
-
-    if stdout.trim().is_empty() {
+    };
+
+    // What `grep -rn` would have printed, as the baseline for savings
+    let raw_output: String = search
+        .hits
+        .iter()
+        .map(|h| {
+            let sep = if h.is_match { ':' } else { '-' };
+            format!("{}{}{}{}{}\n", h.path, sep, h.line, sep, h.text)
+        })
+        .collect();
+    let exit_code = search.exit_code;
+
+    if search.hits.is_empty() {
         // Show stderr for errors (bad regex, missing file, etc.)
-        if exit_code == 2 {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if !stderr.trim().is_empty() {
-                eprintln!("{}", stderr.trim());
-            }
+        if exit_code == 2 && !search.stderr.trim().is_empty() {
+            eprintln!("{}", search.stderr.trim());
         }
         let msg = format!("🔍 0 for '{}'", pattern);
         println!("{}", msg);
@@ -70,52 +119,242 @@ pub fn run(
         return Ok(());
     }
 
-    let mut by_file: HashMap<String, Vec<(usize, String)>> = HashMap::new();
+    let mut by_file: BTreeMap<String, Vec<Hit>> = BTreeMap::new();
     let mut total = 0;
+    for mut hit in search.hits {
+        if hit.is_match {
+            total += 1;
+        }
+        hit.text = clean_line(&hit.text, max_line_len, context_only, pattern);
+        by_file.entry(hit.path.clone()).or_default().push(hit);
+    }
 
-    for line in stdout.lines() {
-        let parts: Vec<&str> = line.splitn(3, ':').collect();
+    let rtk_output = if by_symbol {
+        render_by_symbol(&by_file, total, max_results)
+    } else {
+        render_by_file(&by_file, total, max_results)
+    };
 
-        let (file, line_num, content) = if parts.len() == 3 {
-            let ln = parts[1].parse().unwrap_or(0);
-            (parts[0].to_string(), ln, parts[2])
-        } else if parts.len() == 2 {
-            let ln = parts[0].parse().unwrap_or(0);
-            (path.to_string(), ln, parts[1])
-        } else {
+    print!("{}", rtk_output);
+    timer.track(
+        &format!("grep -rn '{}' {}", pattern, path),
+        "rtk grep",
+        &raw_output,
+        &rtk_output,
+    );
+
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
+
+    Ok(())
+}
+
+/// Run `rg --json`; only the spawn itself fails, so a missing binary can fall back
+fn run_ripgrep(
+    pattern: &str,
+    path: &str,
+    file_type: Option<&str>,
+    extra_args: &[String],
+    ignore_dirs: &[String],
+) -> std::io::Result<SearchOutput> {
+    let mut rg_cmd = Command::new("rg");
+    rg_cmd.args(["--json", pattern, path]);
+
+    if let Some(ft) = file_type {
+        rg_cmd.arg("--type").arg(ft);
+    }
+    for dir in ignore_dirs {
+        rg_cmd.arg("--glob").arg(format!("!{}/", dir));
+    }
+
+    for arg in extra_args {
+        // Fix: skip grep-ism -r flag (rg is recursive by default; rg -r means --replace)
+        if arg == "-r" || arg == "--recursive" {
             continue;
-        };
+        }
+        rg_cmd.arg(arg);
+    }
+
+    let output = rg_cmd.output()?;
+    Ok(SearchOutput {
+        hits: parse_rg_json(&String::from_utf8_lossy(&output.stdout)),
+        exit_code: output.status.code().unwrap_or(1),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+    })
+}
 
-        total += 1;
-        let cleaned = clean_line(content, max_line_len, context_only, pattern);
-        by_file.entry(file).or_default().push((line_num, cleaned));
+/// Match and context lines from `rg --json` output
+fn parse_rg_json(stdout: &str) -> Vec<Hit> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let event: Value = serde_json::from_str(line).ok()?;
+            let is_match = match event["type"].as_str()? {
+                "match" => true,
+                "context" => false,
+                _ => return None,
+            };
+            let data = &event["data"];
+            // Non-UTF-8 paths and lines come base64-encoded under "bytes"; skip them
+            let text = data["lines"]["text"].as_str()?;
+            Some(Hit {
+                path: data["path"]["text"].as_str()?.to_string(),
+                line: data["line_number"].as_u64()? as usize,
+                text: text.lines().next().unwrap_or("").to_string(),
+                is_match,
+            })
+        })
+        .collect()
+}
+
+fn run_builtin(
+    pattern: &str,
+    path: &str,
+    file_type: Option<&str>,
+    extra_args: &[String],
+    ignore_dirs: &[String],
+) -> Result<SearchOutput> {
+    let (mut options, extra_paths, unsupported) = builtin_options(pattern, extra_args);
+    options.file_types.extend(file_type.map(String::from));
+    options.ignore_dirs = ignore_dirs.to_vec();
+    if !unsupported.is_empty() {
+        eprintln!(
+            "grep: built-in search ignores {} (install ripgrep for full flag support)",
+            unsupported.join(" ")
+        );
     }
 
+    // Like rg: a missing path is reported, the others are still searched, exit code 2
+    let mut hits = Vec::new();
+    let mut stderr = String::new();
+    for root in std::iter::once(path).chain(extra_paths.iter().map(String::as_str)) {
+        if Path::new(root).exists() {
+            hits.extend(search::search(root, &options)?);
+        } else {
+            stderr.push_str(&format!("{}: No such file or directory\n", root));
+        }
+    }
+    let exit_code = if !stderr.is_empty() {
+        2
+    } else if hits.is_empty() {
+        1
+    } else {
+        0
+    };
+    Ok(SearchOutput {
+        hits,
+        exit_code,
+        stderr,
+    })
+}
+
+/// Read the ripgrep flags the built-in search supports out of `extra_args`; returns the
+/// options, further paths to search and the arguments it can't honour
+fn builtin_options(
+    pattern: &str,
+    extra_args: &[String],
+) -> (SearchOptions, Vec<String>, Vec<String>) {
+    let mut options = SearchOptions {
+        pattern: pattern.to_string(),
+        ..Default::default()
+    };
+    let mut paths = Vec::new();
+    let mut unsupported = Vec::new();
+    let mut args = extra_args.iter();
+    while let Some(arg) = args.next() {
+        // `-A3`, `--context=3`: the value may be attached
+        let (flag, attached) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ if arg.len() > 2
+                && !arg.starts_with("--")
+                && b"ABCtg".contains(&arg.as_bytes()[1]) =>
+            {
+                (&arg[..2], Some(arg[2..].to_string()))
+            }
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || attached.clone().or_else(|| args.next().cloned());
+        match flag {
+            "-A" | "--after-context" | "-B" | "--before-context" | "-C" | "--context" => {
+                let Some(n) = value().and_then(|v| v.parse::<usize>().ok()) else {
+                    unsupported.push(arg.clone());
+                    continue;
+                };
+                if !matches!(flag, "-B" | "--before-context") {
+                    options.after = n;
+                }
+                if !matches!(flag, "-A" | "--after-context") {
+                    options.before = n;
+                }
+            }
+            "-t" | "--type" => options.file_types.extend(value()),
+            "-g" | "--glob" => options.globs.extend(value()),
+            "-i" | "--ignore-case" => options.ignore_case = true,
+            "-S" | "--smart-case" => options.smart_case = true,
+            "-F" | "--fixed-strings" => options.fixed_strings = true,
+            "-w" | "--word-regexp" => options.word = true,
+            // Recursion and line numbers are always on
+            "-r" | "--recursive" | "-n" | "--line-number" => {}
+            // Bundled short flags: `-iw`
+            short
+                if short.starts_with('-')
+                    && !short.starts_with("--")
+                    && short.len() > 2
+                    && short[1..].chars().all(|c| "iSFwrn".contains(c)) =>
+            {
+                for c in short[1..].chars() {
+                    match c {
+                        'i' => options.ignore_case = true,
+                        'S' => options.smart_case = true,
+                        'F' => options.fixed_strings = true,
+                        'w' => options.word = true,
+                        _ => {}
+                    }
+                }
+            }
+            path if !path.starts_with('-') => paths.push(path.to_string()),
+            _ => unsupported.push(arg.clone()),
+        }
+    }
+    (options, paths, unsupported)
+}
+
+fn render_by_file(
+    by_file: &BTreeMap<String, Vec<Hit>>,
+    total: usize,
+    max_results: usize,
+) -> String {
     let mut rtk_output = String::new();
     rtk_output.push_str(&format!("🔍 {} in {}F:\n\n", total, by_file.len()));
 
     let mut shown = 0;
-    let mut files: Vec<_> = by_file.iter().collect();
-    files.sort_by_key(|(f, _)| *f);
-
-    for (file, matches) in files {
+    for (file, hits) in by_file {
         if shown >= max_results {
             break;
         }
 
+        let matches = hits.iter().filter(|h| h.is_match).count();
         let file_display = compact_path(file);
-        rtk_output.push_str(&format!("📄 {} ({}):\n", file_display, matches.len()));
-
-        for (line_num, content) in matches.iter().take(10) {
-            rtk_output.push_str(&format!("  {:>4}: {}\n", line_num, content));
-            shown += 1;
-            if shown >= max_results {
+        rtk_output.push_str(&format!("📄 {} ({}):\n", file_display, matches));
+
+        let mut file_shown = 0;
+        for hit in hits {
+            // Context lines (`-A`/`-B`/`-C`) print with `-`, like grep
+            if !hit.is_match {
+                rtk_output.push_str(&format!("  {:>4}- {}\n", hit.line, hit.text));
+                continue;
+            }
+            if file_shown == 10 || shown >= max_results {
                 break;
             }
+            rtk_output.push_str(&format!("  {:>4}: {}\n", hit.line, hit.text));
+            file_shown += 1;
+            shown += 1;
         }
 
-        if matches.len() > 10 {
-            rtk_output.push_str(&format!("  +{}\n", matches.len() - 10));
+        if matches > 10 {
+            rtk_output.push_str(&format!("  +{}\n", matches - 10));
         }
         rtk_output.push('\n');
     }
@@ -123,20 +362,113 @@ pub fn run(
     if total > shown {
         rtk_output.push_str(&format!("... +{}\n", total - shown));
     }
+    rtk_output
+}
 
-    print!("{}", rtk_output);
-    timer.track(
-        &format!("grep -rn '{}' {}", pattern, path),
-        "rtk grep",
-        &raw_output,
-        &rtk_output,
-    );
+/// Matches under one symbol; None for top-level code
+type SymbolGroup<'a> = (Option<String>, Vec<&'a Hit>);
 
-    if exit_code != 0 {
-        std::process::exit(exit_code);
+/// Matches of one file collapsed by enclosing symbol, in order of first match
+struct SymbolGroups<'a> {
+    file: &'a str,
+    matches: usize,
+    density: f64,
+    groups: Vec<SymbolGroup<'a>>,
+}
+
+fn group_by_symbol<'a>(file: &'a str, hits: &'a [Hit]) -> SymbolGroups<'a> {
+    let content = fs::read_to_string(file).unwrap_or_default();
+    let index = SymbolIndex::build(file, &content);
+    let mut groups: Vec<SymbolGroup> = Vec::new();
+    let mut matches = 0;
+    // Context lines have no place in the symbol view
+    for hit in hits.iter().filter(|h| h.is_match) {
+        matches += 1;
+        let symbol = index.enclosing(hit.line);
+        match groups.iter_mut().find(|(s, _)| *s == symbol) {
+            Some((_, lines)) => lines.push(hit),
+            None => groups.push((symbol, vec![hit])),
+        }
     }
+    SymbolGroups {
+        file,
+        matches,
+        density: matches as f64 / content.lines().count().max(1) as f64,
+        groups,
+    }
+}
 
-    Ok(())
+/// Files ranked by matches per line, each match under its enclosing symbol:
+///
+/// ```text
+/// 📄 src/read.rs (5):
+///   run (4): 231 240 255 270
+///     231: let specs = expand(args)?;
+///   (top level) (1): 9
+///     9: use std::fs;
+/// ```
+fn render_by_symbol(
+    by_file: &BTreeMap<String, Vec<Hit>>,
+    total: usize,
+    max_results: usize,
+) -> String {
+    let mut ranked: Vec<SymbolGroups> = by_file
+        .iter()
+        .map(|(file, matches)| group_by_symbol(file, matches))
+        .collect();
+    // Densest first, so the result cap keeps the files most about the pattern
+    ranked.sort_by(|a, b| {
+        b.density
+            .total_cmp(&a.density)
+            .then(b.matches.cmp(&a.matches))
+            .then(a.file.cmp(b.file))
+    });
+
+    let symbols: usize = ranked.iter().map(|f| f.groups.len()).sum();
+    let mut rtk_output = format!(
+        "🔍 {} in {}F, {} symbols:\n\n",
+        total,
+        ranked.len(),
+        symbols
+    );
+    let mut shown = 0;
+    for file in &ranked {
+        if shown >= max_results {
+            break;
+        }
+        rtk_output.push_str(&format!(
+            "📄 {} ({}):\n",
+            compact_path(file.file),
+            file.matches
+        ));
+        for (symbol, matches) in &file.groups {
+            if shown >= max_results {
+                break;
+            }
+            let mut lines: Vec<String> = matches
+                .iter()
+                .take(MAX_LINES_PER_SYMBOL)
+                .map(|hit| hit.line.to_string())
+                .collect();
+            if matches.len() > MAX_LINES_PER_SYMBOL {
+                lines.push(format!("+{}", matches.len() - MAX_LINES_PER_SYMBOL));
+            }
+            rtk_output.push_str(&format!(
+                "  {} ({}): {}\n",
+                symbol.as_deref().unwrap_or("(top level)"),
+                matches.len(),
+                lines.join(" ")
+            ));
+            rtk_output.push_str(&format!("    {}: {}\n", matches[0].line, matches[0].text));
+            shown += matches.len();
+        }
+        rtk_output.push('\n');
+    }
+
+    if total > shown {
+        rtk_output.push_str(&format!("... +{}\n", total.saturating_sub(shown)));
+    }
+    rtk_output
 }
 
 fn clean_line(line: &str, max_len: usize, context_only: bool, pattern: &str) -> String {
@@ -248,6 +580,130 @@ mod tests {
         assert!(!cleaned.is_empty());
     }
 
+    fn matches(path: &str, lines: &[usize]) -> Vec<Hit> {
+        lines
+            .iter()
+            .map(|&n| Hit {
+                path: path.to_string(),
+                line: n,
+                text: format!("line {}", n),
+                is_match: true,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_by_symbol_ranks_dense_files_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let sparse = dir.path().join("sparse.py");
+        let dense = dir.path().join("dense.py");
+        let filler = "x = 1\n".repeat(40);
+        fs::write(&sparse, format!("def a():\n    hit()\n{}", filler)).unwrap();
+        fs::write(
+            &dense,
+            "def b():\n    hit()\n    hit()\n\ndef c():\n    hit()\n",
+        )
+        .unwrap();
+        let sparse = sparse.display().to_string();
+        let dense = dense.display().to_string();
+
+        let mut by_file = BTreeMap::new();
+        by_file.insert(sparse.clone(), matches(&sparse, &[2]));
+        by_file.insert(dense.clone(), matches(&dense, &[2, 3, 6]));
+        let out = render_by_symbol(&by_file, 4, 50);
+        assert!(out.starts_with("🔍 4 in 2F, 3 symbols:"));
+        assert!(out.find("dense.py").unwrap() < out.find("sparse.py").unwrap());
+        assert!(out.contains("  b (2): 2 3\n    2: line 2\n"));
+        assert!(out.contains("  c (1): 6\n"));
+
+        // The cap cuts the sparse file, not the dense one
+        let capped = render_by_symbol(&by_file, 4, 3);
+        assert!(!capped.contains("sparse.py"));
+        assert!(capped.ends_with("... +1\n"));
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_builtin_options_from_rg_flags() {
+        let (options, paths, unsupported) = builtin_options(
+            "x",
+            &args(&[
+                "-iw",
+                "-A",
+                "2",
+                "-B1",
+                "--glob=*.rs",
+                "-t",
+                "py",
+                "tests",
+                "--pcre2",
+            ]),
+        );
+        assert!(options.ignore_case && options.word && !options.fixed_strings);
+        assert_eq!((options.before, options.after), (1, 2));
+        assert_eq!(options.globs, ["*.rs"]);
+        assert_eq!(options.file_types, ["py"]);
+        assert_eq!(paths, ["tests"]);
+        assert_eq!(unsupported, ["--pcre2"]);
+
+        let (options, _, unsupported) = builtin_options("x", &args(&["-C", "3", "-F", "-r"]));
+        assert_eq!((options.before, options.after), (3, 3));
+        assert!(options.fixed_strings);
+        assert!(unsupported.is_empty());
+    }
+
+    #[test]
+    fn test_parse_rg_json() {
+        let stdout = r#"{"type":"begin","data":{"path":{"text":"src/a.rs"}}}
+{"type":"context","data":{"path":{"text":"src/a.rs"},"lines":{"text":"// before\n"},"line_number":3,"absolute_offset":10,"submatches":[]}}
+{"type":"match","data":{"path":{"text":"src/a.rs"},"lines":{"text":"fn parse() {}\n"},"line_number":4,"absolute_offset":20,"submatches":[]}}
+{"type":"match","data":{"path":{"bytes":"/w=="},"lines":{"text":"x\n"},"line_number":1,"absolute_offset":0,"submatches":[]}}
+{"type":"end","data":{"path":{"text":"src/a.rs"}}}
+"#;
+        let hits = parse_rg_json(stdout);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(
+            hits[1],
+            Hit {
+                path: "src/a.rs".to_string(),
+                line: 4,
+                text: "fn parse() {}".to_string(),
+                is_match: true,
+            }
+        );
+        assert!(!hits[0].is_match);
+    }
+
+    #[test]
+    fn test_render_by_file_shows_context_lines() {
+        let mut hits = matches("a.rs", &[5]);
+        hits.insert(
+            0,
+            Hit {
+                path: "a.rs".to_string(),
+                line: 4,
+                text: "before".to_string(),
+                is_match: false,
+            },
+        );
+        let by_file = BTreeMap::from([("a.rs".to_string(), hits)]);
+        assert_eq!(
+            render_by_file(&by_file, 1, 50),
+            "🔍 1 in 1F:\n\n📄 a.rs (1):\n     4- before\n     5: line 5\n\n"
+        );
+    }
+
+    #[test]
+    fn test_engine_from_str() {
+        assert_eq!("auto".parse::<Engine>(), Ok(Engine::Auto));
+        assert_eq!("rg".parse::<Engine>(), Ok(Engine::Ripgrep));
+        assert_eq!("builtin".parse::<Engine>(), Ok(Engine::Builtin));
+        assert!("grep".parse::<Engine>().is_err());
+    }
+
     // Fix: BRE \| alternation is translated to PCRE | for rg
     #[test]
     fn test_bre_alternation_translated() {
@@ -268,11 +724,11 @@ mod tests {
         assert_eq!(filtered[0], "-i");
     }
 
-    // Verify line numbers are always enabled in rg invocation (grep_cmd.rs:24).
+    // Verify line numbers are always available (`rg --json` and the built-in search report them).
     // The -n/--line-numbers clap flag in main.rs is a no-op accepted for compat.
     #[test]
     fn test_rg_always_has_line_numbers() {
-        // grep_cmd::run() always passes "-n" to rg (line 24).
+        // grep_cmd::run() reads line numbers from `rg --json` events, never from flags.
         // This test documents that -n is built-in, so the clap flag is safe to ignore.
         let mut cmd = std::process::Command::new("rg");
         cmd.args(["-n", "--no-heading", "NONEXISTENT_PATTERN_12345", "."]);
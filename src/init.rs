@@ -79,6 +79,8 @@ rtk git branch          # Compact branch list
 rtk git fetch           # Compact fetch
 rtk git stash           # Compact stash
 rtk git worktree        # Compact worktree
+rtk git blame <file>    # Line ranges per commit + one-line commit legend
+rtk git merge/rebase    # Outcome only; on conflict, hunks + continue/abort commands
 ```
 
 Note: Git passthrough works for ALL subcommands, even those not explicitly listed.
@@ -87,7 +89,9 @@ Note: Git passthrough works for ALL subcommands, even those not explicitly liste
 ```bash
 rtk gh pr view <num>    # Compact PR view (87%)
 rtk gh pr checks        # Compact PR checks (79%)
+rtk gh pr review-comments <num>  # Unresolved review threads by file
 rtk gh run list         # Compact workflow runs (82%)
+rtk gh run view <id> --log-failed  # Failing step, filtered like a local run
 rtk gh issue list       # Compact issue list (80%)
 rtk gh api              # Compact API responses (26%)
 ```
@@ -1000,10 +1004,10 @@ pub fn show_config() -> Result<()> {
             let is_executable = perms.mode() & 0o111 != 0;
 
             let hook_content = fs::read_to_string(&hook_path)?;
-            let has_guards =
-                hook_content.contains("command -v rtk") && hook_content.contains("command -v jq");
+            let has_guards = hook_content.contains("command -v rtk");
+            let is_native = hook_content.contains("rtk rewrite");
 
-            if is_executable && has_guards {
+            if is_executable && has_guards && is_native {
                 println!("✅ Hook: {} (executable, with guards)", hook_path.display());
             } else if !is_executable {
                 println!(
@@ -1011,7 +1015,10 @@ pub fn show_config() -> Result<()> {
                     hook_path.display()
                 );
             } else {
-                println!("⚠️  Hook: {} (no guards - outdated)", hook_path.display());
+                println!(
+                    "⚠️  Hook: {} (outdated - run: rtk init -g)",
+                    hook_path.display()
+                );
             }
         }
 
@@ -1135,14 +1142,11 @@ mod tests {
     #[test]
     fn test_hook_has_guards() {
         assert!(REWRITE_HOOK.contains("command -v rtk"));
-        assert!(REWRITE_HOOK.contains("command -v jq"));
-        // Guards must be BEFORE set -euo pipefail
+        // Rewrite logic lives in the binary; the hook only delegates to it
         let guard_pos = REWRITE_HOOK.find("command -v rtk").unwrap();
-        let set_pos = REWRITE_HOOK.find("set -euo pipefail").unwrap();
-        assert!(
-            guard_pos < set_pos,
-            "Guards must come before set -euo pipefail"
-        );
+        let exec_pos = REWRITE_HOOK.find("exec rtk rewrite").unwrap();
+        assert!(guard_pos < exec_pos, "Guard must come before exec");
+        assert!(!REWRITE_HOOK.contains("jq"), "Hook must not depend on jq");
     }
 
     #[test]
@@ -1,10 +1,13 @@
+use crate::parser::{
+    render_delta, render_diagnostics, render_json, truncate_output, LintIssue, LintResult,
+    LintSeverity, OutputParser, ParseResult,
+};
 use crate::ruff_cmd;
 use crate::tracking;
 use crate::utils::{package_manager_exec, truncate};
 use anyhow::{Context, Result};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::process::Command;
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -15,6 +18,19 @@ struct EslintMessage {
     message: String,
     line: usize,
     column: usize,
+    #[serde(rename = "endLine")]
+    end_line: Option<usize>,
+    #[serde(rename = "endColumn")]
+    end_column: Option<usize>,
+    /// Present when `eslint --fix` can apply the fix
+    fix: Option<serde_json::Value>,
+    #[serde(default)]
+    suggestions: Vec<EslintSuggestion>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct EslintSuggestion {
+    desc: String,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -157,27 +173,50 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
+    let exit_code = output
+        .status
+        .code()
+        .unwrap_or(if output.status.success() { 0 } else { 1 });
+
     // Dispatch to appropriate filter based on linter
-    let filtered = match linter {
-        "eslint" => filter_eslint_json(&stdout),
-        "ruff" => {
-            // Reuse ruff_cmd's JSON parser
-            if !stdout.trim().is_empty() {
+    let filtered = if crate::parser::json_mode() {
+        let command = format!("{} {}", linter, args[start_idx..].join(" "));
+        match linter {
+            "eslint" => render_json::<EslintParser>(&command, &stdout, exit_code),
+            "ruff" => render_json::<ruff_cmd::RuffCheckParser>(&command, &stdout, exit_code),
+            "pylint" => render_json::<PylintParser>(&command, &stdout, exit_code),
+            "mypy" => render_json::<MypyParser>(&command, &raw, exit_code),
+            _ => render_json::<GenericLintParser>(&command, &raw, exit_code),
+        }
+    } else if crate::parser::delta_mode() && matches!(linter, "eslint" | "ruff") {
+        let command = format!("{} {}", linter, args[start_idx..].join(" "));
+        if linter == "eslint" {
+            render_delta::<EslintParser>("eslint", &command, &stdout, || {
+                filter_eslint_json(&stdout)
+            })
+        } else {
+            render_delta::<ruff_cmd::RuffCheckParser>("ruff", &command, &stdout, || {
                 ruff_cmd::filter_ruff_check_json(&stdout)
-            } else {
-                "✓ Ruff: No issues found".to_string()
+            })
+        }
+    } else {
+        match linter {
+            "eslint" => filter_eslint_json(&stdout),
+            "ruff" => {
+                // Reuse ruff_cmd's JSON parser
+                if !stdout.trim().is_empty() {
+                    ruff_cmd::filter_ruff_check_json(&stdout)
+                } else {
+                    "✓ Ruff: No issues found".to_string()
+                }
             }
+            "pylint" => filter_pylint_json(&stdout),
+            "mypy" => filter_mypy_output(&raw),
+            _ => filter_generic_lint(&raw),
         }
-        "pylint" => filter_pylint_json(&stdout),
-        "mypy" => filter_mypy_output(&raw),
-        _ => filter_generic_lint(&raw),
     };
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
-    if let Some(hint) = crate::tee::tee_and_hint(&raw, "lint", exit_code) {
+    if let Some(hint) = crate::tee::tee_and_hint_for(&filtered, &raw, "lint", exit_code) {
         println!("{}\n{}", filtered, hint);
     } else {
         println!("{}", filtered);
@@ -197,331 +236,177 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     Ok(())
 }
 
-/// Filter ESLint JSON output - group by rule and file
-fn filter_eslint_json(output: &str) -> String {
-    let results: Result<Vec<EslintResult>, _> = serde_json::from_str(output);
-
-    let results = match results {
-        Ok(r) => r,
-        Err(e) => {
-            // Fallback if JSON parsing fails
-            return format!(
-                "ESLint output (JSON parse failed: {})\n{}",
-                e,
-                truncate(output, 500)
-            );
-        }
-    };
-
-    // Count total issues
-    let total_errors: usize = results.iter().map(|r| r.error_count).sum();
-    let total_warnings: usize = results.iter().map(|r| r.warning_count).sum();
-    let total_files = results.iter().filter(|r| !r.messages.is_empty()).count();
-
-    if total_errors == 0 && total_warnings == 0 {
-        return "✓ ESLint: No issues found".to_string();
+/// Parser for `eslint -f json` into a canonical LintResult
+pub struct EslintParser;
+
+impl OutputParser for EslintParser {
+    type Output = LintResult;
+
+    fn parse(input: &str) -> ParseResult<LintResult> {
+        let results: Vec<EslintResult> = match serde_json::from_str(input) {
+            Ok(r) => r,
+            Err(_) => return ParseResult::Passthrough(truncate_output(input, 500)),
+        };
+        let total_files = results.len();
+        let issues: Vec<LintIssue> = results
+            .into_iter()
+            .flat_map(|r| {
+                let file_path = r.file_path;
+                r.messages.into_iter().map(move |m| LintIssue {
+                    file_path: file_path.clone(),
+                    line: m.line,
+                    column: m.column,
+                    severity: if m.severity >= 2 {
+                        LintSeverity::Error
+                    } else {
+                        LintSeverity::Warning
+                    },
+                    end_line: m.end_line,
+                    end_column: m.end_column,
+                    rule_id: m.rule_id.unwrap_or_default(),
+                    message: m.message,
+                    suggestion: match (m.suggestions.into_iter().next(), m.fix) {
+                        (Some(s), _) => Some(s.desc),
+                        (None, Some(_)) => Some("fixable with `eslint --fix`".to_string()),
+                        (None, None) => None,
+                    },
+                    ..LintIssue::default()
+                })
+            })
+            .collect();
+        ParseResult::Full(LintResult {
+            total_files,
+            ..LintResult::from_issues(issues)
+        })
     }
+}
 
-    // Group messages by rule
-    let mut by_rule: HashMap<String, usize> = HashMap::new();
-    for result in &results {
-        for msg in &result.messages {
-            if let Some(rule) = &msg.rule_id {
-                *by_rule.entry(rule.clone()).or_insert(0) += 1;
-            }
-        }
+/// Parser for `pylint --output-format=json2` into a canonical LintResult
+pub struct PylintParser;
+
+impl OutputParser for PylintParser {
+    type Output = LintResult;
+
+    fn parse(input: &str) -> ParseResult<LintResult> {
+        let diagnostics: Vec<PylintDiagnostic> = match serde_json::from_str(input) {
+            Ok(d) => d,
+            Err(_) => return ParseResult::Passthrough(truncate_output(input, 500)),
+        };
+        ParseResult::Full(LintResult::from_issues(
+            diagnostics
+                .into_iter()
+                .map(|d| LintIssue {
+                    severity: match d.msg_type.as_str() {
+                        "error" | "fatal" => LintSeverity::Error,
+                        "warning" => LintSeverity::Warning,
+                        _ => LintSeverity::Info,
+                    },
+                    file_path: d.path,
+                    line: d.line,
+                    column: d.column,
+                    rule_id: format!("{} ({})", d.symbol, d.message_id),
+                    message: d.message,
+                    ..LintIssue::default()
+                })
+                .collect(),
+        ))
     }
+}
 
-    // Group by file
-    let mut by_file: Vec<(&EslintResult, usize)> = results
-        .iter()
-        .filter(|r| !r.messages.is_empty())
-        .map(|r| (r, r.messages.len()))
-        .collect();
-    by_file.sort_by(|a, b| b.1.cmp(&a.1));
-
-    // Build output
-    let mut result = String::new();
-    result.push_str(&format!(
-        "ESLint: {} errors, {} warnings in {} files\n",
-        total_errors, total_warnings, total_files
-    ));
-    result.push_str("═══════════════════════════════════════\n");
+/// Parser for mypy's `file.py:line: error: message [code]` lines
+pub struct MypyParser;
 
-    // Show top rules
-    let mut rule_counts: Vec<_> = by_rule.iter().collect();
-    rule_counts.sort_by(|a, b| b.1.cmp(a.1));
+impl OutputParser for MypyParser {
+    type Output = LintResult;
 
-    if !rule_counts.is_empty() {
-        result.push_str("Top rules:\n");
-        for (rule, count) in rule_counts.iter().take(10) {
-            result.push_str(&format!("  {} ({}x)\n", rule, count));
+    fn parse(input: &str) -> ParseResult<LintResult> {
+        lazy_static::lazy_static! {
+            static ref MYPY_LINE: Regex =
+                Regex::new(r"^(.+\.pyi?):(\d+):(?:(\d+):)? (error|warning|note): (.+?)(?:\s+\[([\w-]+)\])?$")
+                    .unwrap();
         }
-        result.push('\n');
-    }
 
-    // Show top files with most issues
-    result.push_str("Top files:\n");
-    for (file_result, count) in by_file.iter().take(10) {
-        let short_path = compact_path(&file_result.file_path);
-        result.push_str(&format!("  {} ({} issues)\n", short_path, count));
-
-        // Show top 3 rules in this file
-        let mut file_rules: HashMap<String, usize> = HashMap::new();
-        for msg in &file_result.messages {
-            if let Some(rule) = &msg.rule_id {
-                *file_rules.entry(rule.clone()).or_insert(0) += 1;
+        let mut issues: Vec<LintIssue> = Vec::new();
+        for caps in input.lines().filter_map(|line| MYPY_LINE.captures(line)) {
+            let file_path = &caps[1];
+            let line: usize = caps[2].parse().unwrap_or(0);
+            let message = caps[5].to_string();
+
+            // A note on the line of the previous error explains that error
+            if &caps[4] == "note" {
+                if let Some(prev) = issues.last_mut() {
+                    if prev.file_path == file_path && prev.line == line {
+                        prev.notes.push(message);
+                        continue;
+                    }
+                }
             }
-        }
-
-        let mut file_rule_counts: Vec<_> = file_rules.iter().collect();
-        file_rule_counts.sort_by(|a, b| b.1.cmp(a.1));
 
-        for (rule, count) in file_rule_counts.iter().take(3) {
-            result.push_str(&format!("    {} ({})\n", rule, count));
+            issues.push(LintIssue {
+                file_path: file_path.to_string(),
+                line,
+                column: caps
+                    .get(3)
+                    .and_then(|m| m.as_str().parse().ok())
+                    .unwrap_or(0),
+                severity: match &caps[4] {
+                    "error" => LintSeverity::Error,
+                    "warning" => LintSeverity::Warning,
+                    _ => LintSeverity::Info,
+                },
+                message,
+                rule_id: caps.get(6).map_or("", |m| m.as_str()).to_string(),
+                ..LintIssue::default()
+            });
         }
-    }
-
-    if by_file.len() > 10 {
-        result.push_str(&format!("\n... +{} more files\n", by_file.len() - 10));
-    }
-
-    result.trim().to_string()
-}
 
-/// Filter pylint JSON2 output - group by symbol and file
-fn filter_pylint_json(output: &str) -> String {
-    let diagnostics: Result<Vec<PylintDiagnostic>, _> = serde_json::from_str(output);
-
-    let diagnostics = match diagnostics {
-        Ok(d) => d,
-        Err(e) => {
-            // Fallback if JSON parsing fails
-            return format!(
-                "Pylint output (JSON parse failed: {})\n{}",
-                e,
-                truncate(output, 500)
-            );
-        }
-    };
-
-    if diagnostics.is_empty() {
-        return "✓ Pylint: No issues found".to_string();
-    }
-
-    // Count by type
-    let mut errors = 0;
-    let mut warnings = 0;
-    let mut conventions = 0;
-    let mut refactors = 0;
-
-    for diag in &diagnostics {
-        match diag.msg_type.as_str() {
-            "error" => errors += 1,
-            "warning" => warnings += 1,
-            "convention" => conventions += 1,
-            "refactor" => refactors += 1,
-            _ => {}
+        if issues.is_empty() && !input.contains("Success: no issues found") {
+            return ParseResult::Passthrough(truncate_output(input, 500));
         }
+        ParseResult::Full(LintResult::from_issues(issues))
     }
+}
 
-    // Count unique files
-    let unique_files: std::collections::HashSet<_> = diagnostics.iter().map(|d| &d.path).collect();
-    let total_files = unique_files.len();
+/// Linters without a structured format: always passthrough
+pub struct GenericLintParser;
 
-    // Group by symbol (rule code)
-    let mut by_symbol: HashMap<String, usize> = HashMap::new();
-    for diag in &diagnostics {
-        let key = format!("{} ({})", diag.symbol, diag.message_id);
-        *by_symbol.entry(key).or_insert(0) += 1;
-    }
+impl OutputParser for GenericLintParser {
+    type Output = LintResult;
 
-    // Group by file
-    let mut by_file: HashMap<&str, usize> = HashMap::new();
-    for diag in &diagnostics {
-        *by_file.entry(&diag.path).or_insert(0) += 1;
+    fn parse(input: &str) -> ParseResult<LintResult> {
+        ParseResult::Passthrough(truncate_output(input, 500))
     }
+}
 
-    let mut file_counts: Vec<_> = by_file.iter().collect();
-    file_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-    // Build output
-    let mut result = String::new();
-    result.push_str(&format!(
-        "Pylint: {} issues in {} files\n",
-        diagnostics.len(),
-        total_files
-    ));
-
-    if errors > 0 || warnings > 0 {
-        result.push_str(&format!("  {} errors, {} warnings", errors, warnings));
-        if conventions > 0 || refactors > 0 {
-            result.push_str(&format!(
-                ", {} conventions, {} refactors",
-                conventions, refactors
-            ));
-        }
-        result.push('\n');
-    }
-
-    result.push_str("═══════════════════════════════════════\n");
-
-    // Show top symbols (rules)
-    let mut symbol_counts: Vec<_> = by_symbol.iter().collect();
-    symbol_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-    if !symbol_counts.is_empty() {
-        result.push_str("Top rules:\n");
-        for (symbol, count) in symbol_counts.iter().take(10) {
-            result.push_str(&format!("  {} ({}x)\n", symbol, count));
-        }
-        result.push('\n');
-    }
+/// Filter ESLint JSON output - issues grouped by file
+pub(crate) fn filter_eslint_json(output: &str) -> String {
+    render_parsed::<EslintParser>("ESLint", output)
+}
 
-    // Show top files
-    result.push_str("Top files:\n");
-    for (file, count) in file_counts.iter().take(10) {
-        let short_path = compact_path(file);
-        result.push_str(&format!("  {} ({} issues)\n", short_path, count));
-
-        // Show top 3 rules in this file
-        let mut file_symbols: HashMap<String, usize> = HashMap::new();
-        for diag in diagnostics.iter().filter(|d| &d.path == *file) {
-            let key = format!("{} ({})", diag.symbol, diag.message_id);
-            *file_symbols.entry(key).or_insert(0) += 1;
+/// Render a linter's parse with the shared diagnostic renderer, or a truncated
+/// passthrough when its output didn't parse
+fn render_parsed<P: OutputParser<Output = LintResult>>(tool: &str, output: &str) -> String {
+    match P::parse(output) {
+        ParseResult::Full(result) | ParseResult::Degraded(result, _) => {
+            render_diagnostics(tool, &result.issues)
         }
-
-        let mut file_symbol_counts: Vec<_> = file_symbols.iter().collect();
-        file_symbol_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-        for (symbol, count) in file_symbol_counts.iter().take(3) {
-            result.push_str(&format!("    {} ({})\n", symbol, count));
+        ParseResult::Passthrough(_) => {
+            format!("{} output (parse failed)\n{}", tool, truncate(output, 500))
         }
     }
+}
 
-    if file_counts.len() > 10 {
-        result.push_str(&format!("\n... +{} more files\n", file_counts.len() - 10));
-    }
-
-    result.trim().to_string()
+/// Filter pylint JSON2 output - issues grouped by file
+fn filter_pylint_json(output: &str) -> String {
+    render_parsed::<PylintParser>("Pylint", output)
 }
 
-/// Filter mypy text output - parse and group by error code and file
+/// Filter mypy text output - issues grouped by file
 fn filter_mypy_output(output: &str) -> String {
-    // Regex pattern: path/to/file.py:line: error: message [error-code]
-    let re = Regex::new(r"^(.+\.py):(\d+): (error|warning|note): (.+?) \[(.+?)\]").unwrap();
-
-    let mut issues: Vec<(String, String, String, String)> = Vec::new(); // (file, line, level, code)
-    let mut errors = 0;
-    let mut warnings = 0;
-    let mut notes = 0;
-
-    for line in output.lines() {
-        if let Some(caps) = re.captures(line) {
-            let file = caps.get(1).map_or("", |m| m.as_str());
-            let line_num = caps.get(2).map_or("", |m| m.as_str());
-            let level = caps.get(3).map_or("", |m| m.as_str());
-            let code = caps.get(5).map_or("", |m| m.as_str());
-
-            match level {
-                "error" => errors += 1,
-                "warning" => warnings += 1,
-                "note" => notes += 1,
-                _ => {}
-            }
-
-            issues.push((
-                file.to_string(),
-                line_num.to_string(),
-                level.to_string(),
-                code.to_string(),
-            ));
-        }
-    }
-
-    if issues.is_empty() {
-        // Check if mypy output contains "Success" or similar
-        if output.contains("Success") || output.trim().is_empty() {
-            return "✓ Mypy: No issues found".to_string();
-        }
-        // Fallback to generic output if no regex matches
-        return format!("Mypy output:\n{}", truncate(output, 500));
-    }
-
-    // Count unique files
-    let unique_files: std::collections::HashSet<_> = issues.iter().map(|(f, _, _, _)| f).collect();
-    let total_files = unique_files.len();
-
-    // Group by error code
-    let mut by_code: HashMap<String, usize> = HashMap::new();
-    for (_, _, _, code) in &issues {
-        *by_code.entry(code.clone()).or_insert(0) += 1;
-    }
-
-    // Group by file
-    let mut by_file: HashMap<&str, usize> = HashMap::new();
-    for (file, _, _, _) in &issues {
-        *by_file.entry(file.as_str()).or_insert(0) += 1;
-    }
-
-    let mut file_counts: Vec<_> = by_file.iter().collect();
-    file_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-    // Build output
-    let mut result = String::new();
-    result.push_str(&format!(
-        "Mypy: {} issues in {} files\n",
-        issues.len(),
-        total_files
-    ));
-
-    if errors > 0 || warnings > 0 {
-        result.push_str(&format!("  {} errors, {} warnings", errors, warnings));
-        if notes > 0 {
-            result.push_str(&format!(", {} notes", notes));
-        }
-        result.push('\n');
-    }
-
-    result.push_str("═══════════════════════════════════════\n");
-
-    // Show top error codes
-    let mut code_counts: Vec<_> = by_code.iter().collect();
-    code_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-    if !code_counts.is_empty() {
-        result.push_str("Top error codes:\n");
-        for (code, count) in code_counts.iter().take(10) {
-            result.push_str(&format!("  {} ({}x)\n", code, count));
-        }
-        result.push('\n');
-    }
-
-    // Show top files
-    result.push_str("Top files:\n");
-    for (file, count) in file_counts.iter().take(10) {
-        let short_path = compact_path(file);
-        result.push_str(&format!("  {} ({} issues)\n", short_path, count));
-
-        // Show top 3 error codes in this file
-        let mut file_codes: HashMap<String, usize> = HashMap::new();
-        for (_f, _, _, code) in issues.iter().filter(|(f, _, _, _)| f == *file) {
-            *file_codes.entry(code.clone()).or_insert(0) += 1;
-        }
-
-        let mut file_code_counts: Vec<_> = file_codes.iter().collect();
-        file_code_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-        for (code, count) in file_code_counts.iter().take(3) {
-            result.push_str(&format!("    {} ({})\n", code, count));
-        }
+    if output.trim().is_empty() {
+        return "✓ Mypy: No issues found".to_string();
     }
-
-    if file_counts.len() > 10 {
-        result.push_str(&format!("\n... +{} more files\n", file_counts.len() - 10));
-    }
-
-    result.trim().to_string()
+    render_parsed::<MypyParser>("Mypy", output)
 }
 
 /// Filter generic linter output (fallback for non-ESLint linters)
@@ -561,25 +446,10 @@ fn filter_generic_lint(output: &str) -> String {
     result.trim().to_string()
 }
 
-/// Compact file path (remove common prefixes)
-fn compact_path(path: &str) -> String {
-    // Remove common prefixes like /Users/..., /home/..., C:\
-    let path = path.replace('\\', "/");
-
-    if let Some(pos) = path.rfind("/src/") {
-        format!("src/{}", &path[pos + 5..])
-    } else if let Some(pos) = path.rfind("/lib/") {
-        format!("lib/{}", &path[pos + 5..])
-    } else if let Some(pos) = path.rfind('/') {
-        path[pos + 1..].to_string()
-    } else {
-        path
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::envelope::tests::assert_json_snapshot;
 
     #[test]
     fn test_filter_eslint_json() {
@@ -628,19 +498,6 @@ mod tests {
         assert!(result.contains("src/utils.ts"));
     }
 
-    #[test]
-    fn test_compact_path() {
-        assert_eq!(
-            compact_path("/Users/foo/project/src/utils.ts"),
-            "src/utils.ts"
-        );
-        assert_eq!(
-            compact_path("C:\\Users\\project\\src\\api.ts"),
-            "src/api.ts"
-        );
-        assert_eq!(compact_path("simple.ts"), "simple.ts");
-    }
-
     #[test]
     fn test_filter_pylint_json_no_issues() {
         let output = "[]";
@@ -688,13 +545,10 @@ mod tests {
         ]"#;
 
         let result = filter_pylint_json(json);
-        assert!(result.contains("3 issues"));
-        assert!(result.contains("2 files"));
-        assert!(result.contains("1 errors, 2 warnings"));
-        assert!(result.contains("unused-variable (W0612)"));
-        assert!(result.contains("undefined-variable (E0602)"));
-        assert!(result.contains("main.py"));
-        assert!(result.contains("utils.py"));
+        assert!(result.starts_with("Pylint: 1 errors, 2 warnings in 2 files"));
+        assert!(result.contains("src/main.py:10: warning[unused-variable (W0612)]"));
+        assert!(result.contains("src/main.py:15:4: warning[unused-variable (W0612)]"));
+        assert!(result.contains("src/utils.py:20: error[undefined-variable (E0602)]"));
     }
 
     #[test]
@@ -714,14 +568,13 @@ src/utils.py:25: warning: Unused "type: ignore" comment [unused-ignore]
 Found 4 errors in 2 files (checked 5 source files)"#;
 
         let result = filter_mypy_output(output);
-        assert!(result.contains("4 issues"));
-        assert!(result.contains("2 files"));
-        assert!(result.contains("3 errors, 1 warnings"));
-        assert!(result.contains("return-value"));
-        assert!(result.contains("arg-type"));
-        assert!(result.contains("name-defined"));
-        assert!(result.contains("main.py"));
-        assert!(result.contains("utils.py"));
+        assert!(result.starts_with("Mypy: 3 errors, 1 warnings in 2 files"));
+        assert!(
+            result.contains("src/main.py:10: error[return-value]: Incompatible return value type")
+        );
+        assert!(result.contains("src/main.py:15: error[arg-type]"));
+        assert!(result.contains("src/utils.py:20: error[name-defined]"));
+        assert!(result.contains("src/utils.py:25: warning[unused-ignore]"));
     }
 
     #[test]
@@ -734,4 +587,17 @@ Found 4 errors in 2 files (checked 5 source files)"#;
         assert!(!is_python_linter("biome"));
         assert!(!is_python_linter("unknown"));
     }
+
+    #[test]
+    fn test_json_snapshot_eslint() {
+        let actual = render_json::<EslintParser>(
+            "eslint src",
+            include_str!("../tests/fixtures/json_output/eslint.input.json"),
+            1,
+        );
+        assert_json_snapshot(
+            &actual,
+            include_str!("../tests/fixtures/json_output/eslint.expected.json"),
+        );
+    }
 }
@@ -0,0 +1,442 @@
+//! Range summaries for `rtk git log A..B --summary`.
+//!
+//! Commits are rolled up by conventional-commit type, by author and by directory (with
+//! insert/delete totals from `--numstat`); merges and reverts are called out, and the
+//! commit list collapses long runs of fixup commits.
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+
+lazy_static! {
+    /// `feat(cli)!: add budget` → type, breaking marker
+    static ref CONVENTIONAL_RE: Regex =
+        Regex::new(r"^([A-Za-z]+)(?:\([^)]*\))?(!)?:\s").unwrap();
+    /// `src/{old => new}/a.rs` in a rename
+    static ref RENAME_BRACES_RE: Regex = Regex::new(r"\{[^{}]* => ([^{}]*)\}").unwrap();
+}
+
+/// `git log` format read by [`parse`]: a record separator, then hash, parents, author
+/// and subject separated by unit separators; `--numstat` lines follow each header
+pub const LOG_FORMAT: &str = "--format=%x1e%h%x1f%p%x1f%an%x1f%s";
+
+/// Rows shown in each rollup table
+const MAX_ROWS: usize = 8;
+/// Commits listed before the rest are counted
+const MAX_COMMITS_LISTED: usize = 20;
+/// Consecutive fixup commits folded into one line
+const MIN_FIXUP_RUN: usize = 3;
+/// Directory depth paths are rolled up to: `src/outline/rust.rs` → `src/outline/`
+const DIR_DEPTH: usize = 2;
+/// Longest subject shown
+const MAX_SUBJECT_CHARS: usize = 72;
+
+/// Subjects that only touch up an earlier commit
+const FIXUP_SUBJECTS: &[&str] = &["wip", "fix", "fixup", "typo", "fix typo", "oops", "."];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    pub hash: String,
+    pub parents: usize,
+    pub author: String,
+    pub subject: String,
+    /// `--numstat` entries: (insertions, deletions, path); binaries count as 0
+    pub files: Vec<(usize, usize, String)>,
+}
+
+impl Commit {
+    /// Conventional-commit type, lowercased; `merge`, `revert` or `other` for
+    /// subjects without one
+    fn kind(&self) -> String {
+        if let Some(caps) = CONVENTIONAL_RE.captures(&self.subject) {
+            return caps[1].to_lowercase();
+        }
+        if self.is_merge() {
+            "merge".to_string()
+        } else if self.subject.starts_with("Revert ") {
+            "revert".to_string()
+        } else {
+            "other".to_string()
+        }
+    }
+
+    fn breaking(&self) -> bool {
+        CONVENTIONAL_RE
+            .captures(&self.subject)
+            .is_some_and(|caps| caps.get(2).is_some())
+            || self.subject.contains("BREAKING CHANGE")
+    }
+
+    fn is_merge(&self) -> bool {
+        self.parents > 1
+    }
+
+    fn is_revert(&self) -> bool {
+        self.kind() == "revert"
+    }
+
+    fn is_fixup(&self) -> bool {
+        let subject = self.subject.trim().to_lowercase();
+        ["fixup!", "squash!", "amend!"]
+            .iter()
+            .any(|p| subject.starts_with(p))
+            || FIXUP_SUBJECTS.contains(&subject.as_str())
+    }
+
+    fn totals(&self) -> (usize, usize) {
+        self.files
+            .iter()
+            .fold((0, 0), |(i, d), (ins, del, _)| (i + ins, d + del))
+    }
+
+    fn line(&self) -> String {
+        format!(
+            "{} {}",
+            self.hash,
+            truncate(&self.subject, MAX_SUBJECT_CHARS)
+        )
+    }
+}
+
+/// Split `git log <LOG_FORMAT> --numstat` output into commits
+pub fn parse(output: &str) -> Vec<Commit> {
+    output
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut lines = record.lines();
+            let header: Vec<&str> = lines.next()?.splitn(4, '\x1f').collect();
+            let [hash, parents, author, subject] = header[..] else {
+                return None;
+            };
+            let files = lines
+                .filter_map(|line| {
+                    let mut fields = line.splitn(3, '\t');
+                    let ins = fields.next()?;
+                    let del = fields.next()?;
+                    let path = fields.next()?;
+                    Some((
+                        ins.parse().unwrap_or(0),
+                        del.parse().unwrap_or(0),
+                        renamed_to(path),
+                    ))
+                })
+                .collect();
+            Some(Commit {
+                hash: hash.to_string(),
+                parents: parents.split_whitespace().count(),
+                author: author.to_string(),
+                subject: subject.to_string(),
+                files,
+            })
+        })
+        .collect()
+}
+
+/// New path of a `--numstat` rename: `src/{a => b}/x.rs` → `src/b/x.rs`, `a => b` → `b`
+fn renamed_to(path: &str) -> String {
+    let path = RENAME_BRACES_RE.replace_all(path, "$1").replace("//", "/");
+    match path.split_once(" => ") {
+        Some((_, new)) => new.to_string(),
+        None => path,
+    }
+}
+
+/// Directory a path is rolled up under, at most [`DIR_DEPTH`] levels deep
+fn rollup_dir(path: &str) -> String {
+    let parts: Vec<&str> = path.split('/').collect();
+    if parts.len() == 1 {
+        return "./".to_string();
+    }
+    let depth = (parts.len() - 1).min(DIR_DEPTH);
+    format!("{}/", parts[..depth].join("/"))
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text.to_string(),
+    }
+}
+
+/// `1 file`, `2 files`
+pub(crate) fn plural(n: usize, word: &str) -> String {
+    format!("{} {}{}", n, word, if n == 1 { "" } else { "s" })
+}
+
+/// Render the rollups for `commits`, newest first as git lists them
+pub fn summarize(commits: &[Commit]) -> String {
+    if commits.is_empty() {
+        return "no commits".to_string();
+    }
+    let mut out = Vec::new();
+
+    let authors = rollup(commits.iter().map(|c| (c.author.clone(), c.totals())));
+    let files: usize = {
+        let mut paths: Vec<&str> = commits
+            .iter()
+            .flat_map(|c| c.files.iter().map(|(_, _, p)| p.as_str()))
+            .collect();
+        paths.sort_unstable();
+        paths.dedup();
+        paths.len()
+    };
+    let (ins, del) = commits.iter().fold((0, 0), |(i, d), c| {
+        let (ci, cd) = c.totals();
+        (i + ci, d + cd)
+    });
+    out.push(format!(
+        "{} by {}, {} changed, +{} -{}",
+        plural(commits.len(), "commit"),
+        plural(authors.len(), "author"),
+        plural(files, "file"),
+        ins,
+        del
+    ));
+
+    let types = rollup(commits.iter().map(|c| (c.kind(), (0, 0))));
+    let breaking = commits.iter().filter(|c| c.breaking()).count();
+    out.push(format!(
+        "\nBy type:{}",
+        if breaking > 0 {
+            format!(" ({} breaking)", breaking)
+        } else {
+            String::new()
+        }
+    ));
+    out.extend(table(&types, false));
+
+    out.push("\nBy author:".to_string());
+    out.extend(table(&authors, true));
+
+    let dirs = rollup(commits.iter().flat_map(|c| {
+        // One commit per directory, however many of its files it touched
+        let mut per_dir: Vec<(String, (usize, usize))> = Vec::new();
+        for (i, d, path) in &c.files {
+            let dir = rollup_dir(path);
+            match per_dir.iter_mut().find(|(name, _)| *name == dir) {
+                Some((_, (ti, td))) => {
+                    *ti += i;
+                    *td += d;
+                }
+                None => per_dir.push((dir, (*i, *d))),
+            }
+        }
+        per_dir
+    }));
+    if !dirs.is_empty() {
+        out.push("\nTop paths:".to_string());
+        out.extend(table(&dirs, true));
+    }
+
+    for (title, picked) in [
+        (
+            "Merges",
+            commits.iter().filter(|c| c.is_merge()).collect::<Vec<_>>(),
+        ),
+        (
+            "Reverts",
+            commits.iter().filter(|c| c.is_revert()).collect(),
+        ),
+    ] {
+        if picked.is_empty() {
+            continue;
+        }
+        out.push(format!("\n{} ({}):", title, picked.len()));
+        out.extend(
+            picked
+                .iter()
+                .take(MAX_ROWS)
+                .map(|c| format!("  {}", c.line())),
+        );
+        if picked.len() > MAX_ROWS {
+            out.push(format!("  ... +{} more", picked.len() - MAX_ROWS));
+        }
+    }
+
+    out.push("\nCommits:".to_string());
+    let lines = commit_lines(commits);
+    let shown = lines.len().min(MAX_COMMITS_LISTED);
+    out.extend(lines[..shown].iter().map(|l| format!("  {}", l)));
+    if lines.len() > shown {
+        out.push(format!("  ... +{} more", lines.len() - shown));
+    }
+
+    out.join("\n")
+}
+
+/// One rollup row: name, commits, insertions, deletions
+type Row = (String, usize, usize, usize);
+
+/// Group one (key, totals) entry per commit by key, biggest groups first
+fn rollup(entries: impl Iterator<Item = (String, (usize, usize))>) -> Vec<Row> {
+    let mut rows: Vec<Row> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (key, (i, d)) in entries {
+        let at = *index.entry(key.clone()).or_insert_with(|| {
+            rows.push((key, 0, 0, 0));
+            rows.len() - 1
+        });
+        rows[at].1 += 1;
+        rows[at].2 += i;
+        rows[at].3 += d;
+    }
+    // Stable: ties keep first-seen (newest) order
+    rows.sort_by_key(|(_, commits, ins, del)| std::cmp::Reverse((*commits, ins + del)));
+    rows
+}
+
+fn table(rows: &[Row], with_lines: bool) -> Vec<String> {
+    let width = rows
+        .iter()
+        .take(MAX_ROWS)
+        .map(|r| r.0.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut out: Vec<String> = rows
+        .iter()
+        .take(MAX_ROWS)
+        .map(|(name, commits, ins, del)| {
+            if with_lines {
+                format!(
+                    "  {:<width$}  {:>3}  +{} -{}",
+                    name,
+                    commits,
+                    ins,
+                    del,
+                    width = width
+                )
+            } else {
+                format!("  {:<width$}  {:>3}", name, commits, width = width)
+            }
+        })
+        .collect();
+    if rows.len() > MAX_ROWS {
+        out.push(format!("  ... +{} more", rows.len() - MAX_ROWS));
+    }
+    out
+}
+
+/// One line per commit; runs of [`MIN_FIXUP_RUN`] or more fixups become one line
+fn commit_lines(commits: &[Commit]) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut i = 0;
+    while i < commits.len() {
+        let run = commits[i..].iter().take_while(|c| c.is_fixup()).count();
+        if run >= MIN_FIXUP_RUN {
+            let (newest, oldest) = (&commits[i], &commits[i + run - 1]);
+            lines.push(format!(
+                "{}..{} {} fixup commits",
+                oldest.hash, newest.hash, run
+            ));
+            i += run;
+        } else {
+            lines.push(commits[i].line());
+            i += 1;
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(hash: &str, parents: usize, author: &str, subject: &str) -> Commit {
+        Commit {
+            hash: hash.to_string(),
+            parents,
+            author: author.to_string(),
+            subject: subject.to_string(),
+            files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_log_with_numstat() {
+        let output = "\x1eabc1234\x1fp1\x1fAda\x1ffeat(cli)!: add budget\n\n\
+                      10\t2\tsrc/budget.rs\n-\t-\tlogo.png\n3\t3\tsrc/{old => new}/a.rs\n\
+                      \x1edef5678\x1fp1 p2\x1fBob\x1fMerge branch 'x'\n";
+        let commits = parse(output);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(
+            commits[0].files,
+            [
+                (10, 2, "src/budget.rs".to_string()),
+                (0, 0, "logo.png".to_string()),
+                (3, 3, "src/new/a.rs".to_string())
+            ]
+        );
+        assert_eq!(commits[0].kind(), "feat");
+        assert!(commits[0].breaking());
+        assert!(commits[1].is_merge());
+        assert!(commits[1].files.is_empty());
+    }
+
+    #[test]
+    fn test_renames_and_rollup_dirs() {
+        assert_eq!(renamed_to("old.rs => new.rs"), "new.rs");
+        assert_eq!(renamed_to("src/{ => git}/log.rs"), "src/git/log.rs");
+        assert_eq!(rollup_dir("src/outline/rust.rs"), "src/outline/");
+        assert_eq!(rollup_dir("src/outline/deep/x.rs"), "src/outline/");
+        assert_eq!(rollup_dir("src/main.rs"), "src/");
+        assert_eq!(rollup_dir("README.md"), "./");
+    }
+
+    #[test]
+    fn test_fixup_runs_collapse() {
+        let commits = vec![
+            commit("a1", 1, "Ada", "fixup! feat: x"),
+            commit("a2", 1, "Ada", "wip"),
+            commit("a3", 1, "Ada", "squash! feat: x"),
+            commit("a4", 1, "Ada", "feat: x"),
+            commit("a5", 1, "Ada", "typo"),
+        ];
+        assert_eq!(
+            commit_lines(&commits),
+            ["a3..a1 3 fixup commits", "a4 feat: x", "a5 typo"]
+        );
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut feat = commit("aaa", 1, "Ada", "feat(grep): add engine");
+        feat.files = vec![
+            (30, 2, "src/grep.rs".to_string()),
+            (5, 0, "README.md".to_string()),
+        ];
+        let mut fix = commit("bbb", 1, "Bob", "fix: handle empty input");
+        fix.files = vec![(3, 1, "src/grep.rs".to_string())];
+        let revert = commit("ccc", 1, "Ada", "Revert \"feat: add cache\"");
+        let merge = commit("ddd", 2, "Ada", "Merge pull request #7 from ada/engine");
+        assert_eq!(
+            summarize(&[merge, feat, fix, revert]),
+            "\
+4 commits by 2 authors, 2 files changed, +38 -3
+
+By type:
+  merge     1
+  feat      1
+  fix       1
+  revert    1
+
+By author:
+  Ada    3  +35 -2
+  Bob    1  +3 -1
+
+Top paths:
+  src/    2  +33 -3
+  ./      1  +5 -0
+
+Merges (1):
+  ddd Merge pull request #7 from ada/engine
+
+Reverts (1):
+  ccc Revert \"feat: add cache\"
+
+Commits:
+  ddd Merge pull request #7 from ada/engine
+  aaa feat(grep): add engine
+  bbb fix: handle empty input
+  ccc Revert \"feat: add cache\""
+        );
+    }
+}
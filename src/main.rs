@@ -1,11 +1,18 @@
+mod blame;
+mod budget;
+mod cache;
 mod cargo_cmd;
 mod cc_economics;
 mod ccusage;
+mod ci_log;
 mod config;
+mod conflict;
 mod container;
 mod curl_cmd;
+mod custom_filter;
 mod deps;
 mod diff_cmd;
+mod diff_summary;
 mod discover;
 mod display_helpers;
 mod env_cmd;
@@ -14,9 +21,12 @@ mod find_cmd;
 mod format_cmd;
 mod gain;
 mod gh_cmd;
+mod gh_review;
 mod git;
 mod go_cmd;
 mod golangci_cmd;
+#[cfg(test)]
+mod golden;
 mod grep_cmd;
 mod hook_audit_cmd;
 mod init;
@@ -25,9 +35,11 @@ mod learn;
 mod lint_cmd;
 mod local_llm;
 mod log_cmd;
+mod log_summary;
 mod ls;
 mod next_cmd;
 mod npm_cmd;
+mod outline;
 mod parser;
 mod pip_cmd;
 mod playwright_cmd;
@@ -36,10 +48,15 @@ mod prettier_cmd;
 mod prisma_cmd;
 mod pytest_cmd;
 mod read;
+mod rewrite_cmd;
 mod ruff_cmd;
 mod runner;
+mod search;
+mod stream;
 mod summary;
 mod tee;
+mod tee_cmd;
+mod tokenizer;
 mod tracking;
 mod tree;
 mod tsc_cmd;
@@ -47,6 +64,7 @@ mod utils;
 mod vitest_cmd;
 mod wc_cmd;
 mod wget_cmd;
+mod wrap;
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
@@ -75,6 +93,18 @@ struct Cli {
     /// Set SKIP_ENV_VALIDATION=1 for child processes (Next.js, tsc, lint, prisma)
     #[arg(long = "skip-env", global = true)]
     skip_env: bool,
+
+    /// Output format: compact text, or a versioned JSON envelope with the parsed result
+    #[arg(long, value_enum, default_value = "text", global = true)]
+    output: parser::OutputFormat,
+
+    /// Cap the output at about this many tokens (0 = no cap); overrides [budget] in config
+    #[arg(long, value_name = "TOKENS", global = true)]
+    budget: Option<usize>,
+
+    /// Show only what changed since the last run: new and fixed test failures, new lint issues
+    #[arg(long, global = true)]
+    delta: bool,
 }
 
 #[derive(Subcommand)]
@@ -95,9 +125,10 @@ enum Commands {
 
     /// Read file with intelligent filtering
     Read {
-        /// File to read
-        file: PathBuf,
-        /// Filter: none, minimal, aggressive
+        /// Files to read: paths, `path:start-end` ranges or globs (`-` for stdin)
+        #[arg(required = true)]
+        files: Vec<String>,
+        /// Filter: none, minimal, aggressive, outline
         #[arg(short, long, default_value = "minimal")]
         level: filter::FilterLevel,
         /// Max lines
@@ -106,6 +137,9 @@ enum Commands {
         /// Show line numbers
         #[arg(short = 'n', long)]
         line_numbers: bool,
+        /// Print only this item (function, type, method...), e.g. `Store::new`
+        #[arg(long)]
+        symbol: Option<String>,
     },
 
     /// Generate 2-line technical summary (heuristic-based)
@@ -236,15 +270,18 @@ enum Commands {
         /// Path to search in
         #[arg(default_value = ".")]
         path: String,
-        /// Max line length
-        #[arg(short = 'l', long, default_value = "80")]
-        max_len: usize,
-        /// Max results to show
-        #[arg(short, long, default_value = "50")]
-        max: usize,
+        /// Max line length [default: 80, or grep.max_line_len in config]
+        #[arg(short = 'l', long)]
+        max_len: Option<usize>,
+        /// Max results to show [default: 50, or grep.max_results in config]
+        #[arg(short, long)]
+        max: Option<usize>,
         /// Show only match context (not full line)
         #[arg(short, long)]
         context_only: bool,
+        /// Group matches by enclosing function/class, densest files first
+        #[arg(long)]
+        by_symbol: bool,
         /// Filter by file type (e.g., ts, py, rust)
         #[arg(short = 't', long)]
         file_type: Option<String>,
@@ -530,6 +567,9 @@ enum Commands {
         args: Vec<String>,
     },
 
+    /// Claude Code PreToolUse hook: read hook JSON on stdin, print rewritten command JSON
+    Rewrite,
+
     /// Show hook rewrite audit metrics (requires RTK_HOOK_AUDIT=1)
     #[command(name = "hook-audit")]
     HookAudit {
@@ -537,6 +577,54 @@ enum Commands {
         #[arg(short, long, default_value = "7")]
         since: u64,
     },
+
+    /// Manage the output cache ([cache] in config)
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+
+    /// Browse full outputs saved by tee (list, show with --grep/--lines/--around, last)
+    Tee {
+        #[command(subcommand)]
+        command: TeeCommands,
+    },
+
+    /// Runs any other command through a matching [[filters.custom]] entry from config.toml
+    #[command(external_subcommand)]
+    Custom(Vec<OsString>),
+}
+
+#[derive(Subcommand)]
+enum CacheCommands {
+    /// Delete every cached output
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum TeeCommands {
+    /// Recent captures with command, exit code and size
+    List {
+        /// Only captures whose command slug starts with this (e.g. cargo_test)
+        command: Option<String>,
+        /// Max captures to show
+        #[arg(short = 'n', long, default_value = "10")]
+        limit: usize,
+    },
+    /// Print a capture by id
+    Show {
+        /// Capture id from `rtk tee list` or a tee hint
+        id: i64,
+        #[command(flatten)]
+        view: tee_cmd::ViewArgs,
+    },
+    /// Print the most recent failed capture
+    Last {
+        /// Only captures whose command slug starts with this (e.g. pytest)
+        command: Option<String>,
+        #[command(flatten)]
+        view: tee_cmd::ViewArgs,
+    },
 }
 
 #[derive(Subcommand)]
@@ -609,6 +697,24 @@ enum GitCommands {
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
+    /// Blame grouped into ranges per commit, with a one-line legend per commit
+    Blame {
+        /// File, `-L a,b`, `--fold-older <age>` (90d, 6m, 2y) and other git blame flags
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Merge → outcome in a few lines; on conflict, hunks per file and how to continue
+    Merge {
+        /// Git merge arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Rebase → outcome in a few lines; on conflict, step, hunks per file and how to continue
+    Rebase {
+        /// Git rebase arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
     /// Compact worktree listing
     Worktree {
         /// Git worktree arguments (add, remove, prune, or empty for list)
@@ -854,6 +960,15 @@ enum GoCommands {
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    parser::set_output_format(cli.output);
+    parser::set_ultra_compact(cli.ultra_compact);
+    parser::set_delta_mode(cli.delta);
+
+    let budget = budget::resolve(cli.budget);
+    let cache = cache::CacheLookup::resolve(budget);
+    if budget.is_some() || cache.is_some() {
+        std::process::exit(wrap::run(budget, cache)?);
+    }
 
     match cli.command {
         Commands::Ls { args } => {
@@ -865,15 +980,26 @@ fn main() -> Result<()> {
         }
 
         Commands::Read {
-            file,
+            files,
             level,
             max_lines,
             line_numbers,
+            symbol,
         } => {
-            if file == Path::new("-") {
+            if files.len() == 1 && files[0] == "-" {
+                if symbol.is_some() {
+                    anyhow::bail!("--symbol needs a file (the language comes from its extension)");
+                }
                 read::run_stdin(level, max_lines, line_numbers, cli.verbose)?;
             } else {
-                read::run(&file, level, max_lines, line_numbers, cli.verbose)?;
+                read::run(
+                    &files,
+                    level,
+                    max_lines,
+                    line_numbers,
+                    symbol.as_deref(),
+                    cli.verbose,
+                )?;
             }
         }
 
@@ -929,6 +1055,15 @@ fn main() -> Result<()> {
                     cli.verbose,
                 )?;
             }
+            GitCommands::Blame { args } => {
+                git::run(git::GitCommand::Blame, &args, None, cli.verbose)?;
+            }
+            GitCommands::Merge { args } => {
+                git::run(git::GitCommand::Merge, &args, None, cli.verbose)?;
+            }
+            GitCommands::Rebase { args } => {
+                git::run(git::GitCommand::Rebase, &args, None, cli.verbose)?;
+            }
             GitCommands::Worktree { args } => {
                 git::run(git::GitCommand::Worktree, &args, None, cli.verbose)?;
             }
@@ -1091,18 +1226,28 @@ fn main() -> Result<()> {
             max_len,
             max,
             context_only,
+            by_symbol,
             file_type,
             line_numbers: _, // no-op: line numbers always enabled in grep_cmd::run
             extra_args,
         } => {
+            let config = config::Config::load().unwrap_or_default();
+            let engine = config
+                .grep
+                .engine
+                .parse::<grep_cmd::Engine>()
+                .map_err(anyhow::Error::msg)?;
             grep_cmd::run(
                 &pattern,
                 &path,
-                max_len,
-                max,
+                max_len.unwrap_or(config.grep.max_line_len),
+                max.unwrap_or(config.grep.max_results),
                 context_only,
+                by_symbol,
                 file_type.as_deref(),
                 &extra_args,
+                engine,
+                &config.filters.ignore_dirs,
                 cli.verbose,
             )?;
         }
@@ -1427,10 +1572,40 @@ fn main() -> Result<()> {
             golangci_cmd::run(&args, cli.verbose)?;
         }
 
+        Commands::Rewrite => {
+            rewrite_cmd::run(cli.verbose)?;
+        }
+
+        Commands::Cache { command } => match command {
+            CacheCommands::Clear => {
+                if cache::clear()? {
+                    println!("Cache cleared");
+                } else {
+                    println!("Cache is already empty");
+                }
+            }
+        },
+
+        Commands::Tee { command } => match command {
+            TeeCommands::List { command, limit } => {
+                tee_cmd::list(command.as_deref(), limit, cli.verbose)?;
+            }
+            TeeCommands::Show { id, view } => {
+                tee_cmd::show(id, &view, cli.verbose)?;
+            }
+            TeeCommands::Last { command, view } => {
+                tee_cmd::last(command.as_deref(), &view, cli.verbose)?;
+            }
+        },
+
         Commands::HookAudit { since } => {
             hook_audit_cmd::run(since, cli.verbose)?;
         }
 
+        Commands::Custom(args) => {
+            custom_filter::run(&args, cli.verbose)?;
+        }
+
         Commands::Proxy { args } => {
             use std::process::Command;
 
@@ -0,0 +1,207 @@
+//! Go scanner: functions, methods (qualified by receiver type) and type declarations
+use super::{blank, masked_lines, scan_braced, BracedLanguage, Extent, Item, ItemStart};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    /// `func Name`, `func (s *Store[T]) Name`
+    static ref FUNC_RE: Regex = Regex::new(
+        r"^func\s*(?:\(\s*(?:[A-Za-z_]\w*\s+)?\*?\s*([A-Za-z_]\w*)(?:\[[^\]]*\])?\s*\)\s*)?([A-Za-z_]\w*)"
+    )
+    .unwrap();
+    /// A type spec, after the `type` keyword: `Store[T any] struct {`, `ID = int`
+    static ref TYPE_SPEC_RE: Regex =
+        Regex::new(r"^([A-Za-z_]\w*)(?:\[[^\]]*\])?\s*=?\s*(struct|interface)?\b").unwrap();
+}
+
+const LANGUAGE: BracedLanguage = BracedLanguage {
+    matcher: match_item,
+    separator: ".",
+    doc_prefixes: &["//", "/*", "*"],
+};
+
+pub(super) fn scan(content: &str) -> Vec<Item> {
+    scan_braced(content, mask(content), &LANGUAGE)
+}
+
+/// Blank comments and the contents of strings, raw strings and runes
+fn mask(content: &str) -> Vec<String> {
+    let bytes = content.as_bytes();
+    let mut masked = bytes.to_vec();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                let end = find_from(bytes, i, b"\n").unwrap_or(bytes.len());
+                blank(&mut masked, i, end);
+                i = end;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let end = find_from(bytes, i + 2, b"*/").map_or(bytes.len(), |p| p + 2);
+                blank(&mut masked, i, end);
+                i = end;
+            }
+            b'`' => {
+                let end = find_from(bytes, i + 1, b"`").unwrap_or(bytes.len());
+                blank(&mut masked, i + 1, end);
+                i = end + 1;
+            }
+            quote @ (b'"' | b'\'') => {
+                let end = quoted_end(bytes, i + 1, quote);
+                blank(&mut masked, i + 1, end);
+                i = end + 1;
+            }
+            _ => i += 1,
+        }
+    }
+    masked_lines(masked)
+}
+
+fn find_from(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    bytes
+        .get(from..)?
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|p| p + from)
+}
+
+/// Closing quote of an interpreted string or rune; neither spans lines
+fn quoted_end(bytes: &[u8], from: usize, quote: u8) -> usize {
+    let mut j = from;
+    while j < bytes.len() {
+        match bytes[j] {
+            b'\\' if bytes.get(j + 1) != Some(&b'\n') => j += 2,
+            b'\n' => return j,
+            b if b == quote => return j,
+            _ => j += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn match_item(line: &str, parent: Option<&Item>) -> Option<ItemStart> {
+    // Inside `type ( ... )` each line is a type spec without the keyword; only groups
+    // are containers, so a `type` parent is always one
+    if parent.is_some_and(|p| p.kind == "type") {
+        return type_spec(line);
+    }
+
+    if let Some(caps) = FUNC_RE.captures(line) {
+        let receiver = caps.get(1).map(|m| m.as_str().to_string());
+        return Some(ItemStart {
+            kind: if receiver.is_some() { "method" } else { "func" },
+            name: caps[2].to_string(),
+            owner: receiver,
+            extent: Extent::Block(b'{'),
+            container: false,
+        });
+    }
+
+    let rest = line.strip_prefix("type")?;
+    if rest.trim_start().starts_with('(') {
+        return Some(ItemStart {
+            kind: "type",
+            name: "type".to_string(),
+            owner: None,
+            extent: Extent::Block(b'('),
+            container: true,
+        });
+    }
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    type_spec(rest.trim_start())
+}
+
+fn type_spec(spec: &str) -> Option<ItemStart> {
+    let caps = TYPE_SPEC_RE.captures(spec)?;
+    Some(ItemStart {
+        kind: "type",
+        name: caps[1].to_string(),
+        // Group members are not qualified by the group
+        owner: Some(String::new()),
+        extent: if caps.get(2).is_some() {
+            Extent::Block(b'{')
+        } else {
+            Extent::Statement
+        },
+        container: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{find_symbol, render};
+    use super::*;
+
+    const SOURCE: &str = r#"package store
+
+import "fmt"
+
+// Store keeps items.
+// It is safe for concurrent use.
+type Store[T any] struct {
+	items []T // "}"
+}
+
+type (
+	ID      int
+	Handler func(id ID) error
+	Named   interface {
+		Name() string
+	}
+)
+
+var query = `
+func fake() {
+`
+
+// New builds a store.
+func New[T any](items ...T) *Store[T] {
+	return &Store[T]{items: items}
+}
+
+func (s *Store[T]) Len() int {
+	if s == nil {
+		return 0
+	}
+	return len(s.items)
+}
+
+func (Store[T]) Kind(
+	verbose bool,
+) string {
+	return fmt.Sprintf("%c", '}')
+}
+"#;
+
+    #[test]
+    fn test_go_outline() {
+        let items = scan(SOURCE);
+        assert_eq!(
+            render(&items),
+            " 7  type Store[T any] struct
+11  type
+12    ID int
+13    Handler func(id ID) error
+14    Named interface
+24  func New[T any](items ...T) *Store[T]
+28  func (s *Store[T]) Len() int
+35  func (Store[T]) Kind( verbose bool, ) string"
+        );
+    }
+
+    #[test]
+    fn test_go_methods_qualified_by_receiver() {
+        let items = scan(SOURCE);
+        let len = find_symbol(&items, "Store.Len")[0];
+        assert_eq!((len.kind, len.line, len.end_line), ("method", 28, 33));
+        assert_eq!(find_symbol(&items, "Store.Kind")[0].end_line, 39);
+        let store = find_symbol(&items, "Store")[0];
+        assert_eq!((store.doc_line, store.line, store.end_line), (5, 7, 9));
+        let named = find_symbol(&items, "Named")[0];
+        assert_eq!((named.qualified.as_str(), named.end_line), ("Named", 16));
+        assert_eq!(find_symbol(&items, "New")[0].doc_line, 23);
+        assert!(find_symbol(&items, "fake").is_empty());
+    }
+}
@@ -0,0 +1,408 @@
+//! Structural outline of a source file: `rtk read -l outline` and `rtk read --symbol`.
+//!
+//! Each supported language has its own scanner. Scanners first mask comments and string
+//! literals (same byte length, contents blanked), so braces, colons and keywords inside
+//! them never confuse the structure. Rust, TypeScript/JavaScript and Go items are then
+//! found by bracket matching ([`scan_braced`]); Python by indentation.
+mod go;
+mod python;
+mod rust;
+mod symbols;
+mod typescript;
+
+pub use symbols::SymbolIndex;
+
+use crate::filter::Language;
+
+/// One item of the outline: a module, type, function, impl block, class or method
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item {
+    /// What the scanner recognised: `fn`, `struct`, `impl`, `class`, `def`, `func`, ...
+    pub kind: &'static str,
+    pub name: String,
+    /// Name with its owners, for `--symbol`: `Store::new`, `Cart.add`, `Store.Add`
+    pub qualified: String,
+    /// Declaration header, whitespace collapsed, without the body
+    pub signature: String,
+    /// First line of the declaration (1-based)
+    pub line: usize,
+    /// Last line of the body (1-based, inclusive)
+    pub end_line: usize,
+    /// First line of the doc comments, attributes or decorators above the item
+    pub doc_line: usize,
+    /// Nesting level: 0 for top-level items, 1 for methods, ...
+    pub depth: usize,
+}
+
+/// Longest signature shown in the outline
+const MAX_SIGNATURE_CHARS: usize = 120;
+
+/// Items of `content` in source order, or None when `lang` has no scanner
+pub fn outline(content: &str, lang: &Language) -> Option<Vec<Item>> {
+    match lang {
+        Language::Rust => Some(rust::scan(content)),
+        Language::TypeScript | Language::JavaScript => Some(typescript::scan(content)),
+        Language::Python => Some(python::scan(content)),
+        Language::Go => Some(go::scan(content)),
+        _ => None,
+    }
+}
+
+/// One line per item: line number, indentation by depth, signature
+pub fn render(items: &[Item]) -> String {
+    let width = items
+        .iter()
+        .map(|i| i.line)
+        .max()
+        .unwrap_or(0)
+        .to_string()
+        .len();
+    items
+        .iter()
+        .map(|item| {
+            format!(
+                "{:>width$}  {}{}",
+                item.line,
+                "  ".repeat(item.depth),
+                item.signature,
+                width = width
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Items named `query`, by plain or qualified name (`::` and `.` are interchangeable)
+pub fn find_symbol<'a>(items: &'a [Item], query: &str) -> Vec<&'a Item> {
+    let query = query.replace("::", ".");
+    items
+        .iter()
+        .filter(|i| i.name == query || i.qualified.replace("::", ".") == query)
+        .collect()
+}
+
+/// Replace `masked[start..end]` with spaces, keeping newlines
+fn blank(masked: &mut [u8], start: usize, end: usize) {
+    for b in &mut masked[start..end] {
+        if *b != b'\n' {
+            *b = b' ';
+        }
+    }
+}
+
+/// Split masked source into lines that line up byte for byte with `content.lines()`
+fn masked_lines(masked: Vec<u8>) -> Vec<String> {
+    // Blanked ranges start and end at ASCII delimiters, so whole characters were
+    // replaced and the bytes are still valid UTF-8
+    String::from_utf8_lossy(&masked)
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+/// `text` on one line: runs of whitespace collapsed, cut at [`MAX_SIGNATURE_CHARS`]
+fn collapse(text: &str) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() > MAX_SIGNATURE_CHARS {
+        let cut: String = collapsed.chars().take(MAX_SIGNATURE_CHARS - 1).collect();
+        format!("{}…", cut)
+    } else {
+        collapsed
+    }
+}
+
+/// First line of the comment/attribute lines directly above `line` (0-based index)
+fn doc_start(original: &[&str], line: usize, prefixes: &[&str]) -> usize {
+    let mut start = line;
+    while start > 0 {
+        let prev = original[start - 1].trim_start();
+        if prev.is_empty() || !prefixes.iter().any(|p| prev.starts_with(p)) {
+            break;
+        }
+        start -= 1;
+    }
+    start
+}
+
+/// How far an item recognised by a brace-language scanner extends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Extent {
+    /// Header up to the first `open` outside brackets, body to the matching close.
+    /// A `;` before the body ends a bodiless declaration (`fn f();`).
+    Block(u8),
+    /// A statement: to the first `;` outside brackets, or the end of a line that
+    /// doesn't continue on the next one
+    Statement,
+}
+
+/// What a brace-language scanner recognised at the start of a statement
+struct ItemStart {
+    kind: &'static str,
+    name: String,
+    /// Qualify the name with this instead of the enclosing item (Go methods); empty
+    /// for no qualifier at all (Go `type (...)` groups)
+    owner: Option<String>,
+    extent: Extent,
+    /// Scan the body for nested items (impl, trait, mod, class, namespace)
+    container: bool,
+}
+
+/// A language plugged into [`scan_braced`]
+struct BracedLanguage {
+    /// Recognise an item from the masked, trimmed first line of a statement
+    matcher: fn(&str, Option<&Item>) -> Option<ItemStart>,
+    /// Separator for qualified names
+    separator: &'static str,
+    /// Line prefixes of doc comments and attributes that belong to the next item
+    doc_prefixes: &'static [&'static str],
+}
+
+/// Source lines next to their masked copies, for bracket matching
+struct Source<'a> {
+    original: Vec<&'a str>,
+    masked: Vec<String>,
+}
+
+impl Source<'_> {
+    /// Line of the bracket closing the one opened just before `(line, col)`, counting
+    /// `(`, `[` and `{` alike
+    fn close_of(&self, line: usize, col: usize, limit: usize) -> usize {
+        let mut depth = 1i32;
+        let mut l = line;
+        let mut c = col;
+        while l < limit {
+            let bytes = self.masked[l].as_bytes();
+            while c < bytes.len() {
+                match bytes[c] {
+                    b'(' | b'[' | b'{' => depth += 1,
+                    b')' | b']' | b'}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return l;
+                        }
+                    }
+                    _ => {}
+                }
+                c += 1;
+            }
+            l += 1;
+            c = 0;
+        }
+        limit.saturating_sub(1)
+    }
+
+    /// Find where the header of a `Block(open)` item starting at `line` ends.
+    /// Returns (line, column) of the body's opening bracket, or `Err` with the line of
+    /// the `;` that ends a bodiless declaration.
+    fn header_end(&self, line: usize, open: u8, limit: usize) -> Result<(usize, usize), usize> {
+        let mut depth = 0i32;
+        for l in line..limit {
+            for (c, &b) in self.masked[l].as_bytes().iter().enumerate() {
+                if depth == 0 && b == open {
+                    return Ok((l, c));
+                }
+                match b {
+                    b'(' | b'[' | b'{' => depth += 1,
+                    b')' | b']' | b'}' => depth -= 1,
+                    b';' if depth <= 0 => return Err(l),
+                    _ => {}
+                }
+            }
+        }
+        Err(line)
+    }
+
+    /// Last line of the statement starting at `line`
+    fn statement_end(&self, line: usize, limit: usize) -> usize {
+        let mut depth = 0i32;
+        for l in line..limit {
+            for &b in self.masked[l].as_bytes() {
+                match b {
+                    b'(' | b'[' | b'{' => depth += 1,
+                    b')' | b']' | b'}' => depth -= 1,
+                    b';' if depth <= 0 => return l,
+                    _ => {}
+                }
+            }
+            if depth <= 0 && !self.continues_after(l, limit) {
+                return l;
+            }
+        }
+        limit.saturating_sub(1)
+    }
+
+    /// The statement on `line` goes on: it ends in an operator, or the next line
+    /// starts with one
+    fn continues_after(&self, line: usize, limit: usize) -> bool {
+        let trimmed = self.masked[line].trim_end();
+        if [
+            "=", ",", "(", "[", "{", "+", "-", "*", "|", "&", "?", ":", ".", "=>",
+        ]
+        .iter()
+        .any(|op| trimmed.ends_with(op))
+        {
+            return true;
+        }
+        let next = (line + 1..limit)
+            .map(|l| self.masked[l].trim())
+            .find(|l| !l.is_empty());
+        matches!(next, Some(n) if ["|", "&", ".", "?", ":", "=>", "+"].iter().any(|op| n.starts_with(op)))
+    }
+
+    /// Original text from the start of `line` to `(end_line, end_col)`, on one line
+    fn text_between(&self, line: usize, end_line: usize, end_col: usize) -> String {
+        let mut text = String::new();
+        for l in line..=end_line {
+            let original = self.original[l];
+            let part = if l == end_line {
+                original.get(..end_col).unwrap_or(original)
+            } else {
+                original
+            };
+            // Trailing line comments are masked; cut them from the signature too
+            let masked_len = self.masked[l].trim_end().len().min(part.len());
+            text.push_str(part.get(..masked_len).unwrap_or(part));
+            text.push(' ');
+        }
+        collapse(&text)
+    }
+}
+
+/// Scan a brace-delimited language: items start at statement boundaries, bodies are
+/// matched by bracket counting over the masked source
+fn scan_braced(content: &str, masked: Vec<String>, language: &BracedLanguage) -> Vec<Item> {
+    let source = Source {
+        original: content.lines().collect(),
+        masked,
+    };
+    let mut items = Vec::new();
+    let end = source.masked.len().min(source.original.len());
+    scan_block(&source, language, 0, end, None, 0, &mut items);
+    items
+}
+
+fn scan_block(
+    source: &Source,
+    language: &BracedLanguage,
+    start: usize,
+    end: usize,
+    parent: Option<&Item>,
+    depth: usize,
+    items: &mut Vec<Item>,
+) {
+    let mut line = start;
+    while line < end {
+        let trimmed = source.masked[line].trim();
+        if trimmed.is_empty() {
+            line += 1;
+            continue;
+        }
+        let Some(found) = (language.matcher)(trimmed, parent) else {
+            line = source.statement_end(line, end).max(line) + 1;
+            continue;
+        };
+
+        let (signature, end_line, body) = match found.extent {
+            Extent::Block(open) => match source.header_end(line, open, end) {
+                Ok((open_line, open_col)) => {
+                    let close = source.close_of(open_line, open_col + 1, end);
+                    let signature = source.text_between(line, open_line, open_col);
+                    (signature, close, Some((open_line, close)))
+                }
+                Err(semi_line) => {
+                    let last = source.original[semi_line].len();
+                    (source.text_between(line, semi_line, last), semi_line, None)
+                }
+            },
+            Extent::Statement => {
+                let last = source.statement_end(line, end);
+                let signature = source.text_between(line, line, source.original[line].len());
+                let signature = signature.trim_end_matches('{').trim_end().to_string();
+                (signature, last, None)
+            }
+        };
+
+        let owner = match &found.owner {
+            Some(owner) if owner.is_empty() => None,
+            Some(owner) => Some(owner.clone()),
+            None => parent.map(|p| p.qualified.clone()),
+        };
+        let item = Item {
+            kind: found.kind,
+            qualified: match owner {
+                Some(owner) => format!("{}{}{}", owner, language.separator, found.name),
+                None => found.name.clone(),
+            },
+            name: found.name,
+            signature,
+            line: line + 1,
+            end_line: end_line + 1,
+            doc_line: doc_start(&source.original, line, language.doc_prefixes) + 1,
+            depth,
+        };
+        items.push(item.clone());
+
+        if let (true, Some((open_line, close))) = (found.container, body) {
+            if close > open_line + 1 {
+                scan_block(
+                    source,
+                    language,
+                    open_line + 1,
+                    close,
+                    Some(&item),
+                    depth + 1,
+                    items,
+                );
+            }
+        }
+        line = end_line.max(line) + 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(name: &str, qualified: &str, line: usize, depth: usize) -> Item {
+        Item {
+            kind: "fn",
+            name: name.to_string(),
+            qualified: qualified.to_string(),
+            signature: format!("fn {}()", name),
+            line,
+            end_line: line + 2,
+            doc_line: line,
+            depth,
+        }
+    }
+
+    #[test]
+    fn test_render_indents_by_depth() {
+        let items = vec![item("a", "a", 3, 0), item("b", "S::b", 12, 1)];
+        assert_eq!(render(&items), " 3  fn a()\n12    fn b()");
+    }
+
+    #[test]
+    fn test_find_symbol_plain_and_qualified() {
+        let items = vec![
+            item("new", "Store::new", 3, 1),
+            item("new", "Cart::new", 9, 1),
+        ];
+        assert_eq!(find_symbol(&items, "new").len(), 2);
+        assert_eq!(find_symbol(&items, "Cart::new")[0].line, 9);
+        assert_eq!(find_symbol(&items, "Cart.new")[0].line, 9);
+        assert!(find_symbol(&items, "missing").is_empty());
+    }
+
+    #[test]
+    fn test_collapse_cuts_long_signatures() {
+        assert_eq!(collapse("fn  a(\n    x: u32,\n)"), "fn a( x: u32, )");
+        let long = format!("fn {}()", "x".repeat(200));
+        assert_eq!(collapse(&long).chars().count(), MAX_SIGNATURE_CHARS);
+    }
+
+    #[test]
+    fn test_outline_unsupported_language() {
+        assert!(outline("int main() {}", &Language::C).is_none());
+    }
+}
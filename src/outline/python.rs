@@ -0,0 +1,323 @@
+//! Python scanner: classes, their methods and top-level functions, by indentation
+use super::{blank, doc_start, masked_lines, Item, Source};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref DEF_RE: Regex = Regex::new(r"^(async\s+def|def|class)\s+([A-Za-z_]\w*)").unwrap();
+}
+
+/// Decorators and comments above a definition
+const DOC_PREFIXES: &[&str] = &["@", "#"];
+
+pub(super) fn scan(content: &str) -> Vec<Item> {
+    let (masked, in_string) = mask(content);
+    let source = Source {
+        original: content.lines().collect(),
+        masked,
+    };
+    let end = source.masked.len().min(source.original.len());
+    let open_brackets = bracket_depths(&source.masked[..end]);
+    // Lines inside brackets or a multi-line string continue the previous statement,
+    // whatever their indentation
+    let continues: Vec<bool> = (0..end)
+        .map(|l| open_brackets[l] > 0 || in_string.get(l).copied().unwrap_or(false))
+        .collect();
+    let mut items = Vec::new();
+    scan_block(&source, &continues, 0, end, None, 0, &mut items);
+    items
+}
+
+/// Blank comments and the contents of strings, including triple-quoted ones.
+/// Also returns, per line, whether it starts inside a string (up to and including
+/// the line with its closing quotes).
+fn mask(content: &str) -> (Vec<String>, Vec<bool>) {
+    let bytes = content.as_bytes();
+    let mut masked = bytes.to_vec();
+    let mut strings = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'#' => {
+                let end = bytes[i..]
+                    .iter()
+                    .position(|&b| b == b'\n')
+                    .map_or(bytes.len(), |p| p + i);
+                blank(&mut masked, i, end);
+                i = end;
+            }
+            quote @ (b'\'' | b'"') => {
+                let triple = bytes.get(i + 1) == Some(&quote) && bytes.get(i + 2) == Some(&quote);
+                let (open, end) = if triple {
+                    (i + 3, triple_end(bytes, i + 3, quote))
+                } else {
+                    (i + 1, quoted_end(bytes, i + 1, quote))
+                };
+                blank(&mut masked, open, end);
+                strings.push((open, end));
+                i = end + if triple { 3 } else { 1 };
+            }
+            _ => i += 1,
+        }
+    }
+
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(
+            bytes
+                .iter()
+                .enumerate()
+                .filter(|(_, &b)| b == b'\n')
+                .map(|(i, _)| i + 1),
+        )
+        .collect();
+    let in_string = line_starts
+        .iter()
+        .map(|&start| {
+            strings
+                .iter()
+                .any(|&(open, end)| open < start && start <= end)
+        })
+        .collect();
+    (masked_lines(masked), in_string)
+}
+
+/// Start of the closing `"""` / `'''`
+fn triple_end(bytes: &[u8], from: usize, quote: u8) -> usize {
+    let mut j = from;
+    while j < bytes.len() {
+        match bytes[j] {
+            b'\\' => j += 2,
+            b if b == quote
+                && bytes.get(j + 1) == Some(&quote)
+                && bytes.get(j + 2) == Some(&quote) =>
+            {
+                return j
+            }
+            _ => j += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Closing quote of a one-line string (or the line end, for a stray quote)
+fn quoted_end(bytes: &[u8], from: usize, quote: u8) -> usize {
+    let mut j = from;
+    while j < bytes.len() {
+        match bytes[j] {
+            b'\\' if bytes.get(j + 1) != Some(&b'\n') => j += 2,
+            b'\n' => return j,
+            b if b == quote => return j,
+            _ => j += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Open `(`, `[` and `{` at the start of each line
+fn bracket_depths(masked: &[String]) -> Vec<i32> {
+    let mut depth = 0i32;
+    masked
+        .iter()
+        .map(|line| {
+            let at_start = depth;
+            for b in line.bytes() {
+                match b {
+                    b'(' | b'[' | b'{' => depth += 1,
+                    b')' | b']' | b'}' => depth = (depth - 1).max(0),
+                    _ => {}
+                }
+            }
+            at_start
+        })
+        .collect()
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// The `:` ending the header that starts on `line`: (line, column)
+fn header_end(source: &Source, line: usize, end: usize) -> (usize, usize) {
+    let mut depth = 0i32;
+    for l in line..end {
+        for (c, b) in source.masked[l].bytes().enumerate() {
+            match b {
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' | b'}' => depth -= 1,
+                b':' if depth <= 0 => return (l, c),
+                _ => {}
+            }
+        }
+    }
+    (line, source.original[line].len())
+}
+
+fn scan_block(
+    source: &Source,
+    continues: &[bool],
+    start: usize,
+    end: usize,
+    parent: Option<&Item>,
+    depth: usize,
+    items: &mut Vec<Item>,
+) {
+    let is_statement = |l: usize| !continues[l] && !source.masked[l].trim().is_empty();
+    let Some(block_indent) = (start..end)
+        .find(|&l| is_statement(l))
+        .map(|l| indent(&source.masked[l]))
+    else {
+        return;
+    };
+
+    let mut line = start;
+    while line < end {
+        let masked = &source.masked[line];
+        let caps = match DEF_RE.captures(masked.trim_start()) {
+            Some(caps) if is_statement(line) && indent(masked) == block_indent => caps,
+            _ => {
+                line += 1;
+                continue;
+            }
+        };
+
+        let (header_line, colon) = header_end(source, line, end);
+        // The body runs until the next statement indented no deeper than the header
+        let body_end = (header_line + 1..end)
+            .take_while(|&l| !is_statement(l) || indent(&source.masked[l]) > block_indent)
+            .filter(|&l| !source.masked[l].trim().is_empty())
+            .last()
+            .unwrap_or(header_line);
+
+        let kind = if &caps[1] == "class" { "class" } else { "def" };
+        let name = caps[2].to_string();
+        let item = Item {
+            kind,
+            qualified: match parent {
+                Some(parent) => format!("{}.{}", parent.qualified, name),
+                None => name.clone(),
+            },
+            name,
+            signature: source.text_between(line, header_line, colon),
+            line: line + 1,
+            end_line: body_end + 1,
+            doc_line: doc_start(&source.original, line, DOC_PREFIXES) + 1,
+            depth,
+        };
+        items.push(item.clone());
+
+        // Methods and nested classes; functions nested in functions stay hidden
+        if kind == "class" && body_end > header_line {
+            scan_block(
+                source,
+                continues,
+                header_line + 1,
+                body_end + 1,
+                Some(&item),
+                depth + 1,
+                items,
+            );
+        }
+        line = body_end + 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{find_symbol, render};
+    use super::*;
+
+    const SOURCE: &str = r#"import os
+
+TEMPLATE = """
+def not_a_function():
+    pass
+"""
+
+
+@dataclass(frozen=True)
+class Line:
+    sku: str
+    qty: int = 0
+
+
+class Cart(Base):
+    """A cart: {holds lines}"""
+
+    def __init__(self, lines: list[Line] | None = None) -> None:
+        self.lines = lines or []
+
+    @property
+    def size(self) -> int:
+        return len(self.lines)
+
+    async def checkout(
+        self,
+        key=lambda line: line.sku,
+    ) -> dict[str, int]:
+        def helper(x):
+            return x
+
+        return {"ok": ":"}
+
+    class Meta:
+        table = "carts"
+
+
+def total(cart: Cart) -> int:
+    # a comment with def fake():
+    return sum(
+        line.qty
+for line in cart.lines
+    )
+
+if __name__ == "__main__":
+    def hidden():
+        pass
+"#;
+
+    #[test]
+    fn test_python_outline() {
+        let items = scan(SOURCE);
+        assert_eq!(
+            render(&items),
+            "\
+10  class Line
+15  class Cart(Base)
+18    def __init__(self, lines: list[Line] | None = None) -> None
+22    def size(self) -> int
+25    async def checkout( self, key=lambda line: line.sku, ) -> dict[str, int]
+34    class Meta
+38  def total(cart: Cart) -> int"
+        );
+    }
+
+    #[test]
+    fn test_closing_triple_quote_at_column_zero_stays_in_method() {
+        let source = "class A:\n    def f(self):\n        s = \"\"\"\nSELECT 1\n\"\"\"\n        return s\n\n    @property\n    def g(self):\n        return 1\n";
+        let items = scan(source);
+        assert_eq!(
+            render(&items),
+            "1  class A\n2    def f(self)\n9    def g(self)"
+        );
+        let f = find_symbol(&items, "A.f")[0];
+        assert_eq!(f.end_line, 6);
+        assert_eq!(find_symbol(&items, "A")[0].end_line, 10);
+    }
+
+    #[test]
+    fn test_python_extents_and_decorators() {
+        let items = scan(SOURCE);
+        let line = find_symbol(&items, "Line")[0];
+        assert_eq!((line.doc_line, line.line, line.end_line), (9, 10, 12));
+        let size = find_symbol(&items, "Cart.size")[0];
+        assert_eq!((size.doc_line, size.line, size.end_line), (21, 22, 23));
+        let checkout = find_symbol(&items, "checkout")[0];
+        assert_eq!(checkout.end_line, 32);
+        let cart = find_symbol(&items, "Cart")[0];
+        assert_eq!(cart.end_line, 35);
+        // The dedented generator line is inside the call's brackets
+        assert_eq!(find_symbol(&items, "total")[0].end_line, 43);
+        assert!(find_symbol(&items, "helper").is_empty());
+        assert!(find_symbol(&items, "not_a_function").is_empty());
+    }
+}
@@ -0,0 +1,353 @@
+//! Rust scanner: modules, types, traits, impl blocks, functions, consts and macros
+use super::{blank, masked_lines, scan_braced, BracedLanguage, Extent, Item, ItemStart};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref ITEM_RE: Regex = Regex::new(
+        r#"^(?:pub(?:\s*\([^)]*\))?\s+)?(?:default\s+)?(?:const\s+)?(?:async\s+)?(?:unsafe\s+)?(?:extern\s+(?:"\s*"\s+)?)?(fn|struct|enum|union|trait|mod|type|const|static|macro_rules!)\s*(?:mut\s+)?([A-Za-z_][A-Za-z0-9_]*)"#
+    )
+    .unwrap();
+    static ref IMPL_RE: Regex =
+        Regex::new(r"^(?:pub(?:\s*\([^)]*\))?\s+)?(?:unsafe\s+)?impl\b").unwrap();
+}
+
+const LANGUAGE: BracedLanguage = BracedLanguage {
+    matcher: match_item,
+    separator: "::",
+    doc_prefixes: &["//", "#[", "/*", "*"],
+};
+
+pub(super) fn scan(content: &str) -> Vec<Item> {
+    scan_braced(content, mask(content), &LANGUAGE)
+}
+
+/// Blank comments and the contents of string, raw string and char literals
+fn mask(content: &str) -> Vec<String> {
+    let bytes = content.as_bytes();
+    let mut masked = bytes.to_vec();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                let end = find_from(bytes, i, b"\n").unwrap_or(bytes.len());
+                blank(&mut masked, i, end);
+                i = end;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                // Block comments nest
+                let mut depth = 0;
+                let mut j = i;
+                while j < bytes.len() {
+                    if bytes[j..].starts_with(b"/*") {
+                        depth += 1;
+                        j += 2;
+                    } else if bytes[j..].starts_with(b"*/") {
+                        depth -= 1;
+                        j += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    } else {
+                        j += 1;
+                    }
+                }
+                blank(&mut masked, i, j.min(bytes.len()));
+                i = j;
+            }
+            b'r' if is_raw_string_start(bytes, i) => {
+                let hashes = bytes[i + 1..].iter().take_while(|&&b| b == b'#').count();
+                let open = i + 1 + hashes;
+                let mut closing = vec![b'#'; hashes + 1];
+                closing[0] = b'"';
+                let end = find_from(bytes, open + 1, &closing).unwrap_or(bytes.len());
+                blank(&mut masked, open + 1, end);
+                i = (end + closing.len()).min(bytes.len());
+            }
+            b'"' => {
+                let end = string_end(bytes, i + 1, b'"');
+                blank(&mut masked, i + 1, end);
+                i = end + 1;
+            }
+            b'\'' => match char_literal_end(bytes, i) {
+                Some(end) => {
+                    blank(&mut masked, i + 1, end);
+                    i = end + 1;
+                }
+                // A lifetime or loop label
+                None => i += 1,
+            },
+            _ => i += 1,
+        }
+    }
+    masked_lines(masked)
+}
+
+fn find_from(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    bytes
+        .get(from..)?
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|p| p + from)
+}
+
+/// `r"`, `r#"`, `br"`, not the end of an identifier like `for"`
+fn is_raw_string_start(bytes: &[u8], i: usize) -> bool {
+    let prefix_ok = match i.checked_sub(1).map(|p| bytes[p]) {
+        Some(b'b') => i < 2 || !is_ident_byte(bytes[i - 2]),
+        Some(p) => !is_ident_byte(p),
+        None => true,
+    };
+    let after_hashes = bytes[i + 1..].iter().position(|&b| b != b'#');
+    prefix_ok && after_hashes.is_some_and(|n| bytes[i + 1 + n] == b'"')
+}
+
+fn is_ident_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// Index of the unescaped `quote` closing a literal whose contents start at `from`
+fn string_end(bytes: &[u8], from: usize, quote: u8) -> usize {
+    let mut j = from;
+    while j < bytes.len() {
+        match bytes[j] {
+            b'\\' => j += 2,
+            b if b == quote => return j,
+            _ => j += 1,
+        }
+    }
+    bytes.len().saturating_sub(1).max(from)
+}
+
+/// Closing quote of a char literal opening at `i`, None for a lifetime (`'a`)
+fn char_literal_end(bytes: &[u8], i: usize) -> Option<usize> {
+    let next = *bytes.get(i + 1)?;
+    if next == b'\\' {
+        // '\n', '\'', '\u{1F600}'
+        let end = bytes[i + 2..].iter().take(10).position(|&b| b == b'\'')?;
+        return Some(i + 2 + end + (end == 0) as usize);
+    }
+    // One character, possibly multi-byte, then the closing quote
+    let width = std::str::from_utf8(&bytes[i + 1..bytes.len().min(i + 5)])
+        .or_else(|e| std::str::from_utf8(&bytes[i + 1..i + 1 + e.valid_up_to()]))
+        .ok()?
+        .chars()
+        .next()?
+        .len_utf8();
+    (bytes.get(i + 1 + width) == Some(&b'\'')).then_some(i + 1 + width)
+}
+
+/// Drop leading `#[...]` attributes written on the item's own line
+fn strip_attributes(mut line: &str) -> &str {
+    while let Some(rest) = line.strip_prefix("#[") {
+        let mut depth = 1;
+        let end = rest.char_indices().find_map(|(idx, c)| {
+            match c {
+                '[' => depth += 1,
+                ']' => depth -= 1,
+                _ => {}
+            }
+            (depth == 0).then_some(idx)
+        });
+        match end {
+            Some(end) => line = rest[end + 1..].trim_start(),
+            None => return "",
+        }
+    }
+    line
+}
+
+/// The type an impl block is for: `impl<T> Display for Wrapper<T>` → `Wrapper`
+fn impl_target(header: &str) -> String {
+    let mut rest = header[header.find("impl").map_or(0, |p| p + 4)..].trim_start();
+    if rest.starts_with('<') {
+        let mut depth = 0;
+        for (idx, c) in rest.char_indices() {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                rest = rest[idx + 1..].trim_start();
+                break;
+            }
+        }
+    }
+    if let Some(pos) = rest.rfind(" for ") {
+        rest = &rest[pos + 5..];
+    }
+    let path = rest
+        .trim_start_matches(['&', '*'])
+        .trim_start_matches("dyn ")
+        .split(|c: char| c == '<' || c == '{' || c.is_whitespace())
+        .next()
+        .unwrap_or("");
+    path.rsplit("::").next().unwrap_or(path).to_string()
+}
+
+fn match_item(line: &str, _parent: Option<&Item>) -> Option<ItemStart> {
+    let line = strip_attributes(line);
+    if IMPL_RE.is_match(line) {
+        return Some(ItemStart {
+            kind: "impl",
+            name: impl_target(line),
+            owner: None,
+            extent: Extent::Block(b'{'),
+            container: true,
+        });
+    }
+
+    let caps = ITEM_RE.captures(line)?;
+    let kind = match caps.get(1)?.as_str() {
+        "fn" => "fn",
+        "struct" => "struct",
+        "enum" => "enum",
+        "union" => "union",
+        "trait" => "trait",
+        "mod" => "mod",
+        "type" => "type",
+        "const" => "const",
+        "static" => "static",
+        _ => "macro",
+    };
+    let extent = match kind {
+        "type" | "const" | "static" => Extent::Statement,
+        _ => Extent::Block(b'{'),
+    };
+    Some(ItemStart {
+        kind,
+        name: caps[2].to_string(),
+        owner: None,
+        extent,
+        container: matches!(kind, "mod" | "trait"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{find_symbol, render};
+    use super::*;
+
+    const SOURCE: &str = r##"//! Crate docs
+use std::fmt;
+
+/// A store of items
+#[derive(Debug, Clone)]
+pub struct Store<'a> {
+    items: Vec<&'a str>,
+}
+
+const OPEN: char = '{';
+static PATTERN: &str = r#"fn fake() { "#;
+
+impl<'a> Store<'a> {
+    /// Build a store
+    pub fn new(items: Vec<&'a str>) -> Self {
+        let s = "}"; /* } nested /* } */ */
+        Store { items }
+    }
+
+    pub(crate) async fn load(
+        &self,
+        limit: usize,
+    ) -> Result<usize, String>
+    where
+        Self: Sized,
+    {
+        Ok(limit)
+    }
+}
+
+impl<'a> fmt::Display for Store<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.items.len())
+    }
+}
+
+pub trait Source {
+    fn fetch(&self) -> String;
+    fn default_name(&self) -> &str {
+        "source"
+    }
+}
+
+macro_rules! store {
+    ($($x:expr),*) => { Store::new(vec![$($x),*]) };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn builds() {}
+}
+"##;
+
+    #[test]
+    fn test_rust_outline() {
+        let items = scan(SOURCE);
+        assert_eq!(
+            render(&items),
+            " 6  pub struct Store<'a>
+10  const OPEN: char = '{';
+11  static PATTERN: &str = r#\"fn fake() { \"#;
+13  impl<'a> Store<'a>
+15    pub fn new(items: Vec<&'a str>) -> Self
+20    pub(crate) async fn load( &self, limit: usize, ) -> Result<usize, String> where Self: Sized,
+31  impl<'a> fmt::Display for Store<'a>
+32    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+37  pub trait Source
+38    fn fetch(&self) -> String;
+39    fn default_name(&self) -> &str
+44  macro_rules! store
+49  mod tests
+51    fn builds()"
+        );
+    }
+
+    #[test]
+    fn test_rust_extents_and_docs() {
+        let items = scan(SOURCE);
+        let store = &items[0];
+        assert_eq!((store.doc_line, store.line, store.end_line), (4, 6, 8));
+
+        let new = find_symbol(&items, "Store::new");
+        assert_eq!(new.len(), 1);
+        assert_eq!(
+            (new[0].doc_line, new[0].line, new[0].end_line),
+            (14, 15, 18)
+        );
+
+        let load = find_symbol(&items, "load")[0];
+        assert_eq!((load.line, load.end_line), (20, 28));
+
+        // Both impl blocks and the struct answer to `Store`
+        assert_eq!(find_symbol(&items, "Store").len(), 3);
+        assert_eq!(find_symbol(&items, "Source::fetch")[0].end_line, 38);
+        assert_eq!(find_symbol(&items, "tests::builds")[0].doc_line, 50);
+    }
+
+    #[test]
+    fn test_rust_mask_keeps_lifetimes_and_hides_literals() {
+        let masked = mask("fn f<'a>(x: &'a str) -> char { let c = '}'; \"{\" }");
+        assert_eq!(
+            masked[0],
+            "fn f<'a>(x: &'a str) -> char { let c = ' '; \" \" }"
+        );
+        let masked = mask("let s = r#\"a\"b{\"#; // }");
+        assert_eq!(masked[0], "let s = r#\"    \"#;     ");
+    }
+
+    #[test]
+    fn test_impl_target() {
+        assert_eq!(
+            impl_target("impl<T: Clone> From<T> for Wrapper<T> {"),
+            "Wrapper"
+        );
+        assert_eq!(
+            impl_target("unsafe impl Send for crate::db::Pool {}"),
+            "Pool"
+        );
+        assert_eq!(impl_target("impl Store {"), "Store");
+    }
+}
@@ -0,0 +1,141 @@
+//! The symbol enclosing a line: `rtk grep --by-symbol` groups matches by it and
+//! `rtk git diff --summarize` labels hunks with it
+use super::{outline, Item};
+use crate::filter::Language;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::Path;
+
+lazy_static! {
+    /// A definition introduced by a keyword: `def run`, `class Foo`, `function go`,
+    /// `pub fn parse`, `module Api`
+    static ref KEYWORD_DEF_RE: Regex = Regex::new(
+        r"^(?:(?:export|public|private|protected|internal|static|async|abstract|final|override|pub(?:\([^)]*\))?)\s+)*(?:def|fn|func|fun|function|class|struct|interface|module|trait|enum|impl|object|sub|proc)\s+([A-Za-z_][\w:.]*)"
+    )
+    .unwrap();
+    /// A C-style function or method header: `static int parse(`, `public void run(String s) {`
+    static ref C_FUNCTION_RE: Regex = Regex::new(
+        r"^[A-Za-z_][\w:<>,\[\]]*(?:[\s*&]+[A-Za-z_][\w:<>,\[\]]*)*?[\s*&]+([A-Za-z_][\w:~]*)\s*\([^;]*$"
+    )
+    .unwrap();
+}
+
+/// Statements that look like a C-style function header but are not one
+const NOT_FUNCTIONS: &[&str] = &[
+    "if", "else", "for", "while", "switch", "return", "case", "new", "throw", "do", "catch",
+    "delete", "sizeof",
+];
+/// Deepest indentation of a C-style function header (class methods in Java, C++)
+const MAX_HEADER_INDENT: usize = 4;
+
+/// Finds the symbol enclosing a line of one file
+pub enum SymbolIndex {
+    /// Items from the language's outline scanner, with their extents
+    Outline(Vec<Item>),
+    /// Other languages: definition-looking lines, (line, name); a match belongs to
+    /// the nearest one above it
+    Heuristic(Vec<(usize, String)>),
+}
+
+impl SymbolIndex {
+    pub fn build(file: &str, content: &str) -> Self {
+        let lang = Path::new(file)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(Language::from_extension)
+            .unwrap_or(Language::Unknown);
+        match outline(content, &lang) {
+            Some(items) => SymbolIndex::Outline(items),
+            None => SymbolIndex::Heuristic(
+                content
+                    .lines()
+                    .enumerate()
+                    .filter_map(|(i, line)| heuristic_definition(line).map(|name| (i + 1, name)))
+                    .collect(),
+            ),
+        }
+    }
+
+    pub fn enclosing(&self, line: usize) -> Option<String> {
+        match self {
+            // Items are in source order, so the last one containing the line is the
+            // innermost
+            SymbolIndex::Outline(items) => items
+                .iter()
+                .rev()
+                .find(|item| item.doc_line <= line && line <= item.end_line)
+                .map(|item| item.qualified.clone()),
+            SymbolIndex::Heuristic(defs) => defs
+                .iter()
+                .take_while(|(start, _)| *start <= line)
+                .last()
+                .map(|(_, name)| name.clone()),
+        }
+    }
+}
+
+/// Name defined on `line`, for languages without an outline scanner
+fn heuristic_definition(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    if let Some(caps) = KEYWORD_DEF_RE.captures(trimmed) {
+        return Some(caps[1].to_string());
+    }
+    let first_word = trimmed
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next()?;
+    if line.len() - trimmed.len() > MAX_HEADER_INDENT || NOT_FUNCTIONS.contains(&first_word) {
+        return None;
+    }
+    C_FUNCTION_RE
+        .captures(trimmed)
+        .map(|caps| caps[1].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heuristic_definitions() {
+        assert_eq!(
+            heuristic_definition("def run(args)").as_deref(),
+            Some("run")
+        );
+        assert_eq!(
+            heuristic_definition("  class Api::Client").as_deref(),
+            Some("Api::Client")
+        );
+        assert_eq!(
+            heuristic_definition("function deploy() {").as_deref(),
+            Some("deploy")
+        );
+        assert_eq!(
+            heuristic_definition("static int parse_args(int argc, char **argv)").as_deref(),
+            Some("parse_args")
+        );
+        assert_eq!(
+            heuristic_definition("    public void run(String s) {").as_deref(),
+            Some("run")
+        );
+        assert_eq!(heuristic_definition("    if (ready(x)) {"), None);
+        assert_eq!(heuristic_definition("    return compute(x);"), None);
+        assert_eq!(heuristic_definition("  int total = sum(a, b);"), None);
+    }
+
+    #[test]
+    fn test_enclosing_symbol_from_outline() {
+        let content = "use std::fs;\n\nimpl Store {\n    fn load() {\n        read();\n    }\n}\n";
+        let index = SymbolIndex::build("store.rs", content);
+        assert_eq!(index.enclosing(1), None);
+        assert_eq!(index.enclosing(3).as_deref(), Some("Store"));
+        assert_eq!(index.enclosing(5).as_deref(), Some("Store::load"));
+    }
+
+    #[test]
+    fn test_enclosing_symbol_heuristic() {
+        let content = "#include <stdio.h>\n\nint main(void) {\n    puts(\"hi\");\n}\n";
+        let index = SymbolIndex::build("main.c", content);
+        assert_eq!(index.enclosing(1), None);
+        assert_eq!(index.enclosing(4).as_deref(), Some("main"));
+    }
+}
@@ -0,0 +1,377 @@
+//! TypeScript/JavaScript scanner: functions, classes and their methods, interfaces,
+//! enums, type aliases, namespaces and `const` arrow functions
+use super::{blank, masked_lines, scan_braced, BracedLanguage, Extent, Item, ItemStart};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref DECL_RE: Regex = Regex::new(
+        r"^(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:async\s+)?(function\b\s*\*?|(?:const\s+)?enum\b|class\b|interface\b|namespace\b|module\b|type\b)\s*([A-Za-z_$][\w$]*)?"
+    )
+    .unwrap();
+    /// `const add = (a, b) => {`, `let load = async function (`, `const f = x =>`
+    static ref FUNCTION_VAR_RE: Regex = Regex::new(
+        r"^(?:export\s+)?(?:declare\s+)?(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*(?::[^=]+)?=\s*(?:async\s+)?(function\b|\(|<|[A-Za-z_$][\w$]*\s*=>)"
+    )
+    .unwrap();
+    static ref METHOD_RE: Regex = Regex::new(
+        r"^(?:(?:public|private|protected|static|readonly|async|abstract|override|declare|get|set|accessor)\s+)*\*?\s*(#?[A-Za-z_$][\w$]*)\s*[?!]?\s*(?:<[^>]*>)?\s*\("
+    )
+    .unwrap();
+    /// Class fields holding arrow functions: `handleClick = (e: Event) => {`
+    static ref ARROW_FIELD_RE: Regex = Regex::new(
+        r"^(?:(?:public|private|protected|static|readonly|override)\s+)*(#?[A-Za-z_$][\w$]*)\s*(?::[^=]+)?=\s*(?:async\s+)?(?:\(|[A-Za-z_$][\w$]*\s*=>)"
+    )
+    .unwrap();
+}
+
+/// Words that look like a method call at the start of a statement but are not methods
+const NOT_METHODS: &[&str] = &["if", "for", "while", "switch", "catch", "return", "super"];
+
+const LANGUAGE: BracedLanguage = BracedLanguage {
+    matcher: match_item,
+    separator: ".",
+    doc_prefixes: &["//", "/*", "*", "@"],
+};
+
+pub(super) fn scan(content: &str) -> Vec<Item> {
+    scan_braced(content, mask(content), &LANGUAGE)
+}
+
+/// Blank comments, the contents of strings and template literals (with their `${}`
+/// expressions) and regex literals
+fn mask(content: &str) -> Vec<String> {
+    let bytes = content.as_bytes();
+    let mut masked = bytes.to_vec();
+    let mut i = 0;
+    // Last byte of code seen, to tell a regex literal from a division
+    let mut prev: Option<u8> = None;
+    while i < bytes.len() {
+        let b = bytes[i];
+        match b {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                let end = line_end(bytes, i);
+                blank(&mut masked, i, end);
+                i = end;
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let end = find_from(bytes, i + 2, b"*/").map_or(bytes.len(), |p| p + 2);
+                blank(&mut masked, i, end);
+                i = end;
+                continue;
+            }
+            b'/' if starts_regex(prev) => {
+                if let Some(end) = regex_end(bytes, i + 1) {
+                    blank(&mut masked, i + 1, end);
+                    i = end + 1;
+                    prev = Some(b'/');
+                    continue;
+                }
+            }
+            b'\'' | b'"' => {
+                let end = quoted_end(bytes, i + 1, b);
+                blank(&mut masked, i + 1, end);
+                i = end + 1;
+                prev = Some(b);
+                continue;
+            }
+            b'`' => {
+                let end = template_end(bytes, i + 1);
+                blank(&mut masked, i + 1, end);
+                i = end + 1;
+                prev = Some(b);
+                continue;
+            }
+            _ => {}
+        }
+        if !b.is_ascii_whitespace() {
+            prev = Some(b);
+        }
+        i += 1;
+    }
+    masked_lines(masked)
+}
+
+fn find_from(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    bytes
+        .get(from..)?
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|p| p + from)
+}
+
+fn line_end(bytes: &[u8], from: usize) -> usize {
+    find_from(bytes, from, b"\n").unwrap_or(bytes.len())
+}
+
+/// A `/` after these (or at the start) begins a regex literal, not a division
+fn starts_regex(prev: Option<u8>) -> bool {
+    match prev {
+        None => true,
+        Some(p) => b"(,=:[!&|?{};+-*%<>~^".contains(&p),
+    }
+}
+
+/// Closing `/` of a regex literal whose body starts at `from`; None if the line ends
+/// first (then it was a division after all)
+fn regex_end(bytes: &[u8], from: usize) -> Option<usize> {
+    let mut in_class = false;
+    let mut j = from;
+    while j < bytes.len() {
+        match bytes[j] {
+            b'\n' => return None,
+            b'\\' => j += 1,
+            b'[' => in_class = true,
+            b']' => in_class = false,
+            b'/' if !in_class => return Some(j),
+            _ => {}
+        }
+        j += 1;
+    }
+    None
+}
+
+/// Closing quote of a '...' or "..." string; these end at the line end at the latest,
+/// which keeps an apostrophe in JSX text from masking the rest of the file
+fn quoted_end(bytes: &[u8], from: usize, quote: u8) -> usize {
+    let mut j = from;
+    while j < bytes.len() {
+        match bytes[j] {
+            b'\\' if bytes.get(j + 1) != Some(&b'\n') => j += 2,
+            b'\n' => return j,
+            b if b == quote => return j,
+            _ => j += 1,
+        }
+    }
+    bytes.len().max(from + 1) - 1
+}
+
+/// Closing backtick of a template literal, skipping `${ ... }` expressions (which may
+/// hold strings and templates of their own)
+fn template_end(bytes: &[u8], from: usize) -> usize {
+    let mut j = from;
+    while j < bytes.len() {
+        match bytes[j] {
+            b'\\' => j += 2,
+            b'`' => return j,
+            b'$' if bytes.get(j + 1) == Some(&b'{') => j = expression_end(bytes, j + 2) + 1,
+            _ => j += 1,
+        }
+    }
+    bytes.len().max(from + 1) - 1
+}
+
+/// Closing `}` of a template expression starting at `from`
+fn expression_end(bytes: &[u8], from: usize) -> usize {
+    let mut depth = 1;
+    let mut j = from;
+    while j < bytes.len() {
+        match bytes[j] {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return j;
+                }
+            }
+            b'\'' | b'"' => j = quoted_end(bytes, j + 1, bytes[j]),
+            b'`' => j = template_end(bytes, j + 1),
+            _ => {}
+        }
+        j += 1;
+    }
+    bytes.len()
+}
+
+fn match_item(line: &str, parent: Option<&Item>) -> Option<ItemStart> {
+    if parent.is_some_and(|p| p.kind == "class") {
+        return match_member(line);
+    }
+
+    if let Some(caps) = DECL_RE.captures(line) {
+        let keyword = &caps[1];
+        let kind = if keyword.starts_with("function") {
+            "function"
+        } else if keyword.ends_with("enum") {
+            "enum"
+        } else {
+            match keyword {
+                "class" => "class",
+                "interface" => "interface",
+                "namespace" | "module" => "namespace",
+                _ => "type",
+            }
+        };
+        let name = match caps.get(2).map(|m| m.as_str()) {
+            Some("extends" | "implements") => "default".to_string(),
+            Some(name) => name.to_string(),
+            // `export default function () {}`, `export default class extends Base {`
+            None if matches!(kind, "function" | "class") && line.contains("default") => {
+                "default".to_string()
+            }
+            // `module.exports = ...`, `type = 1`
+            None => return None,
+        };
+        return Some(ItemStart {
+            kind,
+            name,
+            owner: None,
+            extent: if kind == "type" {
+                Extent::Statement
+            } else {
+                Extent::Block(b'{')
+            },
+            container: matches!(kind, "class" | "namespace"),
+        });
+    }
+
+    let caps = FUNCTION_VAR_RE.captures(line)?;
+    // `const total = (a + b) * 2` is not a function: a parenthesised initializer must
+    // lead to an arrow (or open a parameter list that continues on the next line)
+    if &caps[2] == "(" && !line.contains("=>") && !line.ends_with('(') {
+        return None;
+    }
+    Some(ItemStart {
+        kind: "function",
+        name: caps[1].to_string(),
+        owner: None,
+        extent: Extent::Statement,
+        container: false,
+    })
+}
+
+fn match_member(line: &str) -> Option<ItemStart> {
+    if let Some(caps) = ARROW_FIELD_RE.captures(line) {
+        if line.contains("=>") || line.ends_with('(') {
+            return Some(ItemStart {
+                kind: "method",
+                name: caps[1].to_string(),
+                owner: None,
+                extent: Extent::Statement,
+                container: false,
+            });
+        }
+    }
+    let caps = METHOD_RE.captures(line)?;
+    let name = caps[1].to_string();
+    if NOT_METHODS.contains(&name.as_str()) {
+        return None;
+    }
+    Some(ItemStart {
+        kind: "method",
+        name,
+        owner: None,
+        extent: Extent::Block(b'{'),
+        container: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{find_symbol, render};
+    use super::*;
+
+    const SOURCE: &str = r#"import { api } from "./api";
+
+/** A line in the cart */
+export interface CartLine {
+  sku: string;
+  qty: number;
+}
+
+export type Totals = { net: number; tax: number };
+
+const PATTERN = /[{]+/g;
+const label = `total: ${format({ currency: "}" })} items`;
+
+@Injectable()
+export class Cart<T extends CartLine> {
+  private lines: T[] = [];
+
+  constructor(private readonly taxRate: number) {}
+
+  /** Add a line; merges same SKU */
+  async add(line: T): Promise<void> {
+    if (line.qty > 0) {
+      this.lines.push(line);
+    }
+  }
+
+  get size(): number {
+    return this.lines.length;
+  }
+
+  handleClick = (event: MouseEvent) => {
+    console.log("clicked {", event);
+  };
+}
+
+export function total(lines: CartLine[]): number {
+  return lines.reduce((sum, l) => sum + l.qty, 0);
+}
+
+export const format = async (
+  value: number,
+): Promise<string> => {
+  return `${value}`;
+};
+
+const ratio = (a + b) / 2;
+
+export enum Status {
+  Open = "open",
+  Closed = "closed",
+}
+
+export namespace Checkout {
+  export function start(): void {}
+}
+
+export default function () {
+  return 'don\'t';
+}
+"#;
+
+    #[test]
+    fn test_typescript_outline() {
+        let items = scan(SOURCE);
+        assert_eq!(
+            render(&items),
+            " 4  export interface CartLine
+ 9  export type Totals = { net: number; tax: number };
+15  export class Cart<T extends CartLine>
+18    constructor(private readonly taxRate: number)
+21    async add(line: T): Promise<void>
+27    get size(): number
+31    handleClick = (event: MouseEvent) =>
+36  export function total(lines: CartLine[]): number
+40  export const format = async (
+48  export enum Status
+53  export namespace Checkout
+54    export function start(): void
+57  export default function ()"
+        );
+    }
+
+    #[test]
+    fn test_typescript_extents() {
+        let items = scan(SOURCE);
+        let add = find_symbol(&items, "Cart.add")[0];
+        assert_eq!((add.doc_line, add.line, add.end_line), (20, 21, 25));
+        let cart = find_symbol(&items, "Cart")[0];
+        assert_eq!((cart.doc_line, cart.line, cart.end_line), (14, 15, 34));
+        let format = find_symbol(&items, "format")[0];
+        assert_eq!((format.line, format.end_line), (40, 44));
+        assert_eq!(find_symbol(&items, "Checkout.start").len(), 1);
+    }
+
+    #[test]
+    fn test_typescript_mask_templates_and_regexes() {
+        let masked = mask("const a = `x ${f({ y: `}` })} {`; const r = /[}]/; x = a / b / c;");
+        assert_eq!(
+            masked[0],
+            "const a = `                    `; const r = /   /; x = a / b / c;"
+        );
+    }
+}
@@ -0,0 +1,371 @@
+/// Delta mode (`--delta`): only what changed since the previous run
+///
+/// The last parsed `TestResult` / `LintResult` is stored per project (working directory)
+/// and command line in the tracking DB. The next `--delta` run compares against it and
+/// prints new failures, fixed tests and new lint issues, plus counts for the rest.
+/// The first run has nothing to compare with and prints the normal output.
+use super::diagnostic::format_diagnostic;
+use super::types::*;
+use super::{OutputParser, ParseResult};
+use chrono::{DateTime, Local, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::OnceLock;
+
+/// Max new failures / issues listed; the rest are counted
+const MAX_LISTED: usize = 10;
+
+static DELTA_MODE: OnceLock<bool> = OnceLock::new();
+
+/// Set once from `main` before any command runs.
+pub fn set_delta_mode(enabled: bool) {
+    let _ = DELTA_MODE.set(enabled);
+}
+
+/// True when the user passed `--delta`.
+pub fn delta_mode() -> bool {
+    DELTA_MODE.get().copied().unwrap_or(false)
+}
+
+/// Canonical results that can be compared across runs.
+pub trait Delta: Serialize + DeserializeOwned {
+    /// Render what changed from `previous` to `self`; `since` is when `previous` ran.
+    fn render_delta(&self, previous: &Self, tool: &str, since: &str) -> String;
+}
+
+impl Delta for TestResult {
+    fn render_delta(&self, previous: &Self, tool: &str, since: &str) -> String {
+        let before: BTreeSet<&str> = previous
+            .failures
+            .iter()
+            .map(|f| f.test_name.as_str())
+            .collect();
+        let now: BTreeSet<&str> = self.failures.iter().map(|f| f.test_name.as_str()).collect();
+
+        let new: Vec<&TestFailure> = self
+            .failures
+            .iter()
+            .filter(|f| !before.contains(f.test_name.as_str()))
+            .collect();
+        let fixed: Vec<&str> = before.difference(&now).copied().collect();
+        let unchanged = now.intersection(&before).count();
+
+        let totals = format!("{} passed, {} failed", self.passed, self.failed);
+        if new.is_empty() && fixed.is_empty() {
+            return format!("{}: no change since {} ({})", tool, since, totals);
+        }
+
+        let mut lines = vec![format!(
+            "{}: {} fixed, {} new {}, {} unchanged ({})",
+            tool,
+            fixed.len(),
+            new.len(),
+            if new.len() == 1 {
+                "failure"
+            } else {
+                "failures"
+            },
+            unchanged,
+            totals
+        )];
+
+        if !new.is_empty() {
+            lines.push(String::new());
+            lines.push("New failures:".to_string());
+            for failure in new.iter().take(MAX_LISTED) {
+                if failure.file_path.is_empty() {
+                    lines.push(format!("✗ {}", failure.test_name));
+                } else {
+                    lines.push(format!("✗ {} ({})", failure.test_name, failure.file_path));
+                }
+                let preview: Vec<&str> = failure.error_message.lines().take(2).collect();
+                if !preview.is_empty() {
+                    lines.push(format!("   {}", preview.join(" ")));
+                }
+            }
+            if new.len() > MAX_LISTED {
+                lines.push(format!("... +{} more new failures", new.len() - MAX_LISTED));
+            }
+        }
+
+        if !fixed.is_empty() {
+            lines.push(String::new());
+            lines.push("Fixed:".to_string());
+            for name in fixed.iter().take(MAX_LISTED) {
+                lines.push(format!("✓ {}", name));
+            }
+            if fixed.len() > MAX_LISTED {
+                lines.push(format!("... +{} more fixed", fixed.len() - MAX_LISTED));
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Identity of a lint issue across runs. Line and column are left out: an edit above
+/// an issue moves it without making it new.
+fn issue_key(issue: &LintIssue) -> (&str, &str, &str) {
+    (&issue.file_path, &issue.rule_id, &issue.message)
+}
+
+impl Delta for LintResult {
+    fn render_delta(&self, previous: &Self, tool: &str, since: &str) -> String {
+        // Multiset of previous issues, so a second copy of an existing issue still counts as new
+        let mut remaining: BTreeMap<(&str, &str, &str), usize> = BTreeMap::new();
+        for issue in &previous.issues {
+            *remaining.entry(issue_key(issue)).or_default() += 1;
+        }
+
+        let mut new = Vec::new();
+        let mut unchanged = 0;
+        for issue in &self.issues {
+            match remaining.get_mut(&issue_key(issue)) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    unchanged += 1;
+                }
+                _ => new.push(issue),
+            }
+        }
+        let fixed: usize = remaining.values().sum();
+
+        let totals = format!("{} errors, {} warnings", self.errors, self.warnings);
+        if new.is_empty() && fixed == 0 {
+            return format!("{}: no change since {} ({})", tool, since, totals);
+        }
+
+        let mut lines = vec![format!(
+            "{}: {} fixed, {} new {}, {} unchanged ({})",
+            tool,
+            fixed,
+            new.len(),
+            if new.len() == 1 { "issue" } else { "issues" },
+            unchanged,
+            totals
+        )];
+
+        if !new.is_empty() {
+            lines.push(String::new());
+            lines.push("New issues:".to_string());
+            for issue in new.iter().take(MAX_LISTED) {
+                lines.push(format_diagnostic(issue));
+            }
+            if new.len() > MAX_LISTED {
+                lines.push(format!("... +{} more new issues", new.len() - MAX_LISTED));
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Delta output for `raw` parsed with `P`, saving the parse as the next baseline.
+///
+/// `full` renders the normal output; it is used on the first run (no baseline yet)
+/// and when parsing falls through to passthrough. A degraded parse (e.g. a crashed test
+/// harness, failures without details) also prints `full` and keeps the old baseline:
+/// comparing a partial list would report every missing failure as fixed.
+pub fn render_delta<P>(
+    tool: &str,
+    command: &str,
+    raw: &str,
+    full: impl FnOnce() -> String,
+) -> String
+where
+    P: OutputParser,
+    P::Output: Delta,
+{
+    let project = std::env::current_dir()
+        .map(|d| d.display().to_string())
+        .unwrap_or_default();
+    let tracker = crate::tracking::Tracker::new().ok();
+    delta_with::<P>(tracker.as_ref(), &project, tool, command, raw, full)
+}
+
+fn delta_with<P>(
+    tracker: Option<&crate::tracking::Tracker>,
+    project: &str,
+    tool: &str,
+    command: &str,
+    raw: &str,
+    full: impl FnOnce() -> String,
+) -> String
+where
+    P: OutputParser,
+    P::Output: Delta,
+{
+    let current = match P::parse(raw) {
+        ParseResult::Full(data) => data,
+        ParseResult::Degraded(..) => {
+            return format!("{}\n[delta: parse incomplete, baseline kept]", full())
+        }
+        ParseResult::Passthrough(_) => return full(),
+    };
+
+    let command = command.trim();
+    let previous: Option<(DateTime<Utc>, P::Output)> = tracker
+        .and_then(|t| t.get_last_result(project, command).ok().flatten())
+        .and_then(|(at, json)| serde_json::from_str(&json).ok().map(|r| (at, r)));
+
+    if let (Some(tracker), Ok(json)) = (tracker, serde_json::to_string(&current)) {
+        let _ = tracker.save_last_result(project, command, &json);
+    }
+
+    match previous {
+        Some((at, previous)) => {
+            let since = at.with_timezone(&Local).format("%H:%M:%S").to_string();
+            current.render_delta(&previous, tool, &since)
+        }
+        None => format!(
+            "{}\n[delta: baseline saved, the next --delta run shows only changes]",
+            full()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure(name: &str) -> TestFailure {
+        TestFailure {
+            test_name: name.to_string(),
+            file_path: String::new(),
+            error_message: format!("{} broke", name),
+            stack_trace: None,
+        }
+    }
+
+    fn tests(passed: usize, failing: &[&str]) -> TestResult {
+        TestResult {
+            total: passed + failing.len(),
+            passed,
+            failed: failing.len(),
+            skipped: 0,
+            duration_ms: None,
+            failures: failing.iter().map(|n| failure(n)).collect(),
+        }
+    }
+
+    fn issue(file: &str, line: usize, rule: &str) -> LintIssue {
+        LintIssue {
+            file_path: file.to_string(),
+            line,
+            column: 1,
+            severity: LintSeverity::Warning,
+            rule_id: rule.to_string(),
+            message: format!("{} here", rule),
+            ..LintIssue::default()
+        }
+    }
+
+    #[test]
+    fn test_test_delta_counts_and_lists() {
+        let before = tests(10, &["a", "b", "c", "d"]);
+        let after = tests(12, &["c", "d", "e"]);
+        let out = after.render_delta(&before, "cargo test", "10:00:00");
+
+        assert!(out
+            .starts_with("cargo test: 2 fixed, 1 new failure, 2 unchanged (12 passed, 3 failed)"));
+        assert!(out.contains("New failures:\n✗ e\n   e broke"));
+        assert!(out.contains("Fixed:\n✓ a\n✓ b"));
+        assert!(!out.contains("✗ c"));
+    }
+
+    #[test]
+    fn test_test_delta_no_change() {
+        let result = tests(5, &["x"]);
+        assert_eq!(
+            result.render_delta(&tests(5, &["x"]), "pytest", "09:30:00"),
+            "pytest: no change since 09:30:00 (5 passed, 1 failed)"
+        );
+    }
+
+    #[test]
+    fn test_lint_delta_ignores_moved_lines() {
+        let before = LintResult::from_issues(vec![
+            issue("src/a.rs", 10, "clippy::clone_on_copy"),
+            issue("src/b.rs", 3, "clippy::needless_return"),
+        ]);
+        let after = LintResult::from_issues(vec![
+            // moved down by an edit: same issue
+            issue("src/a.rs", 14, "clippy::clone_on_copy"),
+            issue("src/a.rs", 20, "clippy::clone_on_copy"),
+            issue("src/c.rs", 1, "clippy::len_zero"),
+        ]);
+        let out = after.render_delta(&before, "cargo clippy", "10:00:00");
+
+        assert!(
+            out.starts_with(
+                "cargo clippy: 1 fixed, 2 new issues, 1 unchanged (0 errors, 3 warnings)"
+            ),
+            "got: {}",
+            out
+        );
+        assert!(out.contains("src/a.rs:20:1: warning[clippy::clone_on_copy]: "));
+        assert!(out.contains("src/c.rs:1:1: warning[clippy::len_zero]: "));
+        assert!(!out.contains("src/a.rs:14"));
+    }
+
+    /// Reads "<passed> <failing,...>" and degrades when the line ends with "?"
+    struct FakeTestParser;
+
+    impl OutputParser for FakeTestParser {
+        type Output = TestResult;
+
+        fn parse(input: &str) -> ParseResult<TestResult> {
+            let (passed, failing) = input.trim_end_matches('?').split_once(' ').unwrap();
+            let failing: Vec<&str> = failing.split(',').filter(|f| !f.is_empty()).collect();
+            let result = tests(passed.parse().unwrap(), &failing);
+            if input.ends_with('?') {
+                ParseResult::Degraded(result, vec!["failures without details".to_string()])
+            } else {
+                ParseResult::Full(result)
+            }
+        }
+    }
+
+    #[test]
+    fn test_degraded_parse_keeps_baseline() {
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = crate::tracking::Tracker::open(&dir.path().join("history.db")).unwrap();
+        let run = |raw: &str| {
+            delta_with::<FakeTestParser>(
+                Some(&tracker),
+                "/repo",
+                "cargo test",
+                "cargo test",
+                raw,
+                || format!("full: {}", raw),
+            )
+        };
+
+        assert!(run("8 a,b")
+            .ends_with("[delta: baseline saved, the next --delta run shows only changes]"));
+        // Only `a` made it into the partial list: `b` must not show as fixed
+        let degraded = run("8 a?");
+        assert_eq!(
+            degraded,
+            "full: 8 a?\n[delta: parse incomplete, baseline kept]"
+        );
+        let saved = tracker
+            .get_last_result("/repo", "cargo test")
+            .unwrap()
+            .unwrap()
+            .1;
+        let baseline: TestResult = serde_json::from_str(&saved).unwrap();
+        assert_eq!(baseline.failed, 2);
+
+        assert!(run("9 a").contains("fixed"));
+    }
+
+    #[test]
+    fn test_delta_roundtrips_through_json() {
+        let result = tests(3, &["flaky"]);
+        let json = serde_json::to_string(&result).unwrap();
+        let back: TestResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.failures[0].test_name, "flaky");
+    }
+}
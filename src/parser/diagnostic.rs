@@ -0,0 +1,486 @@
+/// Shared text rendering for compiler and linter diagnostics
+///
+/// tsc, cargo build/clippy, eslint, ruff, mypy, pylint, golangci-lint and go vet all parse
+/// into `LintIssue`s and print them through [`render_diagnostics`], so an agent reads every
+/// tool's findings with one grammar:
+///
+/// ```text
+/// <tool>: <n> errors, <n> warnings in <n> files
+/// Top rules: <rule> (<n>x), ...
+///
+/// <path>:<line>:<col>: <severity>[<rule>]: <message>
+///   <line> | <source code, when the tool reports it>
+///   help: <suggestion>
+///   note: <note>
+///   also: <path>:<line>:<col>, ...
+/// ```
+///
+/// `:<col>` is left out when the tool has no column, `[<rule>]` when it has no rule.
+/// Diagnostics are grouped by file (most issues first). The same severity, rule and
+/// message reported in several places is printed once, with the other places on `also:`.
+/// Past [`MAX_FILES`] files or [`MAX_DIAGNOSTICS`] diagnostics the rest is counted in a
+/// `... +N more` footer, which [`crate::tee::tee_and_hint_for`] follows with the path of the
+/// saved raw output. tsc, whose
+/// errors usually all need fixing, prints every one ([`render_all_diagnostics`]).
+use super::types::{LintIssue, LintSeverity};
+use crate::utils::truncate;
+use std::collections::HashMap;
+
+/// Start of the footer counting diagnostics left out by the caps
+const HIDDEN_FOOTER: &str = "... +";
+
+/// Notes printed per diagnostic; the rest are in the JSON output
+const MAX_NOTES: usize = 3;
+
+/// Longest source or note line printed; longer ones are cut
+const MAX_LINE_CHARS: usize = 160;
+
+/// Rules listed in the `Top rules:` line
+const MAX_TOP_RULES: usize = 5;
+
+/// Files printed; diagnostics in the others are only counted
+const MAX_FILES: usize = 10;
+
+/// Diagnostics printed (a deduplicated diagnostic counts once)
+const MAX_DIAGNOSTICS: usize = 15;
+
+/// Places listed on an `also:` line
+const MAX_ALSO: usize = 3;
+
+/// Diagnostics printed with their source, help and notes (errors first); the others
+/// get their headline only
+const MAX_FRAMED: usize = 5;
+
+/// Make a tool-reported path short but still usable: relative to the working directory
+/// when it is inside it, otherwise from the last `src/`, `lib/`, `tests/`, `pkg/`,
+/// `cmd/` or `internal/` component. Other paths are kept as they are.
+pub fn compact_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+
+    if let Ok(cwd) = std::env::current_dir() {
+        let cwd = cwd.to_string_lossy().replace('\\', "/");
+        if let Some(rest) = path.strip_prefix(&format!("{}/", cwd.trim_end_matches('/'))) {
+            return rest.to_string();
+        }
+    }
+    let path = path.strip_prefix("./").map(String::from).unwrap_or(path);
+    if !path.starts_with('/') {
+        return path;
+    }
+
+    for anchor in ["/src/", "/lib/", "/tests/", "/pkg/", "/cmd/", "/internal/"] {
+        if let Some(pos) = path.rfind(anchor) {
+            return path[pos + 1..].to_string();
+        }
+    }
+    path
+}
+
+fn severity_label(severity: &LintSeverity) -> &'static str {
+    match severity {
+        LintSeverity::Error => "error",
+        LintSeverity::Warning => "warning",
+        LintSeverity::Info => "info",
+    }
+}
+
+/// `path:line:col`, dropping the parts the tool didn't report
+fn location(issue: &LintIssue) -> String {
+    let path = compact_path(&issue.file_path);
+    match (issue.line, issue.column) {
+        (0, _) => path,
+        (line, 0) => format!("{}:{}", path, line),
+        (line, col) => format!("{}:{}:{}", path, line, col),
+    }
+}
+
+/// The one-line form of a diagnostic: `path:line:col: severity[rule]: message`
+pub fn format_diagnostic(issue: &LintIssue) -> String {
+    let rule = if issue.rule_id.is_empty() {
+        String::new()
+    } else {
+        format!("[{}]", issue.rule_id)
+    };
+    let head = format!(
+        "{}{}: {}",
+        severity_label(&issue.severity),
+        rule,
+        issue.message
+    );
+    if issue.file_path.is_empty() {
+        head
+    } else {
+        format!("{}: {}", location(issue), head)
+    }
+}
+
+/// `  12 | code` lines for the issue's source, dedented to the least indented line,
+/// line numbers right-aligned to `width`
+fn source_frame(issue: &LintIssue, width: usize) -> Vec<String> {
+    let indent = issue
+        .source_lines
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    issue
+        .source_lines
+        .iter()
+        .enumerate()
+        .map(|(offset, code)| {
+            let code = code.get(indent..).unwrap_or(code.trim_start()).trim_end();
+            format!(
+                "  {:>width$} | {}",
+                issue.line + offset,
+                truncate(code, MAX_LINE_CHARS),
+                width = width
+            )
+        })
+        .collect()
+}
+
+/// A diagnostic and the other places the same one was reported
+struct Group<'a> {
+    issue: &'a LintIssue,
+    also: Vec<String>,
+}
+
+/// Render `issues` from `tool` in the shared grammar (see the module docs).
+pub fn render_diagnostics(tool: &str, issues: &[LintIssue]) -> String {
+    render(tool, issues, true)
+}
+
+/// [`render_diagnostics`] without the file and diagnostic caps
+pub fn render_all_diagnostics(tool: &str, issues: &[LintIssue]) -> String {
+    render(tool, issues, false)
+}
+
+fn render(tool: &str, issues: &[LintIssue], capped: bool) -> String {
+    if issues.is_empty() {
+        return format!("✓ {}: No issues found", tool);
+    }
+
+    let count = |severity: LintSeverity| issues.iter().filter(|i| i.severity == severity).count();
+    let (errors, warnings, infos) = (
+        count(LintSeverity::Error),
+        count(LintSeverity::Warning),
+        count(LintSeverity::Info),
+    );
+
+    // Files in order of issue count, then name; issues within a file by position
+    let mut by_file: Vec<(String, Vec<&LintIssue>)> = Vec::new();
+    let mut file_index: HashMap<String, usize> = HashMap::new();
+    for issue in issues {
+        let path = compact_path(&issue.file_path);
+        let idx = *file_index.entry(path.clone()).or_insert_with(|| {
+            by_file.push((path, Vec::new()));
+            by_file.len() - 1
+        });
+        by_file[idx].1.push(issue);
+    }
+    by_file.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
+    for (_, file_issues) in &mut by_file {
+        file_issues.sort_by_key(|i| (i.line, i.column));
+    }
+
+    let mut header = format!("{}: {} errors, {} warnings", tool, errors, warnings);
+    if infos > 0 {
+        header.push_str(&format!(", {} notes", infos));
+    }
+    let named_files = by_file.iter().filter(|(path, _)| !path.is_empty()).count();
+    if named_files > 0 {
+        header.push_str(&format!(" in {} files", named_files));
+    }
+    let mut lines = vec![header];
+
+    let mut rule_counts: HashMap<&str, usize> = HashMap::new();
+    for issue in issues.iter().filter(|i| !i.rule_id.is_empty()) {
+        *rule_counts.entry(issue.rule_id.as_str()).or_default() += 1;
+    }
+    if rule_counts.len() > 1 {
+        let mut rules: Vec<_> = rule_counts.into_iter().collect();
+        rules.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let top: Vec<String> = rules
+            .iter()
+            .take(MAX_TOP_RULES)
+            .map(|(rule, n)| format!("{} ({}x)", rule, n))
+            .collect();
+        lines.push(format!("Top rules: {}", top.join(", ")));
+    }
+
+    // Dedup identical diagnostics across the whole run; each group stays with the file
+    // where it was first seen
+    let mut groups: Vec<Vec<Group>> = Vec::new();
+    let mut seen: HashMap<(&str, &str, &str), (usize, usize)> = HashMap::new();
+    for (file_idx, (_, file_issues)) in by_file.iter().enumerate() {
+        groups.push(Vec::new());
+        for issue in file_issues {
+            let key = (
+                severity_label(&issue.severity),
+                issue.rule_id.as_str(),
+                issue.message.as_str(),
+            );
+            match seen.get(&key) {
+                Some(&(f, g)) => groups[f][g].also.push(location(issue)),
+                None => {
+                    seen.insert(key, (file_idx, groups[file_idx].len()));
+                    groups[file_idx].push(Group {
+                        issue,
+                        also: Vec::new(),
+                    });
+                }
+            }
+        }
+    }
+
+    let (max_files, max_diagnostics, max_framed) = if capped {
+        (MAX_FILES, MAX_DIAGNOSTICS, MAX_FRAMED)
+    } else {
+        (usize::MAX, usize::MAX, usize::MAX)
+    };
+
+    // Which groups are printed, file by file, and how many reports are left out
+    let mut shown: Vec<Vec<&Group>> = Vec::new();
+    let mut printed = 0;
+    let mut hidden = 0;
+    for file_groups in groups.iter().filter(|g| !g.is_empty()) {
+        let mut file_shown = Vec::new();
+        for group in file_groups {
+            if shown.len() >= max_files || printed >= max_diagnostics {
+                hidden += 1 + group.also.len();
+            } else {
+                printed += 1;
+                file_shown.push(group);
+            }
+        }
+        if !file_shown.is_empty() {
+            shown.push(file_shown);
+        }
+    }
+
+    // Source, help and notes for the first errors, then warnings; headlines for the rest
+    let mut framed: Vec<(usize, usize)> = Vec::new();
+    for errors_pass in [true, false] {
+        for (f, file_groups) in shown.iter().enumerate() {
+            for (g, group) in file_groups.iter().enumerate() {
+                let is_error = group.issue.severity == LintSeverity::Error;
+                if is_error == errors_pass && framed.len() < max_framed {
+                    framed.push((f, g));
+                }
+            }
+        }
+    }
+
+    for (f, file_groups) in shown.iter().enumerate() {
+        lines.push(String::new());
+        // One gutter width for the whole file, so consecutive frames line up
+        let width = file_groups
+            .iter()
+            .map(|g| g.issue.line + g.issue.source_lines.len().saturating_sub(1))
+            .max()
+            .unwrap_or(0)
+            .to_string()
+            .len();
+        for (g, group) in file_groups.iter().enumerate() {
+            lines.push(format_diagnostic(group.issue));
+            if framed.contains(&(f, g)) {
+                lines.extend(source_frame(group.issue, width));
+                if let Some(suggestion) = &group.issue.suggestion {
+                    lines.push(format!("  help: {}", truncate(suggestion, MAX_LINE_CHARS)));
+                }
+                for note in group.issue.notes.iter().take(MAX_NOTES) {
+                    lines.push(format!("  note: {}", truncate(note, MAX_LINE_CHARS)));
+                }
+                if group.issue.notes.len() > MAX_NOTES {
+                    lines.push(format!(
+                        "  note: ... +{} more",
+                        group.issue.notes.len() - MAX_NOTES
+                    ));
+                }
+            }
+            if !group.also.is_empty() {
+                let mut also = group.also[..group.also.len().min(MAX_ALSO)].join(", ");
+                if group.also.len() > MAX_ALSO {
+                    also.push_str(&format!(", +{} more", group.also.len() - MAX_ALSO));
+                }
+                lines.push(format!("  also: {}", also));
+            }
+        }
+    }
+    if hidden > 0 {
+        lines.push(String::new());
+        lines.push(format!("{}{} more", HIDDEN_FOOTER, hidden));
+    }
+
+    lines.join("\n")
+}
+
+/// Whether `rendered` (filter output containing a rendering) had diagnostics left out
+pub fn is_capped(rendered: &str) -> bool {
+    rendered.lines().any(|line| {
+        line.strip_prefix(HIDDEN_FOOTER)
+            .and_then(|rest| rest.strip_suffix(" more"))
+            .is_some_and(|n| n.parse::<usize>().is_ok())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(file: &str, line: usize, rule: &str, message: &str) -> LintIssue {
+        LintIssue {
+            file_path: file.to_string(),
+            line,
+            column: 5,
+            severity: LintSeverity::Error,
+            rule_id: rule.to_string(),
+            message: message.to_string(),
+            ..LintIssue::default()
+        }
+    }
+
+    #[test]
+    fn test_format_diagnostic_grammar() {
+        let mut i = issue("src/a.ts", 12, "TS2322", "Type 'string' is not assignable.");
+        assert_eq!(
+            format_diagnostic(&i),
+            "src/a.ts:12:5: error[TS2322]: Type 'string' is not assignable."
+        );
+        i.column = 0;
+        i.rule_id.clear();
+        i.severity = LintSeverity::Warning;
+        assert_eq!(
+            format_diagnostic(&i),
+            "src/a.ts:12: warning: Type 'string' is not assignable."
+        );
+        i.file_path.clear();
+        assert_eq!(
+            format_diagnostic(&i),
+            "warning: Type 'string' is not assignable."
+        );
+    }
+
+    #[test]
+    fn test_render_groups_by_file_and_dedups() {
+        let mut first = issue("src/b.rs", 9, "E0308", "mismatched types");
+        first.suggestion = Some("use `.try_into()`".to_string());
+        first.notes = vec!["expected `u32`, found `usize`".to_string()];
+        let issues = vec![
+            issue(
+                "src/a.rs",
+                3,
+                "clippy::len_zero",
+                "length comparison to zero",
+            ),
+            first,
+            issue(
+                "src/b.rs",
+                2,
+                "clippy::len_zero",
+                "length comparison to zero",
+            ),
+            issue("src/b.rs", 20, "E0425", "cannot find value `x`"),
+        ];
+
+        assert_eq!(
+            render_diagnostics("cargo build", &issues),
+            "\
+cargo build: 4 errors, 0 warnings in 2 files
+Top rules: clippy::len_zero (2x), E0308 (1x), E0425 (1x)
+
+src/b.rs:2:5: error[clippy::len_zero]: length comparison to zero
+  also: src/a.rs:3:5
+src/b.rs:9:5: error[E0308]: mismatched types
+  help: use `.try_into()`
+  note: expected `u32`, found `usize`
+src/b.rs:20:5: error[E0425]: cannot find value `x`"
+        );
+    }
+
+    #[test]
+    fn test_render_source_frame() {
+        let mut i = issue("src/lib.rs", 9, "E0308", "mismatched types");
+        i.source_lines = vec!["        len".to_string(), "            + 1".to_string()];
+        i.suggestion = Some("convert: `len.try_into().unwrap()`".to_string());
+        let rendered = render_diagnostics("cargo build", &[i]);
+        assert!(rendered.ends_with(
+            "\
+src/lib.rs:9:5: error[E0308]: mismatched types
+   9 | len
+  10 |     + 1
+  help: convert: `len.try_into().unwrap()`"
+        ));
+    }
+
+    #[test]
+    fn test_render_caps_diagnostics_and_places() {
+        let mut issues: Vec<LintIssue> = (1..=40)
+            .map(|n| {
+                issue(
+                    "src/a.rs",
+                    n,
+                    "unused_variables",
+                    &format!("unused `x{}`", n),
+                )
+            })
+            .collect();
+        issues.extend((1..=6).map(|n| issue("src/b.rs", n, "E0425", "cannot find value `y`")));
+
+        let rendered = render_diagnostics("cargo build", &issues);
+        assert_eq!(rendered.matches(": error[unused_variables]:").count(), 15);
+        assert!(!rendered.contains("src/b.rs:1:5"));
+        // 25 unused variables and the 6 E0425 reports are not shown
+        assert!(rendered.ends_with("\n\n... +31 more"));
+        assert!(is_capped(&rendered));
+
+        let repeated: Vec<LintIssue> = (1..=6)
+            .map(|n| issue("src/b.rs", n, "E0425", "cannot find value `y`"))
+            .collect();
+        let grouped = render_diagnostics("cargo build", &repeated);
+        assert!(grouped.ends_with("  also: src/b.rs:2:5, src/b.rs:3:5, src/b.rs:4:5, +2 more"));
+        assert!(!is_capped(&grouped));
+    }
+
+    #[test]
+    fn test_render_frames_first_errors_with_shared_width() {
+        let mut issues: Vec<LintIssue> = (8..=14)
+            .map(|n| {
+                let mut i = issue("src/a.rs", n, "E0308", &format!("mismatched types {}", n));
+                i.source_lines = vec![format!("let v{} = 1;", n)];
+                i.suggestion = Some("convert it".to_string());
+                i
+            })
+            .collect();
+        issues[0].severity = LintSeverity::Warning;
+
+        let rendered = render_diagnostics("cargo build", &issues);
+        // Line 9 pads to the width of line 13 below it
+        assert!(rendered.contains("mismatched types 9\n   9 | let v9 = 1;\n  help: convert it\n"));
+        assert!(rendered.contains("mismatched types 13\n  13 | let v13 = 1;\n"));
+        // Five errors framed (9-13); the warning and the last error get their headline only
+        assert!(rendered.contains("mismatched types 8\nsrc/a.rs:9:5"));
+        assert!(rendered.ends_with("error[E0308]: mismatched types 14"));
+        assert_eq!(rendered.matches("  help: convert it").count(), 5);
+    }
+
+    #[test]
+    fn test_render_empty() {
+        assert_eq!(render_diagnostics("Ruff", &[]), "✓ Ruff: No issues found");
+    }
+
+    #[test]
+    fn test_compact_path() {
+        assert_eq!(compact_path("/work/web/src/cart.js"), "src/cart.js");
+        assert_eq!(compact_path("./pkg/api.go"), "pkg/api.go");
+        assert_eq!(compact_path("shop/api.py"), "shop/api.py");
+        assert_eq!(compact_path("/opt/app/api.py"), "/opt/app/api.py");
+        assert_eq!(compact_path("C:\\app\\src\\main.ts"), "C:/app/src/main.ts");
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(
+            compact_path(&cwd.join("deep/file.rs").to_string_lossy()),
+            "deep/file.rs"
+        );
+    }
+}
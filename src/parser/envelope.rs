@@ -0,0 +1,194 @@
+/// Versioned JSON envelope for `--output json`
+///
+/// Every supported command prints exactly one envelope on stdout. The payload in `data`
+/// is one of the canonical types from `types.rs`; `kind` says which. The schema is
+/// documented in docs/json-output.md and bumped via `SCHEMA_VERSION`.
+use super::types::*;
+use super::{OutputParser, ParseResult};
+use serde::Serialize;
+use std::sync::OnceLock;
+
+/// Bump on any breaking change to the envelope or to a canonical type.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Global `--output` format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-compact text (default)
+    #[default]
+    Text,
+    /// Versioned JSON envelope with the parsed canonical struct
+    Json,
+}
+
+static OUTPUT_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// Set once from `main` before any command runs.
+pub fn set_output_format(format: OutputFormat) {
+    let _ = OUTPUT_FORMAT.set(format);
+}
+
+/// True when the user asked for `--output json`.
+pub fn json_mode() -> bool {
+    OUTPUT_FORMAT.get().copied().unwrap_or_default() == OutputFormat::Json
+}
+
+/// Canonical payload types that can appear in an envelope.
+pub trait Canonical: Serialize {
+    /// Value of the envelope's `kind` field
+    const KIND: &'static str;
+}
+
+impl Canonical for TestResult {
+    const KIND: &'static str = "test";
+}
+
+impl Canonical for LintResult {
+    const KIND: &'static str = "lint";
+}
+
+impl Canonical for BuildOutput {
+    const KIND: &'static str = "build";
+}
+
+impl Canonical for DependencyState {
+    const KIND: &'static str = "dependencies";
+}
+
+impl Canonical for GitResult {
+    const KIND: &'static str = "git";
+}
+
+/// Top-level JSON document printed in `--output json` mode
+#[derive(Debug, Serialize)]
+pub struct Envelope<T: Serialize> {
+    pub schema_version: u32,
+    /// Original command, e.g. `cargo test --lib`
+    pub command: String,
+    pub kind: &'static str,
+    /// 1 = full, 2 = degraded, 3 = passthrough
+    pub tier: u8,
+    pub parse: &'static str,
+    pub warnings: Vec<String>,
+    pub exit_code: i32,
+    /// Canonical payload; `null` on passthrough
+    pub data: Option<T>,
+    /// Truncated raw output, only present on passthrough
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
+}
+
+impl<T: Canonical> Envelope<T> {
+    pub fn new(command: &str, result: ParseResult<T>, exit_code: i32) -> Self {
+        let tier = result.tier();
+        let (parse, warnings, data, raw) = match result {
+            ParseResult::Full(data) => ("full", vec![], Some(data), None),
+            ParseResult::Degraded(data, warnings) => ("degraded", warnings, Some(data), None),
+            ParseResult::Passthrough(raw) => (
+                "passthrough",
+                vec!["All parsing tiers failed".to_string()],
+                None,
+                Some(raw),
+            ),
+        };
+
+        Envelope {
+            schema_version: SCHEMA_VERSION,
+            command: command.trim().to_string(),
+            kind: T::KIND,
+            tier,
+            parse,
+            warnings,
+            exit_code,
+            data,
+            raw,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|e| {
+            format!(
+                "{{\"schema_version\":{},\"error\":\"{}\"}}",
+                SCHEMA_VERSION, e
+            )
+        })
+    }
+}
+
+/// Parse `raw` with `P` and render the envelope as pretty JSON.
+pub fn render_json<P>(command: &str, raw: &str, exit_code: i32) -> String
+where
+    P: OutputParser,
+    P::Output: Canonical,
+{
+    Envelope::new(command, P::parse(raw), exit_code).to_json()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Compare a rendered envelope against a snapshot in tests/fixtures/json_output/.
+    /// Compares as JSON values so key order and whitespace don't matter.
+    pub(crate) fn assert_json_snapshot(actual: &str, expected: &str) {
+        let actual: serde_json::Value =
+            serde_json::from_str(actual).expect("envelope is not valid JSON");
+        let expected: serde_json::Value =
+            serde_json::from_str(expected).expect("snapshot is not valid JSON");
+        assert_eq!(
+            actual,
+            expected,
+            "\nactual:\n{}",
+            serde_json::to_string_pretty(&actual).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_envelope_full() {
+        let result = ParseResult::Full(DependencyState {
+            total_packages: 1,
+            outdated_count: 0,
+            dependencies: vec![],
+        });
+        let json: serde_json::Value =
+            serde_json::from_str(&Envelope::new("pnpm list", result, 0).to_json()).unwrap();
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+        assert_eq!(json["kind"], "dependencies");
+        assert_eq!(json["tier"], 1);
+        assert_eq!(json["parse"], "full");
+        assert_eq!(json["data"]["total_packages"], 1);
+        assert!(json.get("raw").is_none());
+    }
+
+    #[test]
+    fn test_envelope_degraded_keeps_warnings() {
+        let result = ParseResult::Degraded(
+            BuildOutput {
+                success: true,
+                duration_ms: None,
+                warnings: 0,
+                errors: 0,
+                bundles: vec![],
+                routes: vec![],
+                issues: vec![],
+            },
+            vec!["no summary line".to_string()],
+        );
+        let env = Envelope::new("cargo build", result, 0);
+        assert_eq!(env.tier, 2);
+        assert_eq!(env.parse, "degraded");
+        assert_eq!(env.warnings, vec!["no summary line"]);
+    }
+
+    #[test]
+    fn test_envelope_passthrough_has_raw_no_data() {
+        let result: ParseResult<TestResult> = ParseResult::Passthrough("boom".to_string());
+        let json: serde_json::Value =
+            serde_json::from_str(&Envelope::new("pytest", result, 2).to_json()).unwrap();
+        assert_eq!(json["tier"], 3);
+        assert_eq!(json["kind"], "test");
+        assert!(json["data"].is_null());
+        assert_eq!(json["raw"], "boom");
+        assert_eq!(json["exit_code"], 2);
+    }
+}
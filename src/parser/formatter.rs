@@ -1,5 +1,13 @@
 /// Token-efficient formatting trait for canonical types
 use super::types::*;
+use std::sync::OnceLock;
+
+static ULTRA_COMPACT: OnceLock<bool> = OnceLock::new();
+
+/// Set once from `main` from the global `-u` flag.
+pub fn set_ultra_compact(enabled: bool) {
+    let _ = ULTRA_COMPACT.set(enabled);
+}
 
 /// Output formatting modes
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -20,6 +28,15 @@ impl FormatMode {
             _ => FormatMode::Ultra,
         }
     }
+
+    /// Mode for the global flags: `-u` forces Ultra, otherwise by verbosity.
+    pub fn resolve(verbosity: u8) -> Self {
+        if ULTRA_COMPACT.get().copied().unwrap_or(false) {
+            FormatMode::Ultra
+        } else {
+            FormatMode::from_verbosity(verbosity)
+        }
+    }
 }
 
 /// Trait for formatting canonical types into token-efficient strings
@@ -50,7 +67,16 @@ impl TokenFormatter for TestResult {
         if !self.failures.is_empty() {
             lines.push(String::new());
             for (idx, failure) in self.failures.iter().enumerate().take(5) {
-                lines.push(format!("{}. {}", idx + 1, failure.test_name));
+                if failure.file_path.is_empty() {
+                    lines.push(format!("{}. {}", idx + 1, failure.test_name));
+                } else {
+                    lines.push(format!(
+                        "{}. {} ({})",
+                        idx + 1,
+                        failure.test_name,
+                        failure.file_path
+                    ));
+                }
                 let error_preview: String = failure
                     .error_message
                     .lines()
@@ -133,7 +159,7 @@ impl TokenFormatter for LintResult {
             }
 
             let mut rules: Vec<_> = by_rule.iter().collect();
-            rules.sort_by_key(|(_, issues)| std::cmp::Reverse(issues.len()));
+            rules.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(b.0)));
 
             lines.push(String::new());
             for (rule, issues) in rules.iter().take(5) {
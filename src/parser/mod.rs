@@ -7,11 +7,17 @@
 //!
 //! The three-tier system ensures RTK never returns false data silently.
 
+pub mod delta;
+pub mod diagnostic;
+pub mod envelope;
 pub mod error;
 pub mod formatter;
 pub mod types;
 
-pub use formatter::{FormatMode, TokenFormatter};
+pub use delta::{delta_mode, render_delta, set_delta_mode};
+pub use diagnostic::{compact_path, is_capped, render_all_diagnostics, render_diagnostics};
+pub use envelope::{json_mode, render_json, set_output_format, OutputFormat};
+pub use formatter::{set_ultra_compact, FormatMode, TokenFormatter};
 pub use types::*;
 
 /// Parse result with degradation tier
@@ -116,6 +122,30 @@ pub fn emit_degradation_warning(tool: &str, reason: &str) {
     eprintln!("[RTK:DEGRADED] {} parser: {}", tool, reason);
 }
 
+/// Render a parse result with its TokenFormatter in the mode chosen by `-v`/`-u`.
+///
+/// Degraded parses always print `[RTK:DEGRADED]` to stderr, not only with `-v`:
+/// a partial summary must never pass for a complete one.
+pub fn format_parsed<T: TokenFormatter>(tool: &str, result: ParseResult<T>, verbose: u8) -> String {
+    let mode = FormatMode::resolve(verbose);
+    match result {
+        ParseResult::Full(data) => {
+            if verbose > 0 {
+                eprintln!("{} (Tier 1: Full parse)", tool);
+            }
+            data.format(mode)
+        }
+        ParseResult::Degraded(data, warnings) => {
+            emit_degradation_warning(tool, &warnings.join(", "));
+            data.format(mode)
+        }
+        ParseResult::Passthrough(raw) => {
+            emit_passthrough_warning(tool, "All parsing tiers failed");
+            raw
+        }
+    }
+}
+
 /// Helper to emit passthrough warning
 pub fn emit_passthrough_warning(tool: &str, reason: &str) {
     eprintln!("[RTK:PASSTHROUGH] {} parser: {}", tool, reason);
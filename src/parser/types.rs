@@ -32,19 +32,60 @@ pub struct LintResult {
     pub issues: Vec<LintIssue>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl LintResult {
+    /// Build a result from a flat issue list, counting files and severities.
+    pub fn from_issues(issues: Vec<LintIssue>) -> Self {
+        let files: std::collections::HashSet<&str> =
+            issues.iter().map(|i| i.file_path.as_str()).collect();
+        let errors = issues
+            .iter()
+            .filter(|i| i.severity == LintSeverity::Error)
+            .count();
+        let warnings = issues
+            .iter()
+            .filter(|i| i.severity == LintSeverity::Warning)
+            .count();
+        LintResult {
+            total_files: files.len(),
+            files_with_issues: files.len(),
+            total_issues: issues.len(),
+            errors,
+            warnings,
+            issues,
+        }
+    }
+}
+
+/// One diagnostic from a compiler or linter, rendered by `parser::diagnostic`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LintIssue {
     pub file_path: String,
     pub line: usize,
     pub column: usize,
+    /// End of the reported span (inclusive), when the tool gives one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<usize>,
     pub severity: LintSeverity,
+    /// Rule or error code: `E0308`, `TS2322`, `clippy::len_zero`, `no-unused-vars`
     pub rule_id: String,
     pub message: String,
+    /// Fix proposed by the tool (rustc `help:`, ruff fix, eslint suggestion)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+    /// Related notes: rustc `= note:`, tsc continuation lines, mypy `note:` lines
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+    /// Source code of the reported span, starting at `line` (first lines only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub source_lines: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub enum LintSeverity {
     Error,
+    #[default]
     Warning,
     Info,
 }
@@ -75,6 +116,9 @@ pub struct BuildOutput {
     pub errors: usize,
     pub bundles: Vec<BundleInfo>,
     pub routes: Vec<RouteInfo>,
+    /// Compiler diagnostics (errors and warnings) with locations
+    #[serde(default)]
+    pub issues: Vec<LintIssue>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
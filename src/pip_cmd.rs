@@ -1,3 +1,6 @@
+use crate::parser::{
+    json_mode, render_json, truncate_output, Dependency, DependencyState, OutputParser, ParseResult,
+};
 use crate::tracking;
 use anyhow::{Context, Result};
 use serde::Deserialize;
@@ -11,6 +14,38 @@ struct Package {
     latest_version: Option<String>,
 }
 
+/// Parser for `pip list --format=json` (with or without `--outdated`)
+pub struct PipListParser;
+
+impl OutputParser for PipListParser {
+    type Output = DependencyState;
+
+    fn parse(input: &str) -> ParseResult<DependencyState> {
+        let packages: Vec<Package> = match serde_json::from_str(input) {
+            Ok(p) => p,
+            Err(_) => return ParseResult::Passthrough(truncate_output(input, 500)),
+        };
+        let outdated_count = packages
+            .iter()
+            .filter(|p| p.latest_version.is_some())
+            .count();
+        ParseResult::Full(DependencyState {
+            total_packages: packages.len(),
+            outdated_count,
+            dependencies: packages
+                .into_iter()
+                .map(|p| Dependency {
+                    name: p.name,
+                    current_version: p.version,
+                    latest_version: p.latest_version,
+                    wanted_version: None,
+                    dev_dependency: false,
+                })
+                .collect(),
+        })
+    }
+}
+
 pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
@@ -75,7 +110,15 @@ fn run_list(base_cmd: &str, args: &[String], verbose: u8) -> Result<(String, Str
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let filtered = filter_pip_list(&stdout);
+    let filtered = if json_mode() {
+        render_json::<PipListParser>(
+            &format!("{} pip list {}", base_cmd, args.join(" ")),
+            &stdout,
+            output.status.code().unwrap_or(1),
+        )
+    } else {
+        filter_pip_list(&stdout)
+    };
     println!("{}", filtered);
 
     if !output.status.success() {
@@ -110,7 +153,15 @@ fn run_outdated(base_cmd: &str, args: &[String], verbose: u8) -> Result<(String,
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let filtered = filter_pip_outdated(&stdout);
+    let filtered = if json_mode() {
+        render_json::<PipListParser>(
+            &format!("{} pip list --outdated {}", base_cmd, args.join(" ")),
+            &stdout,
+            output.status.code().unwrap_or(1),
+        )
+    } else {
+        filter_pip_outdated(&stdout)
+    };
     println!("{}", filtered);
 
     if !output.status.success() {
@@ -254,6 +305,7 @@ fn filter_pip_outdated(output: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::envelope::tests::assert_json_snapshot;
 
     #[test]
     fn test_filter_pip_list() {
@@ -299,4 +351,17 @@ mod tests {
         assert!(result.contains("pytest"));
         assert!(result.contains("7.4.0 → 8.0.0"));
     }
+
+    #[test]
+    fn test_json_snapshot_pip_outdated() {
+        let actual = render_json::<PipListParser>(
+            "pip list --outdated",
+            include_str!("../tests/fixtures/json_output/pip_outdated.input.json"),
+            0,
+        );
+        assert_json_snapshot(
+            &actual,
+            include_str!("../tests/fixtures/json_output/pip_outdated.expected.json"),
+        );
+    }
 }
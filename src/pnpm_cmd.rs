@@ -6,8 +6,8 @@ use std::ffi::OsString;
 use std::process::Command;
 
 use crate::parser::{
-    emit_degradation_warning, emit_passthrough_warning, truncate_output, Dependency,
-    DependencyState, FormatMode, OutputParser, ParseResult, TokenFormatter,
+    format_parsed, json_mode, render_json, truncate_output, Dependency, DependencyState,
+    OutputParser, ParseResult,
 };
 
 /// pnpm list JSON output structure
@@ -313,26 +313,14 @@ fn run_list(depth: usize, args: &[String], verbose: u8) -> Result<()> {
     let stdout = String::from_utf8_lossy(&output.stdout);
 
     // Parse output using PnpmListParser
-    let parse_result = PnpmListParser::parse(&stdout);
-    let mode = FormatMode::from_verbosity(verbose);
-
-    let filtered = match parse_result {
-        ParseResult::Full(data) => {
-            if verbose > 0 {
-                eprintln!("pnpm list (Tier 1: Full JSON parse)");
-            }
-            data.format(mode)
-        }
-        ParseResult::Degraded(data, warnings) => {
-            if verbose > 0 {
-                emit_degradation_warning("pnpm list", &warnings.join(", "));
-            }
-            data.format(mode)
-        }
-        ParseResult::Passthrough(raw) => {
-            emit_passthrough_warning("pnpm list", "All parsing tiers failed");
-            raw
-        }
+    let filtered = if json_mode() {
+        render_json::<PnpmListParser>(
+            &format!("pnpm list --depth={} {}", depth, args.join(" ")),
+            &stdout,
+            0,
+        )
+    } else {
+        format_parsed("pnpm list", PnpmListParser::parse(&stdout), verbose)
     };
 
     println!("{}", filtered);
@@ -365,26 +353,14 @@ fn run_outdated(args: &[String], verbose: u8) -> Result<()> {
     let combined = format!("{}{}", stdout, stderr);
 
     // Parse output using PnpmOutdatedParser
-    let parse_result = PnpmOutdatedParser::parse(&stdout);
-    let mode = FormatMode::from_verbosity(verbose);
-
-    let filtered = match parse_result {
-        ParseResult::Full(data) => {
-            if verbose > 0 {
-                eprintln!("pnpm outdated (Tier 1: Full JSON parse)");
-            }
-            data.format(mode)
-        }
-        ParseResult::Degraded(data, warnings) => {
-            if verbose > 0 {
-                emit_degradation_warning("pnpm outdated", &warnings.join(", "));
-            }
-            data.format(mode)
-        }
-        ParseResult::Passthrough(raw) => {
-            emit_passthrough_warning("pnpm outdated", "All parsing tiers failed");
-            raw
-        }
+    let filtered = if json_mode() {
+        render_json::<PnpmOutdatedParser>(
+            &format!("pnpm outdated {}", args.join(" ")),
+            &stdout,
+            output.status.code().unwrap_or(1),
+        )
+    } else {
+        format_parsed("pnpm outdated", PnpmOutdatedParser::parse(&stdout), verbose)
     };
 
     if filtered.trim().is_empty() {
@@ -515,6 +491,7 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::envelope::tests::assert_json_snapshot;
 
     #[test]
     fn test_pnpm_list_parser_json() {
@@ -570,4 +547,17 @@ mod tests {
         let _args: Vec<OsString> = vec![OsString::from("help")];
         // Compile-time verification that the function exists with correct signature
     }
+
+    #[test]
+    fn test_json_snapshot_pnpm_outdated() {
+        let actual = render_json::<PnpmOutdatedParser>(
+            "pnpm outdated",
+            include_str!("../tests/fixtures/json_output/pnpm_outdated.input.json"),
+            1,
+        );
+        assert_json_snapshot(
+            &actual,
+            include_str!("../tests/fixtures/json_output/pnpm_outdated.expected.json"),
+        );
+    }
 }
@@ -1,16 +1,12 @@
+use crate::parser::{
+    format_parsed, render_delta, render_json, truncate_output, OutputParser, ParseResult,
+    TestFailure, TestResult,
+};
 use crate::tracking;
-use crate::utils::truncate;
 use anyhow::{Context, Result};
+use regex::Regex;
 use std::process::Command;
 
-#[derive(Debug, PartialEq)]
-enum ParseState {
-    Header,
-    TestProgress,
-    Failures,
-    Summary,
-}
-
 pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
@@ -51,12 +47,22 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let filtered = filter_pytest_output(&stdout);
-
     let exit_code = output
         .status
         .code()
         .unwrap_or(if output.status.success() { 0 } else { 1 });
+    let filtered = if crate::parser::json_mode() {
+        render_json::<PytestParser>(&format!("pytest {}", args.join(" ")), &stdout, exit_code)
+    } else if crate::parser::delta_mode() {
+        render_delta::<PytestParser>(
+            "pytest",
+            &format!("pytest {}", args.join(" ")),
+            &stdout,
+            || format_parsed("pytest", PytestParser::parse(&stdout), verbose),
+        )
+    } else {
+        format_parsed("pytest", PytestParser::parse(&stdout), verbose)
+    };
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "pytest", exit_code) {
         println!("{}\n{}", filtered, hint);
     } else {
@@ -83,212 +89,166 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     Ok(())
 }
 
-/// Check if a command exists in PATH
-fn which_command(cmd: &str) -> Option<String> {
-    Command::new("which")
-        .arg(cmd)
-        .output()
-        .ok()
-        .filter(|o| o.status.success())
-        .and_then(|o| String::from_utf8(o.stdout).ok())
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-}
+/// Parser for pytest `-q --tb=short` text output
+pub struct PytestParser;
 
-/// Parse pytest output using state machine
-fn filter_pytest_output(output: &str) -> String {
-    let mut state = ParseState::Header;
-    let mut test_files: Vec<String> = Vec::new();
-    let mut failures: Vec<String> = Vec::new();
-    let mut current_failure: Vec<String> = Vec::new();
-    let mut summary_line = String::new();
-
-    for line in output.lines() {
-        let trimmed = line.trim();
-
-        // State transitions
-        if trimmed.starts_with("===") && trimmed.contains("test session starts") {
-            state = ParseState::Header;
-            continue;
-        } else if trimmed.starts_with("===") && trimmed.contains("FAILURES") {
-            state = ParseState::Failures;
-            continue;
-        } else if trimmed.starts_with("===") && trimmed.contains("short test summary") {
-            state = ParseState::Summary;
-            // Save current failure if any
-            if !current_failure.is_empty() {
-                failures.push(current_failure.join("\n"));
-                current_failure.clear();
-            }
-            continue;
-        } else if trimmed.starts_with("===")
-            && (trimmed.contains("passed") || trimmed.contains("failed"))
-        {
-            summary_line = trimmed.to_string();
-            continue;
+impl OutputParser for PytestParser {
+    type Output = TestResult;
+
+    fn parse(input: &str) -> ParseResult<TestResult> {
+        lazy_static::lazy_static! {
+            // "=== 4 passed, 1 failed in 0.50s ===" or (-q) "4 passed, 1 failed in 0.50s"
+            static ref SUMMARY_RE: Regex =
+                Regex::new(r"^=*\s*(\d+ \w+(?:, \d+ \w+)*) in ([\d.]+)s").unwrap();
+            static ref NO_TESTS_RE: Regex = Regex::new(r"^=*\s*no tests ran in ([\d.]+)s").unwrap();
+            static ref LOCATION_RE: Regex = Regex::new(r"^(\S+\.py:\d+): ").unwrap();
         }
 
-        // Process based on state
-        match state {
-            ParseState::Header => {
-                if trimmed.starts_with("collected") {
-                    state = ParseState::TestProgress;
-                }
-            }
-            ParseState::TestProgress => {
-                // Lines like "tests/test_foo.py ....  [ 40%]"
-                if !trimmed.is_empty()
-                    && !trimmed.starts_with("===")
-                    && (trimmed.contains(".py") || trimmed.contains("%]"))
-                {
-                    test_files.push(trimmed.to_string());
-                }
-            }
-            ParseState::Failures => {
-                // Collect failure details
-                if trimmed.starts_with("___") {
-                    // New failure section
-                    if !current_failure.is_empty() {
-                        failures.push(current_failure.join("\n"));
-                        current_failure.clear();
+        let mut summary: Option<(usize, usize, usize, u64)> = None;
+        let mut failures: Vec<TestFailure> = Vec::new();
+        let mut blocks: Vec<(String, Vec<String>)> = Vec::new();
+        let mut in_failures = false;
+
+        for line in input.lines() {
+            let trimmed = line.trim();
+
+            if let Some(caps) = SUMMARY_RE.captures(trimmed) {
+                let (mut passed, mut failed, mut skipped) = (0, 0, 0);
+                for part in caps[1].split(", ") {
+                    let (n, kind) = part.split_once(' ').unwrap_or(("0", ""));
+                    let n: usize = n.parse().unwrap_or(0);
+                    match kind {
+                        "passed" => passed += n,
+                        "failed" | "error" | "errors" => failed += n,
+                        "skipped" | "xfailed" | "deselected" => skipped += n,
+                        _ => {}
                     }
-                    current_failure.push(trimmed.to_string());
-                } else if !trimmed.is_empty() && !trimmed.starts_with("===") {
-                    current_failure.push(trimmed.to_string());
-                }
-            }
-            ParseState::Summary => {
-                // FAILED test lines
-                if trimmed.starts_with("FAILED") || trimmed.starts_with("ERROR") {
-                    failures.push(trimmed.to_string());
                 }
+                let secs: f64 = caps[2].parse().unwrap_or(0.0);
+                summary = Some((passed, failed, skipped, (secs * 1000.0).round() as u64));
+                continue;
             }
-        }
-    }
 
-    // Save last failure if any
-    if !current_failure.is_empty() {
-        failures.push(current_failure.join("\n"));
-    }
-
-    // Build compact output
-    build_pytest_summary(&summary_line, &test_files, &failures)
-}
-
-fn build_pytest_summary(summary: &str, _test_files: &[String], failures: &[String]) -> String {
-    // Parse summary line
-    let (passed, failed, skipped) = parse_summary_line(summary);
-
-    if failed == 0 && passed > 0 {
-        return format!("✓ Pytest: {} passed", passed);
-    }
-
-    if passed == 0 && failed == 0 {
-        return "Pytest: No tests collected".to_string();
-    }
-
-    let mut result = String::new();
-    result.push_str(&format!("Pytest: {} passed, {} failed", passed, failed));
-    if skipped > 0 {
-        result.push_str(&format!(", {} skipped", skipped));
-    }
-    result.push('\n');
-    result.push_str("═══════════════════════════════════════\n");
-
-    if failures.is_empty() {
-        return result.trim().to_string();
-    }
+            if let Some(caps) = NO_TESTS_RE.captures(trimmed) {
+                let secs: f64 = caps[1].parse().unwrap_or(0.0);
+                summary = Some((0, 0, 0, (secs * 1000.0).round() as u64));
+                continue;
+            }
 
-    // Show failures (limit to key information)
-    result.push_str("\nFailures:\n");
-
-    for (i, failure) in failures.iter().take(5).enumerate() {
-        // Extract test name and key error info
-        let lines: Vec<&str> = failure.lines().collect();
-
-        // First line is usually test name (after ___)
-        if let Some(first_line) = lines.first() {
-            if first_line.starts_with("___") {
-                // Extract test name between ___
-                let test_name = first_line.trim_matches('_').trim();
-                result.push_str(&format!("{}. ❌ {}\n", i + 1, test_name));
-            } else if first_line.starts_with("FAILED") {
-                // Summary format: "FAILED tests/test_foo.py::test_bar - AssertionError"
-                let parts: Vec<&str> = first_line.split(" - ").collect();
-                if let Some(test_path) = parts.first() {
-                    let test_name = test_path.trim_start_matches("FAILED ");
-                    result.push_str(&format!("{}. ❌ {}\n", i + 1, test_name));
-                }
-                if parts.len() > 1 {
-                    result.push_str(&format!("     {}\n", truncate(parts[1], 100)));
-                }
+            if trimmed.starts_with("===") {
+                in_failures = trimmed.contains("FAILURES") || trimmed.contains("ERRORS");
                 continue;
             }
-        }
 
-        // Show relevant error lines (assertions, errors, file locations)
-        let mut relevant_lines = 0;
-        for line in &lines[1..] {
-            let line_lower = line.to_lowercase();
-            let is_relevant = line.trim().starts_with('>')
-                || line.trim().starts_with('E')
-                || line_lower.contains("assert")
-                || line_lower.contains("error")
-                || line.contains(".py:");
-
-            if is_relevant && relevant_lines < 3 {
-                result.push_str(&format!("     {}\n", truncate(line, 100)));
-                relevant_lines += 1;
+            if in_failures && trimmed.starts_with("___") {
+                let name = trimmed.trim_matches('_').trim().to_string();
+                blocks.push((name, Vec::new()));
+            } else if in_failures {
+                if let Some((_, lines)) = blocks.last_mut() {
+                    lines.push(trimmed.to_string());
+                }
             }
-        }
 
-        if i < failures.len() - 1 {
-            result.push('\n');
+            // "FAILED tests/test_foo.py::test_bar - AssertionError: boom"
+            let entry = trimmed
+                .strip_prefix("FAILED ")
+                .or_else(|| trimmed.strip_prefix("ERROR "));
+            if let Some(entry) = entry {
+                let (id, message) = entry.split_once(" - ").unwrap_or((entry, ""));
+                let (file, name) = id.split_once("::").unwrap_or(("", id));
+                failures.push(TestFailure {
+                    test_name: name.to_string(),
+                    file_path: file.to_string(),
+                    error_message: message.to_string(),
+                    stack_trace: None,
+                });
+            }
         }
-    }
 
-    if failures.len() > 5 {
-        result.push_str(&format!("\n... +{} more failures\n", failures.len() - 5));
-    }
-
-    result.trim().to_string()
-}
-
-fn parse_summary_line(summary: &str) -> (usize, usize, usize) {
-    let mut passed = 0;
-    let mut failed = 0;
-    let mut skipped = 0;
-
-    // Parse lines like "=== 4 passed, 1 failed in 0.50s ==="
-    let parts: Vec<&str> = summary.split(',').collect();
-
-    for part in parts {
-        let words: Vec<&str> = part.split_whitespace().collect();
-        for (i, word) in words.iter().enumerate() {
-            if i > 0 {
-                if word.contains("passed") {
-                    if let Ok(n) = words[i - 1].parse::<usize>() {
-                        passed = n;
+        // Enrich summary entries with the traceback blocks (or use blocks alone with -rN)
+        for (name, lines) in blocks {
+            let location = lines
+                .iter()
+                .find_map(|l| LOCATION_RE.captures(l).map(|c| c[1].to_string()));
+            let errors: Vec<&str> = lines
+                .iter()
+                .filter_map(|l| l.strip_prefix("E "))
+                .map(|l| l.trim())
+                .collect();
+            let trace = Some(lines.join("\n").trim().to_string()).filter(|t| !t.is_empty());
+
+            match failures.iter_mut().find(|f| f.test_name == name) {
+                Some(f) => {
+                    if let Some(loc) = location {
+                        f.file_path = loc;
                     }
-                } else if word.contains("failed") {
-                    if let Ok(n) = words[i - 1].parse::<usize>() {
-                        failed = n;
-                    }
-                } else if word.contains("skipped") {
-                    if let Ok(n) = words[i - 1].parse::<usize>() {
-                        skipped = n;
+                    if !errors.is_empty() {
+                        f.error_message = errors.join("\n");
                     }
+                    f.stack_trace = trace;
+                }
+                None => failures.push(TestFailure {
+                    test_name: name,
+                    file_path: location.unwrap_or_default(),
+                    error_message: errors.join("\n"),
+                    stack_trace: trace,
+                }),
+            }
+        }
+
+        match summary {
+            Some((passed, failed, skipped, duration_ms)) => {
+                let result = TestResult {
+                    total: passed + failed + skipped,
+                    passed,
+                    failed,
+                    skipped,
+                    duration_ms: Some(duration_ms),
+                    failures,
+                };
+                if result.failures.len() < failed {
+                    let warning = format!(
+                        "{} tests failed but only {} failure details were found",
+                        failed,
+                        result.failures.len()
+                    );
+                    return ParseResult::Degraded(result, vec![warning]);
                 }
+                ParseResult::Full(result)
             }
+            None if !failures.is_empty() => {
+                let result = TestResult {
+                    total: failures.len(),
+                    passed: 0,
+                    failed: failures.len(),
+                    skipped: 0,
+                    duration_ms: None,
+                    failures,
+                };
+                ParseResult::Degraded(result, vec!["no pytest summary line found".to_string()])
+            }
+            None => ParseResult::Passthrough(truncate_output(input, 500)),
         }
     }
+}
 
-    (passed, failed, skipped)
+/// Check if a command exists in PATH
+fn which_command(cmd: &str) -> Option<String> {
+    Command::new("which")
+        .arg(cmd)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::envelope::tests::assert_json_snapshot;
+    use crate::parser::{FormatMode, TokenFormatter};
 
     #[test]
     fn test_filter_pytest_all_pass() {
@@ -300,9 +260,14 @@ tests/test_foo.py .....                                            [100%]
 
 === 5 passed in 0.50s ==="#;
 
-        let result = filter_pytest_output(output);
-        assert!(result.contains("✓ Pytest"));
-        assert!(result.contains("5 passed"));
+        let result = PytestParser::parse(output);
+        assert_eq!(result.tier(), 1);
+        let formatted = format_parsed("pytest", result, 0);
+        assert!(
+            formatted.starts_with("PASS (5) FAIL (0)"),
+            "got: {}",
+            formatted
+        );
     }
 
     #[test]
@@ -325,10 +290,22 @@ tests/test_foo.py:10: AssertionError
 FAILED tests/test_foo.py::test_something - assert False
 === 4 passed, 1 failed in 0.50s ==="#;
 
-        let result = filter_pytest_output(output);
-        assert!(result.contains("4 passed, 1 failed"));
-        assert!(result.contains("test_something"));
-        assert!(result.contains("assert False"));
+        let data = PytestParser::parse(output).unwrap();
+        assert_eq!((data.passed, data.failed), (4, 1));
+        assert_eq!(data.failures[0].file_path, "tests/test_foo.py:10");
+
+        let formatted = data.format(FormatMode::Compact);
+        assert!(
+            formatted.contains("PASS (4) FAIL (1)"),
+            "got: {}",
+            formatted
+        );
+        assert!(
+            formatted.contains("1. test_something"),
+            "got: {}",
+            formatted
+        );
+        assert!(formatted.contains("assert False"), "got: {}", formatted);
     }
 
     #[test]
@@ -351,11 +328,19 @@ FAILED tests/test_foo.py::test_two - ValueError: invalid value
 FAILED tests/test_foo.py::test_three - KeyError
 === 3 failed in 0.20s ==="#;
 
-        let result = filter_pytest_output(output);
-        assert!(result.contains("3 failed"));
-        assert!(result.contains("test_one"));
-        assert!(result.contains("test_two"));
-        assert!(result.contains("expected 5"));
+        let result = PytestParser::parse(output);
+        assert_eq!(result.tier(), 1);
+        let data = result.unwrap();
+        assert_eq!(data.failed, 3);
+        assert_eq!(data.failures.len(), 3);
+        assert_eq!(data.failures[0].error_message, "AssertionError: expected 5");
+        // No traceback block: message comes from the short summary line
+        assert_eq!(data.failures[2].error_message, "KeyError");
+
+        let formatted = data.format(FormatMode::Compact);
+        assert!(formatted.contains("test_one"));
+        assert!(formatted.contains("test_two"));
+        assert!(formatted.contains("expected 5"));
     }
 
     #[test]
@@ -365,20 +350,50 @@ collected 0 items
 
 === no tests ran in 0.00s ==="#;
 
-        let result = filter_pytest_output(output);
-        assert!(result.contains("No tests collected"));
+        let result = PytestParser::parse(output);
+        assert_eq!(result.tier(), 1);
+        assert_eq!(result.unwrap().total, 0);
     }
 
     #[test]
-    fn test_parse_summary_line() {
-        assert_eq!(parse_summary_line("=== 5 passed in 0.50s ==="), (5, 0, 0));
+    fn test_pytest_parser_summary_counts() {
+        let counts = |line: &str| {
+            let data = PytestParser::parse(line).unwrap();
+            (data.passed, data.failed, data.skipped)
+        };
+        assert_eq!(counts("=== 5 passed in 0.50s ==="), (5, 0, 0));
+        assert_eq!(counts("=== 4 passed, 1 failed in 0.50s ==="), (4, 1, 0));
         assert_eq!(
-            parse_summary_line("=== 4 passed, 1 failed in 0.50s ==="),
-            (4, 1, 0)
-        );
-        assert_eq!(
-            parse_summary_line("=== 3 passed, 1 failed, 2 skipped in 1.0s ==="),
+            counts("=== 3 passed, 1 failed, 2 skipped in 1.0s ==="),
             (3, 1, 2)
         );
+        // -q drops the ===
+        assert_eq!(counts("2 passed, 1 xfailed in 0.10s"), (2, 0, 1));
+    }
+
+    #[test]
+    fn test_pytest_parser_missing_details_degrades() {
+        let result = PytestParser::parse("..F\n1 failed, 2 passed in 0.10s\n");
+        assert_eq!(result.tier(), 2);
+        assert!(!result.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_pytest_parser_collection_error_passthrough() {
+        let result = PytestParser::parse("ImportError while loading conftest '/x/conftest.py'.\n");
+        assert_eq!(result.tier(), 3);
+    }
+
+    #[test]
+    fn test_json_snapshot_pytest() {
+        let actual = render_json::<PytestParser>(
+            "pytest",
+            include_str!("../tests/fixtures/json_output/pytest.input.txt"),
+            1,
+        );
+        assert_json_snapshot(
+            &actual,
+            include_str!("../tests/fixtures/json_output/pytest.expected.json"),
+        );
     }
 }
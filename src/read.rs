@@ -1,69 +1,455 @@
 use crate::filter::{self, FilterLevel, Language};
+use crate::ls::human_size;
+use crate::outline;
 use crate::tracking;
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
 use std::fs;
-use std::path::Path;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+/// Whole files bigger than this are summarized, not printed (ranges are still read)
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+/// Leading bytes searched for a NUL to tell binary files from text
+const BINARY_SNIFF_BYTES: usize = 8000;
+/// Fewest lines a file gets from a shared `--max-lines` budget
+const MIN_LINES_PER_FILE: usize = 3;
+/// Comment starts that can make up a license header
+const HEADER_COMMENT_PREFIXES: &[&str] = &["//", "/*", "*", "#", "--", "<!--"];
+
+/// Lines `start..=end` of a file, 1-based; no `end` reads to the end of the file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineRange {
+    pub start: usize,
+    pub end: Option<usize>,
+}
+
+/// One file to read, from an argument like `src/a.rs`, `src/a.rs:40-90` or `src/a.rs:40-`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSpec {
+    pub path: PathBuf,
+    pub range: Option<LineRange>,
+}
+
+impl FileSpec {
+    pub fn parse(arg: &str) -> Result<Self> {
+        // A file whose name really ends in `:12` wins over the range syntax
+        if let Some((path, range)) = arg.rsplit_once(':') {
+            if !Path::new(arg).exists() {
+                if let Some(range) = parse_range(range) {
+                    if range.start == 0 || range.end.is_some_and(|end| end < range.start) {
+                        bail!("invalid line range in {} (lines start at 1)", arg);
+                    }
+                    return Ok(FileSpec {
+                        path: PathBuf::from(path),
+                        range: Some(range),
+                    });
+                }
+            }
+        }
+        Ok(FileSpec {
+            path: PathBuf::from(arg),
+            range: None,
+        })
+    }
+
+    fn label(&self) -> String {
+        match self.range {
+            Some(LineRange {
+                start,
+                end: Some(end),
+            }) if end == start => format!("{}:{}", self.path.display(), start),
+            Some(LineRange {
+                start,
+                end: Some(end),
+            }) => format!("{}:{}-{}", self.path.display(), start, end),
+            Some(LineRange { start, end: None }) => {
+                format!("{}:{}-", self.path.display(), start)
+            }
+            None => self.path.display().to_string(),
+        }
+    }
+}
+
+/// `40-90`, `40-` or `40` (that line alone)
+fn parse_range(s: &str) -> Option<LineRange> {
+    let (start, end) = match s.split_once('-') {
+        Some((start, "")) => (start.parse().ok()?, None),
+        Some((start, end)) => (start.parse().ok()?, Some(end.parse().ok()?)),
+        None => {
+            let line = s.parse().ok()?;
+            (line, Some(line))
+        }
+    };
+    Some(LineRange { start, end })
+}
+
+fn is_glob(arg: &str) -> bool {
+    arg.contains(['*', '?', '['])
+}
+
+/// Turn `rtk read` arguments into files; globs honour .gitignore and expand sorted
+pub fn expand(args: &[String]) -> Result<Vec<FileSpec>> {
+    let mut specs = Vec::new();
+    for arg in args {
+        if !is_glob(arg) || Path::new(arg).exists() {
+            specs.push(FileSpec::parse(arg)?);
+            continue;
+        }
+        let matches = glob_files(arg)?;
+        if matches.is_empty() {
+            bail!("no files match {}", arg);
+        }
+        specs.extend(
+            matches
+                .into_iter()
+                .map(|path| FileSpec { path, range: None }),
+        );
+    }
+    Ok(specs)
+}
+
+/// Files matching `pattern`, walking from its literal directory prefix
+fn glob_files(pattern: &str) -> Result<Vec<PathBuf>> {
+    let mut root = PathBuf::new();
+    let mut rest: Vec<String> = Vec::new();
+    for component in Path::new(pattern).components() {
+        let part = component.as_os_str().to_string_lossy();
+        if rest.is_empty() && !is_glob(&part) {
+            root.push(component);
+        } else if component != Component::CurDir {
+            rest.push(part.into_owned());
+        }
+    }
+    let implicit_root = root.as_os_str().is_empty();
+    if implicit_root {
+        root.push(".");
+    }
+
+    // Anchored at the root, so `src/*.rs` doesn't reach into src/bin/
+    let mut overrides = OverrideBuilder::new(&root);
+    overrides
+        .add(&format!("/{}", rest.join("/")))
+        .with_context(|| format!("invalid glob: {}", pattern))?;
+    let walker = WalkBuilder::new(&root)
+        .overrides(overrides.build()?)
+        .build();
+
+    let mut files: Vec<PathBuf> = walker
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(|entry| {
+            let path = entry.into_path();
+            match path.strip_prefix(".") {
+                Ok(relative) if implicit_root => relative.to_path_buf(),
+                _ => path,
+            }
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// A file's content, or why it isn't printed
+enum Loaded {
+    Text(String),
+    Binary(u64),
+    TooLarge(u64),
+}
+
+fn load(spec: &FileSpec) -> Result<Loaded> {
+    let context = || format!("Failed to read file: {}", spec.path.display());
+    let size = fs::metadata(&spec.path).with_context(context)?.len();
+    if size > MAX_FILE_BYTES && spec.range.is_none() {
+        let mut head = Vec::with_capacity(BINARY_SNIFF_BYTES);
+        fs::File::open(&spec.path)
+            .and_then(|f| f.take(BINARY_SNIFF_BYTES as u64).read_to_end(&mut head))
+            .with_context(context)?;
+        return Ok(if head.contains(&0) {
+            Loaded::Binary(size)
+        } else {
+            Loaded::TooLarge(size)
+        });
+    }
+    let bytes = fs::read(&spec.path).with_context(context)?;
+    if bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0) {
+        return Ok(Loaded::Binary(size));
+    }
+    Ok(match String::from_utf8(bytes) {
+        Ok(text) => Loaded::Text(text),
+        Err(_) => Loaded::Binary(size),
+    })
+}
+
+fn language_of(path: &Path) -> Language {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(Language::from_extension)
+        .unwrap_or(Language::Unknown)
+}
+
+/// Leading comment block of `content` when it is a license or copyright notice:
+/// (its text, how many lines it spans including trailing blanks)
+fn license_header(content: &str) -> Option<(String, usize)> {
+    let lines = content
+        .lines()
+        .take_while(|line| {
+            let t = line.trim();
+            t.is_empty() || HEADER_COMMENT_PREFIXES.iter().any(|p| t.starts_with(p))
+        })
+        .count();
+    let block = content.lines().take(lines).collect::<Vec<_>>().join("\n");
+    let lower = block.to_lowercase();
+    (lower.contains("license") || lower.contains("copyright"))
+        .then(|| (block.trim().to_string(), lines))
+}
+
+/// Split `budget` lines across files: short files keep every line, the longer ones
+/// share what's left equally
+fn share_budget(lengths: &[usize], budget: usize) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..lengths.len()).collect();
+    order.sort_by_key(|&i| lengths[i]);
+    let mut shares = vec![0; lengths.len()];
+    let mut left = budget;
+    for (n, &i) in order.iter().enumerate() {
+        let fair = left / (lengths.len() - n);
+        shares[i] = lengths[i].min(fair.max(MIN_LINES_PER_FILE));
+        left = left.saturating_sub(shares[i]);
+    }
+    shares
+}
+
+/// One file of the output
+struct Section {
+    /// Header line; None for a lone text file, which prints as before
+    header: Option<String>,
+    body: String,
+    lang: Language,
+    /// Line number of the body's first line, for `-n`
+    first_line: usize,
+}
+
+impl Section {
+    /// A file of a multi-file read that couldn't be printed
+    fn error(spec: &FileSpec, lang: Language, message: &str) -> Self {
+        Section {
+            header: Some(format!("📄 {} (error: {})", spec.label(), message)),
+            body: String::new(),
+            lang,
+            first_line: 1,
+        }
+    }
+}
 
 pub fn run(
-    file: &Path,
+    args: &[String],
     level: FilterLevel,
     max_lines: Option<usize>,
     line_numbers: bool,
+    symbol: Option<&str>,
     verbose: u8,
 ) -> Result<()> {
     let timer = tracking::TimedExecution::start();
+    let specs = expand(args)?;
 
-    if verbose > 0 {
-        eprintln!("Reading: {} (filter: {})", file.display(), level);
+    if let Some(symbol) = symbol {
+        let [spec] = specs.as_slice() else {
+            bail!("--symbol takes a single file");
+        };
+        if spec.range.is_some() {
+            bail!("--symbol reads the whole file; drop the line range");
+        }
+        return run_symbol(&spec.path, symbol, line_numbers, verbose);
     }
 
-    // Read file content
-    let content = fs::read_to_string(file)
-        .with_context(|| format!("Failed to read file: {}", file.display()))?;
+    let with_headers = specs.len() > 1;
+    let filter = filter::get_filter(level);
+    let mut original = String::new();
+    let mut sections = Vec::new();
+    let mut licenses: Vec<(String, String)> = Vec::new();
+    let mut failed = 0;
 
-    // Detect language from extension
-    let lang = file
-        .extension()
-        .and_then(|e| e.to_str())
-        .map(Language::from_extension)
-        .unwrap_or(Language::Unknown);
+    for spec in &specs {
+        if verbose > 0 {
+            eprintln!("Reading: {} (filter: {})", spec.label(), level);
+        }
+        let lang = language_of(&spec.path);
+        if verbose > 1 {
+            eprintln!("Detected language: {:?}", lang);
+        }
 
-    if verbose > 1 {
-        eprintln!("Detected language: {:?}", lang);
-    }
+        let loaded = match load(spec) {
+            Ok(loaded) => loaded,
+            // One unreadable file doesn't hide the others
+            Err(e) if with_headers => {
+                failed += 1;
+                sections.push(Section::error(spec, lang, &e.root_cause().to_string()));
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let content = match loaded {
+            Loaded::Text(content) => content,
+            Loaded::Binary(size) => {
+                sections.push(Section {
+                    header: Some(format!(
+                        "📄 {} (binary, {})",
+                        spec.label(),
+                        human_size(size)
+                    )),
+                    body: String::new(),
+                    lang,
+                    first_line: 1,
+                });
+                continue;
+            }
+            Loaded::TooLarge(size) => {
+                sections.push(Section {
+                    header: Some(format!(
+                        "📄 {} ({}, too large; read a range like {}:1-200)",
+                        spec.label(),
+                        human_size(size),
+                        spec.path.display()
+                    )),
+                    body: String::new(),
+                    lang,
+                    first_line: 1,
+                });
+                continue;
+            }
+        };
+        original.push_str(&content);
+        let total = content.lines().count();
 
-    // Apply filter
-    let filter = filter::get_filter(level);
-    let mut filtered = filter.filter(&content, &lang);
+        // Ranges are printed verbatim, so their line numbers match the file
+        if let Some(range) = spec.range {
+            if range.start > total {
+                let message = format!("range past end ({} lines)", total);
+                if !with_headers {
+                    bail!("{}: {}", spec.label(), message);
+                }
+                failed += 1;
+                sections.push(Section::error(spec, lang, &message));
+                continue;
+            }
+            let end = range.end.unwrap_or(total).min(total);
+            let body = content
+                .lines()
+                .skip(range.start - 1)
+                .take((end + 1).saturating_sub(range.start))
+                .collect::<Vec<_>>()
+                .join("\n");
+            sections.push(Section {
+                header: with_headers.then(|| format!("📄 {} (of {} lines)", spec.label(), total)),
+                body,
+                lang,
+                first_line: range.start,
+            });
+            continue;
+        }
 
-    if verbose > 0 {
-        let original_lines = content.lines().count();
-        let filtered_lines = filtered.lines().count();
-        let reduction = if original_lines > 0 {
-            ((original_lines - filtered_lines) as f64 / original_lines as f64) * 100.0
-        } else {
-            0.0
-        };
-        eprintln!(
-            "Lines: {} -> {} ({:.1}% reduction)",
-            original_lines, filtered_lines, reduction
-        );
+        let mut text = content.as_str();
+        let mut first_line = 1;
+        let mut header = format!("📄 {} ({} lines)", spec.label(), total);
+        if let Some((block, lines)) = license_header(&content).filter(|_| with_headers) {
+            match licenses.iter().find(|(seen, _)| *seen == block) {
+                Some((_, first)) => {
+                    // Skip the repeated notice; byte offset of the line after it
+                    let offset = content
+                        .split_inclusive('\n')
+                        .take(lines)
+                        .map(str::len)
+                        .sum::<usize>();
+                    text = &content[offset..];
+                    first_line = lines + 1;
+                    header = format!(
+                        "📄 {} ({} lines, license header as in {})",
+                        spec.label(),
+                        total,
+                        first
+                    );
+                }
+                None => licenses.push((block, spec.label())),
+            }
+        }
+
+        let filtered = filter.filter(text, &lang);
+        if verbose > 0 {
+            let original_lines = content.lines().count();
+            let filtered_lines = filtered.lines().count();
+            let reduction = if original_lines > 0 {
+                (original_lines.saturating_sub(filtered_lines) as f64 / original_lines as f64)
+                    * 100.0
+            } else {
+                0.0
+            };
+            eprintln!(
+                "Lines: {} -> {} ({:.1}% reduction)",
+                original_lines, filtered_lines, reduction
+            );
+        }
+        sections.push(Section {
+            header: with_headers.then_some(header),
+            body: filtered,
+            lang,
+            first_line,
+        });
     }
 
-    // Apply smart truncation if max_lines is set
+    // Apply smart truncation if max_lines is set, one budget shared by all files
     if let Some(max) = max_lines {
-        filtered = filter::smart_truncate(&filtered, max, &lang);
+        let lengths: Vec<usize> = sections.iter().map(|s| s.body.lines().count()).collect();
+        for (section, share) in sections.iter_mut().zip(share_budget(&lengths, max)) {
+            section.body = filter::smart_truncate(&section.body, share, &section.lang);
+        }
     }
 
-    let rtk_output = if line_numbers {
-        format_with_line_numbers(&filtered)
-    } else {
-        filtered.clone()
-    };
+    let rtk_output = sections
+        .iter()
+        .map(|section| {
+            let body = if line_numbers {
+                format_with_line_numbers(&section.body, section.first_line)
+            } else {
+                section.body.clone()
+            };
+            match &section.header {
+                Some(header) if body.is_empty() => header.clone(),
+                Some(header) => format!("{}\n{}", header, body.trim_end_matches('\n')),
+                None => body,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
     println!("{}", rtk_output);
+
+    let paths: Vec<String> = specs.iter().map(|s| s.path.display().to_string()).collect();
     timer.track(
-        &format!("cat {}", file.display()),
+        &format!("cat {}", paths.join(" ")),
         "rtk read",
+        &original,
+        &rtk_output,
+    );
+    if failed > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn run_symbol(file: &Path, symbol: &str, line_numbers: bool, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+    if verbose > 0 {
+        eprintln!("Reading: {} (symbol: {})", file.display(), symbol);
+    }
+    let content = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read file: {}", file.display()))?;
+    let lang = language_of(file);
+
+    let rtk_output = extract_symbol(file, &content, &lang, symbol, line_numbers)?;
+    println!("{}", rtk_output);
+    timer.track(
+        &format!("cat {}", file.display()),
+        "rtk read --symbol",
         &content,
         &rtk_output,
     );
@@ -122,7 +508,7 @@ pub fn run_stdin(
     }
 
     let rtk_output = if line_numbers {
-        format_with_line_numbers(&filtered)
+        format_with_line_numbers(&filtered, 1)
     } else {
         filtered.clone()
     };
@@ -132,12 +518,81 @@ pub fn run_stdin(
     Ok(())
 }
 
-fn format_with_line_numbers(content: &str) -> String {
+/// The full source of every item named `symbol`, doc comments included
+fn extract_symbol(
+    file: &Path,
+    content: &str,
+    lang: &Language,
+    symbol: &str,
+    line_numbers: bool,
+) -> Result<String> {
+    let Some(items) = outline::outline(content, lang) else {
+        bail!(
+            "--symbol needs a Rust, TypeScript/JavaScript, Python or Go file, got {}",
+            file.display()
+        );
+    };
+    let found = outline::find_symbol(&items, symbol);
+    if found.is_empty() {
+        // Impl blocks repeat their type's name
+        let mut names: Vec<&str> = Vec::new();
+        for item in &items {
+            if !names.contains(&item.qualified.as_str()) {
+                names.push(&item.qualified);
+            }
+        }
+        let more = if names.len() > 20 { ", ..." } else { "" };
+        names.truncate(20);
+        bail!(
+            "symbol `{}` not found in {}; items: {}{}",
+            symbol,
+            file.display(),
+            names.join(", "),
+            more
+        );
+    }
+
+    let lines: Vec<&str> = content.lines().collect();
+    let width = found
+        .iter()
+        .map(|i| i.end_line)
+        .max()
+        .unwrap_or(0)
+        .to_string()
+        .len();
+    let blocks: Vec<String> = found
+        .iter()
+        .map(|item| {
+            let end = item.end_line.min(lines.len());
+            (item.doc_line..=end)
+                .map(|n| {
+                    if line_numbers {
+                        format!("{:>width$} │ {}", n, lines[n - 1], width = width)
+                    } else {
+                        lines[n - 1].to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect();
+    Ok(blocks.join("\n\n"))
+}
+
+fn format_with_line_numbers(content: &str, first_line: usize) -> String {
     let lines: Vec<&str> = content.lines().collect();
-    let width = lines.len().to_string().len();
+    let width = (first_line + lines.len())
+        .saturating_sub(1)
+        .to_string()
+        .len();
     let mut out = String::new();
     for (i, line) in lines.iter().enumerate() {
-        out.push_str(&format!("{:>width$} │ {}\n", i + 1, line, width = width));
+        out.push_str(&format!(
+            "{:>width$} │ {}\n",
+            first_line + i,
+            line,
+            width = width
+        ));
     }
     out
 }
@@ -160,10 +615,120 @@ fn main() {{
         )?;
 
         // Just verify it doesn't panic
-        run(file.path(), FilterLevel::Minimal, None, false, 0)?;
+        let args = [file.path().display().to_string()];
+        run(&args, FilterLevel::Minimal, None, false, None, 0)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_spec_ranges() {
+        let spec = FileSpec::parse("src/a.rs:40-90").unwrap();
+        assert_eq!(spec.path, PathBuf::from("src/a.rs"));
+        assert_eq!(
+            spec.range,
+            Some(LineRange {
+                start: 40,
+                end: Some(90)
+            })
+        );
+        assert_eq!(FileSpec::parse("a.rs:7-").unwrap().range.unwrap().end, None);
+        assert_eq!(
+            FileSpec::parse("a.rs:7").unwrap().range.unwrap().end,
+            Some(7)
+        );
+        assert_eq!(FileSpec::parse("a.rs").unwrap().range, None);
+        // Not a range: stays part of the path
+        assert_eq!(FileSpec::parse("c:x").unwrap().path, PathBuf::from("c:x"));
+        assert!(FileSpec::parse("a.rs:90-40").is_err());
+        assert!(FileSpec::parse("a.rs:0-3").is_err());
+    }
+
+    #[test]
+    fn test_range_past_end_is_an_error() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("short.rs");
+        fs::write(&path, "fn a() {}\nfn b() {}\nfn c() {}\n")?;
+        let arg = format!("{}:500-600", path.display());
+        let err = run(&[arg], FilterLevel::None, None, false, None, 0).unwrap_err();
+        assert!(err.to_string().ends_with("range past end (3 lines)"));
         Ok(())
     }
 
+    #[test]
+    fn test_glob_expansion_is_anchored_and_sorted() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir(dir.path().join("nested"))?;
+        for name in ["b.rs", "a.rs", "c.txt", "nested/d.rs"] {
+            fs::write(dir.path().join(name), "fn x() {}\n")?;
+        }
+        let root = dir.path().display().to_string();
+        let names = |pattern: &str| -> Result<Vec<String>> {
+            Ok(expand(&[format!("{}/{}", root, pattern)])?
+                .iter()
+                .map(|s| s.path.strip_prefix(&root).unwrap().display().to_string())
+                .collect())
+        };
+        assert_eq!(names("*.rs")?, ["a.rs", "b.rs"]);
+        assert_eq!(names("**/*.rs")?, ["a.rs", "b.rs", "nested/d.rs"]);
+        assert!(names("*.go").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_share_budget() {
+        // Short files keep everything, the rest split what is left
+        assert_eq!(share_budget(&[10, 100, 500], 200), [10, 95, 95]);
+        assert_eq!(share_budget(&[50, 50], 1000), [50, 50]);
+        // Every file keeps a few lines, even when the budget is tiny
+        assert_eq!(share_budget(&[100, 100, 100], 4), [3, 3, 3]);
+    }
+
+    #[test]
+    fn test_license_header() {
+        let licensed = "// Copyright 2024 Acme\n// SPDX-License-Identifier: MIT\n\nfn a() {}\n";
+        let (block, lines) = license_header(licensed).unwrap();
+        assert_eq!(lines, 3);
+        assert!(block.ends_with("MIT"));
+        assert!(license_header("// Just a comment\nfn a() {}\n").is_none());
+    }
+
+    #[test]
+    fn test_load_detects_binary() -> Result<()> {
+        let mut file = NamedTempFile::new()?;
+        file.write_all(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR")?;
+        let spec = FileSpec::parse(&file.path().display().to_string())?;
+        assert!(matches!(load(&spec)?, Loaded::Binary(16)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_line_numbers_start_at_range() {
+        assert_eq!(format_with_line_numbers("a\nb", 9), " 9 │ a\n10 │ b\n");
+    }
+
+    #[test]
+    fn test_extract_symbol_with_docs_and_line_numbers() {
+        let content = "use std::fmt;\n\n/// Doubles\nfn double(x: u32) -> u32 {\n    x * 2\n}\n";
+        let path = Path::new("lib.rs");
+        let body = extract_symbol(path, content, &Language::Rust, "double", false).unwrap();
+        assert_eq!(
+            body,
+            "/// Doubles\nfn double(x: u32) -> u32 {\n    x * 2\n}"
+        );
+        let numbered = extract_symbol(path, content, &Language::Rust, "double", true).unwrap();
+        assert!(numbered.starts_with("3 │ /// Doubles\n4 │ fn double"));
+    }
+
+    #[test]
+    fn test_extract_symbol_errors() {
+        let content = "fn a() {}\nfn b() {}\n";
+        let err = extract_symbol(Path::new("lib.rs"), content, &Language::Rust, "c", false)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(err, "symbol `c` not found in lib.rs; items: a, b");
+        assert!(extract_symbol(Path::new("x.sh"), "f() {}", &Language::Shell, "f", false).is_err());
+    }
+
     #[test]
     fn test_stdin_support_signature() {
         // Test that run_stdin has correct signature and compiles
@@ -0,0 +1,458 @@
+use crate::config::{CommandsConfig, Config};
+use crate::discover::registry::{chain_segments, rewrite_command};
+use anyhow::{Context, Result};
+use std::io::{Read, Write};
+
+/// Outcome of a rewrite decision, mirrored 1:1 in the hook audit log.
+#[derive(Debug, PartialEq)]
+enum Decision {
+    Rewrite(String),
+    Skip(&'static str),
+}
+
+impl Decision {
+    fn action(&self) -> String {
+        match self {
+            Decision::Rewrite(_) => "rewrite".to_string(),
+            Decision::Skip(reason) => format!("skip:{}", reason),
+        }
+    }
+}
+
+/// Decide whether a Bash command line should be rewritten to its rtk equivalent.
+/// Every supported segment of a `&&`/`||`/`;` chain is rewritten on its own; operators,
+/// quoting and redirections between them are copied verbatim.
+fn decide(cmd: &str, commands: &CommandsConfig) -> Decision {
+    if cmd.trim().is_empty() {
+        return Decision::Skip("no_match");
+    }
+    if cmd.contains("<<") {
+        return Decision::Skip("heredoc");
+    }
+
+    let mut out = String::with_capacity(cmd.len() + 16);
+    let mut last = 0;
+    let mut rewrote = false;
+    let mut saw_rtk = false;
+
+    for seg in chain_segments(cmd) {
+        if is_rtk(seg.text) {
+            saw_rtk = true;
+            continue;
+        }
+        // Piped segments feed (or are fed by) another program, and output redirected to
+        // a file never reaches the model: leave both untouched.
+        if seg.is_piped() || redirects_stdout(seg.text) {
+            continue;
+        }
+        // `[commands] disabled` in the config opts a command out of rewriting
+        if commands.is_disabled(seg.text) {
+            continue;
+        }
+        if let Some(rewritten) = rewrite_command(seg.text) {
+            out.push_str(&cmd[last..seg.offset]);
+            out.push_str(&rewritten);
+            last = seg.offset + seg.text.len();
+            rewrote = true;
+        }
+    }
+
+    if rewrote {
+        out.push_str(&cmd[last..]);
+        Decision::Rewrite(out)
+    } else if saw_rtk {
+        Decision::Skip("already_rtk")
+    } else {
+        Decision::Skip("no_match")
+    }
+}
+
+/// First word after any `VAR=value` assignments is `rtk` (or a path to it).
+fn is_rtk(segment: &str) -> bool {
+    let first_word = segment
+        .split_whitespace()
+        .find(|w| !w.contains('='))
+        .unwrap_or("");
+    first_word == "rtk" || first_word.ends_with("/rtk")
+}
+
+/// Whether stdout is redirected to a file (`>`, `>>`, `1>`, `&>`) outside quotes.
+/// Fd duplications like `2>&1` or `>&2` keep output on the terminal and don't count.
+fn redirects_stdout(segment: &str) -> bool {
+    let bytes = segment.as_bytes();
+    let mut in_single = false;
+    let mut in_double = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if !in_single => i += 1,
+            b'\'' if !in_double => in_single = !in_single,
+            b'"' if !in_single => in_double = !in_double,
+            b'>' if !in_single && !in_double => {
+                let prev = if i > 0 { bytes[i - 1] } else { b' ' };
+                let is_dup = bytes.get(i + 1) == Some(&b'&');
+                let is_stdout = !prev.is_ascii_digit() || prev == b'1';
+                if is_stdout && !is_dup && prev != b'>' {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Append one line to the hook audit log (read back by `rtk hook-audit`).
+fn audit(original: &str, decision: &Decision) -> Result<()> {
+    let path = crate::hook_audit_cmd::default_log_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let rewritten = match decision {
+        Decision::Rewrite(r) => r.as_str(),
+        Decision::Skip(_) => "-",
+    };
+    let line = format!(
+        "{} | {} | {} | {}\n",
+        chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ"),
+        decision.action(),
+        original.replace('\n', "\\n"),
+        rewritten.replace('\n', "\\n"),
+    );
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Build the PreToolUse response: original tool_input with only `command` replaced.
+fn hook_response(input: &serde_json::Value, rewritten: &str) -> serde_json::Value {
+    let mut updated = input
+        .get("tool_input")
+        .cloned()
+        .unwrap_or_else(|| serde_json::json!({}));
+    updated["command"] = serde_json::Value::String(rewritten.to_string());
+
+    serde_json::json!({
+        "hookSpecificOutput": {
+            "hookEventName": "PreToolUse",
+            "permissionDecision": "allow",
+            "permissionDecisionReason": "RTK auto-rewrite",
+            "updatedInput": updated,
+        }
+    })
+}
+
+/// Claude Code PreToolUse hook entry point.
+/// Reads the hook JSON on stdin and prints `updatedInput` when the command has an rtk
+/// equivalent. Prints nothing (command runs unchanged) otherwise, including on bad input.
+pub fn run(verbose: u8) -> Result<()> {
+    let mut raw = String::new();
+    std::io::stdin()
+        .read_to_string(&mut raw)
+        .context("Failed to read hook input from stdin")?;
+
+    let input: serde_json::Value = match serde_json::from_str(&raw) {
+        Ok(v) => v,
+        Err(e) => {
+            if verbose > 0 {
+                eprintln!("rtk rewrite: invalid hook JSON: {}", e);
+            }
+            return Ok(());
+        }
+    };
+
+    let cmd = match input
+        .get("tool_input")
+        .and_then(|t| t.get("command"))
+        .and_then(|c| c.as_str())
+    {
+        Some(c) if !c.trim().is_empty() => c,
+        _ => return Ok(()),
+    };
+
+    // A broken config must not block the agent's command: fall back to no opt-outs
+    let commands = Config::load().map(|c| c.commands).unwrap_or_default();
+    let decision = decide(cmd, &commands);
+
+    if std::env::var("RTK_HOOK_AUDIT").as_deref() == Ok("1") {
+        if let Err(e) = audit(cmd, &decision) {
+            if verbose > 0 {
+                eprintln!("rtk rewrite: audit log failed: {}", e);
+            }
+        }
+    }
+
+    if let Decision::Rewrite(rewritten) = &decision {
+        println!("{}", hook_response(&input, rewritten));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewritten(cmd: &str) -> Option<String> {
+        match decide(cmd, &CommandsConfig::default()) {
+            Decision::Rewrite(r) => Some(r),
+            Decision::Skip(_) => None,
+        }
+    }
+
+    /// Ported from hooks/test-rtk-rewrite.sh: (input, expected rewrite or None).
+    const CASES: &[(&str, Option<&str>)] = &[
+        // Existing patterns
+        ("git status", Some("rtk git status")),
+        ("git log --oneline -10", Some("rtk git log --oneline -10")),
+        ("git diff HEAD", Some("rtk git diff HEAD")),
+        ("git show abc123", Some("rtk git show abc123")),
+        ("git add .", Some("rtk git add .")),
+        ("git blame -L 1,20 a.rs", Some("rtk git blame -L 1,20 a.rs")),
+        ("git rebase origin/main", Some("rtk git rebase origin/main")),
+        ("gh pr list", Some("rtk gh pr list")),
+        ("npx playwright test", Some("rtk playwright test")),
+        ("ls -la", Some("rtk ls -la")),
+        (
+            "curl -s https://example.com",
+            Some("rtk curl -s https://example.com"),
+        ),
+        ("cat package.json", Some("rtk read package.json")),
+        ("grep -rn pattern src/", Some("rtk grep -rn pattern src/")),
+        ("rg pattern src/", Some("rtk grep pattern src/")),
+        ("cargo test", Some("rtk cargo test")),
+        ("npx prisma migrate", Some("rtk prisma migrate")),
+        // Env var prefix
+        (
+            "TEST_SESSION_ID=2 npx playwright test --config=foo",
+            Some("TEST_SESSION_ID=2 rtk playwright test --config=foo"),
+        ),
+        (
+            "GIT_PAGER=cat git status",
+            Some("GIT_PAGER=cat rtk git status"),
+        ),
+        (
+            "GIT_PAGER=cat git log --oneline -10",
+            Some("GIT_PAGER=cat rtk git log --oneline -10"),
+        ),
+        (
+            "NODE_ENV=test CI=1 npx vitest run",
+            Some("NODE_ENV=test CI=1 rtk vitest run"),
+        ),
+        ("LANG=C ls -la", Some("LANG=C rtk ls -la")),
+        (
+            "NODE_ENV=test npm run test:e2e",
+            Some("NODE_ENV=test rtk npm test:e2e"),
+        ),
+        (
+            "COMPOSE_PROJECT_NAME=test docker compose up -d",
+            Some("COMPOSE_PROJECT_NAME=test rtk docker compose up -d"),
+        ),
+        // npm, tsc, docker, gh, kubectl
+        ("npm run test:e2e", Some("rtk npm test:e2e")),
+        ("npm run build", Some("rtk npm build")),
+        ("npm test", Some("rtk npm test")),
+        ("vue-tsc -b", Some("rtk tsc -b")),
+        ("npx vue-tsc --noEmit", Some("rtk tsc --noEmit")),
+        ("docker compose up -d", Some("rtk docker compose up -d")),
+        (
+            "docker compose logs postgrest",
+            Some("rtk docker compose logs postgrest"),
+        ),
+        ("docker compose down", Some("rtk docker compose down")),
+        (
+            "docker run --rm postgres",
+            Some("rtk docker run --rm postgres"),
+        ),
+        (
+            "docker exec -it db psql",
+            Some("rtk docker exec -it db psql"),
+        ),
+        (
+            "gh api repos/owner/repo",
+            Some("rtk gh api repos/owner/repo"),
+        ),
+        ("gh release list", Some("rtk gh release list")),
+        (
+            "kubectl describe pod foo",
+            Some("rtk kubectl describe pod foo"),
+        ),
+        (
+            "kubectl apply -f deploy.yaml",
+            Some("rtk kubectl apply -f deploy.yaml"),
+        ),
+        // vitest
+        ("vitest", Some("rtk vitest run")),
+        ("vitest run", Some("rtk vitest run")),
+        (
+            "vitest run --reporter=verbose",
+            Some("rtk vitest run --reporter=verbose"),
+        ),
+        ("npx vitest run", Some("rtk vitest run")),
+        (
+            "pnpm vitest run --coverage",
+            Some("rtk vitest run --coverage"),
+        ),
+        // Python / Go
+        ("pytest -x", Some("rtk pytest -x")),
+        ("python -m pytest tests/", Some("rtk pytest tests/")),
+        ("ruff check .", Some("rtk ruff check .")),
+        ("uv pip list", Some("rtk pip list")),
+        ("go test ./...", Some("rtk go test ./...")),
+        ("golangci-lint run", Some("rtk golangci-lint run")),
+        // head -N → read --max-lines
+        (
+            "head -20 src/main.rs",
+            Some("rtk read src/main.rs --max-lines 20"),
+        ),
+        // Should NOT rewrite
+        ("find . -name '*.ts'", None),
+        ("tree src/", None),
+        ("wget https://example.com/file", None),
+        ("rtk git status", None),
+        ("cat <<'EOF'\nhello\nEOF", None),
+        ("echo hello world", None),
+        ("cd /tmp", None),
+        ("mkdir -p foo/bar", None),
+        ("python3 script.py", None),
+        ("node -e 'console.log(1)'", None),
+        ("tail -f app.log", None),
+        ("gh repo view", None),
+    ];
+
+    #[test]
+    fn test_rewrite_table() {
+        let mut failures = Vec::new();
+        for (input, expected) in CASES {
+            let actual = rewritten(input);
+            if actual.as_deref() != *expected {
+                failures.push(format!(
+                    "{:?}: expected {:?}, got {:?}",
+                    input, expected, actual
+                ));
+            }
+        }
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn test_rewrite_keeps_chain_tail() {
+        assert_eq!(
+            rewritten("git status && echo done"),
+            Some("rtk git status && echo done".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rewrite_every_chain_segment() {
+        assert_eq!(
+            rewritten("cargo fmt && cargo clippy && cargo test"),
+            Some("rtk cargo fmt && rtk cargo clippy && rtk cargo test".to_string())
+        );
+        assert_eq!(
+            rewritten("cd /repo; git status || ls -la"),
+            Some("cd /repo; rtk git status || rtk ls -la".to_string())
+        );
+        assert_eq!(
+            rewritten("rtk git status && cargo test"),
+            Some("rtk git status && rtk cargo test".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rewrite_keeps_quoting_and_redirections() {
+        assert_eq!(
+            rewritten(r#"git commit -m "fix: a && b; c" && cargo test 2>&1"#),
+            Some(r#"rtk git commit -m "fix: a && b; c" && rtk cargo test 2>&1"#.to_string())
+        );
+        assert_eq!(
+            rewritten("NODE_ENV=test npm test  &&  git status"),
+            Some("NODE_ENV=test rtk npm test  &&  rtk git status".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rewrite_skips_piped_segments() {
+        assert_eq!(rewritten("cargo test 2>&1 | tail -5"), None);
+        assert_eq!(rewritten("git log | grep fix"), None);
+        assert_eq!(
+            rewritten("git diff | head -20 && cargo build"),
+            Some("git diff | head -20 && rtk cargo build".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rewrite_skips_file_redirects() {
+        assert_eq!(rewritten("cat a.txt > b.txt"), None);
+        assert_eq!(rewritten("cargo build &> build.log"), None);
+        assert_eq!(rewritten("git diff >> patch.diff"), None);
+        assert_eq!(
+            rewritten("cargo test 2> err.log"),
+            Some("rtk cargo test 2> err.log".to_string())
+        );
+        assert_eq!(
+            rewritten("cargo test >&2"),
+            Some("rtk cargo test >&2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_skip_reasons() {
+        let commands = CommandsConfig::default();
+        assert_eq!(
+            decide("rtk git status", &commands),
+            Decision::Skip("already_rtk")
+        );
+        assert_eq!(
+            decide("/usr/local/bin/rtk ls", &commands),
+            Decision::Skip("already_rtk")
+        );
+        assert_eq!(
+            decide("cat <<'EOF'\nhello\nEOF", &commands),
+            Decision::Skip("heredoc")
+        );
+        assert_eq!(
+            decide("echo hello world", &commands),
+            Decision::Skip("no_match")
+        );
+        assert_eq!(decide("git status", &commands).action(), "rewrite");
+    }
+
+    #[test]
+    fn test_disabled_commands_are_left_alone() {
+        let commands = CommandsConfig {
+            disabled: vec!["cargo test".into()],
+        };
+        assert_eq!(decide("cargo test", &commands), Decision::Skip("no_match"));
+        assert_eq!(
+            decide("cargo test && git status", &commands),
+            Decision::Rewrite("cargo test && rtk git status".to_string())
+        );
+        assert_eq!(
+            decide("cargo build", &commands),
+            Decision::Rewrite("rtk cargo build".to_string())
+        );
+    }
+
+    #[test]
+    fn test_hook_response_preserves_tool_input() {
+        let input = serde_json::json!({
+            "tool_name": "Bash",
+            "tool_input": {"command": "git status", "description": "Show status", "timeout": 5000}
+        });
+        let out = hook_response(&input, "rtk git status");
+        let hso = &out["hookSpecificOutput"];
+        assert_eq!(hso["hookEventName"], "PreToolUse");
+        assert_eq!(hso["permissionDecision"], "allow");
+        assert_eq!(hso["updatedInput"]["command"], "rtk git status");
+        assert_eq!(hso["updatedInput"]["description"], "Show status");
+        assert_eq!(hso["updatedInput"]["timeout"], 5000);
+    }
+}
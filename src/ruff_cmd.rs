@@ -1,8 +1,11 @@
+use crate::parser::{
+    compact_path, render_delta, render_diagnostics, render_json, truncate_output, LintIssue,
+    LintResult, LintSeverity, OutputParser, ParseResult,
+};
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
 use serde::Deserialize;
-use std::collections::HashMap;
 use std::process::Command;
 
 #[derive(Debug, Deserialize)]
@@ -15,6 +18,7 @@ struct RuffLocation {
 struct RuffFix {
     #[allow(dead_code)]
     applicability: Option<String>,
+    message: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -22,7 +26,6 @@ struct RuffDiagnostic {
     code: String,
     message: String,
     location: RuffLocation,
-    #[allow(dead_code)]
     end_location: Option<RuffLocation>,
     filename: String,
     fix: Option<RuffFix>,
@@ -84,8 +87,18 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
-
-    let filtered = if is_check && !stdout.trim().is_empty() {
+    let exit_code = output.status.code().unwrap_or(1);
+
+    let filtered = if is_check && crate::parser::json_mode() {
+        render_json::<RuffCheckParser>(&format!("ruff {}", args.join(" ")), &stdout, exit_code)
+    } else if is_check && !stdout.trim().is_empty() && crate::parser::delta_mode() {
+        render_delta::<RuffCheckParser>(
+            "ruff check",
+            &format!("ruff {}", args.join(" ")),
+            &stdout,
+            || filter_ruff_check_json(&stdout),
+        )
+    } else if is_check && !stdout.trim().is_empty() {
         filter_ruff_check_json(&stdout)
     } else if is_format {
         filter_ruff_format(&raw)
@@ -94,7 +107,11 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         raw.trim().to_string()
     };
 
-    println!("{}", filtered);
+    if let Some(hint) = crate::tee::tee_and_hint_for(&filtered, &raw, "ruff", exit_code) {
+        println!("{}\n{}", filtered, hint);
+    } else {
+        println!("{}", filtered);
+    }
 
     timer.track(
         &format!("ruff {}", args.join(" ")),
@@ -105,112 +122,67 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 
     // Preserve exit code for CI/CD
     if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
+        std::process::exit(exit_code);
     }
 
     Ok(())
 }
 
-/// Filter ruff check JSON output - group by rule and file
-pub fn filter_ruff_check_json(output: &str) -> String {
-    let diagnostics: Result<Vec<RuffDiagnostic>, _> = serde_json::from_str(output);
-
-    let diagnostics = match diagnostics {
-        Ok(d) => d,
-        Err(e) => {
-            // Fallback if JSON parsing fails
-            return format!(
-                "Ruff check (JSON parse failed: {})\n{}",
-                e,
-                truncate(output, 500)
-            );
-        }
-    };
-
-    if diagnostics.is_empty() {
-        return "✓ Ruff: No issues found".to_string();
-    }
-
-    let total_issues = diagnostics.len();
-    let fixable_count = diagnostics.iter().filter(|d| d.fix.is_some()).count();
-
-    // Count unique files
-    let unique_files: std::collections::HashSet<_> =
-        diagnostics.iter().map(|d| &d.filename).collect();
-    let total_files = unique_files.len();
-
-    // Group by rule code
-    let mut by_rule: HashMap<String, usize> = HashMap::new();
-    for diag in &diagnostics {
-        *by_rule.entry(diag.code.clone()).or_insert(0) += 1;
-    }
-
-    // Group by file
-    let mut by_file: HashMap<&str, usize> = HashMap::new();
-    for diag in &diagnostics {
-        *by_file.entry(&diag.filename).or_insert(0) += 1;
-    }
-
-    let mut file_counts: Vec<_> = by_file.iter().collect();
-    file_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-    // Build output
-    let mut result = String::new();
-    result.push_str(&format!(
-        "Ruff: {} issues in {} files",
-        total_issues, total_files
-    ));
-
-    if fixable_count > 0 {
-        result.push_str(&format!(" ({} fixable)", fixable_count));
-    }
-    result.push('\n');
-    result.push_str("═══════════════════════════════════════\n");
-
-    // Show top rules
-    let mut rule_counts: Vec<_> = by_rule.iter().collect();
-    rule_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-    if !rule_counts.is_empty() {
-        result.push_str("Top rules:\n");
-        for (rule, count) in rule_counts.iter().take(10) {
-            result.push_str(&format!("  {} ({}x)\n", rule, count));
-        }
-        result.push('\n');
-    }
+/// Parser for `ruff check --output-format=json` into a canonical LintResult
+pub struct RuffCheckParser;
 
-    // Show top files
-    result.push_str("Top files:\n");
-    for (file, count) in file_counts.iter().take(10) {
-        let short_path = compact_path(file);
-        result.push_str(&format!("  {} ({} issues)\n", short_path, count));
+impl OutputParser for RuffCheckParser {
+    type Output = LintResult;
 
-        // Show top 3 rules in this file
-        let mut file_rules: HashMap<String, usize> = HashMap::new();
-        for diag in diagnostics.iter().filter(|d| &d.filename == *file) {
-            *file_rules.entry(diag.code.clone()).or_insert(0) += 1;
+    fn parse(input: &str) -> ParseResult<LintResult> {
+        if input.trim().is_empty() {
+            return ParseResult::Full(LintResult::from_issues(vec![]));
         }
-
-        let mut file_rule_counts: Vec<_> = file_rules.iter().collect();
-        file_rule_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-        for (rule, count) in file_rule_counts.iter().take(3) {
-            result.push_str(&format!("    {} ({})\n", rule, count));
+        match serde_json::from_str::<Vec<RuffDiagnostic>>(input) {
+            Ok(diagnostics) => ParseResult::Full(LintResult::from_issues(
+                diagnostics
+                    .into_iter()
+                    .map(|d| LintIssue {
+                        file_path: d.filename,
+                        line: d.location.row,
+                        column: d.location.column,
+                        // ruff has no severity levels: every violation fails the check
+                        severity: LintSeverity::Error,
+                        end_line: d.end_location.as_ref().map(|l| l.row),
+                        end_column: d.end_location.as_ref().map(|l| l.column),
+                        rule_id: d.code,
+                        message: d.message,
+                        suggestion: d.fix.map(|f| {
+                            f.message
+                                .unwrap_or_else(|| "fixable with `ruff check --fix`".to_string())
+                        }),
+                        ..LintIssue::default()
+                    })
+                    .collect(),
+            )),
+            Err(_) => ParseResult::Passthrough(truncate_output(input, 500)),
         }
     }
+}
 
-    if file_counts.len() > 10 {
-        result.push_str(&format!("\n... +{} more files\n", file_counts.len() - 10));
-    }
+/// Filter ruff check JSON output - issues grouped by file
+pub fn filter_ruff_check_json(output: &str) -> String {
+    let issues = match RuffCheckParser::parse(output) {
+        ParseResult::Full(result) | ParseResult::Degraded(result, _) => result.issues,
+        ParseResult::Passthrough(_) => {
+            return format!("Ruff check (JSON parse failed)\n{}", truncate(output, 500));
+        }
+    };
 
-    if fixable_count > 0 {
+    let mut result = render_diagnostics("Ruff", &issues);
+    let fixable = issues.iter().filter(|i| i.suggestion.is_some()).count();
+    if fixable > 0 {
         result.push_str(&format!(
-            "\n💡 Run `ruff check --fix` to auto-fix {} issues\n",
-            fixable_count
+            "\n\n💡 Run `ruff check --fix` to auto-fix {} issues",
+            fixable
         ));
     }
-
-    result.trim().to_string()
+    result
 }
 
 /// Filter ruff format output - show files that need formatting
@@ -299,26 +271,10 @@ pub fn filter_ruff_format(output: &str) -> String {
     result.trim().to_string()
 }
 
-/// Compact file path (remove common prefixes)
-fn compact_path(path: &str) -> String {
-    let path = path.replace('\\', "/");
-
-    if let Some(pos) = path.rfind("/src/") {
-        format!("src/{}", &path[pos + 5..])
-    } else if let Some(pos) = path.rfind("/lib/") {
-        format!("lib/{}", &path[pos + 5..])
-    } else if let Some(pos) = path.rfind("/tests/") {
-        format!("tests/{}", &path[pos + 7..])
-    } else if let Some(pos) = path.rfind('/') {
-        path[pos + 1..].to_string()
-    } else {
-        path
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::envelope::tests::assert_json_snapshot;
 
     #[test]
     fn test_filter_ruff_check_no_issues() {
@@ -357,13 +313,10 @@ mod tests {
   }
 ]"#;
         let result = filter_ruff_check_json(output);
-        assert!(result.contains("3 issues"));
-        assert!(result.contains("2 files"));
-        assert!(result.contains("1 fixable"));
-        assert!(result.contains("F401"));
-        assert!(result.contains("E501"));
-        assert!(result.contains("main.py"));
-        assert!(result.contains("utils.py"));
+        assert!(result.starts_with("Ruff: 3 errors, 0 warnings in 2 files"));
+        assert!(result.contains("src/main.py:1:8: error[F401]: `os` imported but unused"));
+        assert!(result.contains("src/utils.py:10:89: error[E501]: Line too long"));
+        assert!(result.contains("auto-fix 1 issues"));
     }
 
     #[test]
@@ -387,16 +340,15 @@ Would reformat: tests/test_utils.py
     }
 
     #[test]
-    fn test_compact_path() {
-        assert_eq!(
-            compact_path("/Users/foo/project/src/main.py"),
-            "src/main.py"
+    fn test_json_snapshot_ruff() {
+        let actual = render_json::<RuffCheckParser>(
+            "ruff check",
+            include_str!("../tests/fixtures/json_output/ruff.input.json"),
+            1,
         );
-        assert_eq!(compact_path("/home/user/app/lib/utils.py"), "lib/utils.py");
-        assert_eq!(
-            compact_path("C:\\Users\\foo\\project\\tests\\test.py"),
-            "tests/test.py"
+        assert_json_snapshot(
+            &actual,
+            include_str!("../tests/fixtures/json_output/ruff.expected.json"),
         );
-        assert_eq!(compact_path("relative/file.py"), "file.py");
     }
 }
@@ -1,4 +1,12 @@
+use crate::cargo_cmd::{CargoTestParser, NextestParser};
+use crate::go_cmd::GoTestParser;
+use crate::parser::{
+    emit_passthrough_warning, format_parsed, truncate_output, OutputParser, ParseResult,
+    TestFailure, TestResult,
+};
+use crate::pytest_cmd::PytestParser;
 use crate::tracking;
+use crate::vitest_cmd::VitestParser;
 use anyhow::{Context, Result};
 use regex::Regex;
 use std::process::{Command, Stdio};
@@ -93,7 +101,7 @@ pub fn run_test(command: &str, verbose: u8) -> Result<()> {
         .status
         .code()
         .unwrap_or(if output.status.success() { 0 } else { 1 });
-    let summary = extract_test_summary(&raw, command);
+    let summary = extract_test_summary(&raw, command, verbose);
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "test", exit_code) {
         println!("{}\n{}", summary, hint);
     } else {
@@ -160,101 +168,199 @@ fn filter_errors(output: &str) -> String {
     result.join("\n")
 }
 
-fn extract_test_summary(output: &str, command: &str) -> String {
-    let mut result = Vec::new();
-    let lines: Vec<&str> = output.lines().collect();
-
-    // Detect test framework
-    let is_cargo = command.contains("cargo test");
-    let is_pytest = command.contains("pytest");
-    let is_jest =
-        command.contains("jest") || command.contains("npm test") || command.contains("yarn test");
-    let is_go = command.contains("go test");
-
-    // Collect failures
-    let mut failures = Vec::new();
-    let mut in_failure = false;
-    let mut failure_lines = Vec::new();
-
-    for line in lines.iter() {
-        // Cargo test
-        if is_cargo {
-            if line.contains("test result:") {
-                result.push(line.to_string());
-            }
-            if line.contains("FAILED") && !line.contains("test result") {
-                failures.push(line.to_string());
-            }
-            if line.starts_with("failures:") {
-                in_failure = true;
-            }
-            if in_failure && line.starts_with("    ") {
-                failure_lines.push(line.to_string());
-            }
-        }
+/// Test parsers `rtk test` can dispatch to, keyed by words in the command line
+struct TestFramework {
+    tool: &'static str,
+    keywords: &'static [&'static str],
+    parse: fn(&str) -> ParseResult<TestResult>,
+}
 
-        // Pytest
-        if is_pytest {
-            if line.contains(" passed") || line.contains(" failed") || line.contains(" error") {
-                result.push(line.to_string());
-            }
-            if line.contains("FAILED") {
-                failures.push(line.to_string());
-            }
-        }
+const TEST_FRAMEWORKS: &[TestFramework] = &[
+    TestFramework {
+        tool: "cargo nextest",
+        keywords: &["cargo nextest"],
+        parse: NextestParser::parse,
+    },
+    TestFramework {
+        tool: "cargo test",
+        keywords: &["cargo test"],
+        parse: CargoTestParser::parse,
+    },
+    TestFramework {
+        tool: "pytest",
+        keywords: &["pytest"],
+        parse: PytestParser::parse,
+    },
+    TestFramework {
+        tool: "go test",
+        keywords: &["go test"],
+        parse: GoTestParser::parse,
+    },
+    TestFramework {
+        tool: "vitest",
+        keywords: &["vitest"],
+        parse: VitestParser::parse,
+    },
+    TestFramework {
+        tool: "jest",
+        keywords: &["jest", "npm test", "yarn test", "pnpm test"],
+        parse: JestParser::parse,
+    },
+];
 
-        // Jest
-        if is_jest {
-            if line.contains("Tests:") || line.contains("Test Suites:") {
-                result.push(line.to_string());
-            }
-            if line.contains("✕") || line.contains("FAIL") {
-                failures.push(line.to_string());
-            }
+/// Parse test output with the framework named in `command`. Unknown commands try every
+/// parser; the first one that doesn't fall through to passthrough wins.
+fn parse_test_output(output: &str, command: &str) -> (&'static str, ParseResult<TestResult>) {
+    let detected: Vec<&TestFramework> = TEST_FRAMEWORKS
+        .iter()
+        .filter(|f| f.keywords.iter().any(|k| command.contains(k)))
+        .collect();
+    let candidates = if detected.is_empty() {
+        TEST_FRAMEWORKS.iter().collect()
+    } else {
+        detected
+    };
+
+    for framework in candidates {
+        let result = (framework.parse)(output);
+        if result.is_ok() {
+            return (framework.tool, result);
         }
+    }
+    ("test", ParseResult::Passthrough(output.to_string()))
+}
 
-        // Go test
-        if is_go {
-            if line.starts_with("ok") || line.starts_with("FAIL") || line.starts_with("---") {
-                result.push(line.to_string());
-            }
-            if line.contains("FAIL") {
-                failures.push(line.to_string());
+fn extract_test_summary(output: &str, command: &str, verbose: u8) -> String {
+    match parse_test_output(output, command) {
+        (tool, ParseResult::Passthrough(_)) => {
+            emit_passthrough_warning(tool, "no test framework summary found");
+            // The tail of a test run is where runners print their results
+            let lines: Vec<&str> = output.lines().filter(|l| !l.trim().is_empty()).collect();
+            let start = lines.len().saturating_sub(5);
+            let mut result = String::from("📊 OUTPUT (last 5 lines):\n");
+            for line in &lines[start..] {
+                result.push_str(&format!("  {}\n", line));
             }
+            result
         }
+        (tool, result) => format_parsed(tool, result, verbose),
     }
+}
 
-    // Build output
-    let mut output = String::new();
+/// Parser for Jest's default reporter (`Tests:       1 failed, 2 passed, 3 total`)
+pub struct JestParser;
 
-    if !failures.is_empty() {
-        output.push_str("❌ FAILURES:\n");
-        for f in failures.iter().take(10) {
-            output.push_str(&format!("  {}\n", f));
+impl OutputParser for JestParser {
+    type Output = TestResult;
+
+    fn parse(input: &str) -> ParseResult<TestResult> {
+        lazy_static::lazy_static! {
+            static ref TESTS_RE: Regex = Regex::new(r"^Tests:\s+(.+?)\s*$").unwrap();
+            static ref TIME_RE: Regex = Regex::new(r"^Time:\s+([\d.]+)\s*s").unwrap();
+            static ref CROSS_RE: Regex = Regex::new(r"^\s*✕ (.+?)(?: \(\d+ ms\))?$").unwrap();
         }
-        if failures.len() > 10 {
-            output.push_str(&format!("  ... +{} more failures\n", failures.len() - 10));
+
+        let mut counts: Option<(usize, usize, usize)> = None;
+        let mut duration_ms = None;
+        let mut failures: Vec<TestFailure> = Vec::new();
+        let mut crossed: Vec<String> = Vec::new();
+        let mut in_block = false;
+
+        for line in input.lines() {
+            let trimmed = line.trim();
+
+            if let Some(caps) = TESTS_RE.captures(trimmed) {
+                let (mut passed, mut failed, mut skipped) = (0, 0, 0);
+                for part in caps[1].split(", ") {
+                    let (n, kind) = part.split_once(' ').unwrap_or(("0", ""));
+                    let n: usize = n.parse().unwrap_or(0);
+                    match kind {
+                        "passed" => passed += n,
+                        "failed" => failed += n,
+                        "skipped" | "todo" => skipped += n,
+                        _ => {}
+                    }
+                }
+                counts = Some((passed, failed, skipped));
+                in_block = false;
+                continue;
+            }
+            if let Some(caps) = TIME_RE.captures(trimmed) {
+                duration_ms = caps[1]
+                    .parse::<f64>()
+                    .ok()
+                    .map(|s| (s * 1000.0).round() as u64);
+                continue;
+            }
+            if trimmed.starts_with("Test Suites:") {
+                in_block = false;
+                continue;
+            }
+
+            if let Some(name) = trimmed.strip_prefix("● ") {
+                failures.push(TestFailure {
+                    test_name: name.to_string(),
+                    file_path: String::new(),
+                    error_message: String::new(),
+                    stack_trace: None,
+                });
+                in_block = true;
+            } else if in_block && !trimmed.is_empty() {
+                if let Some(f) = failures.last_mut() {
+                    if let Some(location) = trimmed.strip_prefix("at ") {
+                        if f.file_path.is_empty() {
+                            f.file_path = location
+                                .trim_start_matches("Object.<anonymous> ")
+                                .trim_matches(|c| c == '(' || c == ')')
+                                .to_string();
+                        }
+                    } else if f.error_message.lines().count() < 10 {
+                        if !f.error_message.is_empty() {
+                            f.error_message.push('\n');
+                        }
+                        f.error_message.push_str(trimmed);
+                    }
+                }
+            } else if let Some(caps) = CROSS_RE.captures(line) {
+                crossed.push(caps[1].to_string());
+            }
         }
-        output.push('\n');
-    }
 
-    if !result.is_empty() {
-        output.push_str("📊 SUMMARY:\n");
-        for r in &result {
-            output.push_str(&format!("  {}\n", r));
+        // No detail blocks (e.g. --silent): fall back to the ✕ lines
+        if failures.is_empty() {
+            failures = crossed
+                .into_iter()
+                .map(|name| TestFailure {
+                    test_name: name,
+                    file_path: String::new(),
+                    error_message: String::new(),
+                    stack_trace: None,
+                })
+                .collect();
         }
-    } else {
-        // Fallback: show last few lines
-        output.push_str("📊 OUTPUT (last 5 lines):\n");
-        let start = lines.len().saturating_sub(5);
-        for line in &lines[start..] {
-            if !line.trim().is_empty() {
-                output.push_str(&format!("  {}\n", line));
-            }
+
+        let Some((passed, failed, skipped)) = counts else {
+            return ParseResult::Passthrough(truncate_output(input, 500));
+        };
+
+        let result = TestResult {
+            total: passed + failed + skipped,
+            passed,
+            failed,
+            skipped,
+            duration_ms,
+            failures,
+        };
+        if result.failures.len() < failed {
+            let warning = format!(
+                "{} tests failed but only {} failure details were found",
+                failed,
+                result.failures.len()
+            );
+            return ParseResult::Degraded(result, vec![warning]);
         }
+        ParseResult::Full(result)
     }
-
-    output
 }
 
 #[cfg(test)]
@@ -268,4 +374,61 @@ mod tests {
         assert!(filtered.contains("error"));
         assert!(!filtered.contains("info"));
     }
+
+    #[test]
+    fn test_extract_test_summary_cargo() {
+        let output = "running 2 tests\ntest a ... ok\ntest b ... ok\n\ntest result: ok. 2 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.01s\n";
+        let summary = extract_test_summary(output, "cargo test", 0);
+        assert!(summary.starts_with("PASS (2) FAIL (0)"), "got: {}", summary);
+    }
+
+    #[test]
+    fn test_parse_test_output_detects_framework_by_output() {
+        let output = "FAILED tests/test_x.py::test_y - assert 0\n1 failed, 3 passed in 0.20s\n";
+        let (tool, result) = parse_test_output(output, "make check");
+        assert_eq!(tool, "pytest");
+        assert_eq!(result.tier(), 1);
+    }
+
+    #[test]
+    fn test_extract_test_summary_unknown_shows_tail() {
+        let output = "building\nstep 1\nstep 2\nstep 3\nstep 4\nall good\n";
+        let summary = extract_test_summary(output, "./run-tests.sh", 0);
+        assert!(summary.contains("all good"));
+        assert!(!summary.contains("building"));
+    }
+
+    #[test]
+    fn test_jest_parser() {
+        let output = r#"FAIL src/sum.test.js
+  ● sum › handles negatives
+
+    expect(received).toBe(expected)
+
+    Expected: 1
+    Received: -1
+
+      at Object.<anonymous> (src/sum.test.js:8:20)
+
+Test Suites: 1 failed, 1 total
+Tests:       1 failed, 2 passed, 3 total
+Time:        0.512 s
+"#;
+        let result = JestParser::parse(output);
+        assert_eq!(result.tier(), 1);
+        let data = result.unwrap();
+        assert_eq!((data.passed, data.failed, data.total), (2, 1, 3));
+        assert_eq!(data.duration_ms, Some(512));
+        assert_eq!(data.failures[0].test_name, "sum › handles negatives");
+        assert_eq!(data.failures[0].file_path, "src/sum.test.js:8:20");
+        assert!(data.failures[0]
+            .error_message
+            .starts_with("expect(received).toBe(expected)"));
+    }
+
+    #[test]
+    fn test_jest_parser_degrades_without_details() {
+        let output = "Tests:       2 failed, 1 passed, 3 total\n";
+        assert_eq!(JestParser::parse(output).tier(), 2);
+    }
 }
@@ -0,0 +1,265 @@
+//! In-process search for `rtk grep` when ripgrep isn't installed.
+//!
+//! Walks with the `ignore` crate, so .gitignore, hidden files, `-t` types and `-g` globs
+//! behave as in rg, and matches with the `regex` crate. Results come back in the same
+//! shape as parsed `rg --json` output, so both backends render identically.
+use anyhow::{Context, Result};
+use ignore::overrides::OverrideBuilder;
+use ignore::types::TypesBuilder;
+use ignore::{WalkBuilder, WalkState};
+use regex::{Regex, RegexBuilder};
+use std::fs;
+use std::sync::Mutex;
+
+/// Leading bytes searched for a NUL; files with one are binary and skipped, like rg does
+const BINARY_SNIFF_BYTES: usize = 8000;
+
+/// What to search for and where, from `rtk grep` arguments
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    pub pattern: String,
+    /// `-F`: the pattern is a literal string
+    pub fixed_strings: bool,
+    /// `-i`
+    pub ignore_case: bool,
+    /// `-S`: ignore case unless the pattern has an uppercase letter
+    pub smart_case: bool,
+    /// `-w`: match whole words only
+    pub word: bool,
+    /// `-t`: ripgrep file type names (`rust`, `py`, `ts`, ...)
+    pub file_types: Vec<String>,
+    /// `-g`: include globs, `!` to exclude
+    pub globs: Vec<String>,
+    /// `-B` / `-A`: context lines before and after each match
+    pub before: usize,
+    pub after: usize,
+    /// Directory names never descended into (`filters.ignore_dirs`)
+    pub ignore_dirs: Vec<String>,
+}
+
+/// One output line: a match, or a context line around one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hit {
+    pub path: String,
+    /// 1-based
+    pub line: usize,
+    pub text: String,
+    pub is_match: bool,
+}
+
+impl SearchOptions {
+    fn regex(&self) -> Result<Regex> {
+        let pattern = if self.fixed_strings {
+            regex::escape(&self.pattern)
+        } else {
+            self.pattern.clone()
+        };
+        let pattern = if self.word {
+            format!(r"\b(?:{})\b", pattern)
+        } else {
+            pattern
+        };
+        let ignore_case =
+            self.ignore_case || (self.smart_case && !self.pattern.chars().any(char::is_uppercase));
+        RegexBuilder::new(&pattern)
+            .case_insensitive(ignore_case)
+            .multi_line(true)
+            .build()
+            .with_context(|| format!("invalid pattern: {}", self.pattern))
+    }
+}
+
+/// Search every file under `root` (or `root` itself, if it is a file). Hits are sorted by
+/// path, then line.
+pub fn search(root: &str, options: &SearchOptions) -> Result<Vec<Hit>> {
+    let regex = options.regex()?;
+
+    let mut builder = WalkBuilder::new(root);
+    if !options.file_types.is_empty() {
+        let mut types = TypesBuilder::new();
+        types.add_defaults();
+        for name in &options.file_types {
+            types.select(name);
+        }
+        builder.types(types.build().context("unknown file type for -t")?);
+    }
+    if !options.globs.is_empty() {
+        let mut overrides = OverrideBuilder::new(root);
+        for glob in &options.globs {
+            overrides
+                .add(glob)
+                .with_context(|| format!("invalid glob: {}", glob))?;
+        }
+        builder.overrides(overrides.build()?);
+    }
+    let ignore_dirs = options.ignore_dirs.clone();
+    builder.filter_entry(move |entry| {
+        // The search root is searched even when its name is on the list
+        entry.depth() == 0
+            || !entry.file_type().is_some_and(|t| t.is_dir())
+            || !ignore_dirs.iter().any(|d| entry.file_name() == d.as_str())
+    });
+
+    let found: Mutex<Vec<Hit>> = Mutex::new(Vec::new());
+    builder.build_parallel().run(|| {
+        let regex = &regex;
+        let found = &found;
+        Box::new(move |entry| {
+            let Ok(entry) = entry else {
+                return WalkState::Continue;
+            };
+            if !entry.file_type().is_some_and(|t| t.is_file()) {
+                return WalkState::Continue;
+            }
+            let Ok(bytes) = fs::read(entry.path()) else {
+                return WalkState::Continue;
+            };
+            if bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0) {
+                return WalkState::Continue;
+            }
+            let text = String::from_utf8_lossy(&bytes);
+            let path = entry.path().to_string_lossy();
+            let hits = search_text(&path, &text, regex, options.before, options.after);
+            if !hits.is_empty() {
+                found.lock().unwrap().extend(hits);
+            }
+            WalkState::Continue
+        })
+    });
+
+    let mut hits = found.into_inner().unwrap();
+    hits.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    Ok(hits)
+}
+
+/// Matching lines of one file, with `before`/`after` lines of context around them
+fn search_text(path: &str, text: &str, regex: &Regex, before: usize, after: usize) -> Vec<Hit> {
+    // Most files don't match at all: one pass over the whole text rules them out
+    if !regex.is_match(text) {
+        return Vec::new();
+    }
+    let lines: Vec<&str> = text.lines().collect();
+    let matched: Vec<usize> = (0..lines.len())
+        .filter(|&i| regex.is_match(lines[i]))
+        .collect();
+
+    let mut hits = Vec::new();
+    let mut next = 0;
+    for &m in &matched {
+        let start = m.saturating_sub(before).max(next);
+        let end = (m + after).min(lines.len() - 1);
+        for (i, text) in lines.iter().enumerate().take(end + 1).skip(start) {
+            hits.push(Hit {
+                path: path.to_string(),
+                line: i + 1,
+                text: text.to_string(),
+                is_match: matched.binary_search(&i).is_ok(),
+            });
+        }
+        next = end + 1;
+    }
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(pattern: &str) -> SearchOptions {
+        SearchOptions {
+            pattern: pattern.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn tree() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("node_modules/dep")).unwrap();
+        fs::create_dir_all(root.join("build")).unwrap();
+        fs::write(root.join(".gitignore"), "build/\n").unwrap();
+        // The walker only honours .gitignore inside a git repository
+        fs::create_dir(root.join(".git")).unwrap();
+        fs::write(
+            root.join("src/lib.rs"),
+            "fn parse() {}\nfn Parse_all() {}\n",
+        )
+        .unwrap();
+        fs::write(root.join("src/app.py"), "def parse():\n    pass\n").unwrap();
+        fs::write(root.join("node_modules/dep/index.js"), "parse()\n").unwrap();
+        fs::write(root.join("build/out.rs"), "fn parse() {}\n").unwrap();
+        fs::write(root.join("src/blob.bin"), b"parse\0\x01").unwrap();
+        dir
+    }
+
+    fn paths(hits: &[Hit], root: &str) -> Vec<String> {
+        let mut paths: Vec<String> = hits
+            .iter()
+            .map(|h| h.path.trim_start_matches(root).to_string())
+            .collect();
+        paths.dedup();
+        paths
+    }
+
+    #[test]
+    fn test_search_respects_gitignore_ignore_dirs_and_binaries() {
+        let dir = tree();
+        let root = dir.path().display().to_string();
+        let mut opts = options("parse");
+        opts.ignore_dirs = vec!["node_modules".to_string()];
+        let hits = search(&root, &opts).unwrap();
+        assert_eq!(paths(&hits, &root), ["/src/app.py", "/src/lib.rs"]);
+    }
+
+    #[test]
+    fn test_search_file_types_and_case() {
+        let dir = tree();
+        let root = dir.path().display().to_string();
+        let mut opts = options("parse");
+        opts.file_types = vec!["rust".to_string()];
+        assert_eq!(search(&root, &opts).unwrap().len(), 1);
+        opts.ignore_case = true;
+        assert_eq!(search(&root, &opts).unwrap().len(), 2);
+        opts.ignore_case = false;
+        opts.smart_case = true;
+        assert_eq!(search(&root, &opts).unwrap().len(), 2);
+        opts.pattern = "Parse".to_string();
+        assert_eq!(search(&root, &opts).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_fixed_strings_and_words() {
+        let mut opts = options("a.b(");
+        opts.fixed_strings = true;
+        let regex = opts.regex().unwrap();
+        assert!(regex.is_match("x = a.b(1)"));
+        assert!(!regex.is_match("x = aXb(1)"));
+
+        let mut opts = options("parse");
+        opts.word = true;
+        let regex = opts.regex().unwrap();
+        assert!(regex.is_match("parse(x)"));
+        assert!(!regex.is_match("parser(x)"));
+    }
+
+    #[test]
+    fn test_context_lines_merge() {
+        let text = "a\nhit\nb\nc\nhit\nd\ne\nf\n";
+        let regex = Regex::new("hit").unwrap();
+        let hits = search_text("f", text, &regex, 1, 1);
+        let lines: Vec<(usize, bool)> = hits.iter().map(|h| (h.line, h.is_match)).collect();
+        assert_eq!(
+            lines,
+            [
+                (1, false),
+                (2, true),
+                (3, false),
+                (4, false),
+                (5, true),
+                (6, false)
+            ]
+        );
+        assert!(search_text("f", "nothing\n", &regex, 2, 2).is_empty());
+    }
+}
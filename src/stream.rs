@@ -0,0 +1,379 @@
+//! Line-by-line execution for long-running commands.
+//!
+//! `run_streaming` reads the child's stdout and stderr as they are produced instead of
+//! buffering everything with `Command::output()`. A [`StreamFilter`] sees each line once
+//! and decides whether to print it right away (errors, failing tests) and whether the
+//! final summary needs it. Only kept lines stay in memory; raw tokens are counted in
+//! chunks as lines go by and the tee copy of stdout and stderr together is capped at the
+//! tee file size limit, so tracking and tee behave as they do for buffered commands.
+//!
+//! While the command runs, a heartbeat line goes to stderr so an agent watching a
+//! ten-minute build sees progress.
+use crate::tracking::estimate_tokens;
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often a progress line is printed while the command runs
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Bytes of raw output gathered before their tokens are counted
+const COUNT_CHUNK: usize = 64 * 1024;
+
+/// Which pipe a line came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamSource {
+    Stdout,
+    Stderr,
+}
+
+/// Lines a filter keeps for the final summary, split by source
+#[derive(Debug, Default)]
+pub struct KeptOutput {
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl KeptOutput {
+    pub fn push(&mut self, source: StreamSource, line: &str) {
+        let buf = match source {
+            StreamSource::Stdout => &mut self.stdout,
+            StreamSource::Stderr => &mut self.stderr,
+        };
+        buf.push_str(line);
+        buf.push('\n');
+    }
+
+    /// stdout then stderr, the same layout buffered commands pass to their filters
+    pub fn combined(&self) -> String {
+        format!("{}\n{}", self.stdout, self.stderr)
+    }
+}
+
+/// A filter that can work on output as it arrives.
+///
+/// Filters declare themselves stream-capable by implementing this trait. The final
+/// summary is still produced by the command's regular parser, run over the lines the
+/// stream filter kept; a stream filter must keep every line that parser relies on.
+pub trait StreamFilter {
+    /// Handle one line. Push it to `kept` if the final summary needs it; return text
+    /// to print immediately (on the line's own stream), e.g. a failing test.
+    fn feed(&mut self, line: &str, source: StreamSource, kept: &mut KeptOutput) -> Option<String>;
+
+    /// Short status for heartbeats, e.g. "412 passed, 1 failed"
+    fn progress(&self) -> Option<String> {
+        None
+    }
+
+    /// Called once after the last line, for filters that hold lines back
+    fn flush(&mut self, _kept: &mut KeptOutput) {}
+}
+
+/// Keeps every line and prints nothing early: buffered behaviour plus heartbeats.
+pub struct KeepAll;
+
+impl StreamFilter for KeepAll {
+    fn feed(&mut self, line: &str, source: StreamSource, kept: &mut KeptOutput) -> Option<String> {
+        kept.push(source, line);
+        None
+    }
+}
+
+/// Result of a streamed run
+#[derive(Debug, Default)]
+pub struct StreamedOutput {
+    pub kept: KeptOutput,
+    pub exit_code: i32,
+    pub success: bool,
+    /// Tokens of the full raw output
+    pub raw_tokens: usize,
+    /// Everything already printed while streaming (counted as rtk output in tracking)
+    pub emitted: String,
+    tee_stdout: String,
+    tee_stderr: String,
+}
+
+impl StreamedOutput {
+    /// Raw output for tee, capped at the tee file size limit (stdout then stderr)
+    pub fn tee_text(&self) -> String {
+        format!("{}\n{}", self.tee_stdout, self.tee_stderr)
+    }
+
+    /// Add a line to the tee copy; stdout and stderr share one `cap` in bytes
+    fn push_tee(&mut self, source: StreamSource, line: &str, cap: usize) {
+        let used = self.tee_stdout.len() + self.tee_stderr.len();
+        let buf = match source {
+            StreamSource::Stdout => &mut self.tee_stdout,
+            StreamSource::Stderr => &mut self.tee_stderr,
+        };
+        let cap = buf.len() + cap.saturating_sub(used);
+        push_capped(buf, line, cap);
+    }
+}
+
+/// Spawn `cmd`, feed its output through `filter` line by line and wait for it to exit.
+/// `label` names the command in heartbeats.
+pub fn run_streaming(
+    cmd: Command,
+    label: &str,
+    filter: &mut dyn StreamFilter,
+) -> Result<StreamedOutput> {
+    run_streaming_with(cmd, label, filter, HEARTBEAT_INTERVAL)
+}
+
+fn run_streaming_with(
+    mut cmd: Command,
+    label: &str,
+    filter: &mut dyn StreamFilter,
+    heartbeat: Duration,
+) -> Result<StreamedOutput> {
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", label))?;
+
+    let (tx, rx) = mpsc::channel::<(StreamSource, String)>();
+    let readers = [
+        child
+            .stdout
+            .take()
+            .map(|pipe| spawn_reader(pipe, StreamSource::Stdout, tx.clone())),
+        child
+            .stderr
+            .take()
+            .map(|pipe| spawn_reader(pipe, StreamSource::Stderr, tx.clone())),
+    ];
+    drop(tx);
+
+    let tee_cap = crate::tee::max_file_size().saturating_add(1);
+    // JSON and --delta print one result at the end, not findings as they happen
+    let quiet = crate::parser::json_mode() || crate::parser::delta_mode();
+    let started = Instant::now();
+    let mut next_heartbeat = started + heartbeat;
+    let mut lines_seen = 0usize;
+    let mut out = StreamedOutput {
+        success: true,
+        ..StreamedOutput::default()
+    };
+    // Raw output not counted yet: counting whole chunks keeps the estimate close to
+    // counting the joined text, without holding all of it
+    let mut uncounted = String::new();
+
+    loop {
+        let timeout = next_heartbeat.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(timeout) {
+            Ok((source, line)) => {
+                lines_seen += 1;
+                uncounted.push_str(&line);
+                uncounted.push('\n');
+                if uncounted.len() >= COUNT_CHUNK {
+                    out.raw_tokens += estimate_tokens(&uncounted);
+                    uncounted.clear();
+                }
+                out.push_tee(source, &line, tee_cap);
+
+                if let Some(text) = filter.feed(&line, source, &mut out.kept) {
+                    if !quiet {
+                        match source {
+                            StreamSource::Stdout => println!("{}", text),
+                            StreamSource::Stderr => eprintln!("{}", text),
+                        }
+                    }
+                    out.emitted.push_str(&text);
+                    out.emitted.push('\n');
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                eprintln!(
+                    "{}",
+                    heartbeat_line(label, started.elapsed(), lines_seen, filter.progress())
+                );
+                next_heartbeat = Instant::now() + heartbeat;
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    out.raw_tokens += estimate_tokens(&uncounted);
+    filter.flush(&mut out.kept);
+
+    for reader in readers.into_iter().flatten() {
+        let _ = reader.join();
+    }
+    let status = child
+        .wait()
+        .with_context(|| format!("Failed to wait for {}", label))?;
+    out.success = status.success();
+    out.exit_code = status.code().unwrap_or(if out.success { 0 } else { 1 });
+    Ok(out)
+}
+
+/// Read `pipe` line by line (lossy UTF-8) into the channel until EOF.
+fn spawn_reader<R: Read + Send + 'static>(
+    pipe: R,
+    source: StreamSource,
+    tx: mpsc::Sender<(StreamSource, String)>,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(pipe);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let line = String::from_utf8_lossy(&buf);
+                    let line = line.trim_end_matches(['\n', '\r']).to_string();
+                    if tx.send((source, line)).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Append `line` to `buf` without growing it past `cap` bytes.
+fn push_capped(buf: &mut String, line: &str, cap: usize) {
+    if buf.len() >= cap {
+        return;
+    }
+    let room = cap - buf.len();
+    if line.len() < room {
+        buf.push_str(line);
+        buf.push('\n');
+    } else {
+        let mut end = room;
+        while !line.is_char_boundary(end) {
+            end -= 1;
+        }
+        buf.push_str(&line[..end]);
+    }
+}
+
+fn heartbeat_line(
+    label: &str,
+    elapsed: Duration,
+    lines: usize,
+    progress: Option<String>,
+) -> String {
+    let secs = elapsed.as_secs();
+    let elapsed = if secs >= 60 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    };
+    match progress {
+        Some(p) => format!("⏳ {} running {} ({} lines, {})", label, elapsed, lines, p),
+        None => format!("⏳ {} running {} ({} lines)", label, elapsed, lines),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Keeps stdout, prints stderr lines containing "error" right away
+    struct ErrorsNow;
+
+    impl StreamFilter for ErrorsNow {
+        fn feed(
+            &mut self,
+            line: &str,
+            source: StreamSource,
+            kept: &mut KeptOutput,
+        ) -> Option<String> {
+            match source {
+                StreamSource::Stdout => {
+                    kept.push(source, line);
+                    None
+                }
+                StreamSource::Stderr => line.contains("error").then(|| format!("! {}", line)),
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_streaming_keeps_and_emits() {
+        let mut cmd = Command::new("sh");
+        cmd.args([
+            "-c",
+            "echo one; echo 'error: boom' >&2; echo noise >&2; echo two; exit 3",
+        ]);
+        let out = run_streaming(cmd, "sh", &mut ErrorsNow).unwrap();
+        assert_eq!(out.kept.stdout, "one\ntwo\n");
+        assert_eq!(out.kept.stderr, "");
+        assert_eq!(out.emitted, "! error: boom\n");
+        assert_eq!(out.exit_code, 3);
+        assert!(!out.success);
+        assert!(out.tee_text().contains("noise"));
+        assert!(out.raw_tokens > 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_streaming_keep_all_matches_buffered() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "printf 'a\\nb\\n'; printf 'c\\n' >&2"]);
+        let out = run_streaming_with(cmd, "sh", &mut KeepAll, Duration::from_millis(5)).unwrap();
+        assert_eq!(out.kept.combined(), "a\nb\n\nc\n");
+        assert_eq!(out.exit_code, 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_streaming_counts_tokens_on_joined_text() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "printf 'a\\nb\\n'; printf 'c\\n' >&2"]);
+        let out = run_streaming(cmd, "sh", &mut KeepAll).unwrap();
+        assert_eq!(out.raw_tokens, estimate_tokens("a\nb\nc\n"));
+    }
+
+    #[test]
+    fn test_push_tee_shares_cap_between_streams() {
+        let mut out = StreamedOutput::default();
+        out.push_tee(StreamSource::Stdout, "hello", 10);
+        out.push_tee(StreamSource::Stderr, "world!", 10);
+        out.push_tee(StreamSource::Stdout, "more", 10);
+        assert_eq!(out.tee_stdout, "hello\n");
+        assert_eq!(out.tee_stderr, "worl");
+        assert_eq!(out.tee_stdout.len() + out.tee_stderr.len(), 10);
+    }
+
+    #[test]
+    fn test_push_capped_stops_at_cap() {
+        let mut buf = String::new();
+        push_capped(&mut buf, "hello", 10);
+        push_capped(&mut buf, "world!", 10);
+        push_capped(&mut buf, "more", 10);
+        assert_eq!(buf, "hello\nworl");
+    }
+
+    #[test]
+    fn test_push_capped_respects_char_boundary() {
+        let mut buf = String::new();
+        push_capped(&mut buf, "héllo", 2);
+        assert_eq!(buf, "h");
+    }
+
+    #[test]
+    fn test_heartbeat_line() {
+        assert_eq!(
+            heartbeat_line(
+                "cargo test",
+                Duration::from_secs(95),
+                1200,
+                Some("40 passed".into())
+            ),
+            "⏳ cargo test running 1m35s (1200 lines, 40 passed)"
+        );
+        assert_eq!(
+            heartbeat_line("go test", Duration::from_secs(30), 7, None),
+            "⏳ go test running 30s (7 lines)"
+        );
+    }
+}
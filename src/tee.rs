@@ -137,9 +137,15 @@ fn write_tee_file(
     Some(filepath)
 }
 
+/// A written tee file and its id in the tracking DB (None if indexing failed)
+pub struct TeeFile {
+    pub path: PathBuf,
+    pub id: Option<i64>,
+}
+
 /// Write raw output to tee file if conditions are met.
-/// Returns file path on success, None if skipped/failed.
-pub fn tee_raw(raw: &str, command_slug: &str, exit_code: i32) -> Option<PathBuf> {
+/// Returns the file on success, None if skipped/failed.
+pub fn tee_raw(raw: &str, command_slug: &str, exit_code: i32) -> Option<TeeFile> {
     // Check RTK_TEE=0 env override (disable)
     if std::env::var("RTK_TEE").ok().as_deref() == Some("0") {
         return None;
@@ -150,17 +156,42 @@ pub fn tee_raw(raw: &str, command_slug: &str, exit_code: i32) -> Option<PathBuf>
 
     let tee_dir = should_tee(&config.tee, raw.len(), exit_code, Some(tee_dir))?;
 
-    write_tee_file(
+    write_and_index(raw, command_slug, exit_code, &tee_dir, &config.tee)
+}
+
+/// Write the tee file and record it in the tracking DB for `rtk tee`.
+fn write_and_index(
+    raw: &str,
+    command_slug: &str,
+    exit_code: i32,
+    tee_dir: &std::path::Path,
+    config: &TeeConfig,
+) -> Option<TeeFile> {
+    let path = write_tee_file(
         raw,
         command_slug,
-        &tee_dir,
-        config.tee.max_file_size,
-        config.tee.max_files,
-    )
+        tee_dir,
+        config.max_file_size,
+        config.max_files,
+    )?;
+    let id = crate::tracking::Tracker::new()
+        .and_then(|t| t.record_tee(&sanitize_slug(command_slug), exit_code, raw.len(), &path))
+        .ok();
+    Some(TeeFile { path, id })
 }
 
-/// Format the hint line with ~ shorthand for home directory.
-fn format_hint(path: &std::path::Path) -> String {
+/// Largest raw output a tee file keeps. Streamed commands cap their tee copy here
+/// instead of holding the whole output.
+pub fn max_file_size() -> usize {
+    Config::load()
+        .map(|c| c.tee.max_file_size)
+        .unwrap_or(DEFAULT_MAX_FILE_SIZE)
+}
+
+/// Format the hint line with ~ shorthand for home directory, plus the
+/// `rtk tee show` id when the file was indexed.
+fn format_hint(file: &TeeFile) -> String {
+    let path = &file.path;
     let display = if let Some(home) = dirs::home_dir() {
         if let Ok(relative) = path.strip_prefix(&home) {
             format!("~/{}", relative.display())
@@ -171,14 +202,59 @@ fn format_hint(path: &std::path::Path) -> String {
         path.display().to_string()
     };
 
-    format!("[full output: {}]", display)
+    match file.id {
+        Some(id) => format!("[full output: {} (rtk tee show {})]", display, id),
+        None => format!("[full output: {}]", display),
+    }
 }
 
 /// Convenience: tee + format hint in one call.
 /// Returns hint string if file was written, None if skipped.
+/// In `--output json` mode the file is still written but no hint is returned,
+/// so stdout stays a single JSON document.
 pub fn tee_and_hint(raw: &str, command_slug: &str, exit_code: i32) -> Option<String> {
-    let path = tee_raw(raw, command_slug, exit_code)?;
-    Some(format_hint(&path))
+    let file = tee_raw(raw, command_slug, exit_code)?;
+    if crate::parser::json_mode() {
+        return None;
+    }
+    Some(format_hint(&file))
+}
+
+/// [`tee_and_hint`] for output rendered by `filtered`. When the rendering left diagnostics
+/// out (a `... +N more` footer) the raw output is saved through [`tee_dropped`] even on
+/// success, so the footer is always followed by a hint to the file holding the rest.
+pub fn tee_and_hint_for(
+    filtered: &str,
+    raw: &str,
+    command_slug: &str,
+    exit_code: i32,
+) -> Option<String> {
+    if let Some(hint) = tee_and_hint(raw, command_slug, exit_code) {
+        return Some(hint);
+    }
+    if crate::parser::json_mode() || !crate::parser::is_capped(filtered) {
+        return None;
+    }
+    tee_dropped(raw, command_slug, exit_code)
+}
+
+/// Save output that is about to be cut (e.g. by `--budget`) and return the hint line.
+/// Unlike [`tee_and_hint`] this ignores the exit code and minimum size: whenever output
+/// is dropped the agent needs a way back to it. `enabled = false`, `mode = "never"` and
+/// `RTK_TEE=0` still turn it off.
+pub fn tee_dropped(full: &str, command_slug: &str, exit_code: i32) -> Option<String> {
+    if std::env::var("RTK_TEE").ok().as_deref() == Some("0") {
+        return None;
+    }
+
+    let config = Config::load().ok()?;
+    if !config.tee.enabled || config.tee.mode == TeeMode::Never {
+        return None;
+    }
+    let tee_dir = get_tee_dir(&config)?;
+
+    let file = write_and_index(full, command_slug, exit_code, &tee_dir, &config.tee)?;
+    Some(format_hint(&file))
 }
 
 /// TeeMode controls when tee writes files.
@@ -350,11 +426,17 @@ mod tests {
 
     #[test]
     fn test_format_hint() {
-        let path = PathBuf::from("/tmp/rtk/tee/123_cargo_test.log");
-        let hint = format_hint(&path);
+        let mut file = TeeFile {
+            path: PathBuf::from("/tmp/rtk/tee/123_cargo_test.log"),
+            id: None,
+        };
+        let hint = format_hint(&file);
         assert!(hint.starts_with("[full output: "));
         assert!(hint.ends_with(']'));
         assert!(hint.contains("123_cargo_test.log"));
+
+        file.id = Some(7);
+        assert!(format_hint(&file).ends_with("123_cargo_test.log (rtk tee show 7)]"));
     }
 
     #[test]
@@ -0,0 +1,324 @@
+//! `rtk tee`: find and read the full-output files written by `tee.rs`.
+//!
+//! Captures are indexed in the tracking DB (`tee_captures`), so an agent can pull the
+//! few lines it needs (`--grep`, `--lines`, `--around`) instead of reading the whole file.
+use crate::tracking::{TeeCapture, TimedExecution, Tracker};
+use anyhow::{bail, Context, Result};
+use chrono::Local;
+use regex::Regex;
+
+/// How to cut a capture down; no options prints it whole
+#[derive(Debug, Default, clap::Args)]
+pub struct ViewArgs {
+    /// Only lines matching this regex
+    #[arg(short, long)]
+    pub grep: Option<String>,
+    /// Only this line range, e.g. 120:180, 120: or :40 (1-based, inclusive)
+    #[arg(short, long, value_name = "A:B")]
+    pub lines: Option<String>,
+    /// Matches of this regex with surrounding context
+    #[arg(short, long, value_name = "PATTERN")]
+    pub around: Option<String>,
+    /// Context lines for --around
+    #[arg(short = 'C', long, default_value = "3")]
+    pub context: usize,
+}
+
+/// List recent captures, newest first.
+pub fn list(slug: Option<&str>, limit: usize, verbose: u8) -> Result<()> {
+    let tracker = Tracker::new()?;
+    // Rotated-out files are skipped, so over-fetch a little
+    let captures: Vec<TeeCapture> = tracker
+        .get_tee_captures(slug, limit.saturating_mul(4))?
+        .into_iter()
+        .filter(|c| c.path.exists())
+        .take(limit)
+        .collect();
+
+    if captures.is_empty() {
+        match slug {
+            Some(slug) => println!("No tee captures for '{}'", slug),
+            None => println!("No tee captures yet"),
+        }
+        return Ok(());
+    }
+
+    println!(
+        "{:>4}  {:<11}  {:>4}  {:>7}  COMMAND",
+        "ID", "WHEN", "EXIT", "SIZE"
+    );
+    for capture in &captures {
+        println!(
+            "{:>4}  {:<11}  {:>4}  {:>7}  {}",
+            capture.id,
+            capture
+                .timestamp
+                .with_timezone(&Local)
+                .format("%m-%d %H:%M"),
+            capture.exit_code,
+            crate::ls::human_size(capture.size_bytes as u64),
+            capture.slug
+        );
+        if verbose > 0 {
+            println!("      {}", capture.path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Print capture `id`, cut down by `view`.
+pub fn show(id: i64, view: &ViewArgs, verbose: u8) -> Result<()> {
+    let capture = Tracker::new()?
+        .get_tee_capture(id)?
+        .with_context(|| format!("No tee capture #{} (see `rtk tee list`)", id))?;
+    print_capture(&capture, view, verbose)
+}
+
+/// Print the most recent failed capture, optionally for one command slug.
+pub fn last(slug: Option<&str>, view: &ViewArgs, verbose: u8) -> Result<()> {
+    let capture = Tracker::new()?.last_failed_tee(slug)?;
+    match capture {
+        Some(capture) => print_capture(&capture, view, verbose),
+        None => {
+            match slug {
+                Some(slug) => println!("No failed tee captures for '{}'", slug),
+                None => println!("No failed tee captures yet"),
+            }
+            Ok(())
+        }
+    }
+}
+
+fn print_capture(capture: &TeeCapture, view: &ViewArgs, verbose: u8) -> Result<()> {
+    let timer = TimedExecution::start();
+
+    if !capture.path.exists() {
+        bail!(
+            "Tee capture #{} was rotated out (tee keeps the newest tee.max_files files): {}",
+            capture.id,
+            capture.path.display()
+        );
+    }
+    let content = std::fs::read_to_string(&capture.path)
+        .with_context(|| format!("Failed to read {}", capture.path.display()))?;
+    if verbose > 0 {
+        eprintln!("Reading {}", capture.path.display());
+    }
+
+    let header = format!(
+        "#{} {} (exit {}, {}, {})",
+        capture.id,
+        capture.slug,
+        capture.exit_code,
+        crate::ls::human_size(capture.size_bytes as u64),
+        capture
+            .timestamp
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+    );
+    let body = render_view(&content, view)?;
+    let output = format!("{}\n{}", header, body.trim_end());
+    println!("{}", output);
+
+    timer.track(
+        &format!("cat {}", capture.path.display()),
+        &format!("rtk tee show {}", capture.id),
+        &content,
+        &output,
+    );
+    Ok(())
+}
+
+/// Apply `view` to a capture's text. Filtered output is numbered grep-style:
+/// `12:match`, `13-context`, `--` between separate groups.
+fn render_view(content: &str, view: &ViewArgs) -> Result<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let (start, end) = match &view.lines {
+        Some(spec) => parse_range(spec, lines.len())?,
+        None => (1, lines.len()),
+    };
+    if view.grep.is_none() && view.around.is_none() && view.lines.is_none() {
+        return Ok(content.to_string());
+    }
+
+    let in_range: Vec<(usize, &str)> = (start..=end)
+        .filter_map(|n| lines.get(n - 1).map(|line| (n, *line)))
+        .collect();
+
+    if let Some(pattern) = &view.grep {
+        let re =
+            Regex::new(pattern).with_context(|| format!("Invalid --grep regex: {}", pattern))?;
+        let matched: Vec<String> = in_range
+            .iter()
+            .filter(|(_, line)| re.is_match(line))
+            .map(|(n, line)| format!("{}:{}", n, line))
+            .collect();
+        return Ok(if matched.is_empty() {
+            format!("(no lines match /{}/)", pattern)
+        } else {
+            matched.join("\n")
+        });
+    }
+
+    if let Some(pattern) = &view.around {
+        let re =
+            Regex::new(pattern).with_context(|| format!("Invalid --around regex: {}", pattern))?;
+        return Ok(render_around(&in_range, &re, view.context)
+            .unwrap_or_else(|| format!("(no lines match /{}/)", pattern)));
+    }
+
+    Ok(in_range
+        .iter()
+        .map(|(n, line)| format!("{}:{}", n, line))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Matches with `context` lines either side; None when nothing matches.
+fn render_around(lines: &[(usize, &str)], re: &Regex, context: usize) -> Option<String> {
+    let matches: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, line))| re.is_match(line))
+        .map(|(i, _)| i)
+        .collect();
+    if matches.is_empty() {
+        return None;
+    }
+
+    let mut out = Vec::new();
+    let mut shown_until: Option<usize> = None;
+    for &m in &matches {
+        let from = m.saturating_sub(context);
+        let to = (m + context).min(lines.len() - 1);
+        let from = match shown_until {
+            Some(prev) if from <= prev + 1 => prev + 1,
+            Some(_) => {
+                out.push("--".to_string());
+                from
+            }
+            None => from,
+        };
+        for (i, (n, line)) in lines.iter().enumerate().take(to + 1).skip(from) {
+            let sep = if matches.binary_search(&i).is_ok() {
+                ':'
+            } else {
+                '-'
+            };
+            out.push(format!("{}{}{}", n, sep, line));
+        }
+        shown_until = Some(shown_until.map_or(to, |prev| prev.max(to)));
+    }
+    Some(out.join("\n"))
+}
+
+/// Parse `A:B` (either side optional) into an inclusive 1-based range within `total`.
+fn parse_range(spec: &str, total: usize) -> Result<(usize, usize)> {
+    let (a, b) = spec
+        .split_once(':')
+        .with_context(|| format!("Invalid --lines '{}': expected A:B, e.g. 120:180", spec))?;
+    let parse = |s: &str, default: usize| -> Result<usize> {
+        if s.trim().is_empty() {
+            Ok(default)
+        } else {
+            s.trim()
+                .parse()
+                .with_context(|| format!("Invalid line number '{}' in --lines", s))
+        }
+    };
+    let start = parse(a, 1)?.max(1);
+    let end = parse(b, total)?.min(total);
+    if start > end && total > 0 {
+        bail!("Empty --lines range {} (capture has {} lines)", spec, total);
+    }
+    Ok((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAPTURE: &str = "\
+running 3 tests
+test a ... ok
+test b ... FAILED
+test c ... ok
+
+failures:
+
+---- b stdout ----
+thread 'b' panicked at src/lib.rs:10:5:
+assertion failed
+";
+
+    fn view() -> ViewArgs {
+        ViewArgs {
+            context: 1,
+            ..ViewArgs::default()
+        }
+    }
+
+    #[test]
+    fn test_render_view_without_options_is_unchanged() {
+        assert_eq!(render_view(CAPTURE, &view()).unwrap(), CAPTURE);
+    }
+
+    #[test]
+    fn test_render_view_grep() {
+        let v = ViewArgs {
+            grep: Some("FAILED|panicked".into()),
+            ..view()
+        };
+        assert_eq!(
+            render_view(CAPTURE, &v).unwrap(),
+            "3:test b ... FAILED\n9:thread 'b' panicked at src/lib.rs:10:5:"
+        );
+    }
+
+    #[test]
+    fn test_render_view_lines_and_grep_combine() {
+        let v = ViewArgs {
+            lines: Some("1:4".into()),
+            grep: Some("ok$".into()),
+            ..view()
+        };
+        assert_eq!(
+            render_view(CAPTURE, &v).unwrap(),
+            "2:test a ... ok\n4:test c ... ok"
+        );
+    }
+
+    #[test]
+    fn test_render_view_around_merges_and_separates_groups() {
+        let v = ViewArgs {
+            around: Some("FAILED|assertion".into()),
+            ..view()
+        };
+        assert_eq!(
+            render_view(CAPTURE, &v).unwrap(),
+            "2-test a ... ok\n3:test b ... FAILED\n4-test c ... ok\n--\n9-thread 'b' panicked at src/lib.rs:10:5:\n10:assertion failed"
+        );
+    }
+
+    #[test]
+    fn test_render_view_no_match() {
+        let v = ViewArgs {
+            grep: Some("segfault".into()),
+            ..view()
+        };
+        assert_eq!(
+            render_view(CAPTURE, &v).unwrap(),
+            "(no lines match /segfault/)"
+        );
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("3:5", 10).unwrap(), (3, 5));
+        assert_eq!(parse_range("8:", 10).unwrap(), (8, 10));
+        assert_eq!(parse_range(":2", 10).unwrap(), (1, 2));
+        assert_eq!(parse_range("5:99", 10).unwrap(), (5, 10));
+        assert!(parse_range("7", 10).is_err());
+        assert!(parse_range("9:3", 10).is_err());
+    }
+}
@@ -0,0 +1,387 @@
+//! Pluggable token estimators used by tracking.
+//!
+//! Two estimators are available, selected with `[tracking] tokenizer` in config.toml:
+//! - `heuristic` (default): `ceil(bytes / 4)`, fast and dependency-free
+//! - `cl100k`: byte-pair encoding with a cl100k-style vocabulary read from
+//!   `~/.local/share/rtk/cl100k_base.tiktoken` (or `tracking.tokenizer_path`)
+//!
+//! The vocabulary file uses the tiktoken format (`<base64 token> <rank>` per line)
+//! and is read from disk once per process, so no network access is ever needed.
+//! If the file is missing or unreadable, rtk falls back to the heuristic and
+//! records `heuristic` as the estimator for that row.
+
+use crate::config::Config;
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Default vocabulary filename inside the rtk data directory
+const CL100K_FILENAME: &str = "cl100k_base.tiktoken";
+/// Longest piece merged in one go. Every merge rescans the piece, so longer ones
+/// (minified code, base64, a run of one character) are counted in chunks.
+const MAX_PIECE_BYTES: usize = 256;
+
+/// Counts tokens in a piece of text.
+pub trait Tokenizer: Send + Sync {
+    /// Identifier stored alongside each tracking row (e.g. "heuristic", "cl100k")
+    fn name(&self) -> &'static str;
+
+    /// Number of tokens `text` encodes to
+    fn count(&self, text: &str) -> usize;
+}
+
+/// Which estimator to use, as written in config.toml.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenizerKind {
+    #[default]
+    Heuristic,
+    Cl100k,
+}
+
+/// ~4 bytes per token approximation.
+pub struct HeuristicTokenizer;
+
+impl Tokenizer for HeuristicTokenizer {
+    fn name(&self) -> &'static str {
+        "heuristic"
+    }
+
+    fn count(&self, text: &str) -> usize {
+        (text.len() as f64 / 4.0).ceil() as usize
+    }
+}
+
+/// Byte-pair encoding over a tiktoken-format vocabulary.
+pub struct BpeTokenizer {
+    ranks: HashMap<Vec<u8>, u32>,
+}
+
+impl BpeTokenizer {
+    /// Load a tiktoken vocabulary file (`<base64 token> <rank>` per line).
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read tokenizer vocabulary {}", path.display()))?;
+        Self::from_tiktoken(&content)
+            .with_context(|| format!("Invalid tokenizer vocabulary {}", path.display()))
+    }
+
+    /// Parse tiktoken-format vocabulary content.
+    pub fn from_tiktoken(content: &str) -> Result<Self> {
+        let mut ranks = HashMap::new();
+        for (idx, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (token, rank) = line
+                .split_once(' ')
+                .with_context(|| format!("line {}: expected '<token> <rank>'", idx + 1))?;
+            let bytes = decode_base64(token)
+                .with_context(|| format!("line {}: invalid base64 token", idx + 1))?;
+            let rank: u32 = rank
+                .trim()
+                .parse()
+                .with_context(|| format!("line {}: invalid rank", idx + 1))?;
+            ranks.insert(bytes, rank);
+        }
+        if ranks.is_empty() {
+            anyhow::bail!("vocabulary is empty");
+        }
+        Ok(Self { ranks })
+    }
+
+    /// Number of BPE tokens for one pre-tokenized piece.
+    fn count_piece(&self, piece: &[u8]) -> usize {
+        if piece.is_empty() {
+            return 0;
+        }
+        if self.ranks.contains_key(piece) {
+            return 1;
+        }
+        if piece.len() > MAX_PIECE_BYTES {
+            return piece
+                .chunks(MAX_PIECE_BYTES)
+                .map(|chunk| self.count_piece(chunk))
+                .sum();
+        }
+
+        // Part boundaries; repeatedly merge the adjacent pair with the lowest rank.
+        // Pair ranks are cached, so a merge only looks up the pairs next to it.
+        let mut parts: Vec<usize> = (0..=piece.len()).collect();
+        let pair_rank = |parts: &[usize], i: usize| -> Option<u32> {
+            let end = *parts.get(i + 2)?;
+            self.ranks.get(&piece[parts[i]..end]).copied()
+        };
+        let mut ranks: Vec<Option<u32>> = (0..piece.len()).map(|i| pair_rank(&parts, i)).collect();
+        // Lowest rank, leftmost on ties
+        while let Some((_, i)) = ranks
+            .iter()
+            .enumerate()
+            .filter_map(|(i, rank)| rank.map(|rank| (rank, i)))
+            .min()
+        {
+            parts.remove(i + 1);
+            ranks.remove(i + 1);
+            ranks[i] = pair_rank(&parts, i);
+            if i > 0 {
+                ranks[i - 1] = pair_rank(&parts, i - 1);
+            }
+        }
+        parts.len() - 1
+    }
+}
+
+impl Tokenizer for BpeTokenizer {
+    fn name(&self) -> &'static str {
+        "cl100k"
+    }
+
+    fn count(&self, text: &str) -> usize {
+        pre_tokenize(text)
+            .into_iter()
+            .map(|piece| self.count_piece(piece.as_bytes()))
+            .sum()
+    }
+}
+
+/// Split text into pieces the way cl100k does before applying BPE.
+///
+/// The upstream pattern uses `\s+(?!\S)`, which the `regex` crate cannot express;
+/// a whitespace run followed by a non-space is instead shortened by one char so the
+/// last space attaches to the next word, matching tiktoken's behavior.
+fn pre_tokenize(text: &str) -> Vec<&str> {
+    static PIECE_RE: OnceLock<Regex> = OnceLock::new();
+    let re = PIECE_RE.get_or_init(|| {
+        Regex::new(
+            r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+",
+        )
+        .unwrap()
+    });
+
+    let mut pieces = Vec::new();
+    let mut pos = 0;
+    while pos < text.len() {
+        let Some(m) = re.find_at(text, pos) else {
+            break;
+        };
+        let mut end = m.end();
+        let matched = m.as_str();
+        let is_plain_ws =
+            matched.chars().all(char::is_whitespace) && !matched.contains(['\r', '\n']);
+        if is_plain_ws && end < text.len() && matched.chars().count() > 1 {
+            let last = matched.chars().next_back().map_or(0, char::len_utf8);
+            end -= last;
+        }
+        pieces.push(&text[m.start()..end]);
+        pos = end;
+    }
+    pieces
+}
+
+/// Minimal standard-alphabet base64 decoder for vocabulary tokens.
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    fn value(c: u8) -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some((c - b'A') as u32),
+            b'a'..=b'z' => Some((c - b'a' + 26) as u32),
+            b'0'..=b'9' => Some((c - b'0' + 52) as u32),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    }
+
+    let bytes = input.trim_end_matches('=').as_bytes();
+    let mut out = Vec::with_capacity(bytes.len() * 3 / 4);
+    for chunk in bytes.chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let mut acc = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            acc |= value(c)? << (18 - 6 * i);
+        }
+        out.push((acc >> 16) as u8);
+        if chunk.len() > 2 {
+            out.push((acc >> 8) as u8);
+        }
+        if chunk.len() > 3 {
+            out.push(acc as u8);
+        }
+    }
+    Some(out)
+}
+
+/// Resolve the vocabulary path: config override, then the rtk data directory.
+pub fn vocab_path(config: &Config) -> Option<PathBuf> {
+    if let Some(ref path) = config.tracking.tokenizer_path {
+        return Some(path.clone());
+    }
+    dirs::data_local_dir().map(|d| d.join("rtk").join(CL100K_FILENAME))
+}
+
+fn build(kind: TokenizerKind, config: &Config) -> Box<dyn Tokenizer> {
+    match kind {
+        TokenizerKind::Heuristic => Box::new(HeuristicTokenizer),
+        TokenizerKind::Cl100k => vocab_path(config)
+            .and_then(|path| BpeTokenizer::from_file(&path).ok())
+            .map(|t| Box::new(t) as Box<dyn Tokenizer>)
+            .unwrap_or_else(|| Box::new(HeuristicTokenizer)),
+    }
+}
+
+/// The estimator selected in config, loaded once per process.
+pub fn active() -> &'static dyn Tokenizer {
+    static ACTIVE: OnceLock<Box<dyn Tokenizer>> = OnceLock::new();
+    ACTIVE
+        .get_or_init(|| {
+            let config = Config::load().unwrap_or_default();
+            build(config.tracking.tokenizer, &config)
+        })
+        .as_ref()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tiny vocabulary: every single byte, plus a few merges in rank order.
+    fn tiny_vocab() -> String {
+        let mut lines: Vec<String> = (0u32..256)
+            .map(|b| format!("{} {}", encode_base64(&[b as u8]), b))
+            .collect();
+        for (rank, token) in ["he", "ll", "hell", "hello", " w", "or", " wor", " world"]
+            .iter()
+            .enumerate()
+        {
+            lines.push(format!(
+                "{} {}",
+                encode_base64(token.as_bytes()),
+                256 + rank
+            ));
+        }
+        lines.join("\n")
+    }
+
+    fn encode_base64(bytes: &[u8]) -> String {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut out = String::new();
+        for chunk in bytes.chunks(3) {
+            let b = [
+                chunk[0],
+                *chunk.get(1).unwrap_or(&0),
+                *chunk.get(2).unwrap_or(&0),
+            ];
+            let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | b[2] as u32;
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    out.push(ALPHABET[((n >> (18 - 6 * i)) & 63) as usize] as char);
+                } else {
+                    out.push('=');
+                }
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_heuristic_matches_legacy_formula() {
+        let t = HeuristicTokenizer;
+        assert_eq!(t.name(), "heuristic");
+        assert_eq!(t.count(""), 0);
+        assert_eq!(t.count("abcd"), 1);
+        assert_eq!(t.count("abcde"), 2);
+    }
+
+    #[test]
+    fn test_decode_base64() {
+        assert_eq!(decode_base64("aGVsbG8="), Some(b"hello".to_vec()));
+        assert_eq!(decode_base64("IHdvcmxk"), Some(b" world".to_vec()));
+        assert_eq!(decode_base64("IQ=="), Some(b"!".to_vec()));
+        assert_eq!(decode_base64("@@@@"), None);
+    }
+
+    #[test]
+    fn test_bpe_merges_known_words() {
+        let t = BpeTokenizer::from_tiktoken(&tiny_vocab()).unwrap();
+        assert_eq!(t.name(), "cl100k");
+        assert_eq!(t.count("hello world"), 2);
+        assert_eq!(t.count("hello"), 1);
+        // "help" -> "hel" is not a token: "hell" can't form, so he + l + p
+        assert_eq!(t.count("help"), 3);
+    }
+
+    #[test]
+    fn test_bpe_counts_long_piece_in_chunks() {
+        let t = BpeTokenizer::from_tiktoken(&tiny_vocab()).unwrap();
+        // One 100 KB piece: merging it whole would rescan it once per merge
+        let long = "hello".repeat(20_000);
+        let chunks = long.len().div_ceil(MAX_PIECE_BYTES);
+        let count = t.count(&long);
+        // A word cut at a chunk edge costs at most a few extra tokens
+        assert!(count >= 20_000 && count <= 20_000 + 3 * chunks, "{}", count);
+    }
+
+    #[test]
+    fn test_bpe_non_ascii_falls_back_to_bytes() {
+        let t = BpeTokenizer::from_tiktoken(&tiny_vocab()).unwrap();
+        // 'é' is 2 UTF-8 bytes with no merge in the tiny vocab
+        assert_eq!(t.count("é"), 2);
+    }
+
+    #[test]
+    fn test_from_tiktoken_rejects_garbage() {
+        assert!(BpeTokenizer::from_tiktoken("").is_err());
+        assert!(BpeTokenizer::from_tiktoken("notbase64! 1").is_err());
+        assert!(BpeTokenizer::from_tiktoken("aGk= x").is_err());
+    }
+
+    #[test]
+    fn test_pre_tokenize_attaches_space_to_word() {
+        assert_eq!(pre_tokenize("hello world"), vec!["hello", " world"]);
+        assert_eq!(pre_tokenize("a   b"), vec!["a", "  ", " b"]);
+        assert_eq!(pre_tokenize("x = 12345"), vec!["x", " =", " ", "123", "45"]);
+        assert_eq!(
+            pre_tokenize("fn main() {\n}"),
+            vec!["fn", " main", "()", " {\n", "}"]
+        );
+    }
+
+    #[test]
+    fn test_pre_tokenize_covers_input() {
+        let text =
+            "error[E0308]: mismatched types\n  --> src/main.rs:4:5\n    let x: i32 = \"héllo\";";
+        assert_eq!(pre_tokenize(text).concat(), text);
+    }
+
+    #[test]
+    fn test_build_cl100k_missing_file_falls_back() {
+        let mut config = Config::default();
+        config.tracking.tokenizer_path = Some(PathBuf::from("/nonexistent/rtk/vocab.tiktoken"));
+        let t = build(TokenizerKind::Cl100k, &config);
+        assert_eq!(t.name(), "heuristic");
+    }
+
+    #[test]
+    fn test_build_cl100k_from_file() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(tmp.path(), tiny_vocab()).unwrap();
+        let mut config = Config::default();
+        config.tracking.tokenizer_path = Some(tmp.path().to_path_buf());
+        let t = build(TokenizerKind::Cl100k, &config);
+        assert_eq!(t.name(), "cl100k");
+        assert_eq!(t.count("hello world"), 2);
+    }
+
+    #[test]
+    fn test_tokenizer_kind_serde() {
+        let kind: TokenizerKind = serde_json::from_str(r#""cl100k""#).unwrap();
+        assert_eq!(kind, TokenizerKind::Cl100k);
+        let kind: TokenizerKind = serde_json::from_str(r#""heuristic""#).unwrap();
+        assert_eq!(kind, TokenizerKind::Heuristic);
+    }
+}
@@ -34,7 +34,7 @@ use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection};
 use serde::Serialize;
 use std::ffi::OsString;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
 /// Number of days to retain tracking history before automatic cleanup.
@@ -84,6 +84,19 @@ pub struct CommandRecord {
     pub savings_pct: f64,
 }
 
+/// A tee file indexed in the tracking database.
+#[derive(Debug)]
+pub struct TeeCapture {
+    pub id: i64,
+    pub timestamp: DateTime<Utc>,
+    /// Command slug the file was written for (e.g., "cargo_test")
+    pub slug: String,
+    pub exit_code: i32,
+    /// Size of the raw output in bytes, before tee truncation
+    pub size_bytes: usize,
+    pub path: PathBuf,
+}
+
 /// Aggregated statistics across all recorded commands.
 ///
 /// Provides overall metrics and breakdowns by command and by day.
@@ -108,6 +121,8 @@ pub struct GainSummary {
     pub by_command: Vec<(String, usize, usize, f64, u64)>,
     /// Last 30 days of activity: (date, saved_tokens)
     pub by_day: Vec<(String, usize)>,
+    /// Rows per token estimator: (estimator, count), most used first
+    pub by_estimator: Vec<(String, usize)>,
 }
 
 /// Daily statistics for token savings and execution metrics.
@@ -221,12 +236,16 @@ impl Tracker {
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn new() -> Result<Self> {
-        let db_path = get_db_path()?;
+        Self::open(&get_db_path()?)
+    }
+
+    /// Open (or create) the tracking database at `db_path`.
+    pub(crate) fn open(db_path: &Path) -> Result<Self> {
         if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent)?;
         }
 
-        let conn = Connection::open(&db_path)?;
+        let conn = Connection::open(db_path)?;
         conn.execute(
             "CREATE TABLE IF NOT EXISTS commands (
                 id INTEGER PRIMARY KEY,
@@ -252,12 +271,48 @@ impl Tracker {
             [],
         );
 
+        // Migration: add estimator column (rows before this were all heuristic)
+        let _ = conn.execute(
+            "ALTER TABLE commands ADD COLUMN estimator TEXT DEFAULT 'heuristic'",
+            [],
+        );
+
+        // Index of tee files, so `rtk tee` can find captures by id or command slug
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tee_captures (
+                id INTEGER PRIMARY KEY,
+                timestamp TEXT NOT NULL,
+                slug TEXT NOT NULL,
+                exit_code INTEGER NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                path TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_tee_slug ON tee_captures(slug, timestamp)",
+            [],
+        )?;
+
+        // Last parsed result per project and command, for `--delta`
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS last_results (
+                project TEXT NOT NULL,
+                command TEXT NOT NULL,
+                timestamp TEXT NOT NULL,
+                result TEXT NOT NULL,
+                PRIMARY KEY (project, command)
+            )",
+            [],
+        )?;
+
         Ok(Self { conn })
     }
 
     /// Record a command execution with token counts and timing.
     ///
-    /// Calculates savings metrics and stores the record in the database.
+    /// Calculates savings metrics and stores the record in the database, tagged
+    /// with the name of the active token estimator (see [`crate::tokenizer`]).
     /// Automatically cleans up records older than 90 days after insertion.
     ///
     /// # Arguments
@@ -293,8 +348,8 @@ impl Tracker {
         };
 
         self.conn.execute(
-            "INSERT INTO commands (timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens, saved_tokens, savings_pct, exec_time_ms)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT INTO commands (timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens, saved_tokens, savings_pct, exec_time_ms, estimator)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             params![
                 Utc::now().to_rfc3339(),
                 original_cmd,
@@ -303,7 +358,8 @@ impl Tracker {
                 output_tokens as i64,
                 saved as i64,
                 pct,
-                exec_time_ms as i64
+                exec_time_ms as i64,
+                crate::tokenizer::active().name()
             ],
         )?;
 
@@ -317,9 +373,106 @@ impl Tracker {
             "DELETE FROM commands WHERE timestamp < ?1",
             params![cutoff.to_rfc3339()],
         )?;
+        self.conn.execute(
+            "DELETE FROM tee_captures WHERE timestamp < ?1",
+            params![cutoff.to_rfc3339()],
+        )?;
         Ok(())
     }
 
+    /// Replace the stored result (JSON) for `command` in `project`.
+    pub fn save_last_result(&self, project: &str, command: &str, result: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO last_results (project, command, timestamp, result)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![project, command, Utc::now().to_rfc3339(), result],
+        )?;
+        Ok(())
+    }
+
+    /// Result stored by the previous run of `command` in `project`: (timestamp, JSON).
+    pub fn get_last_result(
+        &self,
+        project: &str,
+        command: &str,
+    ) -> Result<Option<(DateTime<Utc>, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT timestamp, result FROM last_results WHERE project = ?1 AND command = ?2",
+        )?;
+        let mut rows = stmt.query_map(params![project, command], |row| {
+            let timestamp = DateTime::parse_from_rfc3339(&row.get::<_, String>(0)?)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now());
+            Ok((timestamp, row.get::<_, String>(1)?))
+        })?;
+        Ok(rows.next().transpose()?)
+    }
+
+    /// Index a tee file written by [`crate::tee`]. Returns the capture id.
+    pub fn record_tee(
+        &self,
+        slug: &str,
+        exit_code: i32,
+        size_bytes: usize,
+        path: &Path,
+    ) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO tee_captures (timestamp, slug, exit_code, size_bytes, path)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                Utc::now().to_rfc3339(),
+                slug,
+                exit_code,
+                size_bytes as i64,
+                path.to_string_lossy()
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Most recent tee captures first, optionally only those whose slug starts with `slug`.
+    pub fn get_tee_captures(&self, slug: Option<&str>, limit: usize) -> Result<Vec<TeeCapture>> {
+        self.query_tee(
+            "WHERE (?1 IS NULL OR substr(slug, 1, length(?1)) = ?1) ORDER BY id DESC LIMIT ?2",
+            params![slug, limit as i64],
+        )
+    }
+
+    pub fn get_tee_capture(&self, id: i64) -> Result<Option<TeeCapture>> {
+        Ok(self.query_tee("WHERE id = ?1", params![id])?.pop())
+    }
+
+    /// Most recent capture of a failed command (non-zero exit code).
+    pub fn last_failed_tee(&self, slug: Option<&str>) -> Result<Option<TeeCapture>> {
+        Ok(self
+            .query_tee(
+                "WHERE exit_code != 0 AND (?1 IS NULL OR substr(slug, 1, length(?1)) = ?1) ORDER BY id DESC LIMIT 1",
+                params![slug],
+            )?
+            .pop())
+    }
+
+    fn query_tee(&self, clause: &str, params: impl rusqlite::Params) -> Result<Vec<TeeCapture>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, timestamp, slug, exit_code, size_bytes, path FROM tee_captures {}",
+            clause
+        ))?;
+        let rows = stmt.query_map(params, |row| {
+            Ok(TeeCapture {
+                id: row.get(0)?,
+                timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(1)?)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or_else(|_| Utc::now()),
+                slug: row.get(2)?,
+                exit_code: row.get(3)?,
+                size_bytes: row.get::<_, i64>(4)? as usize,
+                path: PathBuf::from(row.get::<_, String>(5)?),
+            })
+        })?;
+
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
     /// Get overall summary statistics across all recorded commands.
     ///
     /// Returns aggregated metrics including:
@@ -382,6 +535,7 @@ impl Tracker {
 
         let by_command = self.get_by_command()?;
         let by_day = self.get_by_day()?;
+        let by_estimator = self.get_by_estimator()?;
 
         Ok(GainSummary {
             total_commands,
@@ -393,6 +547,7 @@ impl Tracker {
             avg_time_ms,
             by_command,
             by_day,
+            by_estimator,
         })
     }
 
@@ -418,6 +573,21 @@ impl Tracker {
         Ok(rows.collect::<Result<Vec<_>, _>>()?)
     }
 
+    fn get_by_estimator(&self) -> Result<Vec<(String, usize)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT COALESCE(estimator, 'heuristic'), COUNT(*)
+             FROM commands
+             GROUP BY COALESCE(estimator, 'heuristic')
+             ORDER BY COUNT(*) DESC",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+        })?;
+
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
     fn get_by_day(&self) -> Result<Vec<(String, usize)>> {
         let mut stmt = self.conn.prepare(
             "SELECT DATE(timestamp), SUM(saved_tokens)
@@ -677,7 +847,7 @@ impl Tracker {
     }
 }
 
-fn get_db_path() -> Result<PathBuf> {
+pub(crate) fn get_db_path() -> Result<PathBuf> {
     // Priority 1: Environment variable RTK_DB_PATH
     if let Ok(custom_path) = std::env::var("RTK_DB_PATH") {
         return Ok(PathBuf::from(custom_path));
@@ -695,12 +865,12 @@ fn get_db_path() -> Result<PathBuf> {
     Ok(data_dir.join("rtk").join("history.db"))
 }
 
-/// Estimate token count from text using ~4 chars = 1 token heuristic.
+/// Estimate token count from text with the configured estimator.
 ///
-/// This is a fast approximation suitable for tracking purposes.
-/// For precise counts, integrate with your LLM's tokenizer API.
+/// Defaults to the ~4 chars = 1 token heuristic; set `[tracking] tokenizer = "cl100k"`
+/// to count with the BPE vocabulary instead (see [`crate::tokenizer`]).
 ///
-/// # Formula
+/// # Formula (heuristic)
 ///
 /// `tokens = ceil(chars / 4)`
 ///
@@ -715,8 +885,7 @@ fn get_db_path() -> Result<PathBuf> {
 /// assert_eq!(estimate_tokens("hello world"), 3); // 11 chars = ceil(2.75) = 3
 /// ```
 pub fn estimate_tokens(text: &str) -> usize {
-    // ~4 chars per token on average
-    (text.len() as f64 / 4.0).ceil() as usize
+    crate::tokenizer::active().count(text)
 }
 
 /// Helper struct for timing command execution
@@ -802,6 +971,31 @@ impl TimedExecution {
         }
     }
 
+    /// Track a streamed command whose raw output was never held in memory.
+    ///
+    /// Same as [`track`](Self::track), but the input side is a token count summed
+    /// line by line while the output streamed (see `stream::run_streaming`).
+    pub fn track_streamed(
+        &self,
+        original_cmd: &str,
+        rtk_cmd: &str,
+        input_tokens: usize,
+        output: &str,
+    ) {
+        let elapsed_ms = self.start.elapsed().as_millis() as u64;
+        let output_tokens = estimate_tokens(output);
+
+        if let Ok(tracker) = Tracker::new() {
+            let _ = tracker.record(
+                original_cmd,
+                rtk_cmd,
+                input_tokens,
+                output_tokens,
+                elapsed_ms,
+            );
+        }
+    }
+
     /// Track passthrough commands (timing-only, no token counting).
     ///
     /// For commands that stream output or run interactively where output
@@ -1012,7 +1206,97 @@ mod tests {
         assert_eq!(pt.saved_tokens, 0);
     }
 
-    // 7. get_db_path respects environment variable RTK_DB_PATH
+    // 7. record tags each row with the active estimator
+    #[test]
+    fn test_summary_by_estimator() {
+        let tracker = Tracker::new().expect("Failed to create tracker");
+        let test_cmd = format!("rtk estimator_test_{}", std::process::id());
+        tracker
+            .record("estimator", &test_cmd, 40, 10, 1)
+            .expect("Failed to record");
+
+        let summary = tracker.get_summary().expect("Failed to get summary");
+        let active = crate::tokenizer::active().name();
+        assert!(summary
+            .by_estimator
+            .iter()
+            .any(|(name, count)| name == active && *count > 0));
+    }
+
+    #[test]
+    fn test_tee_capture_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = Tracker::open(&dir.path().join("history.db")).unwrap();
+
+        let first = tracker
+            .record_tee("cargo_test", 101, 5000, Path::new("/tmp/1_cargo_test.log"))
+            .unwrap();
+        tracker
+            .record_tee("cargo_build", 0, 800, Path::new("/tmp/2_cargo_build.log"))
+            .unwrap();
+        let third = tracker
+            .record_tee("pytest", 1, 1200, Path::new("/tmp/3_pytest.log"))
+            .unwrap();
+
+        let all = tracker.get_tee_captures(None, 10).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].id, third);
+
+        let cargo = tracker.get_tee_captures(Some("cargo"), 10).unwrap();
+        assert_eq!(cargo.len(), 2);
+        // `_` and `%` in a slug are literal, not LIKE wildcards
+        let build = tracker.get_tee_captures(Some("cargo_b"), 10).unwrap();
+        assert_eq!(build.len(), 1);
+        assert!(tracker
+            .get_tee_captures(Some("c_rgo"), 10)
+            .unwrap()
+            .is_empty());
+        assert!(tracker
+            .get_tee_captures(Some("%test"), 10)
+            .unwrap()
+            .is_empty());
+
+        let capture = tracker.get_tee_capture(first).unwrap().unwrap();
+        assert_eq!(capture.slug, "cargo_test");
+        assert_eq!(capture.exit_code, 101);
+        assert_eq!(capture.size_bytes, 5000);
+        assert!(tracker.get_tee_capture(9999).unwrap().is_none());
+
+        assert_eq!(tracker.last_failed_tee(None).unwrap().unwrap().id, third);
+        assert_eq!(
+            tracker.last_failed_tee(Some("cargo")).unwrap().unwrap().id,
+            first
+        );
+        assert!(tracker.last_failed_tee(Some("go")).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_last_result_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = Tracker::open(&dir.path().join("history.db")).unwrap();
+
+        assert!(tracker
+            .get_last_result("/repo", "cargo test")
+            .unwrap()
+            .is_none());
+        tracker
+            .save_last_result("/repo", "cargo test", "{\"failed\":1}")
+            .unwrap();
+        tracker
+            .save_last_result("/repo", "cargo test", "{\"failed\":0}")
+            .unwrap();
+        let (_, json) = tracker
+            .get_last_result("/repo", "cargo test")
+            .unwrap()
+            .unwrap();
+        assert_eq!(json, "{\"failed\":0}");
+        assert!(tracker
+            .get_last_result("/other", "cargo test")
+            .unwrap()
+            .is_none());
+    }
+
+    // 8. get_db_path respects environment variable RTK_DB_PATH
     #[test]
     fn test_custom_db_path_env() {
         use std::env;
@@ -1026,7 +1310,7 @@ mod tests {
         env::remove_var("RTK_DB_PATH");
     }
 
-    // 8. get_db_path falls back to default when no custom config
+    // 9. get_db_path falls back to default when no custom config
     #[test]
     fn test_default_db_path() {
         use std::env;
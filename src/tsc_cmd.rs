@@ -1,8 +1,10 @@
+use crate::parser::{
+    render_all_diagnostics, render_delta, render_json, truncate_output, LintIssue, LintResult,
+    LintSeverity, OutputParser, ParseResult,
+};
 use crate::tracking;
-use crate::utils::truncate;
 use anyhow::{Context, Result};
 use regex::Regex;
-use std::collections::HashMap;
 use std::process::Command;
 
 pub fn run(args: &[String], verbose: u8) -> Result<()> {
@@ -39,9 +41,17 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let filtered = filter_tsc_output(&raw);
-
     let exit_code = output.status.code().unwrap_or(1);
+    let filtered = if crate::parser::json_mode() {
+        render_json::<TscParser>(&format!("tsc {}", args.join(" ")), &raw, exit_code)
+    } else if crate::parser::delta_mode() {
+        render_delta::<TscParser>("tsc", &format!("tsc {}", args.join(" ")), &raw, || {
+            filter_tsc_output(&raw)
+        })
+    } else {
+        filter_tsc_output(&raw)
+    };
+
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "tsc", exit_code) {
         println!("{}\n{}", filtered, hint);
     } else {
@@ -59,127 +69,77 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     std::process::exit(exit_code);
 }
 
-/// Filter TypeScript compiler output - group errors by file, show every error
-fn filter_tsc_output(output: &str) -> String {
-    lazy_static::lazy_static! {
-        // Pattern: src/file.ts(12,5): error TS2322: Type 'string' is not assignable to type 'number'.
-        static ref TSC_ERROR: Regex = Regex::new(
-            r"^(.+?)\((\d+),(\d+)\):\s+(error|warning)\s+(TS\d+):\s+(.+)$"
-        ).unwrap();
-    }
-
-    struct TsError {
-        file: String,
-        line: usize,
-        code: String,
-        message: String,
-        context_lines: Vec<String>,
-    }
-
-    let mut errors: Vec<TsError> = Vec::new();
-    let lines: Vec<&str> = output.lines().collect();
-    let mut i = 0;
-
-    while i < lines.len() {
-        let line = lines[i];
-        if let Some(caps) = TSC_ERROR.captures(line) {
-            let mut err = TsError {
-                file: caps[1].to_string(),
-                line: caps[2].parse().unwrap_or(0),
-                code: caps[5].to_string(),
-                message: caps[6].to_string(),
-                context_lines: Vec::new(),
-            };
+lazy_static::lazy_static! {
+    // Pattern: src/file.ts(12,5): error TS2322: Type 'string' is not assignable to type 'number'.
+    static ref TSC_ERROR: Regex = Regex::new(
+        r"^(.+?)\((\d+),(\d+)\):\s+(error|warning)\s+(TS\d+):\s+(.+)$"
+    ).unwrap();
+}
 
-            // Capture continuation lines (indented context from tsc)
-            i += 1;
-            while i < lines.len() {
-                let next = lines[i];
-                if !next.is_empty()
-                    && (next.starts_with("  ") || next.starts_with('\t'))
-                    && !TSC_ERROR.is_match(next)
-                {
-                    err.context_lines.push(next.trim().to_string());
-                    i += 1;
-                } else {
-                    break;
+/// Parser for `tsc` diagnostics into a canonical LintResult.
+/// Indented lines after a diagnostic (elaboration chains) become its notes.
+pub struct TscParser;
+
+impl OutputParser for TscParser {
+    type Output = LintResult;
+
+    fn parse(input: &str) -> ParseResult<LintResult> {
+        let mut issues: Vec<LintIssue> = Vec::new();
+        let mut in_issue = false;
+
+        for line in input.lines() {
+            if let Some(caps) = TSC_ERROR.captures(line) {
+                issues.push(LintIssue {
+                    file_path: caps[1].to_string(),
+                    line: caps[2].parse().unwrap_or(0),
+                    column: caps[3].parse().unwrap_or(0),
+                    severity: if &caps[4] == "error" {
+                        LintSeverity::Error
+                    } else {
+                        LintSeverity::Warning
+                    },
+                    rule_id: caps[5].to_string(),
+                    message: caps[6].to_string(),
+                    ..LintIssue::default()
+                });
+                in_issue = true;
+            } else if in_issue && (line.starts_with("  ") || line.starts_with('\t')) {
+                if let Some(issue) = issues.last_mut() {
+                    issue.notes.push(line.trim().to_string());
                 }
+            } else {
+                in_issue = false;
             }
+        }
 
-            errors.push(err);
-        } else {
-            i += 1;
+        if issues.is_empty() && input.contains("error TS") {
+            // e.g. `error TS5058: The specified path does not exist` has no location
+            return ParseResult::Passthrough(truncate_output(input, 500));
         }
+        ParseResult::Full(LintResult::from_issues(issues))
     }
+}
+
+/// Filter TypeScript compiler output - every error, grouped by file
+pub(crate) fn filter_tsc_output(output: &str) -> String {
+    let result = match TscParser::parse(output) {
+        ParseResult::Full(result) | ParseResult::Degraded(result, _) => result,
+        ParseResult::Passthrough(raw) => return raw,
+    };
 
-    if errors.is_empty() {
+    if result.issues.is_empty() {
         if output.contains("Found 0 errors") {
             return "✓ TypeScript: No errors found".to_string();
         }
         return "TypeScript compilation completed".to_string();
     }
-
-    // Group by file
-    let mut by_file: HashMap<String, Vec<&TsError>> = HashMap::new();
-    for err in &errors {
-        by_file.entry(err.file.clone()).or_default().push(err);
-    }
-
-    // Count by error code for summary
-    let mut by_code: HashMap<String, usize> = HashMap::new();
-    for err in &errors {
-        *by_code.entry(err.code.clone()).or_insert(0) += 1;
-    }
-
-    let mut result = String::new();
-    result.push_str(&format!(
-        "TypeScript: {} errors in {} files\n",
-        errors.len(),
-        by_file.len()
-    ));
-    result.push_str("═══════════════════════════════════════\n");
-
-    // Top error codes summary (compact, one line)
-    let mut code_counts: Vec<_> = by_code.iter().collect();
-    code_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-    if code_counts.len() > 1 {
-        let codes_str: Vec<String> = code_counts
-            .iter()
-            .take(5)
-            .map(|(code, count)| format!("{} ({}x)", code, count))
-            .collect();
-        result.push_str(&format!("Top codes: {}\n\n", codes_str.join(", ")));
-    }
-
-    // Files sorted by error count (most errors first)
-    let mut files_sorted: Vec<_> = by_file.iter().collect();
-    files_sorted.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
-
-    // Show every error per file — no limits
-    for (file, file_errors) in &files_sorted {
-        result.push_str(&format!("{} ({} errors)\n", file, file_errors.len()));
-
-        for err in *file_errors {
-            result.push_str(&format!(
-                "  L{}: {} {}\n",
-                err.line,
-                err.code,
-                truncate(&err.message, 120)
-            ));
-            for ctx in &err.context_lines {
-                result.push_str(&format!("    {}\n", truncate(ctx, 120)));
-            }
-        }
-        result.push('\n');
-    }
-
-    result.trim().to_string()
+    render_all_diagnostics("TypeScript", &result.issues)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::envelope::tests::assert_json_snapshot;
 
     #[test]
     fn test_filter_tsc_output() {
@@ -192,10 +152,11 @@ src/components/Button.tsx(10,5): error TS2322: Type 'string' is not assignable t
 Found 4 errors in 2 files.
 "#;
         let result = filter_tsc_output(output);
-        assert!(result.contains("TypeScript: 4 errors in 2 files"));
-        assert!(result.contains("auth.ts (2 errors)"));
-        assert!(result.contains("Button.tsx (2 errors)"));
-        assert!(result.contains("TS2322"));
+        assert!(result.starts_with("TypeScript: 4 errors, 0 warnings in 2 files"));
+        assert!(result.contains("src/components/Button.tsx:8:3: error[TS2339]"));
+        assert!(result.contains("src/server/api/auth.ts:15:10: error[TS2345]"));
+        // Same TS2322 message in both files is printed once
+        assert!(result.contains("  also: src/server/api/auth.ts:12:5"));
         assert!(!result.contains("Found 4 errors")); // Summary line should be replaced
     }
 
@@ -211,9 +172,9 @@ src/api.ts(30,5): error TS2322: Type 'null' is not assignable to type 'object'.
         assert!(result.contains("Type 'string' is not assignable to type 'number'"));
         assert!(result.contains("Type 'boolean' is not assignable to type 'string'"));
         assert!(result.contains("Type 'null' is not assignable to type 'object'"));
-        assert!(result.contains("L10:"));
-        assert!(result.contains("L20:"));
-        assert!(result.contains("L30:"));
+        assert!(result.contains("src/api.ts:10:5: error[TS2322]"));
+        assert!(result.contains("src/api.ts:20:5: error[TS2322]"));
+        assert!(result.contains("src/api.ts:30:5: error[TS2322]"));
     }
 
     #[test]
@@ -224,9 +185,9 @@ src/app.tsx(10,3): error TS2322: Type '{ children: Element; }' is not assignable
 src/app.tsx(20,5): error TS2345: Argument of type 'number' is not assignable to parameter of type 'string'.
 ";
         let result = filter_tsc_output(output);
-        assert!(result.contains("Property 'children' does not exist on type 'Props'"));
-        assert!(result.contains("L10:"));
-        assert!(result.contains("L20:"));
+        assert!(result.contains("  note: Property 'children' does not exist on type 'Props'."));
+        assert!(result.contains("src/app.tsx:10:3: error[TS2322]"));
+        assert!(result.contains("src/app.tsx:20:5: error[TS2345]"));
     }
 
     #[test]
@@ -240,7 +201,7 @@ src/app.tsx(20,5): error TS2345: Argument of type 'number' is not assignable to
             ));
         }
         let result = filter_tsc_output(&output);
-        assert!(result.contains("15 errors in 15 files"));
+        assert!(result.contains("15 errors, 0 warnings in 15 files"));
         for i in 1..=15 {
             assert!(
                 result.contains(&format!("file{}.ts", i)),
@@ -256,4 +217,17 @@ src/app.tsx(20,5): error TS2345: Argument of type 'number' is not assignable to
         let result = filter_tsc_output(output);
         assert!(result.contains("No errors found"));
     }
+
+    #[test]
+    fn test_json_snapshot_tsc() {
+        let actual = render_json::<TscParser>(
+            "tsc --noEmit",
+            include_str!("../tests/fixtures/json_output/tsc.input.txt"),
+            2,
+        );
+        assert_json_snapshot(
+            &actual,
+            include_str!("../tests/fixtures/json_output/tsc.expected.json"),
+        );
+    }
 }
@@ -3,8 +3,8 @@ use regex::Regex;
 use serde::Deserialize;
 
 use crate::parser::{
-    emit_degradation_warning, emit_passthrough_warning, extract_json_object, truncate_output,
-    FormatMode, OutputParser, ParseResult, TestFailure, TestResult, TokenFormatter,
+    delta_mode, extract_json_object, format_parsed, json_mode, render_delta, render_json,
+    truncate_output, OutputParser, ParseResult, TestFailure, TestResult,
 };
 use crate::tracking;
 use crate::utils::{package_manager_exec, strip_ansi};
@@ -237,30 +237,26 @@ fn run_vitest(args: &[String], verbose: u8) -> Result<()> {
     let stderr = String::from_utf8_lossy(&output.stderr);
     let combined = format!("{}{}", stdout, stderr);
 
-    // Parse output using VitestParser
-    let parse_result = VitestParser::parse(&stdout);
-    let mode = FormatMode::from_verbosity(verbose);
+    let exit_code = output.status.code().unwrap_or(1);
 
-    let filtered = match parse_result {
-        ParseResult::Full(data) => {
-            if verbose > 0 {
-                eprintln!("vitest run (Tier 1: Full JSON parse)");
-            }
-            data.format(mode)
-        }
-        ParseResult::Degraded(data, warnings) => {
-            if verbose > 0 {
-                emit_degradation_warning("vitest", &warnings.join(", "));
-            }
-            data.format(mode)
-        }
-        ParseResult::Passthrough(raw) => {
-            emit_passthrough_warning("vitest", "All parsing tiers failed");
-            raw
-        }
+    // Parse output using VitestParser
+    let filtered = if json_mode() {
+        render_json::<VitestParser>(
+            &format!("vitest run {}", args.join(" ")),
+            &stdout,
+            exit_code,
+        )
+    } else if delta_mode() {
+        render_delta::<VitestParser>(
+            "vitest",
+            &format!("vitest run {}", args.join(" ")),
+            &stdout,
+            || format_parsed("vitest", VitestParser::parse(&stdout), verbose),
+        )
+    } else {
+        format_parsed("vitest", VitestParser::parse(&stdout), verbose)
     };
 
-    let exit_code = output.status.code().unwrap_or(1);
     if let Some(hint) = crate::tee::tee_and_hint(&combined, "vitest_run", exit_code) {
         println!("{}\n{}", filtered, hint);
     } else {
@@ -276,6 +272,7 @@ fn run_vitest(args: &[String], verbose: u8) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::envelope::tests::assert_json_snapshot;
 
     #[test]
     fn test_vitest_parser_json() {
@@ -382,4 +379,17 @@ Scope: all 6 workspace projects
         assert_eq!(data.total, 2);
         assert_eq!(data.passed, 2);
     }
+
+    #[test]
+    fn test_json_snapshot_vitest() {
+        let actual = render_json::<VitestParser>(
+            "vitest run",
+            include_str!("../tests/fixtures/json_output/vitest.input.json"),
+            1,
+        );
+        assert_json_snapshot(
+            &actual,
+            include_str!("../tests/fixtures/json_output/vitest.expected.json"),
+        );
+    }
 }
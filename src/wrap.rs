@@ -0,0 +1,130 @@
+//! Post-processing of a whole rtk invocation: output budget and output cache.
+//!
+//! Both work on what a command prints, whichever command it is, so instead of touching
+//! every filter `main` re-runs the same rtk arguments as a child process with stdout
+//! piped. Lines are passed on as they arrive, so failures a streaming command prints
+//! early still show up live; only output past the budget is held back until the child
+//! exits. stdin and stderr are inherited, so prompts and heartbeats are unaffected. The
+//! child sees `RTK_WRAPPED_CHILD` and runs the command normally.
+use crate::budget;
+use crate::cache::CacheLookup;
+use crate::tracking::estimate_tokens;
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+
+/// Set in the child process so it doesn't wrap itself again
+const CHILD_ENV: &str = "RTK_WRAPPED_CHILD";
+
+/// Global flags that take a separate value (skipped when naming the command)
+const FLAGS_WITH_VALUE: &[&str] = &["--output", "--budget"];
+
+/// True inside the child started by [`run`].
+pub fn is_child() -> bool {
+    std::env::var_os(CHILD_ENV).is_some()
+}
+
+/// The rtk subcommand being run, e.g. "cargo test" for `rtk -v cargo test --lib`.
+pub fn invoked_command() -> String {
+    let args: Vec<String> = std::env::args().collect();
+    command_key(&args)
+}
+
+/// Answer from the cache, or run the command as a child and pass its stdout on.
+/// Returns the exit code to exit with.
+pub fn run(budget: Option<usize>, cache: Option<CacheLookup>) -> Result<i32> {
+    if let Some(lookup) = &cache {
+        if let Some(exit_code) = lookup.replay() {
+            return Ok(exit_code);
+        }
+    }
+
+    let exe = std::env::current_exe().context("Failed to locate the rtk executable")?;
+    let mut child = Command::new(exe)
+        .args(std::env::args_os().skip(1))
+        .env(CHILD_ENV, "1")
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .context("Failed to run rtk")?;
+
+    let command = invoked_command();
+    let limit = budget.and_then(|tokens| budget::live_limit(tokens, &command));
+    // Passed on as it arrived, and held back from the first line over the limit
+    let mut printed = String::new();
+    let mut held = String::new();
+    let mut used = 0;
+    if let Some(pipe) = child.stdout.take() {
+        let mut reader = BufReader::new(pipe);
+        let mut stdout = std::io::stdout();
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+            let line = String::from_utf8_lossy(&buf);
+            used += estimate_tokens(&line) + 1;
+            if held.is_empty() && limit.is_none_or(|limit| used <= limit) {
+                // A closed stdout must not stop the child: keep reading
+                let _ = stdout.write_all(line.as_bytes());
+                let _ = stdout.flush();
+                printed.push_str(&line);
+            } else {
+                held.push_str(&line);
+            }
+        }
+    }
+    let status = child.wait().context("Failed to wait for rtk")?;
+    let exit_code = status.code().unwrap_or(1);
+
+    let rest = match budget {
+        Some(tokens) => budget::enforce(&printed, &held, tokens, &command, exit_code),
+        None => held,
+    };
+    print!("{}", rest);
+    printed.push_str(&rest);
+
+    if let Some(lookup) = &cache {
+        lookup.store(&printed, exit_code);
+    }
+    Ok(exit_code)
+}
+
+/// The first two words of `args` (after argv[0]) that aren't flags.
+fn command_key(args: &[String]) -> String {
+    let mut words = Vec::new();
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        if FLAGS_WITH_VALUE.contains(&arg.as_str()) {
+            iter.next();
+        } else if !arg.starts_with('-') {
+            words.push(arg.as_str());
+            if words.len() == 2 {
+                break;
+            }
+        }
+    }
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_command_key_skips_flags() {
+        assert_eq!(command_key(&args("rtk cargo test --lib")), "cargo test");
+        assert_eq!(
+            command_key(&args("rtk -v --budget 500 --output text git diff HEAD")),
+            "git diff"
+        );
+        assert_eq!(command_key(&args("rtk --budget=500 ls -la")), "ls");
+    }
+}